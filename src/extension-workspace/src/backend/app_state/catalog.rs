@@ -0,0 +1,41 @@
+use std::time::SystemTime;
+
+use common::catalog::GameCatalog;
+use tokio::sync::Mutex;
+
+use crate::prelude::*;
+
+/// Game catalog parsed from the file at `CATALOG_PATH`. The file is parsed
+/// again only after it gets modified, so it can be updated without a restart.
+#[derive(Debug, Default)]
+pub struct CatalogCache {
+    cached: Mutex<Option<(SystemTime, GameCatalog)>>,
+}
+
+impl CatalogCache {
+    /// Returns `None` if `CATALOG_PATH` isn't set.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be read or doesn't contain a valid catalog.
+    pub async fn get(&self) -> Result<Option<GameCatalog>> {
+        let Ok(path) = std::env::var("CATALOG_PATH") else {
+            return Ok(None);
+        };
+        let modified = tokio::fs::metadata(&path).await?.modified()?;
+        let mut cached = self.cached.lock().await;
+
+        if let Some((cached_modified, catalog)) = cached.as_ref()
+            && *cached_modified == modified
+        {
+            return Ok(Some(catalog.clone()));
+        }
+
+        let json = tokio::fs::read_to_string(&path).await?;
+        let catalog = serde_json::from_str::<GameCatalog>(&json)?;
+
+        *cached = Some((modified, catalog.clone()));
+
+        Ok(Some(catalog))
+    }
+}
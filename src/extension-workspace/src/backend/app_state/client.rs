@@ -1,6 +1,7 @@
 use std::time::Duration;
 
-use common::connection::SessionScope;
+use common::{clan_roster::ClanRoster, connection::SessionScope, messaging::prelude::*};
+use futures::TryStreamExt;
 use mongodb::{
     Collection, IndexModel,
     bson::{self, DateTime, Document, doc},
@@ -15,6 +16,8 @@ use serde_with::{DisplayFromStr, serde_as};
 use crate::prelude::*;
 
 const DB_NAME: &str = "margonem";
+/// How long diagnostics reports are kept.
+const DIAGNOSTICS_TTL: Duration = Duration::from_secs(60 * 60 * 24 * 30); // 30 days
 
 #[derive(Debug, Clone)]
 pub struct Client(mongodb::Client);
@@ -89,6 +92,35 @@ impl Client {
             );
         }
 
+        let diagnostics = client.get_collection::<DiagnosticsEntry>();
+        let index_name = "diagnostics_ttl";
+        if !diagnostics
+            .list_index_names()
+            .await?
+            .iter()
+            .any(|name| name == index_name)
+        {
+            let index_name = diagnostics
+                .create_index(
+                    IndexModel::builder()
+                        .keys(doc! { "inserted_at": 1 })
+                        .options(
+                            IndexOptions::builder()
+                                .expire_after(DIAGNOSTICS_TTL)
+                                .name(index_name.to_string())
+                                .build(),
+                        )
+                        .build(),
+                )
+                .await?
+                .index_name;
+
+            info!(
+                "Created index '{index_name}' in collection '{}'.",
+                <DiagnosticsEntry as IntoCollection>::COLLECTION_NAME
+            );
+        }
+
         info!("--- Collections in '{DB_NAME}' database ---");
 
         for name in collections {
@@ -130,6 +162,14 @@ impl Client {
                 <Premium as IntoCollection>::COLLECTION_NAME.into(),
                 <Premium as IntoCollection>::validator(),
             ),
+            (
+                <DiagnosticsEntry as IntoCollection>::COLLECTION_NAME.into(),
+                <DiagnosticsEntry as IntoCollection>::validator(),
+            ),
+            (
+                <ClanRosterEntry as IntoCollection>::COLLECTION_NAME.into(),
+                <ClanRosterEntry as IntoCollection>::validator(),
+            ),
         ]
     }
 
@@ -174,6 +214,24 @@ impl Client {
         Ok(discord_accounts.find_one(filter).await?)
     }
 
+    /// Update the scope the settings of the discord account are stored for.
+    pub async fn update_session_scope(
+        &self,
+        uid: serenity::UserId,
+        session_scope: SessionScope,
+    ) -> Result<()> {
+        let discord_accounts = self.get_collection::<DiscordAccount>();
+        let filter = doc! { "_id": uid.to_string() };
+        let update = doc! { "$set": { "session_scope": session_scope as i32 } };
+
+        discord_accounts
+            .update_one(filter, update)
+            .await
+            .with_context(|| format!("Could not update session scope for {uid}"))?;
+
+        Ok(())
+    }
+
     pub async fn get_premium_details(&self, uid: serenity::UserId) -> Result<Option<Premium>> {
         let premium_details = self.get_collection::<Premium>();
         let filter = doc! { "_id": uid.to_string() };
@@ -184,6 +242,113 @@ impl Client {
             .with_context(|| format!("Could not get premium details for {uid}"))
     }
 
+    /// Store a diagnostics report sent by the user's extension.
+    pub async fn insert_diagnostics(
+        &self,
+        uid: serenity::UserId,
+        report: DiagnosticsReport,
+    ) -> Result<()> {
+        let diagnostics = self.get_collection::<DiagnosticsEntry>();
+
+        diagnostics
+            .insert_one(DiagnosticsEntry::new(uid, report))
+            .await
+            .with_context(|| format!("Could not insert diagnostics for {uid}"))?;
+
+        Ok(())
+    }
+
+    /// Fetch the shared roster of the clan, if any of its members synced one.
+    pub async fn get_clan_roster(
+        &self,
+        world: &str,
+        clan_id: i32,
+    ) -> Result<Option<ClanRosterEntry>> {
+        let clan_rosters = self.get_collection::<ClanRosterEntry>();
+        let filter = doc! { "_id": ClanRosterEntry::id(world, clan_id) };
+
+        clan_rosters
+            .find_one(filter)
+            .await
+            .with_context(|| format!("Could not get roster of clan {clan_id} on {world}"))
+    }
+
+    /// Replace the shared roster of the clan with `entry`.
+    pub async fn replace_clan_roster(&self, entry: &ClanRosterEntry) -> Result<()> {
+        let clan_rosters = self.get_collection::<ClanRosterEntry>();
+        let filter = doc! { "_id": &entry.id };
+
+        clan_rosters
+            .replace_one(filter, entry)
+            .upsert(true)
+            .await
+            .with_context(|| {
+                format!(
+                    "Could not replace roster of clan {} on {}",
+                    entry.roster.clan_id, entry.roster.world
+                )
+            })?;
+
+        Ok(())
+    }
+
+    /// Error counts reported since `since`, grouped by release and error code,
+    /// most frequent first.
+    pub async fn get_diagnostics_summary(
+        &self,
+        since: DateTime,
+        version: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<DiagnosticsSummary>> {
+        let diagnostics = self.get_collection::<DiagnosticsEntry>();
+        let mut filter = doc! { "inserted_at": { "$gte": since } };
+
+        if let Some(version) = version {
+            filter.insert("version", version);
+        }
+
+        let pipeline = [
+            doc! { "$match": filter },
+            doc! { "$unwind": "$errors" },
+            doc! {
+                "$group": {
+                    "_id": {
+                        "version": "$version",
+                        "code": "$errors.code",
+                        "kind": "$errors.kind",
+                    },
+                    "count": { "$sum": "$errors.count" },
+                    "fatal": { "$max": "$errors.fatal" },
+                    "users": { "$addToSet": "$uid" },
+                    "interfaces": { "$addToSet": "$interface" },
+                    "last_seen": { "$max": "$inserted_at" },
+                }
+            },
+            doc! {
+                "$project": {
+                    "_id": 0,
+                    "version": "$_id.version",
+                    "code": "$_id.code",
+                    "kind": "$_id.kind",
+                    "count": 1,
+                    "fatal": 1,
+                    "users": { "$size": "$users" },
+                    "interfaces": 1,
+                    "last_seen": 1,
+                }
+            },
+            doc! { "$sort": { "count": -1 } },
+            doc! { "$limit": limit },
+        ];
+
+        let documents: Vec<Document> = diagnostics.aggregate(pipeline).await?.try_collect().await?;
+
+        documents
+            .into_iter()
+            .map(|document| Ok(bson::from_document(document)?))
+            .collect()
+    }
+
     /// Find one or insert a new [`DiscordAccount`], updating it with the
     /// provided `email_verified`.
     pub(super) async fn update_discord_account_login(&self, uid: serenity::UserId) -> Result<()> {
@@ -297,6 +462,131 @@ pub struct Premium {
     pub(super) animation: bool,
 }
 
+/// Diagnostics report sent by the extension of a user who opted into
+/// reporting errors.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiagnosticsEntry {
+    #[serde_as(as = "DisplayFromStr")]
+    uid: serenity::UserId,
+    version: String,
+    interface: String,
+    addons: Vec<String>,
+    errors: Vec<ReportedError>,
+    events: Vec<String>,
+    inserted_at: DateTime,
+}
+
+impl DiagnosticsEntry {
+    fn new(uid: serenity::UserId, report: DiagnosticsReport) -> Self {
+        let DiagnosticsReport {
+            version,
+            interface,
+            addons,
+            errors,
+            events,
+        } = report;
+
+        Self {
+            uid,
+            version,
+            interface,
+            addons,
+            errors,
+            events,
+            inserted_at: DateTime::now(),
+        }
+    }
+}
+
+/// Roster shared by the members of a single clan.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClanRosterEntry {
+    /// `{world}:{clan_id}`
+    #[serde(rename = "_id")]
+    id: String,
+    pub roster: ClanRoster,
+    /// Characters which synced the roster, each claimed by the first user who
+    /// synced it.
+    #[serde(default)]
+    contributors: Vec<RosterContributor>,
+    /// Last user who synced the roster.
+    #[serde_as(as = "DisplayFromStr")]
+    updated_by: serenity::UserId,
+    updated_at: DateTime,
+}
+
+impl ClanRosterEntry {
+    fn id(world: &str, clan_id: i32) -> String {
+        format!("{world}:{clan_id}")
+    }
+
+    /// Seeds the shared roster of a clan with the roster synced by `char_id`.
+    pub fn new(uid: serenity::UserId, char_id: i32, roster: ClanRoster) -> Self {
+        Self {
+            id: Self::id(&roster.world, roster.clan_id),
+            roster,
+            contributors: vec![RosterContributor { char_id, uid }],
+            updated_by: uid,
+            updated_at: DateTime::now(),
+        }
+    }
+
+    /// Whether the user syncing as `char_id` can update and read the roster.
+    /// The character has to be a member of the clan in the shared snapshot and
+    /// can't be claimed by another user.
+    pub fn admits(&self, uid: serenity::UserId, char_id: i32) -> bool {
+        self.roster.has_member(char_id)
+            && self
+                .contributors
+                .iter()
+                .all(|contributor| contributor.char_id != char_id || contributor.uid == uid)
+    }
+
+    /// Merges the roster synced by an admitted character. Characters which
+    /// are no longer members of the clan lose their claim.
+    pub fn update(&mut self, uid: serenity::UserId, char_id: i32, roster: ClanRoster) {
+        self.roster.replace_members(&roster);
+        self.roster.merge(roster);
+
+        if self
+            .contributors
+            .iter()
+            .all(|contributor| contributor.char_id != char_id)
+        {
+            self.contributors.push(RosterContributor { char_id, uid });
+        }
+        self.contributors
+            .retain(|contributor| self.roster.has_member(contributor.char_id));
+
+        self.updated_by = uid;
+        self.updated_at = DateTime::now();
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+struct RosterContributor {
+    char_id: i32,
+    #[serde_as(as = "DisplayFromStr")]
+    uid: serenity::UserId,
+}
+
+/// Aggregated occurrences of a single error code in a release.
+#[derive(Debug, Deserialize)]
+pub struct DiagnosticsSummary {
+    pub version: String,
+    pub code: i64,
+    pub kind: String,
+    pub count: i64,
+    pub fatal: bool,
+    /// Amount of distinct users who reported the error.
+    pub users: i64,
+    pub interfaces: Vec<String>,
+    pub last_seen: DateTime,
+}
+
 into_collection! {
     DiscordAccount: {
         @name: "discord_accounts",
@@ -425,5 +715,96 @@ into_collection! {
                 }
             }
         }
+    },
+    DiagnosticsEntry: {
+        @name: "diagnostics",
+        @validator: doc! {
+            "$jsonSchema": doc! {
+                "bsonType": "object",
+                "title": "Diagnostics Report Validation",
+                "additionalProperties": false,
+                "required": [ "_id", "uid", "version", "interface", "addons", "errors", "events", "inserted_at" ],
+                "properties": doc! {
+                    "_id": {
+                        "bsonType": "objectId",
+                        "description": "Report identifier."
+                    },
+                    "uid": {
+                        "bsonType": "string",
+                        "description": "Discord user identifier."
+                    },
+                    "version": {
+                        "bsonType": "string",
+                        "description": "Extension release the errors were reported from."
+                    },
+                    "interface": {
+                        "enum": [ "ni", "si" ],
+                        "description": "Game interface the extension was running on."
+                    },
+                    "addons": {
+                        "bsonType": "array",
+                        "items": { "bsonType": "string" },
+                        "description": "Addons active when the report was sent."
+                    },
+                    "errors": {
+                        "bsonType": "array",
+                        "items": {
+                            "bsonType": "object",
+                            "required": [ "code", "kind", "fatal", "count" ],
+                            "properties": {
+                                "code": { "bsonType": [ "int", "long" ] },
+                                "kind": { "bsonType": "string" },
+                                "fatal": { "bsonType": "bool" },
+                                "count": { "bsonType": [ "int", "long" ] }
+                            }
+                        },
+                        "description": "Encoded error locations along with their occurrences."
+                    },
+                    "events": {
+                        "bsonType": "array",
+                        "items": { "bsonType": "string" },
+                        "description": "Events preceding the errors, oldest first."
+                    },
+                    "inserted_at": {
+                        "bsonType": "date",
+                        "description": "Report insertion timestamp, expires after 30 days."
+                    }
+                }
+            }
+        }
+    },
+    ClanRosterEntry: {
+        @name: "clan_rosters",
+        @validator: doc! {
+            "$jsonSchema": doc! {
+                "bsonType": "object",
+                "title": "Clan Roster Validation",
+                "additionalProperties": false,
+                "required": [ "_id", "roster", "updated_by", "updated_at" ],
+                "properties": doc! {
+                    "_id": {
+                        "bsonType": "string",
+                        "description": "World name and clan identifier separated with a colon."
+                    },
+                    "roster": {
+                        "bsonType": "object",
+                        "required": [ "world", "clan_id", "updated_at", "members", "events" ],
+                        "description": "Merged roster history of the clan."
+                    },
+                    "contributors": {
+                        "bsonType": "array",
+                        "description": "Characters which synced the roster and the discord identifiers of the users who claimed them."
+                    },
+                    "updated_by": {
+                        "bsonType": "string",
+                        "description": "Discord identifier of the user who synced the roster last."
+                    },
+                    "updated_at": {
+                        "bsonType": "date",
+                        "description": "Last sync timestamp."
+                    }
+                }
+            }
+        }
     }
 }
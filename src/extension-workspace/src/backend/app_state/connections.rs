@@ -26,7 +26,8 @@ pub struct Connections {
     /// settings are guaranteed to be in the database, otherwise they are to be
     /// taken from the connection corresponding to the cid. Then the session
     /// for that cid should be closed, since no two players can play on one
-    /// account.
+    /// account. Several game accounts can map to the same cid, game tabs open
+    /// in one browser share the connection of its background.
     field: DashMap<GameAccountId, Simple>,
 }
 
@@ -159,6 +160,38 @@ impl Connections {
             .is_some_and(|connection| connection.has_active_session())
     }
 
+    /// Send a message over the socket of the connection with `cid`.
+    pub(super) fn send(&self, cid: &Simple, msg: WsMessage) -> Result<()> {
+        self.all
+            .get(cid)
+            .ok_or_else(|| anyhow!("Missing `Connection` with id `{cid}`!"))?
+            .tx
+            .unbounded_send(msg)
+            .map_err(|err| anyhow!("Failed to send to connection with id `{cid}`! {err}"))
+    }
+
+    /// Scope of the user authorized on the connection with `cid`.
+    pub(super) fn scope(&self, cid: &Simple) -> Option<SessionScope> {
+        self.all
+            .get(cid)
+            .and_then(|connection| Some(connection.user.as_ref()?.scope))
+    }
+
+    /// Apply `scope` to every authorized connection of the user.
+    pub(super) fn set_scope(&self, uid: &serenity::UserId, scope: SessionScope) {
+        let Some(cids) = self.authorized.get(uid) else {
+            return;
+        };
+
+        for cid in cids.iter() {
+            if let Some(mut connection) = self.all.get_mut(cid)
+                && let Some(user) = connection.user.as_mut()
+            {
+                user.scope = scope;
+            }
+        }
+    }
+
     // TODO: Incomplete functionality.
     /// Withdraw access for an authorized session, by removing the current user
     /// data.
@@ -0,0 +1,77 @@
+use std::time::{Duration, Instant};
+
+use common::messaging::prelude::*;
+use dashmap::{DashMap, mapref::entry::Entry};
+
+use crate::prelude::*;
+
+/// Minimal time between two stored reports of a single user.
+const REPORT_INTERVAL: Duration = Duration::from_secs(60);
+/// Reports are trimmed to these sizes before they get stored.
+const MAX_ERRORS: usize = 50;
+const MAX_EVENTS: usize = 20;
+const MAX_ADDONS: usize = 64;
+const MAX_FIELD_LENGTH: usize = 128;
+/// Interfaces accepted by the `diagnostics` collection validator.
+const INTERFACES: [&str; 2] = ["ni", "si"];
+
+/// Rate limits diagnostics reports sent by the extension.
+#[derive(Debug, Default)]
+pub struct DiagnosticsLimiter {
+    last_report: DashMap<serenity::UserId, Instant>,
+}
+
+impl DiagnosticsLimiter {
+    /// Returns `true` and marks the report as sent if the user didn't send
+    /// another report during the last [`REPORT_INTERVAL`].
+    pub fn try_acquire(&self, uid: serenity::UserId) -> bool {
+        let now = Instant::now();
+
+        match self.last_report.entry(uid) {
+            Entry::Occupied(entry) if now.duration_since(*entry.get()) < REPORT_INTERVAL => false,
+            Entry::Occupied(mut entry) => {
+                entry.insert(now);
+                true
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(now);
+                true
+            }
+        }
+    }
+}
+
+/// Trims every list and string of a report sent by the client, so a single
+/// report can't take up an arbitrary amount of space.
+///
+/// # Errors
+///
+/// If the report comes from an unknown interface, it wouldn't pass the
+/// collection validator.
+pub fn sanitize_report(mut report: DiagnosticsReport) -> Result<DiagnosticsReport> {
+    if !INTERFACES.contains(&report.interface.as_str()) {
+        bail!("Unknown interface: `{}`!", report.interface.escape_debug());
+    }
+
+    fn truncate(value: &mut String) {
+        if let Some((index, _)) = value.char_indices().nth(MAX_FIELD_LENGTH) {
+            value.truncate(index);
+        }
+    }
+
+    report.errors.truncate(MAX_ERRORS);
+    // Events are sorted oldest first, the latest ones are the most relevant.
+    let excess_events = report.events.len().saturating_sub(MAX_EVENTS);
+    report.events.drain(..excess_events);
+    report.addons.truncate(MAX_ADDONS);
+
+    truncate(&mut report.version);
+    report.addons.iter_mut().for_each(truncate);
+    report.events.iter_mut().for_each(truncate);
+    report
+        .errors
+        .iter_mut()
+        .for_each(|error| truncate(&mut error.kind));
+
+    Ok(report)
+}
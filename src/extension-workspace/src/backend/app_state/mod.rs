@@ -1,8 +1,12 @@
-use std::{net::SocketAddr, sync::Arc};
+use std::{
+    net::SocketAddr,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use async_session::MemoryStore;
 use axum::extract::ws::Message as WsMessage;
-use common::messaging::prelude::*;
+use common::{clan_roster::ClanRoster, messaging::prelude::*};
 use futures::{SinkExt, channel::mpsc};
 use oauth2::{
     AuthType, AuthUrl, ClientId, ClientSecret, RedirectUrl, TokenUrl, basic::BasicClient,
@@ -14,12 +18,20 @@ use crate::prelude::*;
 /// Module containing the API used for manipulating the MongoDb client
 /// instance's contents.
 pub mod client;
-use client::Client;
+use client::{ClanRosterEntry, Client};
 
 /// In game web socket connection session.
 pub mod connections;
 use connections::Connections;
 
+/// Rate limiting and sanitization of error reports sent by the extension.
+pub mod diagnostics;
+use diagnostics::DiagnosticsLimiter;
+
+/// Game catalog served to the extension.
+pub mod catalog;
+use catalog::CatalogCache;
+
 const GUILD_ID: serenity::GuildId = serenity::GuildId::new(to_u64(env!("GUILD_ID")));
 
 /// State of the app shared between the discord bot, requests and web socket
@@ -35,6 +47,8 @@ pub struct AppState {
     pub oauth_client: OAuth2Client,
     pub store: MemoryStore,
     pub http_client: oauth2::reqwest::Client,
+    pub diagnostics_limiter: Arc<DiagnosticsLimiter>,
+    pub catalog: Arc<CatalogCache>,
 }
 
 impl AppState {
@@ -62,6 +76,8 @@ impl AppState {
             oauth_client,
             store,
             http_client,
+            diagnostics_limiter: Default::default(),
+            catalog: Default::default(),
         })
     }
 
@@ -122,6 +138,207 @@ impl AppState {
         Ok(())
     }
 
+    /// Issue new tokens for an authorized connection before its access token
+    /// expires.
+    ///
+    /// A rejected refresh token gets a response without tokens, after which
+    /// the extension logs the user out.
+    async fn refresh_tokens(&self, uid: serenity::UserId, cid: Simple, msg: Message) -> Result<()> {
+        let refresh_token = msg
+            .refresh_token
+            .ok_or_else(|| anyhow!("`Message` missing refresh token!"))?;
+        let discord_acc = match self.client.validate_refresh_token(&refresh_token).await {
+            Ok(discord_acc) if discord_acc.id == uid => discord_acc,
+            Ok(discord_acc) => bail!("Refresh token of '{}' used by '{uid}'!", discord_acc.id),
+            Err(AuthError::InvalidToken) => {
+                let response =
+                    Message::new(Task::Tokens, Target::Background, MessageKind::Response)
+                        .into_ws_message()?;
+
+                return self.connections.send(&cid, response);
+            }
+            Err(err) => bail!("Failed to refresh tokens for '{uid}'! {err}"),
+        };
+        let member = self.get_member_data(uid).await?;
+        let access_level = AccessLevel::new(discord_acc.email_verified, &member.roles);
+        let access_token = Jwt::<AccessClaims>::new(access_level, cid)?;
+        let refresh_token = Jwt::<RefreshClaims>::new(uid, discord_acc.version)?;
+        let response = Message::builder(Task::Tokens, Target::Background, MessageKind::Response)
+            .access_token(access_token.into())
+            .refresh_token(refresh_token.into())
+            .build()
+            .into_ws_message()?;
+
+        debug!("Refreshed tokens of connection with id '{cid}' for '{uid}'.");
+
+        self.connections.send(&cid, response)
+    }
+
+    /// Responds with the game catalog stored at `CATALOG_PATH`, the extension
+    /// keeps using its bundled catalog if there is none or it can't be read.
+    async fn send_catalog(&self, cid: Simple) -> Result<()> {
+        let catalog = self.catalog.get().await.unwrap_or_else(|err| {
+            warn!("Could not load the game catalog! {err:#?}");
+            None
+        });
+        let response = Message::builder(Task::Catalog, Target::Background, MessageKind::Response)
+            .maybe_catalog(catalog)
+            .build()
+            .into_ws_message()?;
+
+        self.connections.send(&cid, response)
+    }
+
+    /// Merges the roster sent by a clan member into the shared one and
+    /// responds with the result.
+    ///
+    /// If the shared roster can't be updated, or the sender isn't admitted to
+    /// it, the response contains the sent roster, so that the member keeps
+    /// their own until the next sync.
+    async fn sync_clan_roster(
+        &self,
+        uid: serenity::UserId,
+        cid: Simple,
+        msg: Message,
+    ) -> Result<()> {
+        let mut clan_roster = msg
+            .clan_roster
+            .ok_or_else(|| anyhow!("`Message` missing `ClanRoster`!"))?;
+        let char_id = msg
+            .character
+            .ok_or_else(|| anyhow!("`Message` missing `CharacterId`!"))?
+            .char_id
+            .parse::<i32>()?;
+
+        clan_roster.sanitize(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs_f64());
+
+        let clan_roster = match self
+            .merge_clan_roster(uid, char_id, clan_roster.clone())
+            .await
+        {
+            Ok(shared_roster) => shared_roster,
+            Err(err) => {
+                warn!(
+                    "Could not sync roster of clan {} for '{uid}'! {err:#?}",
+                    clan_roster.clan_id
+                );
+                clan_roster
+            }
+        };
+        let response =
+            Message::builder(Task::ClanRoster, Target::Background, MessageKind::Response)
+                .clan_roster(clan_roster)
+                .build()
+                .into_ws_message()?;
+
+        self.connections.send(&cid, response)
+    }
+
+    /// The game doesn't let the backend check clan membership, so the sender
+    /// is admitted only if their character is a member in both the sent and
+    /// the shared roster and no other user synced as that character before.
+    async fn merge_clan_roster(
+        &self,
+        uid: serenity::UserId,
+        char_id: i32,
+        clan_roster: ClanRoster,
+    ) -> Result<ClanRoster> {
+        let (world, clan_id) = (clan_roster.world.clone(), clan_roster.clan_id);
+
+        if !clan_roster.has_member(char_id) {
+            bail!("Character {char_id} of '{uid}' missing in its roster of clan {clan_id}!");
+        }
+
+        let entry = match self.client.get_clan_roster(&world, clan_id).await? {
+            Some(mut entry) if entry.admits(uid, char_id) => {
+                entry.update(uid, char_id, clan_roster);
+                entry
+            }
+            Some(_) => bail!("Character {char_id} of '{uid}' not admitted to clan {clan_id}!"),
+            None => ClanRosterEntry::new(uid, char_id, clan_roster),
+        };
+        self.client.replace_clan_roster(&entry).await?;
+
+        Ok(entry.roster)
+    }
+
+    /// Stores a diagnostics report. Reports over the limit are sent back, so
+    /// that the extension keeps them for the next flush.
+    ///
+    /// Invalid reports and failed inserts aren't errors of the connection,
+    /// they're only logged.
+    async fn store_diagnostics(
+        &self,
+        uid: serenity::UserId,
+        cid: Simple,
+        msg: Message,
+    ) -> Result<()> {
+        let report = msg
+            .diagnostics
+            .ok_or_else(|| anyhow!("`Message` missing `DiagnosticsReport`!"))?;
+
+        if !self.diagnostics_limiter.try_acquire(uid) {
+            debug!("Rejected diagnostics report from '{uid}', cid: '{cid}'.");
+
+            let response =
+                Message::builder(Task::Diagnostics, Target::Background, MessageKind::Response)
+                    .diagnostics(report)
+                    .build()
+                    .into_ws_message()?;
+
+            return self.connections.send(&cid, response);
+        }
+
+        let result = match diagnostics::sanitize_report(report) {
+            Ok(report) => self.client.insert_diagnostics(uid, report).await,
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = result {
+            warn!("Could not store diagnostics report from '{uid}'! {err:#?}");
+        }
+
+        Ok(())
+    }
+
+    /// Saves the scope the settings are stored for and responds with it once
+    /// it's applied to every connection of the user.
+    ///
+    /// If the scope can't be saved the response contains the unchanged one.
+    async fn update_session_scope(
+        &self,
+        uid: serenity::UserId,
+        cid: Simple,
+        msg: Message,
+    ) -> Result<()> {
+        let requested_scope = msg
+            .session_scope
+            .ok_or_else(|| anyhow!("`Message` missing `SessionScope`!"))?;
+        let session_scope = match self.client.update_session_scope(uid, requested_scope).await {
+            Ok(()) => {
+                self.connections.set_scope(&uid, requested_scope);
+                requested_scope
+            }
+            Err(err) => {
+                warn!("Could not update session scope of '{uid}'! {err:#?}");
+                self.connections
+                    .scope(&cid)
+                    .ok_or_else(|| anyhow!("Missing user of connection with id '{cid}'!"))?
+            }
+        };
+
+        let response = Message::builder(
+            Task::SessionScope,
+            Target::Background,
+            MessageKind::Response,
+        )
+        .session_scope(session_scope)
+        .build()
+        .into_ws_message()?;
+
+        self.connections.send(&cid, response)
+    }
+
     /// # Errors
     ///
     /// If this method returns an [`Err`] the connection does not get added into
@@ -235,12 +452,17 @@ impl AppState {
         }
 
         match msg.task {
+            Task::Tokens => self.refresh_tokens(uid, cid, msg).await?,
+            Task::Catalog => self.send_catalog(cid).await?,
+            Task::ClanRoster => self.sync_clan_roster(uid, cid, msg).await?,
+            Task::SessionScope => self.update_session_scope(uid, cid, msg).await?,
             Task::LogOut => {
                 let all_devices = msg
                     .log_out
                     .ok_or_else(|| anyhow!("`Message` missing `LogOutDetails`!"))?
                     .all_devices;
             }
+            Task::Diagnostics => self.store_diagnostics(uid, cid, msg).await?,
             _ => bail!("Incorrect task: `{:?}`! `{msg:?}`", msg.task),
         }
 
@@ -63,6 +63,11 @@ impl AccessLevel {
         self.0 >= Self::ANTYDUCH
     }
 
+    /// Whether the member has access to the developer commands.
+    pub fn is_dev(roles: &[serenity::RoleId]) -> bool {
+        roles.iter().any(|role| role.get() == DEV_ROLE_ID)
+    }
+
     pub fn get(self) -> u8 {
         self.0
     }
@@ -0,0 +1,82 @@
+use std::{
+    fmt::Write,
+    time::{Duration, SystemTime},
+};
+
+use mongodb::bson::DateTime;
+
+use crate::{discord_bot::Context, prelude::*};
+
+const SUMMARY_LIMIT: i64 = 15;
+const MAX_DAYS: u64 = 30;
+/// Discord rejects messages longer than this many characters.
+const MESSAGE_LIMIT: usize = 2_000;
+const TRUNCATED_NOTE: &str = "…i więcej, zawęź wyszukiwanie wersją zestawu.";
+
+async fn is_dev(ctx: Context<'_>) -> Result<bool> {
+    let member = ctx.data().get_member_data(ctx.author().id).await?;
+
+    Ok(AccessLevel::is_dev(&member.roles))
+}
+
+/// Wyświetla najczęściej zgłaszane błędy zestawu.
+///
+/// Kody błędów można odczytać przy pomocy `extract_filenames decode <kod> --release <wersja>`.
+#[poise::command(prefix_command, check = "is_dev", aliases("bledy"))]
+pub(in crate::discord_bot) async fn diagnostics(
+    ctx: Context<'_>,
+    #[description = "Wersja zestawu, domyślnie wszystkie"] version: Option<String>,
+    #[description = "Liczba dni wstecz, domyślnie 7"] days: Option<u64>,
+) -> Result<()> {
+    let days = days.unwrap_or(7).clamp(1, MAX_DAYS);
+    let since = DateTime::from_system_time(
+        SystemTime::now() - Duration::from_secs(60 * 60 * 24 * days),
+    );
+    let summary = ctx
+        .data()
+        .client
+        .get_diagnostics_summary(since, version.as_deref(), SUMMARY_LIMIT)
+        .await?;
+
+    if summary.is_empty() {
+        ctx.say(format!("Brak zgłoszonych błędów z ostatnich {days} dni."))
+            .await?;
+        return Ok(());
+    }
+
+    let mut response = format!("**Najczęstsze błędy z ostatnich {days} dni:**\n");
+
+    for entry in summary {
+        let fatal = match entry.fatal {
+            true => " **[krytyczny]**",
+            false => "",
+        };
+        let mut line = String::new();
+
+        writeln!(
+            line,
+            "- `{}` ({}) v{}{fatal}: **{}**x, użytkownicy: {}, interfejs: {}, ostatnio: <t:{}:R>",
+            entry.code,
+            entry.kind,
+            entry.version,
+            entry.count,
+            entry.users,
+            entry.interfaces.join("/"),
+            entry.last_seen.timestamp_millis() / 1000,
+        )?;
+
+        // Entries are sorted by count, the ones that don't fit are the least relevant.
+        if response.chars().count() + line.chars().count() + TRUNCATED_NOTE.chars().count()
+            > MESSAGE_LIMIT
+        {
+            response.push_str(TRUNCATED_NOTE);
+            break;
+        }
+
+        response.push_str(&line);
+    }
+
+    ctx.say(response).await?;
+
+    Ok(())
+}
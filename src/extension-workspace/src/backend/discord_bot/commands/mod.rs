@@ -1 +1,2 @@
+pub mod diagnostics;
 pub mod helpers;
\ No newline at end of file
@@ -16,7 +16,7 @@ pub(super) async fn start(client: Client, tx: Sender<AppState>) {
 
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
-            commands: vec![commands::helpers::doc()],
+            commands: vec![commands::helpers::doc(), commands::diagnostics::diagnostics()],
             prefix_options: poise::PrefixFrameworkOptions {
                 prefix: Some("?".into()),
                 additional_prefixes: vec![
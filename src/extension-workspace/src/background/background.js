@@ -11,6 +11,12 @@ let handleMessage = async (message, sender, sendResponse) =>
 let handleActionClick = async (tab) =>
     (await exports).handleActionClick(tab);
 
+let handleTabRemoved = async (tabId) =>
+    (await exports).handleTabRemoved(tabId);
+
+let handleTabActivated = async (activeInfo) =>
+    (await exports).handleTabActivated(activeInfo);
+
 // let handleTabsUpdated = async (tabId, changeInfo, tab) =>
 //     (await exports).handleTabsUpdated(tabId, changeInfo, tab);
 
@@ -22,5 +28,7 @@ chrome.runtime.onConnectExternal.addListener(handlePortConnect);
 chrome.runtime.onMessage.addListener(handleMessage);
 chrome.runtime.onMessageExternal.addListener(handleMessage);
 chrome.action.onClicked.addListener(handleActionClick);
+chrome.tabs.onRemoved.addListener(handleTabRemoved);
+chrome.tabs.onActivated.addListener(handleTabActivated);
 // chrome.webNavigation.onCommitted.addListener(handleOnCommitted)
 // chrome.tabs.onUpdated.addListener(handleTabsUpdated);
@@ -1,22 +1,29 @@
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     pin::Pin,
     task::{Context, Poll},
 };
 
 use common::{
-    debug_log, err_code, map_err, messaging::prelude::*, sleep, web_extension_sys::browser,
+    debug_log, err_code,
+    i18n::Msg,
+    map_err,
+    messaging::prelude::*,
+    sleep,
+    web_extension_sys::{browser, tabs::TabId},
 };
 use futures::{channel::oneshot, future::FusedFuture};
 use pin_project::pin_project;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use wasm_bindgen::prelude::*;
 
 use crate::{
-    FOREGROUND_PORT, console_error,
+    console_error,
     dispatcher::Dispatcher,
-    types::{AuthResponse, ExecutionError, Jwt, MessageExt, StorageRefreshToken},
+    session::{self, BrowserSessionStore, SessionStore},
+    tabs::GameTabs,
+    types::{AuthResponse, ExecutionError, Jwt, MessageExt, StorageLanguage, StorageRefreshToken},
 };
 
 const POPUP_OPEN_DEADLINE: u32 = 300;
@@ -40,19 +47,32 @@ impl<'a> WebAuthFlowDetails<'a> {
 pub struct Connection {
     /// User details available after connection authorization.
     pub user: RefCell<Option<User>>,
+    /// Incremented whenever a token refresh gets scheduled, so that only the
+    /// latest one is executed.
+    refresh_generation: Cell<u32>,
 }
 
 impl Connection {
     fn new(user: User) -> Self {
         Self {
             user: RefCell::new(Some(user)),
+            refresh_generation: Cell::new(0),
         }
     }
 
+    /// Resumes the session stored before the service worker got restarted.
+    pub(super) async fn restore() -> Result<Option<Self>, JsValue> {
+        let stored = BrowserSessionStore.load().await?;
+        let now = js_sys::Date::new_0().get_time() as u64 / 1000;
+
+        Ok(session::resume(stored, now).map(Self::new))
+    }
+
     pub(super) async fn establish_authorized(dispatcher: &mut Dispatcher) -> Result<Self, JsValue> {
         let (user, from_authorized) = Self::_establish_authorized(dispatcher).await?;
 
         Self::store_refresh_token(user.refresh_token.clone()).await?;
+        BrowserSessionStore.save(&user).await?;
 
         if !from_authorized {
             Self::notify_login_success(&user).await?;
@@ -62,19 +82,7 @@ impl Connection {
     }
 
     async fn _establish_authorized(dispatcher: &mut Dispatcher) -> Result<(User, bool), JsValue> {
-        let storage_obj = browser()
-            .storage()
-            .sync()
-            .get(&JsValue::from_str("refresh_token"))
-            .await
-            .map_err(map_err!())?
-            .into();
-        let Some(refresh_token) =
-            serde_wasm_bindgen::from_value::<StorageRefreshToken>(storage_obj)
-                .map_err(map_err!(from))?
-                .token
-                .and_then(|token| token.validate().then(|| token.into_inner()))
-        else {
+        let Some(refresh_token) = StorageRefreshToken::load().await? else {
             return Self::from_unauthorized(dispatcher).await;
         };
 
@@ -82,7 +90,7 @@ impl Connection {
             .socket
             .send(
                 Message::builder(Task::Tokens, Target::Backend, MessageKind::Request)
-                    .refresh_token(refresh_token)
+                    .refresh_token(refresh_token.into_inner())
                     .build(),
             )
             .await
@@ -92,7 +100,7 @@ impl Connection {
             .socket
             .recv()
             .await
-            .ok_or_else(|| err_code!())??;
+            .ok_or_else(|| err_code!())?;
         let validator = MessageValidator::builder(Target::Backend)
             .kind(MessageKind::Response)
             .task(Task::Tokens)
@@ -119,7 +127,7 @@ impl Connection {
             .socket
             .recv()
             .await
-            .ok_or_else(|| err_code!())??;
+            .ok_or_else(|| err_code!())?;
 
         debug_log!(@f "from_unauthorized response: {response:#?}");
 
@@ -204,11 +212,12 @@ impl Connection {
 
                     match msg.task {
                         Task::Handshake => Message::builder(Task::Handshake, msg.sender, MessageKind::Response)
-                            .error("[MDMA::RS] Aby korzystać z zestawu dokończ logowanie wewnątrz okna Discord!")
+                            .error(Msg::HandshakeFinishLogin.text_in(StorageLanguage::locale().await))
+                            .maybe_tab_id(msg.tab_id)
                             .build()
                             .execute()
                             .await?,
-                        Task::OpenPopup => AuthFlow::on_open_popup(dispatcher, PopupState::LoggingIn).await?,
+                        Task::OpenPopup => AuthFlow::on_open_popup(dispatcher, PopupState::LoggingIn, msg.tab_id).await?,
                         _ => unreachable!(),
                     }
                 }
@@ -218,6 +227,101 @@ impl Connection {
         AuthResponse::from_url(redirect_url).ok_or_else(|| err_code!())
     }
 
+    /// Requests new tokens shortly before the access token expires.
+    pub(super) fn schedule_token_refresh(&'static self) {
+        let generation = self.refresh_generation.get().wrapping_add(1);
+        let now = js_sys::Date::new_0().get_time() as u64 / 1000;
+
+        self.refresh_generation.set(generation);
+
+        let Some(delay) = self
+            .user
+            .borrow()
+            .as_ref()
+            .and_then(|user| session::refresh_delay(&user.access_token, now))
+        else {
+            return;
+        };
+
+        debug_log!(@f "Access token refresh scheduled in {delay}ms.");
+
+        wasm_bindgen_futures::spawn_local(async move {
+            sleep(delay).await;
+
+            // Superseded by tokens received in the meantime.
+            if self.refresh_generation.get() != generation {
+                return;
+            }
+
+            let Some(refresh_token) = self
+                .user
+                .borrow()
+                .as_ref()
+                .map(|user| user.refresh_token.clone().into_inner())
+            else {
+                return;
+            };
+            let request = Message::builder(Task::Tokens, Target::Backend, MessageKind::Request)
+                .refresh_token(refresh_token)
+                .build()
+                .execute()
+                .await;
+
+            if let Err(err_code) = request {
+                console_error!(err_code);
+            }
+        });
+    }
+
+    /// Updates the tokens after they were refreshed or the backend connection
+    /// was re-established. The user is logged out if the stored refresh token
+    /// got rejected.
+    pub(crate) async fn update_tokens(&'static self, response: Message) -> Result<(), JsValue> {
+        let validator = MessageValidator::builder(Target::Backend)
+            .kind(MessageKind::Response)
+            .task(Task::Tokens)
+            .build();
+
+        validator.validate(&response)?;
+
+        let (Some(access_token), Some(refresh_token)) =
+            (response.access_token, response.refresh_token)
+        else {
+            debug_log!("Refresh token rejected.");
+            self.user.borrow_mut().take();
+            browser()
+                .storage()
+                .sync()
+                .remove(&JsValue::from_str(StorageRefreshToken::KEY))
+                .await
+                .map_err(map_err!())?;
+            BrowserSessionStore.clear().await?;
+
+            return Message::builder(Task::LogOut, Target::Popup, MessageKind::Event)
+                .popup(PopupState::LoggedOut)
+                .build()
+                .execute()
+                .await;
+        };
+        let refresh_token = Jwt::new(refresh_token);
+
+        if let Some(user) = self.user.borrow_mut().as_mut() {
+            user.set_tokens(Jwt::new(access_token), refresh_token.clone());
+        }
+
+        Self::store_refresh_token(refresh_token).await?;
+
+        let user_opt = self.user.borrow().clone();
+
+        if let Some(user) = user_opt.as_ref() {
+            BrowserSessionStore.save(user).await?;
+        }
+
+        self.schedule_token_refresh();
+
+        Ok(())
+    }
+
     async fn store_refresh_token(token: Jwt) -> Result<(), JsValue> {
         browser()
             .storage()
@@ -232,7 +336,9 @@ impl Connection {
     }
 
     async fn notify_login_success(user: &User) -> Result<(), JsValue> {
-        if FOREGROUND_PORT.with_borrow(Option::is_some) {
+        let game_connected = GameTabs::any_connected();
+
+        if game_connected {
             Message::new(Task::Handshake, Target::Foreground, MessageKind::Event)
                 .execute()
                 .await?;
@@ -243,9 +349,7 @@ impl Connection {
             .maybe_premium(user.premium)
             .popup(PopupUpdate {
                 state: Some(PopupState::LoggedIn),
-                msg: FOREGROUND_PORT
-                    .with_borrow(Option::is_some)
-                    .then_some(PopupMessage::RefreshAfterLogin),
+                msg: game_connected.then_some(PopupMessage::RefreshAfterLogin),
             })
             .build()
             .execute()
@@ -306,11 +410,12 @@ impl AuthFlow {
 
                     match msg.task {
                         Task::Handshake => Message::builder(Task::Handshake, msg.sender, MessageKind::Response)
-                            .error("[MDMA::RS] Aby korzystać z zestawu zaloguj się klikając w ikonę rozszerzenia!")
+                            .error(Msg::HandshakeLogin.text_in(StorageLanguage::locale().await))
+                            .maybe_tab_id(msg.tab_id)
                             .build()
                             .execute()
                             .await?,
-                        Task::OpenPopup => Self::on_open_popup(dispatcher, PopupState::LoggedOut).await?,
+                        Task::OpenPopup => Self::on_open_popup(dispatcher, PopupState::LoggedOut, msg.tab_id).await?,
                         _ => unreachable!(),
                     }
 
@@ -344,19 +449,24 @@ impl AuthFlow {
         Ok(Self { rx })
     }
 
+    /// `tab_id` is the game tab which asked for the popup.
     async fn on_open_popup<B: Into<PopupUpdate>>(
         dispatcher: &mut Dispatcher,
         popup_update: B,
+        tab_id: Option<TabId>,
     ) -> Result<(), JsValue> {
-        let popup_open = Self::try_open_popup(dispatcher, popup_update).await?;
+        let popup_open = Self::try_open_popup(dispatcher, popup_update, tab_id).await?;
         if !popup_open {
             debug_log!("POPUP FAILED TO OPEN!");
         }
-        let maybe_error =
-            (!popup_open).then_some("[MDMA::RS] Nie udało się otworzyć okna rozszerzenia!");
+        let maybe_error = match popup_open {
+            true => None,
+            false => Some(Msg::PopupOpenFailed.text_in(StorageLanguage::locale().await)),
+        };
 
         Message::builder(Task::OpenPopup, Target::Foreground, MessageKind::Response)
             .maybe_error(maybe_error)
+            .maybe_tab_id(tab_id)
             .build()
             .execute()
             .await?;
@@ -371,6 +481,7 @@ impl AuthFlow {
     async fn try_open_popup<B: Into<PopupUpdate>>(
         dispatcher: &mut Dispatcher,
         popup_update: B,
+        tab_id: Option<TabId>,
     ) -> Result<bool, JsValue> {
         let message =
             Message::new(Task::OpenPopup, Target::Popup, MessageKind::Event).to_value()?;
@@ -388,7 +499,7 @@ impl AuthFlow {
             Err(ExecutionError::NoReceiver) => {} // try opening
         }
 
-        if Dispatcher::open_popup().await.is_err() {
+        if Dispatcher::open_popup(tab_id).await.is_err() {
             return Ok(false);
         }
 
@@ -412,11 +523,13 @@ impl AuthFlow {
 }
 
 /// User details available after connection authorization.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     /// Determines session persistance.
     pub scope: SessionScope,
-    /// Current game session data.
+    /// Current game session data, stored with the tokens so that it survives
+    /// service worker restarts.
+    #[serde(default)]
     pub session: Option<Session>,
     access_token: Jwt,
     refresh_token: Jwt,
@@ -425,7 +538,7 @@ pub struct User {
 }
 
 impl User {
-    fn new(
+    pub(crate) fn new(
         scope: SessionScope,
         access_token: Jwt,
         refresh_token: Jwt,
@@ -441,6 +554,19 @@ impl User {
             premium,
         }
     }
+
+    pub(crate) fn access_token(&self) -> &Jwt {
+        &self.access_token
+    }
+
+    pub(crate) fn refresh_token(&self) -> &Jwt {
+        &self.refresh_token
+    }
+
+    pub(crate) fn set_tokens(&mut self, access_token: Jwt, refresh_token: Jwt) {
+        self.access_token = access_token;
+        self.refresh_token = refresh_token;
+    }
 }
 
 impl TryFrom<Message> for User {
@@ -471,7 +597,7 @@ impl TryFrom<Message> for User {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
     /// Id of the account the user is currently logged in to.
     pub account_id: u64,
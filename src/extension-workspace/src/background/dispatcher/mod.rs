@@ -1,19 +1,29 @@
 use std::{fmt, sync::OnceLock};
 
-use common::{debug_log, err_code, map_err, messaging::prelude::*, sleep, web_extension_sys::browser};
-use futures::{SinkExt, StreamExt, channel::mpsc, stream::SplitStream};
-use gloo_net::websocket::futures::WebSocket;
+use common::{
+    addon_dashboard::AddonDashboard,
+    debug_log, err_code, map_err,
+    messaging::prelude::*,
+    sleep,
+    web_extension_sys::{browser, tabs::TabId},
+};
+use futures::{SinkExt, StreamExt, channel::mpsc};
 use port::{PORT_DISPATCHER_TX, PortDispatcher};
 use serde::Serialize;
+use socket::SocketSupervisor;
 use wasm_bindgen::prelude::*;
 
 use crate::{
+    GAME_TABS,
     connection::Connection,
     console_error,
-    types::{MessageExt, StorageRefreshToken},
+    session::{BrowserSessionStore, SessionStore},
+    tabs::GameTabs,
+    types::{MessageExt, StorageAddonDashboard, StorageLanguage, StorageRefreshToken},
 };
 
 pub mod port;
+mod socket;
 
 // TODO: rename to sth like `RUNTIME_TX` since that's what it is.
 static RUNTIME_TX: OnceLock<mpsc::UnboundedSender<Message>> = OnceLock::new();
@@ -99,6 +109,7 @@ impl RuntimeDispatcher {
                 let user_opt = state.user.borrow();
                 let username = user_opt.as_ref().map(|user| user.nick.clone());
                 let premium = user_opt.as_ref().and_then(|user| user.premium.clone());
+                let session_scope = user_opt.as_ref().map(|user| user.scope);
                 debug_log!("SENDING USER DATA");
                 // TODO: Is this correct ?
                 let state = match username.is_some() {
@@ -108,10 +119,18 @@ impl RuntimeDispatcher {
 
                 drop(user_opt);
 
+                let language = StorageLanguage::load().await.unwrap_or_else(|err_code| {
+                    console_error!(err_code);
+                    None
+                });
+
                 Message::builder(Task::UserData, Target::Popup, MessageKind::Response)
+                    .maybe_language(language)
                     .maybe_username(username)
                     .maybe_premium(premium)
+                    .maybe_session_scope(session_scope)
                     .popup(state)
+                    .connection(SocketSupervisor::state())
                     .build()
                     .execute()
                     .await?
@@ -125,6 +144,7 @@ impl RuntimeDispatcher {
                     .remove(&JsValue::from_str(StorageRefreshToken::KEY))
                     .await
                     .map_err(map_err!())?;
+                BrowserSessionStore.clear().await?;
 
                 Message::builder(Task::LogOut, Target::Backend, MessageKind::Request)
                     .log_out(msg.log_out.ok_or_else(|| err_code!())?)
@@ -132,6 +152,94 @@ impl RuntimeDispatcher {
                     .execute()
                     .await?;
             }
+            // Responds with the dashboard of the character played in the active game tab,
+            // or the last one stored. Toggles are forwarded to the active game tab or
+            // queued until a tab of the character connects.
+            Task::AddonDashboard => {
+                let (active_tab, active_character) = GAME_TABS.with_borrow(|tabs| {
+                    let active_tab = tabs.active();
+                    let active_character = active_tab.and_then(|tab_id| tabs.character(tab_id));
+
+                    (active_tab, active_character)
+                });
+                let game_connected = active_character.is_some();
+                let character = match active_character {
+                    Some(character) => Some(character),
+                    None => StorageAddonDashboard::last_character().await?,
+                };
+                let Some(character) = character else {
+                    return Message::builder(
+                        Task::AddonDashboard,
+                        Target::Popup,
+                        MessageKind::Response,
+                    )
+                    .addon_dashboard(AddonDashboard::default())
+                    .build()
+                    .execute()
+                    .await;
+                };
+                let mut stored = StorageAddonDashboard::load(&character).await?;
+
+                match msg.addon_toggles {
+                    Some(toggles) => {
+                        let toggles: Vec<_> = toggles
+                            .into_iter()
+                            .filter(|toggle| {
+                                stored.dashboard.set_active(&toggle.key, toggle.active)
+                            })
+                            .collect();
+
+                        match game_connected {
+                            true => {
+                                Message::builder(
+                                    Task::AddonDashboard,
+                                    Target::Foreground,
+                                    MessageKind::Request,
+                                )
+                                .addon_toggles(toggles)
+                                .maybe_tab_id(active_tab)
+                                .build()
+                                .execute()
+                                .await?
+                            }
+                            false => stored.queue_toggles(toggles),
+                        }
+                        stored.save(&character).await?;
+                    }
+                    // The game tab reports the current error count in an event.
+                    None if game_connected => {
+                        Message::builder(
+                            Task::AddonDashboard,
+                            Target::Foreground,
+                            MessageKind::Request,
+                        )
+                        .maybe_tab_id(active_tab)
+                        .build()
+                        .execute()
+                        .await?
+                    }
+                    None => {}
+                }
+
+                stored.dashboard.game_connected = game_connected;
+
+                Message::builder(Task::AddonDashboard, Target::Popup, MessageKind::Response)
+                    .addon_dashboard(stored.dashboard)
+                    .build()
+                    .execute()
+                    .await?
+            }
+            Task::SessionScope => {
+                if state.user.borrow().is_none() {
+                    return Ok(());
+                }
+
+                Message::builder(Task::SessionScope, Target::Backend, MessageKind::Request)
+                    .session_scope(msg.session_scope.ok_or_else(|| err_code!())?)
+                    .build()
+                    .execute()
+                    .await?
+            }
             _ => unreachable!(),
         }
 
@@ -141,7 +249,7 @@ impl RuntimeDispatcher {
 
 pub struct SocketDispatcher {
     tx: mpsc::UnboundedSender<Message>,
-    rx: SplitStream<WebSocket>,
+    rx: mpsc::UnboundedReceiver<Message>,
 }
 
 impl SocketDispatcher {
@@ -149,27 +257,78 @@ impl SocketDispatcher {
         self.tx.send(item).await
     }
 
-    pub async fn recv(&mut self) -> Option<Result<Message, JsValue>> {
-        self.rx.next().await.map(|res| {
-            res.map_err(map_err!(from))
-                .and_then(|msg| Message::try_from(msg).map_err(|_| err_code!()))
-        })
+    pub fn recv(&mut self) -> futures::stream::Next<'_, mpsc::UnboundedReceiver<Message>> {
+        self.rx.next()
     }
 }
 
 impl SocketDispatcher {
-    fn new(tx: mpsc::UnboundedSender<Message>, rx: SplitStream<WebSocket>) -> Self {
+    fn new(tx: mpsc::UnboundedSender<Message>, rx: mpsc::UnboundedReceiver<Message>) -> Self {
         Self { tx, rx }
     }
 
-    async fn run_event_loop(&mut self, _state: &'static Connection) -> Result<(), JsValue> {
-        let msg = self.recv().await.ok_or_else(|| err_code!())??;
+    async fn run_event_loop(&mut self, state: &'static Connection) -> Result<(), JsValue> {
+        let msg = self.recv().await.ok_or_else(|| err_code!())?;
 
-        if msg.target != Target::Background || msg.kind != MessageKind::Request {
+        if msg.target != Target::Background {
             return Err(err_code!());
         }
 
-        Ok(())
+        match (msg.task, msg.kind) {
+            // Response to a scheduled token refresh or the handshake made after reconnecting.
+            (Task::Tokens, MessageKind::Response) => state.update_tokens(msg).await,
+            // Responses are shared by every game tab since they're all connected through
+            // the same socket, tabs of another clan ignore the roster.
+            (Task::Catalog, MessageKind::Response) => {
+                if !GameTabs::any_connected() {
+                    return Ok(());
+                }
+
+                Message::builder(Task::Catalog, Target::Foreground, MessageKind::Response)
+                    .maybe_catalog(msg.catalog)
+                    .build()
+                    .execute()
+                    .await
+            }
+            (Task::ClanRoster, MessageKind::Response) => {
+                if !GameTabs::any_connected() {
+                    return Ok(());
+                }
+
+                Message::builder(Task::ClanRoster, Target::Foreground, MessageKind::Response)
+                    .clan_roster(msg.clan_roster.ok_or_else(|| err_code!())?)
+                    .build()
+                    .execute()
+                    .await
+            }
+            // Reports over the limit, every tab checks whether it sent the report.
+            (Task::Diagnostics, MessageKind::Response) => {
+                if !GameTabs::any_connected() {
+                    return Ok(());
+                }
+
+                Message::builder(Task::Diagnostics, Target::Foreground, MessageKind::Response)
+                    .diagnostics(msg.diagnostics.ok_or_else(|| err_code!())?)
+                    .build()
+                    .execute()
+                    .await
+            }
+            (Task::SessionScope, MessageKind::Response) => {
+                let session_scope = msg.session_scope.ok_or_else(|| err_code!())?;
+
+                if let Some(user) = state.user.borrow_mut().as_mut() {
+                    user.scope = session_scope;
+                }
+
+                Message::builder(Task::SessionScope, Target::Popup, MessageKind::Response)
+                    .session_scope(session_scope)
+                    .build()
+                    .execute()
+                    .await
+            }
+            (_, MessageKind::Request) => Ok(()),
+            _ => Err(err_code!()),
+        }
     }
 }
 
@@ -190,15 +349,15 @@ impl Dispatcher {
         PORT_DISPATCHER_TX.wait().send(item).await
     }
 
-    /// Opens the popup window.
-    pub async fn open_popup() -> Result<(), JsValue> {
-        let window_id = crate::FOREGROUND_PORT
-            .with_borrow(|port| {
-                port.as_ref()
-                    .ok_or_else(|| err_code!())?
-                    .sender()
-                    .ok_or_else(|| err_code!())
-            })?
+    /// Opens the popup window in the window of `tab_id`, or of the active game
+    /// tab if it's `None`.
+    pub async fn open_popup(tab_id: Option<TabId>) -> Result<(), JsValue> {
+        let port = GAME_TABS
+            .with_borrow(|tabs| tabs.port(tab_id.or_else(|| tabs.active())?))
+            .ok_or_else(|| err_code!())?;
+        let window_id = port
+            .sender()
+            .ok_or_else(|| err_code!())?
             .tab()
             .ok_or_else(|| err_code!())?
             .window_id();
@@ -248,30 +407,14 @@ impl Dispatcher {
         }
     }
 
-    pub(super) fn init() -> Result<Self, JsValue> {
-        let (mut socket_tx, socket_rx) = WebSocket::open(Self::SOCKET_URL)
-            .map_err(map_err!(from))?
-            .split();
-
-        // State machine for sending messages via socket tx.
-        let (new_socket_tx, mut rx) = mpsc::unbounded::<Message>();
+    /// `resumed` should be set if the session was restored after a service
+    /// worker restart, the socket is then authorized on the first connection.
+    pub(super) fn init(resumed: bool) -> Result<Self, JsValue> {
+        let (socket_tx, outbound_rx) = mpsc::unbounded::<Message>();
+        let (inbound_tx, socket_rx) = mpsc::unbounded::<Message>();
 
-        SOCKET_TX
-            .set(new_socket_tx.clone())
-            .map_err(|_| err_code!())?;
-
-        wasm_bindgen_futures::spawn_local(async move {
-            while let Some(msg) = rx.next().await {
-                let msg = match msg.into_ws_message().map_err(map_err!(from)) {
-                    Ok(msg) => msg,
-                    Err(err_code) => return console_error!(err_code),
-                };
-
-                if let Err(err_code) = socket_tx.send(msg).await.map_err(map_err!(from)) {
-                    console_error!(err_code)
-                }
-            }
-        });
+        SOCKET_TX.set(socket_tx.clone()).map_err(|_| err_code!())?;
+        SocketSupervisor::new(Self::SOCKET_URL, outbound_rx, inbound_tx, resumed).spawn();
 
         let (tx, rx) = mpsc::unbounded();
 
@@ -283,7 +426,7 @@ impl Dispatcher {
         PORT_DISPATCHER_TX.set(tx).map_err(|_| err_code!())?;
 
         let port = PortDispatcher::new(rx);
-        let socket = SocketDispatcher::new(new_socket_tx, socket_rx);
+        let socket = SocketDispatcher::new(socket_tx, socket_rx);
         Ok(Self::_new(runtime, port, socket))
     }
 
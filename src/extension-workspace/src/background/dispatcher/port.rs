@@ -1,5 +1,7 @@
 use common::{
-    err_code, map_err,
+    err_code,
+    game_tabs::TabSignal,
+    map_err,
     messaging::prelude::*,
     web_extension_sys::{browser, cookies},
 };
@@ -8,7 +10,12 @@ use wasm_bindgen::prelude::*;
 
 use futures::{StreamExt, channel::mpsc};
 
-use crate::{connection::Connection, types::MessageExt};
+use crate::{
+    GAME_TABS,
+    connection::Connection,
+    console_error,
+    types::{MessageExt, StorageAddonDashboard, StorageConsoleLogs, StorageLanguage},
+};
 
 pub(super) static PORT_DISPATCHER_TX: OnceLock<mpsc::UnboundedSender<Message>> = OnceLock::new();
 
@@ -48,8 +55,17 @@ impl PortDispatcher {
         }
 
         match msg.task {
+            // The stored language is applied before the manager renders anything.
             Task::Handshake => {
-                Message::new(Task::Handshake, Target::Foreground, MessageKind::Response)
+                let language = StorageLanguage::load().await.unwrap_or_else(|err_code| {
+                    console_error!(err_code);
+                    None
+                });
+
+                Message::builder(Task::Handshake, Target::Foreground, MessageKind::Response)
+                    .maybe_language(language)
+                    .maybe_tab_id(msg.tab_id)
+                    .build()
                     .execute()
                     .await
             }
@@ -70,6 +86,7 @@ impl PortDispatcher {
 
                 Message::builder(Task::Cookie, Target::Foreground, MessageKind::Response)
                     .cookie(cookie)
+                    .maybe_tab_id(msg.tab_id)
                     .build()
                     .execute()
                     .await
@@ -79,6 +96,151 @@ impl PortDispatcher {
                 // Message::builder(Task::InitSession, Target::Backend,
                 // MessageKind::Request) .
             }
+            // Saves the entries of the character if the message contains them, responds
+            // with the stored ones otherwise.
+            Task::ConsoleLogs => {
+                let tab_id = msg.tab_id.ok_or_else(|| err_code!())?;
+                let character = msg.character.ok_or_else(|| err_code!())?;
+
+                GAME_TABS.with_borrow_mut(|tabs| tabs.set_character(tab_id, character.clone()));
+
+                if let Some(logs) = msg.console_logs {
+                    return StorageConsoleLogs::new(logs).save(&character).await;
+                }
+
+                let stored = StorageConsoleLogs::load(&character).await?;
+
+                Message::builder(Task::ConsoleLogs, Target::Foreground, MessageKind::Response)
+                    .console_logs(stored.logs)
+                    .tab_id(tab_id)
+                    .build()
+                    .execute()
+                    .await
+            }
+            Task::Diagnostics => {
+                let report = msg.diagnostics.ok_or_else(|| err_code!())?;
+
+                // Reports are only accepted from authorized sessions, the tab
+                // keeps the report until it can be sent.
+                if state.user.borrow().is_none() {
+                    return Message::builder(
+                        Task::Diagnostics,
+                        Target::Foreground,
+                        MessageKind::Response,
+                    )
+                    .diagnostics(report)
+                    .maybe_tab_id(msg.tab_id)
+                    .build()
+                    .execute()
+                    .await;
+                }
+
+                Message::builder(Task::Diagnostics, Target::Backend, MessageKind::Request)
+                    .diagnostics(report)
+                    .build()
+                    .execute()
+                    .await
+            }
+            Task::Catalog => {
+                if state.user.borrow().is_none() {
+                    return Ok(());
+                }
+
+                Message::new(Task::Catalog, Target::Backend, MessageKind::Request)
+                    .execute()
+                    .await
+            }
+            Task::ClanRoster => {
+                if state.user.borrow().is_none() {
+                    return Ok(());
+                }
+
+                Message::builder(Task::ClanRoster, Target::Backend, MessageKind::Request)
+                    .clan_roster(msg.clan_roster.ok_or_else(|| err_code!())?)
+                    .character(msg.character.ok_or_else(|| err_code!())?)
+                    .build()
+                    .execute()
+                    .await
+            }
+            // Stores the reported addons of the character, responds with the toggles queued
+            // while none of its game tabs was connected and updates the popup if it's open
+            // and displays the character.
+            Task::AddonDashboard => {
+                let tab_id = msg.tab_id.ok_or_else(|| err_code!())?;
+                let character = msg.character.ok_or_else(|| err_code!())?;
+                let mut dashboard = msg.addon_dashboard.ok_or_else(|| err_code!())?;
+                let displayed = GAME_TABS.with_borrow_mut(|tabs| {
+                    tabs.set_character(tab_id, character.clone());
+                    tabs.active() == Some(tab_id)
+                });
+                let mut stored = StorageAddonDashboard::load(&character).await?;
+                let pending_toggles = std::mem::take(&mut stored.pending_toggles);
+
+                for toggle in &pending_toggles {
+                    dashboard.set_active(&toggle.key, toggle.active);
+                }
+                dashboard.game_connected = true;
+                stored.dashboard = dashboard.clone();
+                stored.save(&character).await?;
+
+                if !pending_toggles.is_empty() {
+                    Message::builder(
+                        Task::AddonDashboard,
+                        Target::Foreground,
+                        MessageKind::Response,
+                    )
+                    .addon_toggles(pending_toggles)
+                    .tab_id(tab_id)
+                    .build()
+                    .execute()
+                    .await?;
+                }
+
+                if !displayed {
+                    return Ok(());
+                }
+
+                Message::builder(Task::AddonDashboard, Target::Popup, MessageKind::Event)
+                    .addon_dashboard(dashboard)
+                    .build()
+                    .execute()
+                    .await
+            }
+            Task::GameTab => {
+                let tab_id = msg.tab_id.ok_or_else(|| err_code!())?;
+                let hero = msg.game_tab.ok_or_else(|| err_code!())?;
+
+                GAME_TABS.with_borrow_mut(|tabs| tabs.identify(tab_id, hero));
+
+                Ok(())
+            }
+            // Relays the signal as coming from the character identified by the sending tab.
+            // Invite requests go to a single tab on the same world, tabs which didn't
+            // identify their character yet are skipped.
+            Task::TabBroadcast => {
+                let tab_id = msg.tab_id.ok_or_else(|| err_code!())?;
+                let mut broadcast = msg.tab_broadcast.ok_or_else(|| err_code!())?;
+                let (hero, receivers) = GAME_TABS.with_borrow(|tabs| {
+                    let receivers = match broadcast.signal {
+                        TabSignal::InviteRequest => tabs.inviter(tab_id).into_iter().collect(),
+                        TabSignal::UnderAttack { .. } => tabs.siblings(tab_id),
+                    };
+                    (tabs.hero(tab_id), receivers)
+                });
+                broadcast.from = hero.ok_or_else(|| err_code!())?;
+
+                for sibling in receivers {
+                    Message::builder(Task::TabBroadcast, Target::Foreground, MessageKind::Event)
+                        .tab_broadcast(broadcast.clone())
+                        .tab_id(sibling)
+                        .build()
+                        .execute()
+                        .await?;
+                }
+
+                Ok(())
+            }
+            Task::Language => StorageLanguage::save(msg.language).await,
             _ => unreachable!(),
         }
     }
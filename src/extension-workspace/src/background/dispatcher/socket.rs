@@ -0,0 +1,292 @@
+use std::{cell::Cell, collections::VecDeque};
+
+use common::{debug_log, err_code, map_err, messaging::prelude::*, sleep};
+use futures::{
+    SinkExt, StreamExt,
+    channel::mpsc,
+    stream::{FusedStream, SplitSink},
+};
+use gloo_net::websocket::{Message as WsMessage, State, futures::WebSocket};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    console_error,
+    tabs::GameTabs,
+    types::{MessageExt, StorageRefreshToken},
+};
+
+thread_local! {
+    static CONNECTION_STATE: Cell<ConnectionState> = const { Cell::new(ConnectionState::Connecting) };
+}
+
+/// Owns the backend socket and reopens it with exponential backoff whenever
+/// the connection drops.
+///
+/// Messages sent while offline are kept in a bounded queue and flushed after
+/// the connection is re-established and re-authorized with the stored
+/// refresh token.
+pub(super) struct SocketSupervisor {
+    url: &'static str,
+    /// Messages waiting for the connection to be re-established.
+    queue: VecDeque<Message>,
+    outbound_rx: mpsc::UnboundedReceiver<Message>,
+    inbound_tx: mpsc::UnboundedSender<Message>,
+    /// Failed connection attempts since the last successful one.
+    attempt: u32,
+    /// Whether the next connection has to be authorized with the stored
+    /// refresh token.
+    reauthorize: bool,
+}
+
+impl SocketSupervisor {
+    /// Highest amount of messages kept while offline, older messages are
+    /// dropped first.
+    const QUEUE_CAPACITY: usize = 64;
+    const BASE_DELAY: u32 = 1_000;
+    const MAX_DELAY: u32 = 60_000;
+    const OPEN_POLL_INTERVAL: u32 = 50;
+
+    pub(super) fn new(
+        url: &'static str,
+        outbound_rx: mpsc::UnboundedReceiver<Message>,
+        inbound_tx: mpsc::UnboundedSender<Message>,
+        reauthorize: bool,
+    ) -> Self {
+        Self {
+            url,
+            queue: VecDeque::with_capacity(Self::QUEUE_CAPACITY),
+            outbound_rx,
+            inbound_tx,
+            attempt: 0,
+            reauthorize,
+        }
+    }
+
+    /// Last known state of the backend connection.
+    pub(crate) fn state() -> ConnectionState {
+        CONNECTION_STATE.get()
+    }
+
+    pub(super) fn spawn(self) {
+        wasm_bindgen_futures::spawn_local(self.run());
+    }
+
+    async fn run(mut self) {
+        loop {
+            if let Some(socket) = Self::open(self.url).await {
+                if Self::state() != ConnectionState::Connected {
+                    self.attempt = 0;
+                    Self::set_state(ConnectionState::Connected).await;
+                }
+
+                if let Err(err_code) = self.serve(socket).await {
+                    console_error!(err_code);
+                }
+
+                self.reauthorize = true;
+                debug_log!("Backend connection closed.");
+            }
+
+            if self.outbound_rx.is_terminated() {
+                return;
+            }
+
+            self.backoff().await;
+        }
+    }
+
+    /// Opens the socket and waits until the connection is established.
+    async fn open(url: &str) -> Option<WebSocket> {
+        let socket = WebSocket::open(url).ok()?;
+
+        while socket.state() == State::Connecting {
+            sleep(Self::OPEN_POLL_INTERVAL).await;
+        }
+
+        (socket.state() == State::Open).then_some(socket)
+    }
+
+    /// Serves the connection until it drops.
+    async fn serve(&mut self, socket: WebSocket) -> Result<(), JsValue> {
+        let (mut sink, stream) = socket.split();
+        let mut stream = stream.fuse();
+
+        if self.reauthorize {
+            match StorageRefreshToken::load().await? {
+                Some(token) => {
+                    let msg = Message::builder(Task::Tokens, Target::Backend, MessageKind::Request)
+                        .refresh_token(token.into_inner())
+                        .build();
+
+                    Self::send(&mut sink, &msg).await?;
+                }
+                // The backend closes connections which don't start with either of the two.
+                None => {
+                    let msg = Message::new(Task::Handshake, Target::Backend, MessageKind::Request);
+
+                    Self::send(&mut sink, &msg).await?;
+                    // Unauthorized connections only accept logging in.
+                    self.queue.retain(|queued| queued.task == Task::Tokens);
+                }
+            }
+        }
+
+        while let Some(msg) = self.queue.pop_front() {
+            if let Err(err_code) = Self::send(&mut sink, &msg).await {
+                self.queue.push_front(msg);
+                return Err(err_code);
+            }
+        }
+
+        loop {
+            futures::select! {
+                outbound = self.outbound_rx.next() => {
+                    let msg = outbound.ok_or_else(|| err_code!())?;
+
+                    if let Err(err_code) = Self::send(&mut sink, &msg).await {
+                        self.enqueue(msg);
+                        return Err(err_code);
+                    }
+                }
+                inbound = stream.next() => match inbound {
+                    Some(Ok(ws_msg)) => self.forward(ws_msg)?,
+                    // Errors are always followed by closing the connection.
+                    Some(Err(_err)) => debug_log!(@f "Backend connection error: {_err:?}"),
+                    None => return Ok(()),
+                },
+            }
+        }
+    }
+
+    fn forward(&self, ws_msg: WsMessage) -> Result<(), JsValue> {
+        // A single malformed frame shouldn't tear the connection down.
+        let msg = match Message::try_from(ws_msg) {
+            Ok(msg) => msg,
+            Err(_ws_msg) => {
+                console_error!();
+                debug_log!(@f "Malformed backend message: {_ws_msg:?}");
+                return Ok(());
+            }
+        };
+
+        // Acknowledges the handshake sent by `serve`, nothing else awaits it.
+        if msg.task == Task::Handshake && msg.kind == MessageKind::Response {
+            return Ok(());
+        }
+
+        self.inbound_tx.unbounded_send(msg).map_err(map_err!(from))
+    }
+
+    async fn send(
+        sink: &mut SplitSink<WebSocket, WsMessage>,
+        msg: &Message,
+    ) -> Result<(), JsValue> {
+        let ws_msg = msg.into_ws_message().map_err(map_err!(from))?;
+
+        sink.send(ws_msg).await.map_err(map_err!(from))
+    }
+
+    /// Keeps the message until the connection is re-established. Settings
+    /// diffs are merged into a single message and log outs are never dropped.
+    fn enqueue(&mut self, msg: Message) {
+        // Pointless once the connection is re-established.
+        if msg.task == Task::KeepAlive {
+            return;
+        }
+
+        if msg.task == Task::UserData
+            && let Some(queued) = self
+                .queue
+                .iter_mut()
+                .find(|queued| queued.task == Task::UserData)
+            && let Some(old_settings) = queued.settings.as_mut()
+        {
+            if let Some(new_settings) = msg.settings {
+                Message::merge_json_objects(old_settings, new_settings);
+            }
+            return;
+        }
+
+        if self.queue.len() >= Self::QUEUE_CAPACITY
+            && let Some(index) = self
+                .queue
+                .iter()
+                .position(|queued| queued.task != Task::LogOut)
+        {
+            let _dropped = self.queue.remove(index);
+            debug_log!(@f "Outbound queue full, dropped: {_dropped:?}");
+        }
+
+        self.queue.push_back(msg);
+    }
+
+    /// Waits before the next connection attempt, queueing messages sent in
+    /// the meantime.
+    async fn backoff(&mut self) {
+        self.attempt += 1;
+
+        let retry_in = Self::delay(self.attempt);
+
+        Self::set_state(ConnectionState::Reconnecting {
+            attempt: self.attempt,
+            retry_in,
+        })
+        .await;
+
+        let mut timeout = sleep(retry_in);
+
+        loop {
+            futures::select! {
+                _ = timeout => return,
+                outbound = self.outbound_rx.next() => match outbound {
+                    Some(msg) => self.enqueue(msg),
+                    None => return,
+                },
+            }
+        }
+    }
+
+    /// Exponential backoff with jitter, the delay is randomized within the
+    /// upper half so that clients don't reconnect all at once after a backend
+    /// restart.
+    fn delay(attempt: u32) -> u32 {
+        let delay = Self::BASE_DELAY
+            .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+            .min(Self::MAX_DELAY);
+        let jitter = (js_sys::Math::random() * f64::from(delay / 2)) as u32;
+
+        delay / 2 + jitter
+    }
+
+    /// Notifies the popup and the foreground about the state change. Game tabs
+    /// aren't notified about the first connection being established.
+    async fn set_state(state: ConnectionState) {
+        let previous = CONNECTION_STATE.replace(state);
+
+        let popup_update = Message::builder(Task::ConnectionState, Target::Popup, MessageKind::Event)
+            .connection(state)
+            .build()
+            .execute()
+            .await;
+
+        if let Err(err_code) = popup_update {
+            console_error!(err_code);
+        }
+
+        if previous == ConnectionState::Connecting || !GameTabs::any_connected() {
+            return;
+        }
+
+        let foreground_update =
+            Message::builder(Task::ConnectionState, Target::Foreground, MessageKind::Event)
+                .connection(state)
+                .build()
+                .execute()
+                .await;
+
+        if let Err(err_code) = foreground_update {
+            console_error!(err_code);
+        }
+    }
+}
+
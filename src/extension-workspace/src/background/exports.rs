@@ -1,9 +1,20 @@
 use common::{
-    closure, debug_log, map_err, messaging::prelude::*, web_extension_sys::runtime::port::Port,
+    closure, debug_log, map_err,
+    messaging::prelude::*,
+    web_extension_sys::{
+        browser,
+        runtime::port::Port,
+        tabs::{TabActiveInfo, TabId},
+    },
 };
 use wasm_bindgen::prelude::*;
 
-use crate::{FOREGROUND_PORT, console_error, dispatcher::Dispatcher};
+use crate::{
+    GAME_TABS, console_error,
+    dispatcher::Dispatcher,
+    tabs::GameTabs,
+    types::{MessageExt, StorageAddonDashboard},
+};
 
 #[wasm_bindgen(js_name = "handlePortConnect")]
 pub async fn handle_port_connect(port: Port) {
@@ -14,19 +25,36 @@ pub async fn handle_port_connect(port: Port) {
         return;
     }
 
-    port.on_message()
-        .add_listener(&closure!(|message: JsValue| async move {
-            if let Err(err_code) = port_on_message(message).await {
+    let Some(tab_id) = port
+        .sender()
+        .and_then(|sender| sender.tab())
+        .and_then(|tab| tab.id())
+    else {
+        debug_log!("Port not connected from a tab:", port);
+        return;
+    };
+
+    port.on_message().add_listener(&closure!(
+        { let tab_id = tab_id },
+        move |message: JsValue| async move {
+            if let Err(err_code) = port_on_message(message, tab_id).await {
+                console_error!(err_code);
+            }
+        },
+    ));
+    port.on_disconnect()
+        .add_listener(&closure!(|port: Port| async move {
+            if let Err(err_code) = port_on_disconnect(port).await {
                 console_error!(err_code);
             }
         }));
-    // port.on_disconnect().add_listener(&on_disconnect_factory());
 
-    FOREGROUND_PORT.set(Some(port));
+    GAME_TABS.with_borrow_mut(|tabs| tabs.connect(tab_id, port));
 }
 
-async fn port_on_message(message: JsValue) -> Result<(), JsValue> {
-    let msg: Message = serde_wasm_bindgen::from_value(message).map_err(map_err!(from))?;
+async fn port_on_message(message: JsValue, tab_id: TabId) -> Result<(), JsValue> {
+    let mut msg: Message = serde_wasm_bindgen::from_value(message).map_err(map_err!(from))?;
+    msg.tab_id = Some(tab_id);
     debug_log!(@f "handlePortMessage: {msg:#?}");
 
     Dispatcher::dispatch_from_port(msg)
@@ -34,6 +62,57 @@ async fn port_on_message(message: JsValue) -> Result<(), JsValue> {
         .map_err(map_err!(from))
 }
 
+/// Marks the game as disconnected on the popup dashboard once the last game tab
+/// disconnects.
+async fn port_on_disconnect(port: Port) -> Result<(), JsValue> {
+    if let Some(err) = browser().runtime().last_error() {
+        debug_log!("Runtime error when disconnecting port:", err);
+    }
+
+    // The game tab might have reconnected with a new port in the meantime.
+    if !GAME_TABS.with_borrow_mut(|tabs| tabs.disconnect(&port)) {
+        return Ok(());
+    }
+
+    on_game_tab_closed().await
+}
+
+#[wasm_bindgen(js_name = "handleTabRemoved")]
+pub async fn handle_tab_removed(tab_id: TabId) {
+    // The port usually disconnects first, this covers tabs closed before it
+    // did.
+    if !GAME_TABS.with_borrow_mut(|tabs| tabs.remove(tab_id)) {
+        return;
+    }
+
+    if let Err(err_code) = on_game_tab_closed().await {
+        console_error!(err_code);
+    }
+}
+
+#[wasm_bindgen(js_name = "handleTabActivated")]
+pub fn handle_tab_activated(active_info: TabActiveInfo) {
+    GAME_TABS.with_borrow_mut(|tabs| tabs.activate(active_info.tab_id()));
+}
+
+async fn on_game_tab_closed() -> Result<(), JsValue> {
+    if GameTabs::any_connected() {
+        return Ok(());
+    }
+
+    let Some(character) = StorageAddonDashboard::last_character().await? else {
+        return Ok(());
+    };
+    let mut stored = StorageAddonDashboard::load(&character).await?;
+    stored.dashboard.game_connected = false;
+
+    Message::builder(Task::AddonDashboard, Target::Popup, MessageKind::Event)
+        .addon_dashboard(stored.dashboard)
+        .build()
+        .execute()
+        .await
+}
+
 // #[derive(Serialize)]
 // #[serde(rename_all = "camelCase")]
 // struct Debuggee {
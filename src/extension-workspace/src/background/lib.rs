@@ -4,7 +4,6 @@ use std::collections::HashMap;
 
 pub use common;
 use common::messaging::prelude::*;
-use common::web_extension_sys::runtime::port::Port;
 use futures::channel::oneshot;
 pub use obfstr::obfstr as s;
 use wasm_bindgen::prelude::*;
@@ -12,12 +11,13 @@ use wasm_bindgen::prelude::*;
 pub mod connection;
 mod dispatcher;
 mod exports;
+mod session;
+mod tabs;
 pub mod types;
 
 thread_local! {
     static PENDING_REQUESTS: RefCell<HashMap<Task, oneshot::Sender<Message>>> = RefCell::new(HashMap::new());
-    #[doc = "Needed for `console_err!` macro to display a message to the user."]
-    static FOREGROUND_PORT: RefCell<Option<Port>> = const { RefCell::new(None) };
+    static GAME_TABS: RefCell<tabs::GameTabs> = RefCell::new(tabs::GameTabs::default());
     static TASK_QUEUE: RefCell<types::TaskQueue> = RefCell::new(types::TaskQueue::new());
 }
 
@@ -26,15 +26,25 @@ async fn main() -> Result<(), JsValue> {
     #[cfg(debug_assertions)]
     console_error_panic_hook::set_once();
 
-    let mut dispatcher = dispatcher::Dispatcher::init()?;
+    // An unreadable `storage.session` only costs going through the login flow again.
+    let restored = connection::Connection::restore()
+        .await
+        .unwrap_or_else(|err_code| {
+            crate::console_error!(err_code);
+            None
+        });
+    let mut dispatcher = dispatcher::Dispatcher::init(restored.is_some())?;
 
     types::TaskQueue::init();
 
-    let connection: &'static _ = Box::leak(Box::new(
-        connection::Connection::establish_authorized(&mut dispatcher).await?,
-    ));
+    let connection = match restored {
+        Some(connection) => connection,
+        None => connection::Connection::establish_authorized(&mut dispatcher).await?,
+    };
+    let connection: &'static _ = Box::leak(Box::new(connection));
     common::debug_log!("AUTHORIZED CONNECTION ESTABLISHED!");
 
+    connection.schedule_token_refresh();
     dispatcher.spawn_event_loop(connection);
 
     Ok(())
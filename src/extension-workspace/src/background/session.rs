@@ -0,0 +1,260 @@
+//! Session state kept between service worker restarts.
+//!
+//! Browsers kill idle service workers at will, `storage.session` survives that
+//! for as long as the browser is running, so the background can resume without
+//! going through the login flow again.
+
+use common::{map_err, web_extension_sys::browser};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{connection::User, types::Jwt};
+
+/// Access tokens get refreshed this many seconds before they expire.
+const REFRESH_MARGIN: u64 = 60;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct StorageSession {
+    #[serde(rename = "session", default)]
+    pub user: Option<User>,
+}
+
+impl StorageSession {
+    pub const KEY: &str = "session";
+}
+
+#[derive(Serialize)]
+struct StorageSessionRef<'a> {
+    #[serde(rename = "session")]
+    user: &'a User,
+}
+
+/// Returns the stored user if the session can still be resumed at `now` (in
+/// seconds).
+pub fn resume(stored: Option<User>, now: u64) -> Option<User> {
+    stored.filter(|user| user.refresh_token().is_valid_at(now))
+}
+
+/// Milliseconds left until the access token should be refreshed, `None` if
+/// the token can't be decoded.
+pub fn refresh_delay(access_token: &Jwt, now: u64) -> Option<u32> {
+    let refresh_at = access_token.expires_at()?.saturating_sub(REFRESH_MARGIN);
+    let delay = refresh_at.saturating_sub(now).saturating_mul(1000);
+
+    // Timeouts longer than `i32::MAX` fire immediately.
+    Some(delay.min(i32::MAX as u64) as u32)
+}
+
+/// Storage area the session is kept in.
+pub(crate) trait SessionStore {
+    async fn load(&self) -> Result<Option<User>, JsValue>;
+    async fn save(&self, user: &User) -> Result<(), JsValue>;
+    async fn clear(&self) -> Result<(), JsValue>;
+}
+
+/// The `storage.session` area of the extension.
+pub(crate) struct BrowserSessionStore;
+
+impl SessionStore for BrowserSessionStore {
+    async fn load(&self) -> Result<Option<User>, JsValue> {
+        let storage_obj = browser()
+            .storage()
+            .session()
+            .get(&JsValue::from_str(StorageSession::KEY))
+            .await
+            .map_err(map_err!())?
+            .into();
+
+        serde_wasm_bindgen::from_value::<StorageSession>(storage_obj)
+            .map(|stored| stored.user)
+            .map_err(map_err!(from))
+    }
+
+    async fn save(&self, user: &User) -> Result<(), JsValue> {
+        browser()
+            .storage()
+            .session()
+            .set(
+                serde_wasm_bindgen::to_value(&StorageSessionRef { user })
+                    .map_err(map_err!(from))?
+                    .unchecked_ref(),
+            )
+            .await
+            .map_err(map_err!())
+    }
+
+    async fn clear(&self) -> Result<(), JsValue> {
+        browser()
+            .storage()
+            .session()
+            .remove(&JsValue::from_str(StorageSession::KEY))
+            .await
+            .map_err(map_err!())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+    use common::connection::SessionScope;
+    use futures::executor::block_on;
+
+    use super::*;
+
+    /// Arbitrary point in time the tests start at, in seconds.
+    const NOW: u64 = 1_750_000_000;
+    const ACCESS_TOKEN_DURATION: u64 = 60 * 15;
+    const REFRESH_TOKEN_DURATION: u64 = 60 * 60 * 24 * 30;
+
+    /// Keeps the session serialized like the browser does, dropping every
+    /// other piece of state simulates a service worker restart.
+    #[derive(Default)]
+    struct MemorySessionStore(RefCell<Option<String>>);
+
+    impl SessionStore for MemorySessionStore {
+        async fn load(&self) -> Result<Option<User>, JsValue> {
+            let stored = self.0.borrow();
+            let Some(json) = stored.as_deref() else {
+                return Ok(None);
+            };
+
+            Ok(serde_json::from_str::<StorageSession>(json).unwrap().user)
+        }
+
+        async fn save(&self, user: &User) -> Result<(), JsValue> {
+            let json = serde_json::to_string(&StorageSessionRef { user }).unwrap();
+
+            self.0.replace(Some(json));
+            Ok(())
+        }
+
+        async fn clear(&self) -> Result<(), JsValue> {
+            self.0.take();
+            Ok(())
+        }
+    }
+
+    /// Signature of a token issued by the backend, it's never verified by the background.
+    const SIGNATURE: &str = "SflKxwRJSMeKKF2QT4fwpMeJf36POk6yJV_adQssw5c";
+
+    /// Token shaped like the ones issued by the backend.
+    fn token_with_claims(claims: &str) -> Jwt {
+        let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#);
+        let claims = URL_SAFE_NO_PAD.encode(claims);
+
+        Jwt::new(format!("{header}.{claims}.{SIGNATURE}"))
+    }
+
+    fn token(exp: u64) -> Jwt {
+        token_with_claims(&format!(
+            r#"{{"sub":"67e55044-10b1-426f-9247-bb680e5fe0c8","exp":{exp},"access":1}}"#
+        ))
+    }
+
+    fn user(issued_at: u64) -> User {
+        User::new(
+            SessionScope::GameAccount,
+            token(issued_at + ACCESS_TOKEN_DURATION),
+            token(issued_at + REFRESH_TOKEN_DURATION),
+            "nick".to_owned(),
+            None,
+        )
+    }
+
+    #[test]
+    fn refresh_is_scheduled_before_expiry() {
+        let user = user(NOW);
+
+        assert_eq!(
+            refresh_delay(user.access_token(), NOW),
+            Some(((ACCESS_TOKEN_DURATION - REFRESH_MARGIN) * 1000) as u32)
+        );
+    }
+
+    #[test]
+    fn expired_access_token_is_refreshed_immediately() {
+        let user = user(NOW);
+
+        assert_eq!(
+            refresh_delay(user.access_token(), NOW + ACCESS_TOKEN_DURATION - 30),
+            Some(0)
+        );
+        assert_eq!(
+            refresh_delay(user.access_token(), NOW + ACCESS_TOKEN_DURATION * 2),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn url_safe_claims_are_decoded() {
+        // `?>` encodes to characters which only exist in the base64url alphabet.
+        let claims = format!(r#"{{"sub":"?>?>","exp":{NOW}}}"#);
+
+        assert!(URL_SAFE_NO_PAD.encode(&claims).contains(['-', '_']));
+        assert_eq!(token_with_claims(&claims).expires_at(), Some(NOW));
+    }
+
+    #[test]
+    fn malformed_access_token_is_not_scheduled() {
+        assert_eq!(refresh_delay(&Jwt::new("not a token".to_owned()), NOW), None);
+    }
+
+    #[test]
+    fn restart_resumes_stored_session() {
+        let store = MemorySessionStore::default();
+
+        block_on(store.save(&user(NOW))).unwrap();
+
+        // The worker got restarted after the access token expired.
+        let restarted_at = NOW + ACCESS_TOKEN_DURATION + 1;
+        let resumed = resume(block_on(store.load()).unwrap(), restarted_at).unwrap();
+
+        assert_eq!(resumed.nick, "nick");
+        assert_eq!(resumed.refresh_token(), &token(NOW + REFRESH_TOKEN_DURATION));
+        assert_eq!(refresh_delay(resumed.access_token(), restarted_at), Some(0));
+    }
+
+    #[test]
+    fn restart_after_refresh_token_expiry_requires_login() {
+        let store = MemorySessionStore::default();
+
+        block_on(store.save(&user(NOW))).unwrap();
+
+        let restarted_at = NOW + REFRESH_TOKEN_DURATION;
+
+        assert!(resume(block_on(store.load()).unwrap(), restarted_at).is_none());
+    }
+
+    #[test]
+    fn restart_after_log_out_requires_login() {
+        let store = MemorySessionStore::default();
+
+        block_on(store.save(&user(NOW))).unwrap();
+        block_on(store.clear()).unwrap();
+
+        assert!(resume(block_on(store.load()).unwrap(), NOW).is_none());
+    }
+
+    #[test]
+    fn refreshed_tokens_survive_restart() {
+        let store = MemorySessionStore::default();
+        let mut user = user(NOW);
+        let refreshed_at = NOW + ACCESS_TOKEN_DURATION - REFRESH_MARGIN;
+
+        block_on(store.save(&user)).unwrap();
+        user.set_tokens(
+            token(refreshed_at + ACCESS_TOKEN_DURATION),
+            token(refreshed_at + REFRESH_TOKEN_DURATION),
+        );
+        block_on(store.save(&user)).unwrap();
+
+        let resumed = resume(block_on(store.load()).unwrap(), refreshed_at + 1).unwrap();
+
+        assert_eq!(
+            refresh_delay(resumed.access_token(), refreshed_at + 1),
+            Some(((ACCESS_TOKEN_DURATION - REFRESH_MARGIN - 1) * 1000) as u32)
+        );
+    }
+}
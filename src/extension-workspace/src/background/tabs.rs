@@ -0,0 +1,147 @@
+//! Game tabs connected to the background, e.g. a main character and its alts
+//! played at once.
+
+use std::collections::HashMap;
+
+use common::{
+    game_tabs::{CharacterId, TabHero},
+    web_extension_sys::{runtime::port::Port, tabs::TabId},
+};
+
+use crate::GAME_TABS;
+
+#[derive(Debug)]
+struct GameTab {
+    port: Port,
+    /// `None` until the tab identifies its character.
+    hero: Option<TabHero>,
+    /// Known before the tab identifies its character if it already stored
+    /// its console logs or addon dashboard.
+    character: Option<CharacterId>,
+}
+
+#[derive(Debug, Default)]
+pub struct GameTabs {
+    tabs: HashMap<TabId, GameTab>,
+    /// Tab the user interacted with last, the popup is opened in its window
+    /// and the dashboard displays its addons.
+    active: Option<TabId>,
+}
+
+impl GameTabs {
+    /// Returns whether any game tab is connected.
+    pub fn any_connected() -> bool {
+        GAME_TABS.with_borrow(|tabs| !tabs.tabs.is_empty())
+    }
+
+    /// Keeps the identified character if the tab reconnected with a new
+    /// port.
+    pub fn connect(&mut self, tab_id: TabId, port: Port) {
+        let (hero, character) = self
+            .tabs
+            .remove(&tab_id)
+            .map(|tab| (tab.hero, tab.character))
+            .unwrap_or_default();
+
+        self.tabs.insert(
+            tab_id,
+            GameTab {
+                port,
+                hero,
+                character,
+            },
+        );
+        self.active = Some(tab_id);
+    }
+
+    /// Removes the tab only if it's still connected through `port`, returns
+    /// whether it was removed.
+    pub fn disconnect(&mut self, port: &Port) -> bool {
+        let Some(tab_id) = self
+            .tabs
+            .iter()
+            .find_map(|(tab_id, tab)| (tab.port == *port).then_some(*tab_id))
+        else {
+            return false;
+        };
+
+        self.remove(tab_id)
+    }
+
+    pub fn remove(&mut self, tab_id: TabId) -> bool {
+        if self.active == Some(tab_id) {
+            self.active = None;
+        }
+
+        self.tabs.remove(&tab_id).is_some()
+    }
+
+    pub fn activate(&mut self, tab_id: TabId) {
+        if self.tabs.contains_key(&tab_id) {
+            self.active = Some(tab_id);
+        }
+    }
+
+    pub fn identify(&mut self, tab_id: TabId, hero: TabHero) {
+        if let Some(tab) = self.tabs.get_mut(&tab_id) {
+            tab.character = Some(hero.character());
+            tab.hero = Some(hero);
+        }
+    }
+
+    pub fn set_character(&mut self, tab_id: TabId, character: CharacterId) {
+        if let Some(tab) = self.tabs.get_mut(&tab_id) {
+            tab.character = Some(character);
+        }
+    }
+
+    pub fn hero(&self, tab_id: TabId) -> Option<TabHero> {
+        self.tabs.get(&tab_id)?.hero.clone()
+    }
+
+    pub fn character(&self, tab_id: TabId) -> Option<CharacterId> {
+        self.tabs.get(&tab_id)?.character.clone()
+    }
+
+    /// Last active tab, or any connected one if it was closed.
+    pub fn active(&self) -> Option<TabId> {
+        self.active
+            .filter(|tab_id| self.tabs.contains_key(tab_id))
+            .or_else(|| self.tabs.keys().next().copied())
+    }
+
+    pub fn port(&self, tab_id: TabId) -> Option<Port> {
+        self.tabs.get(&tab_id).map(|tab| tab.port.clone())
+    }
+
+    /// Ports of every connected tab if `tab_id` is `None`.
+    pub fn ports(&self, tab_id: Option<TabId>) -> Vec<Port> {
+        match tab_id {
+            Some(tab_id) => self.port(tab_id).into_iter().collect(),
+            None => self.tabs.values().map(|tab| tab.port.clone()).collect(),
+        }
+    }
+
+    /// Identified tabs other than `tab_id`.
+    pub fn siblings(&self, tab_id: TabId) -> Vec<TabId> {
+        self.tabs
+            .iter()
+            .filter(|(id, tab)| **id != tab_id && tab.hero.is_some())
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Sibling answering the invite requests of `tab_id`, the party leader
+    /// if it's played on the same world, otherwise the same tab every time.
+    pub fn inviter(&self, tab_id: TabId) -> Option<TabId> {
+        let hero = self.tabs.get(&tab_id)?.hero.as_ref()?;
+
+        self.tabs
+            .iter()
+            .filter(|(id, _)| **id != tab_id)
+            .filter_map(|(id, tab)| Some((*id, tab.hero.as_ref()?)))
+            .filter(|(_, sibling)| sibling.is_same_world(hero))
+            .min_by_key(|(id, sibling)| (!sibling.leads_party, *id))
+            .map(|(id, _)| id)
+    }
+}
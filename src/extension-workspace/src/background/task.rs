@@ -215,7 +215,7 @@ impl Task {
     }
 
     fn validate_token_exp(token: &str) -> Result<bool, JsValue> {
-        use base64::{Engine as _, engine::general_purpose::STANDARD_NO_PAD};
+        use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
 
         let encoded_token_claims = token.split('.').nth(1).ok_or_else(|| err_code!())?;
 
@@ -225,7 +225,7 @@ impl Task {
         );
 
         let mut claims = [0u8; TOKEN_CLAIMS_BUFFER_SIZE];
-        let decoded_len = STANDARD_NO_PAD
+        let decoded_len = URL_SAFE_NO_PAD
             .decode_slice_unchecked(encoded_token_claims, &mut claims)
             .map_err(map_err!(from))?;
         let claims = unsafe { std::str::from_utf8_unchecked(&claims[..decoded_len]) };
@@ -1,15 +1,22 @@
 use std::collections::VecDeque;
 
 use common::{
-    UnwrapJsExt, debug_log, err_code, map_err, messaging::prelude::*, sleep,
+    UnwrapJsExt,
+    addon_dashboard::{AddonDashboard, AddonToggle},
+    debug_log, err_code,
+    game_tabs::CharacterId,
+    i18n::Locale,
+    map_err,
+    messaging::prelude::*,
+    sleep,
     web_extension_sys::browser,
 };
 use futures::{SinkExt, channel::mpsc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_with::skip_serializing_none;
 use wasm_bindgen::{intern, prelude::*};
 
-use crate::{FOREGROUND_PORT, TASK_QUEUE, console_error, dispatcher::SOCKET_TX};
+use crate::{GAME_TABS, TASK_QUEUE, console_error, dispatcher::SOCKET_TX};
 
 // TODO: Better name.
 #[skip_serializing_none]
@@ -25,6 +32,191 @@ impl StorageRefreshToken {
     pub fn new(token: Jwt) -> Self {
         Self { token: Some(token) }
     }
+
+    /// Returns the stored refresh token if it's still valid.
+    pub async fn load() -> Result<Option<Jwt>, JsValue> {
+        let storage_obj = browser()
+            .storage()
+            .sync()
+            .get(&JsValue::from_str(Self::KEY))
+            .await
+            .map_err(map_err!())?
+            .into();
+        let token = serde_wasm_bindgen::from_value::<Self>(storage_obj)
+            .map_err(map_err!(from))?
+            .token;
+
+        Ok(token.filter(Jwt::validate))
+    }
+}
+
+/// Language chosen in the manager settings, shared by the game tabs and the
+/// popup.
+#[skip_serializing_none]
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StorageLanguage {
+    pub language: Option<Locale>,
+}
+
+impl StorageLanguage {
+    pub const KEY: &str = "language";
+
+    pub async fn load() -> Result<Option<Locale>, JsValue> {
+        let storage_obj = browser()
+            .storage()
+            .sync()
+            .get(&JsValue::from_str(Self::KEY))
+            .await
+            .map_err(map_err!())?
+            .into();
+
+        serde_wasm_bindgen::from_value::<Self>(storage_obj)
+            .map(|stored| stored.language)
+            .map_err(map_err!(from))
+    }
+
+    /// Locale of the texts sent from the background. The domain of the game
+    /// isn't known here, so the language of the browser is used when no
+    /// language was chosen.
+    pub async fn locale() -> Locale {
+        let language = Self::load().await.unwrap_or_else(|err_code| {
+            console_error!(err_code);
+            None
+        });
+
+        language.unwrap_or_else(|| {
+            let tag = js_sys::global()
+                .dyn_into::<web_sys::WorkerGlobalScope>()
+                .ok()
+                .and_then(|scope| scope.navigator().language())
+                .unwrap_or_default();
+
+            Locale::from_language_tag(&tag)
+        })
+    }
+
+    /// Removes the stored language when it's `None`, so the game domain is
+    /// followed again.
+    pub async fn save(language: Option<Locale>) -> Result<(), JsValue> {
+        let storage = browser().storage().sync();
+
+        if language.is_none() {
+            return storage
+                .remove(&JsValue::from_str(Self::KEY))
+                .await
+                .map_err(map_err!());
+        }
+
+        storage
+            .set(
+                serde_wasm_bindgen::to_value(&Self { language })
+                    .map_err(map_err!(from))?
+                    .unchecked_ref(),
+            )
+            .await
+            .map_err(map_err!())
+    }
+}
+
+/// Reads one entry of the local storage, `None` if it isn't stored.
+async fn load_local<T: DeserializeOwned>(key: &str) -> Result<Option<T>, JsValue> {
+    let key = JsValue::from_str(key);
+    let storage_obj: JsValue = browser()
+        .storage()
+        .local()
+        .get(&key)
+        .await
+        .map_err(map_err!())?
+        .into();
+    let value = js_sys::Reflect::get(&storage_obj, &key).map_err(map_err!())?;
+
+    serde_wasm_bindgen::from_value(value).map_err(map_err!(from))
+}
+
+async fn save_local<T: Serialize>(key: &str, value: &T) -> Result<(), JsValue> {
+    let storage_obj = js_sys::Object::new();
+    let value = serde_wasm_bindgen::to_value(value).map_err(map_err!(from))?;
+
+    js_sys::Reflect::set(&storage_obj, &JsValue::from_str(key), &value).map_err(map_err!())?;
+    browser()
+        .storage()
+        .local()
+        .set(&storage_obj)
+        .await
+        .map_err(map_err!())
+}
+
+/// MDMA console entries of a character kept in the local storage between page
+/// reloads.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct StorageConsoleLogs {
+    pub logs: Vec<PersistedLog>,
+}
+
+impl StorageConsoleLogs {
+    const KEY_PREFIX: &str = "console_logs";
+    /// Highest amount of entries stored, older entries are dropped first.
+    pub const CAPACITY: usize = 200;
+
+    pub fn new(mut logs: Vec<PersistedLog>) -> Self {
+        let excess = logs.len().saturating_sub(Self::CAPACITY);
+        logs.drain(..excess);
+
+        Self { logs }
+    }
+
+    pub async fn load(character: &CharacterId) -> Result<Self, JsValue> {
+        load_local(&character.storage_key(Self::KEY_PREFIX))
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
+    pub async fn save(&self, character: &CharacterId) -> Result<(), JsValue> {
+        save_local(&character.storage_key(Self::KEY_PREFIX), self).await
+    }
+}
+
+/// Addon dashboard last reported by the game tabs of a character along with
+/// the toggles made in the popup while none of them was connected.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StorageAddonDashboard {
+    #[serde(default)]
+    pub dashboard: AddonDashboard,
+    /// Applied by the next game tab of the character that reports its addons.
+    #[serde(default)]
+    pub pending_toggles: Vec<AddonToggle>,
+}
+
+impl StorageAddonDashboard {
+    const KEY_PREFIX: &str = "addon_dashboard";
+    /// Character which stored its dashboard last, the popup displays it while
+    /// no game tab is connected.
+    const LAST_CHARACTER_KEY: &str = "addon_dashboard_character";
+
+    pub async fn load(character: &CharacterId) -> Result<Self, JsValue> {
+        load_local(&character.storage_key(Self::KEY_PREFIX))
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
+    pub async fn save(&self, character: &CharacterId) -> Result<(), JsValue> {
+        save_local(&character.storage_key(Self::KEY_PREFIX), self).await?;
+        save_local(Self::LAST_CHARACTER_KEY, character).await
+    }
+
+    /// Character whose dashboard the popup displays while its game tab isn't
+    /// the active one.
+    pub async fn last_character() -> Result<Option<CharacterId>, JsValue> {
+        load_local(Self::LAST_CHARACTER_KEY).await
+    }
+
+    /// Replaces the queued toggles of the same addons.
+    pub fn queue_toggles(&mut self, toggles: Vec<AddonToggle>) {
+        self.pending_toggles
+            .retain(|pending| !toggles.iter().any(|toggle| toggle.key == pending.key));
+        self.pending_toggles.extend(toggles);
+    }
 }
 
 #[derive(Debug)]
@@ -185,12 +377,19 @@ impl MessageExt for Message {
                     return Err(val).map_err(map_err!());
                 }
             }
+            // Sent to every game tab if the message isn't addressed to one.
             Target::Foreground => {
-                let port = FOREGROUND_PORT
-                    .with_borrow(Option::clone)
-                    .ok_or_else(|| err_code!())?;
+                let ports = GAME_TABS.with_borrow(|tabs| tabs.ports(self.tab_id));
+
+                if ports.is_empty() {
+                    return Err(err_code!());
+                }
+
                 let message = self.to_value()?;
-                port.post_message(&message);
+
+                for port in ports {
+                    port.post_message(&message);
+                }
             }
             Target::Background => unreachable!(),
         }
@@ -205,7 +404,7 @@ impl MessageExt for Message {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[repr(transparent)]
 pub struct Jwt(String);
 
@@ -218,23 +417,26 @@ impl Jwt {
 
     /// Returns true if the String is a valid JWT and isn't expired.
     pub fn validate(&self) -> bool {
-        use base64::{Engine, engine::general_purpose::STANDARD_NO_PAD};
+        self.is_valid_at(js_sys::Date::new_0().get_time() as u64 / 1000)
+    }
 
-        let Some(encoded_token_claims) = self.0.split('.').nth(1) else {
-            return false;
-        };
+    /// Returns true if the String is a valid JWT and isn't expired at `now`
+    /// (in seconds).
+    pub fn is_valid_at(&self, now: u64) -> bool {
+        self.expires_at().is_some_and(|exp| now < exp)
+    }
 
-        debug_log!(
-            "decoded len estimate:",
-            base64::decoded_len_estimate(encoded_token_claims.len())
-        );
+    /// Expiration time of the token in seconds, `None` if the String isn't a
+    /// valid JWT. JWT segments are base64url encoded without padding.
+    pub fn expires_at(&self) -> Option<u64> {
+        use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+
+        let encoded_token_claims = self.0.split('.').nth(1)?;
 
         let mut claims = [0u8; Self::CLAIMS_BUFFER_SIZE];
-        let Ok(decoded_len) =
-            STANDARD_NO_PAD.decode_slice_unchecked(encoded_token_claims, &mut claims)
-        else {
-            return false;
-        };
+        let decoded_len = URL_SAFE_NO_PAD
+            .decode_slice_unchecked(encoded_token_claims, &mut claims)
+            .ok()?;
         let claims = unsafe { std::str::from_utf8_unchecked(&claims[..decoded_len]) };
 
         #[derive(Debug, Deserialize)]
@@ -242,11 +444,9 @@ impl Jwt {
             exp: u64,
         }
 
-        let Ok(claims) = serde_json::from_str::<Claims>(claims) else {
-            return false;
-        };
-
-        js_sys::Date::new_0().get_time() as u64 / 1000 < claims.exp
+        serde_json::from_str::<Claims>(claims)
+            .ok()
+            .map(|claims| claims.exp)
     }
 
     pub fn into_inner(self) -> String {
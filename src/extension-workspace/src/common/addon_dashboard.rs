@@ -0,0 +1,62 @@
+//! Addon states displayed on the popup dashboard.
+//!
+//! The game tab reports a snapshot whenever an addon is toggled, the background
+//! keeps the last one so the dashboard works without a game tab open.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddonStatus {
+    /// Key the settings of the addon are stored under.
+    pub key: String,
+    pub name: String,
+    pub premium: bool,
+    /// Premium addons are unavailable without an active premium.
+    pub available: bool,
+    pub active: bool,
+    /// Whether the addon failed to load in the game tab.
+    #[serde(default)]
+    pub failed: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AddonDashboard {
+    pub addons: Vec<AddonStatus>,
+    /// `ni` or `si`, empty until a game tab reported the addons.
+    pub interface: String,
+    /// Errors logged in the game tab since it was loaded.
+    pub errors: u32,
+    /// Whether a game tab is currently connected to the background.
+    #[serde(default)]
+    pub game_connected: bool,
+}
+
+impl AddonDashboard {
+    /// Returns `false` if the addon isn't on the dashboard or can't be used.
+    pub fn set_active(&mut self, key: &str, active: bool) -> bool {
+        match self
+            .addons
+            .iter_mut()
+            .find(|addon| addon.key == key && addon.available)
+        {
+            Some(addon) => {
+                addon.active = active;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Addon toggled from the popup.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddonToggle {
+    pub key: String,
+    pub active: bool,
+}
+
+impl AddonToggle {
+    pub fn new(key: String, active: bool) -> Self {
+        Self { key, active }
+    }
+}
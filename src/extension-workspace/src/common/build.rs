@@ -1,6 +1,19 @@
-use std::fs;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// Directories which never contain sources of the workspace crates.
+const IGNORED_DIRS: [&str; 2] = ["target", "node_modules"];
+/// Message catalogs in the order of the `Locale` variants, the first one is
+/// the reference every other catalog is checked against.
+const LOCALES: [&str; 2] = ["pl", "en"];
 
 fn main() {
+    generate_file_names();
+    generate_messages();
+
     if option_env!("PROFILING").is_some() {
         return;
     }
@@ -29,3 +42,252 @@ fn main() {
         }
     }
 }
+
+/// Recursively collects paths of all `.rs` files relative to `root`, using `/` as the separator.
+fn collect_sources(root: &Path, dir: &Path, sources: &mut Vec<String>) {
+    let entries = fs::read_dir(dir).expect("Failed to read the workspace directory");
+
+    for entry in entries {
+        let path = entry.expect("Failed to read a directory entry").path();
+        let is_ignored = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with('.') || IGNORED_DIRS.contains(&name));
+
+        if is_ignored {
+            continue;
+        }
+        if path.is_dir() {
+            collect_sources(root, &path, sources);
+            continue;
+        }
+        if path.extension().is_none_or(|extension| extension != "rs") {
+            continue;
+        }
+
+        let relative = path.strip_prefix(root).expect("Source outside of the workspace");
+        let components: Vec<_> = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect();
+
+        sources.push(components.join("/"));
+    }
+}
+
+/// Generates the file index used by `err_code!` and the symbol table of this release.
+///
+/// The index of a file is its position in the sorted list of all sources in the workspace, so
+/// the table of a given release is enough to decode any error code reported from it.
+fn generate_file_names() {
+    let workspace_src = Path::new("..")
+        .canonicalize()
+        .expect("Failed to resolve the workspace directory");
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR not set"));
+    let version = env::var("CARGO_PKG_VERSION").expect("CARGO_PKG_VERSION not set");
+    let mut sources = Vec::new();
+
+    collect_sources(&workspace_src, &workspace_src, &mut sources);
+    sources.sort();
+
+    // Tracking the sources would rebuild every crate on each change, so debug builds only
+    // regenerate the table along with the rest of this crate. Error codes from debug builds are
+    // not reported by players anyway.
+    if env::var("PROFILE").is_ok_and(|profile| profile == "release") {
+        let crate_dirs: BTreeSet<_> = sources
+            .iter()
+            .filter_map(|source| source.split_once('/').map(|(crate_dir, _)| crate_dir))
+            .collect();
+
+        for crate_dir in crate_dirs {
+            println!("cargo:rerun-if-changed=../{crate_dir}");
+        }
+    }
+
+    let mut generated = String::from(
+        "// This file is generated by common/build.rs\n// Do not edit manually.\n\n",
+    );
+
+    generated.push_str(&format!(
+        "/// Release the file index was generated for.\npub const SYMBOLS_VERSION: &str = {version:?};\n\n"
+    ));
+    generated.push_str(
+        "fn find_generated_index(pkg_name: Option<&str>, file: &str) -> Option<u16> {\n    [\n",
+    );
+    for source in &sources {
+        generated.push_str(&format!("        i(s!({source:?})),\n"));
+    }
+    generated.push_str(
+        r#"    ]
+    .iter()
+    .enumerate()
+    .find(|(_, partial_file_path)| {
+        file.ends_with(*partial_file_path)
+            && match pkg_name {
+                Some(pkg_name) => partial_file_path.starts_with(pkg_name),
+                None => true,
+            }
+    })
+    .map(|(index, _)| index as u16)
+}
+
+fn generated_file_name(index: u16) -> Option<String> {
+    match index {
+"#,
+    );
+    for (index, source) in sources.iter().enumerate() {
+        generated.push_str(&format!("        {index} => Some(s!({source:?}).to_owned()),\n"));
+    }
+    generated.push_str("        _ => None,\n    }\n}\n");
+
+    fs::write(out_dir.join("file_names.rs"), generated).expect("Failed to write file_names.rs");
+
+    // Symbol table decoded by the `extract_filenames` tool, first line is the release.
+    let symbols = format!("# {version}\n{}\n", sources.join("\n"));
+
+    fs::write(out_dir.join("symbols.txt"), &symbols).expect("Failed to write symbols.txt");
+
+    // Collecting the tables outside of `OUT_DIR` has to be requested explicitly, relative paths
+    // are resolved against the workspace root like in `extract_filenames`.
+    println!("cargo:rerun-if-env-changed=MDMA_SYMBOLS_DIR");
+    if let Ok(symbols_dir) = env::var("MDMA_SYMBOLS_DIR")
+        && env::var("PROFILE").is_ok_and(|profile| profile == "release")
+    {
+        let symbols_dir = workspace_src.join("..").join(symbols_dir);
+
+        fs::create_dir_all(&symbols_dir).expect("Failed to create the symbols directory");
+        fs::write(symbols_dir.join(format!("{version}.txt")), symbols)
+            .expect("Failed to write the release symbol table");
+    }
+}
+
+/// Parses a message catalog into `key -> text`, panicking on malformed lines.
+fn parse_catalog(locale: &str, contents: &str) -> BTreeMap<String, String> {
+    let mut messages = BTreeMap::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        // Trailing whitespace is trimmed along with the text, so that a
+        // trailing `\ ` keeps its space.
+        let line = line.trim_start();
+
+        if line.trim_end().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((key, text)) = line.split_once('=') else {
+            panic!("{locale}.properties:{}: expected `key = text`", index + 1);
+        };
+        let key = key.trim();
+
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            panic!("{locale}.properties:{}: invalid key `{key}`", index + 1);
+        }
+
+        let text = text.trim_start();
+        let text = match text.trim_end() {
+            trimmed if trimmed.ends_with('\\') && trimmed.len() < text.len() => {
+                &text[..=trimmed.len()]
+            }
+            trimmed => trimmed,
+        };
+        let text = text.replace("\\n", "\n").replace("\\ ", " ");
+
+        if messages.insert(key.to_owned(), text).is_some() {
+            panic!("{locale}.properties:{}: duplicated key `{key}`", index + 1);
+        }
+    }
+
+    messages
+}
+
+/// Names of the `{placeholder}`s used in the text.
+fn placeholders(text: &str) -> BTreeSet<&str> {
+    text.split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+        .collect()
+}
+
+fn variant_name(key: &str) -> String {
+    key.split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Generates the `Msg` enum from the message catalogs in `i18n/`.
+///
+/// Every catalog has to contain exactly the keys of the reference catalog with the same
+/// placeholders, so a missing translation fails the build instead of showing up in game.
+fn generate_messages() {
+    println!("cargo:rerun-if-changed=i18n");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR not set"));
+    let catalogs: Vec<_> = LOCALES
+        .iter()
+        .map(|locale| {
+            let path = Path::new("i18n").join(format!("{locale}.properties"));
+            let contents = fs::read_to_string(&path)
+                .unwrap_or_else(|err| panic!("Failed to read {}: {err}", path.display()));
+
+            (*locale, parse_catalog(locale, &contents))
+        })
+        .collect();
+    let (reference_locale, reference) = &catalogs[0];
+
+    for (locale, catalog) in &catalogs[1..] {
+        for (key, text) in reference {
+            let Some(translation) = catalog.get(key) else {
+                panic!(
+                    "{locale}.properties: missing key `{key}` from {reference_locale}.properties"
+                );
+            };
+            if placeholders(text) != placeholders(translation) {
+                panic!(
+                    "{locale}.properties: placeholders of `{key}` differ from {reference_locale}.properties"
+                );
+            }
+        }
+        if let Some(key) = catalog.keys().find(|key| !reference.contains_key(*key)) {
+            panic!(
+                "{locale}.properties: key `{key}` is missing from {reference_locale}.properties"
+            );
+        }
+    }
+
+    let mut generated =
+        String::from("// This file is generated by common/build.rs\n// Do not edit manually.\n\n");
+
+    generated.push_str(
+        "/// Key of a message from the catalogs in `common/i18n`.\n#[derive(Debug, Clone, Copy, PartialEq, Eq)]\npub enum Msg {\n",
+    );
+    for key in reference.keys() {
+        generated.push_str(&format!("    /// `{key}`\n    {},\n", variant_name(key)));
+    }
+    generated.push_str("}\n\n");
+
+    generated.push_str(&format!(
+        "const CATALOGS: [[&str; {}]; {}] = [\n",
+        reference.len(),
+        catalogs.len()
+    ));
+    for (_, catalog) in &catalogs {
+        generated.push_str("    [\n");
+        for text in catalog.values() {
+            generated.push_str(&format!("        {text:?},\n"));
+        }
+        generated.push_str("    ],\n");
+    }
+    generated.push_str("];\n");
+
+    fs::write(out_dir.join("messages.rs"), generated).expect("Failed to write messages.rs");
+}
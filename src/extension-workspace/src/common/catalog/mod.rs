@@ -0,0 +1,83 @@
+//! Game knowledge shared by the addons: titans, colossi and elites II along
+//! with the maps they can be found on.
+//!
+//! A catalog is bundled with the extension, the backend can serve a newer one
+//! so that new game content doesn't require an extension release.
+
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+
+/// Catalog compiled into the extension.
+const BUNDLED: &str = include_str!("catalog.json");
+
+/// Prefix of the npc images in the catalog.
+pub const NPC_PATH: &str = "https://micc.garmory-cdn.cloud/obrazki/npc/";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameCatalog {
+    /// Incremented whenever the contents change, only newer catalogs replace
+    /// the one in use.
+    pub version: u32,
+    /// Warrior types of colossi npcs.
+    pub colossus_warrior_types: RangeInclusive<i32>,
+    pub entries: Vec<CatalogEntry>,
+}
+
+impl GameCatalog {
+    pub fn bundled() -> serde_json::Result<Self> {
+        serde_json::from_str(BUNDLED)
+    }
+
+    pub fn by_map_id(&self, map_id: i32) -> Option<&CatalogEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.maps.iter().any(|map| map.id == Some(map_id)))
+    }
+
+    pub fn by_map_name(&self, map_name: &str) -> Option<&CatalogEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.maps.iter().any(|map| map.name == map_name))
+    }
+
+    pub fn is_colossus(&self, warrior_type: i32) -> bool {
+        self.colossus_warrior_types.contains(&warrior_type)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogCategory {
+    Titan,
+    Colossus,
+    Elite2,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    pub category: CatalogCategory,
+    /// Location alias used by the players, e.g. `T-51` or `K-36`.
+    pub alias: Option<String>,
+    pub boss: Option<String>,
+    pub level: Option<u16>,
+    /// Short text displayed on the teleports leading to the maps.
+    pub label: String,
+    /// Image of the boss, relative to [`NPC_PATH`].
+    pub img: Option<String>,
+    /// Maps the boss can be found on, or leading to it.
+    pub maps: Vec<CatalogMap>,
+}
+
+impl CatalogEntry {
+    pub fn img_url(&self) -> Option<String> {
+        self.img.as_ref().map(|img| format!("{NPC_PATH}{img}"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogMap {
+    /// Not every map has a known id, those are only matched by name.
+    pub id: Option<i32>,
+    pub name: String,
+}
@@ -0,0 +1,409 @@
+//! Clan roster history recorded by the `clan_roster` addon.
+//!
+//! Members of the same clan can sync their rosters through the backend, which
+//! merges the events and seen online timestamps of every roster it receives
+//! into a single shared history. The backend only accepts rosters from
+//! characters listed among the members of the shared one.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Oldest events above this count get dropped.
+pub const MAX_EVENTS: usize = 2_000;
+/// Clans are limited in size by the game, anything above is not a real roster.
+pub const MAX_MEMBERS: usize = 500;
+/// Events of the same kind recorded within this many seconds of each other
+/// describe the same change observed by two different members.
+const DUPLICATE_WINDOW: f64 = 60.0 * 60.0;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClanRoster {
+    pub world: String,
+    pub clan_id: i32,
+    /// Unix timestamp in seconds of the newest members snapshot.
+    pub updated_at: f64,
+    pub members: Vec<RosterMember>,
+    /// Oldest first.
+    pub events: Vec<RosterEvent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RosterMember {
+    pub char_id: i32,
+    pub nick: String,
+    pub lvl: u16,
+    /// Profession character, e.g. `m`.
+    pub prof: String,
+    pub online: bool,
+    /// Unix timestamp in seconds of the last snapshot in which the member was
+    /// seen online, or which followed one in which they were. Snapshots are
+    /// only taken when the clan list is refreshed, so the member might have
+    /// gone offline long before. `None` if they weren't seen online since the
+    /// roster is tracked.
+    pub last_online: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RosterEventKind {
+    Joined,
+    Left,
+    LevelUp,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RosterEvent {
+    /// Unix timestamp in seconds.
+    pub ts: f64,
+    pub char_id: i32,
+    pub nick: String,
+    pub kind: RosterEventKind,
+    /// Level of the member after the event.
+    pub lvl: u16,
+}
+
+impl RosterEvent {
+    fn new(ts: f64, member: &RosterMember, kind: RosterEventKind) -> Self {
+        Self {
+            ts,
+            char_id: member.char_id,
+            nick: member.nick.clone(),
+            kind,
+            lvl: member.lvl,
+        }
+    }
+}
+
+impl ClanRoster {
+    pub fn new(world: String, clan_id: i32) -> Self {
+        Self {
+            world,
+            clan_id,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_same_clan(&self, other: &Self) -> bool {
+        self.world == other.world && self.clan_id == other.clan_id
+    }
+
+    /// Replaces the members with a new snapshot and records the changes since
+    /// the previous one. The first snapshot of a roster records no events,
+    /// since there is nothing to compare it with.
+    pub fn record(&mut self, mut members: Vec<RosterMember>, now: f64) {
+        let previous: HashMap<i32, &RosterMember> = self
+            .members
+            .iter()
+            .map(|member| (member.char_id, member))
+            .collect();
+        let first_snapshot = previous.is_empty();
+        let mut events = Vec::new();
+
+        for member in members.iter_mut() {
+            let old_member = previous.get(&member.char_id);
+
+            // Members who went offline since the previous snapshot were
+            // online until at most now, the game doesn't tell when exactly.
+            member.last_online = match member.online || old_member.is_some_and(|old| old.online) {
+                true => Some(now),
+                false => old_member.and_then(|old| old.last_online),
+            };
+
+            if first_snapshot {
+                continue;
+            }
+
+            let kind = match old_member {
+                None => RosterEventKind::Joined,
+                Some(old_member) if member.lvl > old_member.lvl => RosterEventKind::LevelUp,
+                Some(_) => continue,
+            };
+            events.push(RosterEvent::new(now, member, kind));
+        }
+
+        if !first_snapshot {
+            let left = self
+                .members
+                .iter()
+                .filter(|old_member| {
+                    members
+                        .iter()
+                        .all(|member| member.char_id != old_member.char_id)
+                })
+                .map(|old_member| RosterEvent::new(now, old_member, RosterEventKind::Left));
+            events.extend(left);
+        }
+
+        self.members = members;
+        self.updated_at = now;
+        self.events.extend(events);
+        self.dedup_events();
+    }
+
+    /// Merges a roster of the same clan recorded by another member. Only the
+    /// events and the seen online timestamps of the tracked members are
+    /// taken, the members snapshot is replaced by [`Self::replace_members`].
+    pub fn merge(&mut self, other: ClanRoster) {
+        self.merge_last_online(&other.members);
+        self.events.extend(other.events);
+        self.dedup_events();
+    }
+
+    /// Takes the members snapshot of `other` if it's newer than the tracked
+    /// one, dropping the members who left the clan since. Seen online
+    /// timestamps of the members in both snapshots are kept.
+    pub fn replace_members(&mut self, other: &ClanRoster) {
+        if other.updated_at <= self.updated_at {
+            return;
+        }
+
+        let previous = std::mem::replace(&mut self.members, other.members.clone());
+        self.updated_at = other.updated_at;
+        self.merge_last_online(&previous);
+    }
+
+    pub fn has_member(&self, char_id: i32) -> bool {
+        self.members.iter().any(|member| member.char_id == char_id)
+    }
+
+    /// Keeps the newest seen online timestamp of every tracked member found
+    /// in `members`.
+    fn merge_last_online(&mut self, members: &[RosterMember]) {
+        let last_online: HashMap<i32, f64> = members
+            .iter()
+            .filter_map(|member| Some((member.char_id, member.last_online?)))
+            .collect();
+
+        self.members.iter_mut().for_each(|member| {
+            if let Some(&ts) = last_online.get(&member.char_id) {
+                member.last_online = Some(member.last_online.map_or(ts, |own| own.max(ts)));
+            }
+        });
+    }
+
+    /// Sorts the events, removes the ones describing the same change and
+    /// drops the oldest ones above [`MAX_EVENTS`].
+    fn dedup_events(&mut self) {
+        let mut last_kept: HashMap<(i32, RosterEventKind, u16), f64> = HashMap::new();
+
+        self.events.sort_by(|a, b| a.ts.total_cmp(&b.ts));
+        self.events.retain(|event| {
+            let key = (event.char_id, event.kind, event.lvl);
+            let duplicate = last_kept
+                .get(&key)
+                .is_some_and(|ts| event.ts - ts < DUPLICATE_WINDOW);
+
+            if !duplicate {
+                last_kept.insert(key, event.ts);
+            }
+            !duplicate
+        });
+
+        let excess = self.events.len().saturating_sub(MAX_EVENTS);
+        self.events.drain(..excess);
+    }
+
+    /// Limits the roster to sizes a real clan can reach and makes sure none of
+    /// its timestamps are newer than `now`.
+    pub fn sanitize(&mut self, now: f64) {
+        self.updated_at = self.updated_at.min(now);
+        self.members.truncate(MAX_MEMBERS);
+        self.members.iter_mut().for_each(|member| {
+            member.last_online = member.last_online.map(|ts| ts.min(now));
+        });
+        self.events.retain(|event| event.ts <= now);
+        self.dedup_events();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(char_id: i32, lvl: u16, online: bool) -> RosterMember {
+        RosterMember {
+            char_id,
+            nick: format!("member{char_id}"),
+            lvl,
+            prof: "m".to_owned(),
+            online,
+            last_online: None,
+        }
+    }
+
+    fn event(ts: f64, char_id: i32, kind: RosterEventKind, lvl: u16) -> RosterEvent {
+        RosterEvent::new(ts, &member(char_id, lvl, false), kind)
+    }
+
+    fn roster() -> ClanRoster {
+        ClanRoster::new("world".to_owned(), 1)
+    }
+
+    fn kinds(roster: &ClanRoster) -> Vec<(i32, RosterEventKind)> {
+        roster
+            .events
+            .iter()
+            .map(|event| (event.char_id, event.kind))
+            .collect()
+    }
+
+    #[test]
+    fn first_snapshot_records_no_events() {
+        let mut roster = roster();
+        roster.record(vec![member(1, 10, true), member(2, 20, false)], 100.0);
+
+        assert!(roster.events.is_empty());
+        assert_eq!(roster.updated_at, 100.0);
+        assert_eq!(roster.members[0].last_online, Some(100.0));
+        assert_eq!(roster.members[1].last_online, None);
+    }
+
+    #[test]
+    fn record_changes_since_previous_snapshot() {
+        let mut roster = roster();
+        roster.record(vec![member(1, 10, false), member(2, 20, false)], 100.0);
+        roster.record(vec![member(1, 11, false), member(3, 30, false)], 200.0);
+
+        assert_eq!(
+            kinds(&roster),
+            vec![
+                (1, RosterEventKind::LevelUp),
+                (3, RosterEventKind::Joined),
+                (2, RosterEventKind::Left),
+            ]
+        );
+        assert_eq!(roster.events[0].lvl, 11);
+        assert_eq!(roster.events[2].nick, "member2");
+    }
+
+    #[test]
+    fn seen_online_until_the_snapshot_after_going_offline() {
+        let mut roster = roster();
+        roster.record(vec![member(1, 10, true)], 100.0);
+        roster.record(vec![member(1, 10, false)], 200.0);
+        assert_eq!(roster.members[0].last_online, Some(200.0));
+
+        roster.record(vec![member(1, 10, false)], 300.0);
+        assert_eq!(roster.members[0].last_online, Some(200.0));
+    }
+
+    #[test]
+    fn merge_keeps_newest_seen_online_and_own_members() {
+        let mut own = roster();
+        own.members = vec![member(1, 10, false), member(2, 20, false)];
+        own.members[0].last_online = Some(100.0);
+        own.members[1].last_online = Some(300.0);
+
+        let mut other = roster();
+        other.members = vec![
+            member(1, 10, false),
+            member(2, 20, false),
+            member(3, 30, false),
+        ];
+        other.members[0].last_online = Some(200.0);
+        other.members[1].last_online = Some(250.0);
+        other.members[2].last_online = Some(400.0);
+        other.events = vec![event(50.0, 3, RosterEventKind::Left, 30)];
+
+        own.merge(other);
+
+        assert_eq!(own.members.len(), 2);
+        assert_eq!(own.members[0].last_online, Some(200.0));
+        assert_eq!(own.members[1].last_online, Some(300.0));
+        assert_eq!(kinds(&own), vec![(3, RosterEventKind::Left)]);
+    }
+
+    #[test]
+    fn newer_snapshot_drops_members_who_left() {
+        let mut shared = roster();
+        shared.updated_at = 100.0;
+        shared.members = vec![member(1, 10, false), member(2, 20, false)];
+        shared.members[0].last_online = Some(100.0);
+
+        let mut other = roster();
+        other.updated_at = 200.0;
+        other.members = vec![member(1, 11, false), member(3, 30, true)];
+        other.members[1].last_online = Some(200.0);
+
+        shared.replace_members(&other);
+
+        let members: Vec<_> = shared
+            .members
+            .iter()
+            .map(|member| (member.char_id, member.lvl, member.last_online))
+            .collect();
+        assert_eq!(members, vec![(1, 11, Some(100.0)), (3, 30, Some(200.0))]);
+        assert_eq!(shared.updated_at, 200.0);
+    }
+
+    #[test]
+    fn older_snapshot_is_not_taken() {
+        let mut shared = roster();
+        shared.updated_at = 200.0;
+        shared.members = vec![member(1, 10, false)];
+
+        let mut other = roster();
+        other.updated_at = 100.0;
+        other.members = vec![member(2, 20, false)];
+
+        shared.replace_members(&other);
+
+        assert!(shared.has_member(1));
+        assert!(!shared.has_member(2));
+    }
+
+    #[test]
+    fn merge_drops_events_seen_by_both_members() {
+        let mut own = roster();
+        own.events = vec![event(1_000.0, 1, RosterEventKind::LevelUp, 11)];
+
+        let mut other = roster();
+        other.events = vec![
+            event(
+                1_000.0 + DUPLICATE_WINDOW - 1.0,
+                1,
+                RosterEventKind::LevelUp,
+                11,
+            ),
+            event(1_000.0 + DUPLICATE_WINDOW, 1, RosterEventKind::LevelUp, 12),
+            event(500.0, 2, RosterEventKind::Joined, 20),
+        ];
+
+        own.merge(other);
+
+        let events: Vec<_> = own
+            .events
+            .iter()
+            .map(|event| (event.ts, event.lvl))
+            .collect();
+        assert_eq!(
+            events,
+            vec![(500.0, 20), (1_000.0, 11), (1_000.0 + DUPLICATE_WINDOW, 12)]
+        );
+    }
+
+    #[test]
+    fn same_change_outside_duplicate_window_is_kept() {
+        let mut roster = roster();
+        roster.events = vec![
+            event(0.0, 1, RosterEventKind::Joined, 10),
+            event(DUPLICATE_WINDOW, 1, RosterEventKind::Joined, 10),
+        ];
+        roster.dedup_events();
+
+        assert_eq!(roster.events.len(), 2);
+    }
+
+    #[test]
+    fn oldest_events_above_limit_are_dropped() {
+        let mut roster = roster();
+        roster.events = (0..MAX_EVENTS + 10)
+            .map(|i| event(i as f64, i as i32, RosterEventKind::Joined, 1))
+            .collect();
+        roster.dedup_events();
+
+        assert_eq!(roster.events.len(), MAX_EVENTS);
+        assert_eq!(roster.events[0].ts, 10.0);
+    }
+}
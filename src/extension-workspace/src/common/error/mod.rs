@@ -1,23 +1,12 @@
-macro_rules! make_errors {
-    ($err_type: ident, $($variant:ident),+) => {
-        $(
-        ::paste::paste! {
-            #[macro_export]
-            macro_rules! [<_ obf _ $variant:lower>] {
-                ($msg:expr) => {
-                    ::std::boxed::Box::new($crate::error::$err_type::Error::$variant {
-                        msg: ::obfstr::obfstr!($msg).into(),
-                        line: line!(),
-                    }) as ::std::boxed::Box<dyn ::std::error::Error>
-                };
-            }
-            pub use [<_ obf _ $variant:lower>] as [<obf _ $variant:lower>];
-        }
-        )+
-    };
-}
+use std::{borrow::Cow, fmt};
+
+use wasm_bindgen::{JsCast, JsValue};
 
-use crate::file_names::find_file_index;
+use crate::file_names::{SYMBOLS_VERSION, file_name, find_file_index};
+
+mod report;
+
+pub use report::{ReportedCode, parse_code, parse_error_report};
 
 pub fn encode_location(pkg_name: Option<&str>, file: &str, line: u16) -> u32 {
     let file_hash = find_file_index(pkg_name, file).unwrap_or_default();
@@ -31,91 +20,269 @@ pub fn decode_location(encoded: u32) -> (u16, u16) {
     (high, low)
 }
 
-//TODO: Add Error traits by hand obfuscating the error messages.
-pub mod std {
-    pub use thiserror::Error;
-
-    #[derive(Error, Debug, Clone)]
-    pub enum Error {
-        #[error("[{line:?}] Could not get {msg:?}")]
-        Get { msg: String, line: u32 },
-        #[error("[{line:?}] Could not set {msg:?}")]
-        Set { msg: String, line: u32 },
-        #[error("[{line:?}] Could not cast {msg:?}")]
-        Cast { msg: String, line: u32 },
-        #[error("[{line:?}] Could not call {msg:?}")]
-        Call { msg: String, line: u32 },
-        #[error("[{line:?}] {msg:?} timed out")]
-        Timeout { msg: String, line: u32 },
-        #[error("[{line:?}] Could not delete {msg:?}")]
-        Delete { msg: String, line: u32 },
-        #[error("[{line:?}] Could not fetch {msg:?}")]
-        Fetch { msg: String, line: u32 },
-    }
-
-    make_errors! {
-        std,
-        Get,
-        Set,
-        Cast,
-        Call,
-        Timeout,
-        Delete,
-        Fetch,
-        Lock
+/// Error code decoded with the symbol table of the running release.
+#[derive(Debug, Clone)]
+pub struct DecodedLocation {
+    pub code: u32,
+    /// Path relative to the workspace `src` directory, `None` if the index is out of the table.
+    pub file: Option<String>,
+    pub line: u16,
+}
+
+impl DecodedLocation {
+    pub fn decode(code: u32) -> Self {
+        let (file_index, line) = decode_location(code);
+
+        Self {
+            code,
+            file: file_name(file_index),
+            line,
+        }
+    }
+
+    /// Crate the error originated from, e.g. `foreground`.
+    pub fn crate_name(&self) -> Option<&str> {
+        self.file.as_deref()?.split('/').next()
     }
+
+    pub fn release() -> &'static str {
+        SYMBOLS_VERSION
+    }
+}
+
+impl fmt::Display for DecodedLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.crate_name(), self.file.as_deref()) {
+            (Some(crate_name), Some(file)) => write!(
+                f,
+                "{} => {crate_name}: {file}:{} (v{SYMBOLS_VERSION})",
+                self.code, self.line
+            ),
+            _ => write!(f, "{} => ?:{} (v{SYMBOLS_VERSION})", self.code, self.line),
+        }
+    }
+}
+
+/// What went wrong, replaces the bare location codes returned by `err_code!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Get,
+    Set,
+    Cast,
+    Call,
+    Timeout,
+    Delete,
+    Fetch,
+    Serialize,
+    Deserialize,
+    /// Error thrown by the game or the browser, its code doesn't encode a location.
+    Js,
+    /// Error created by `err_code!` without any additional information.
+    Unknown,
+}
+
+impl ErrorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "get",
+            Self::Set => "set",
+            Self::Cast => "cast",
+            Self::Call => "call",
+            Self::Timeout => "timeout",
+            Self::Delete => "delete",
+            Self::Fetch => "fetch",
+            Self::Serialize => "serialize",
+            Self::Deserialize => "deserialize",
+            Self::Js => "js",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    fn from_str(value: &str) -> Self {
+        match value {
+            "get" => Self::Get,
+            "set" => Self::Set,
+            "cast" => Self::Cast,
+            "call" => Self::Call,
+            "timeout" => Self::Timeout,
+            "delete" => Self::Delete,
+            "fetch" => Self::Fetch,
+            "serialize" => Self::Serialize,
+            "deserialize" => Self::Deserialize,
+            "js" => Self::Js,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Severity {
+    /// The addon can keep working, e.g. a single message could not be parsed.
+    #[default]
+    Recoverable,
+    /// The addon or the whole manager can't work anymore.
+    Fatal,
+}
+
+/// Structured error of the foreground.
+///
+/// Crosses the wasm boundary as a plain object (see [`From<MdmaError> for JsValue`]) so that it
+/// survives being passed through functions returning `Result<T, JsValue>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MdmaError {
+    pub kind: ErrorKind,
+    /// Location encoded by [`encode_location`], `0` for errors thrown by js.
+    pub code: u32,
+    /// Context added while the error was propagated, innermost first.
+    pub context: Vec<Cow<'static, str>>,
+    pub severity: Severity,
 }
 
-pub mod serde {
-    pub use thiserror::Error;
+impl MdmaError {
+    pub fn new(kind: ErrorKind, code: u32) -> Self {
+        Self {
+            kind,
+            code,
+            context: Vec::new(),
+            severity: Severity::default(),
+        }
+    }
+
+    #[track_caller]
+    pub fn at_caller(kind: ErrorKind) -> Self {
+        let caller = ::std::panic::Location::caller();
+
+        Self::new(kind, encode_location(None, caller.file(), caller.line() as u16))
+    }
+
+    pub fn context(mut self, context: impl Into<Cow<'static, str>>) -> Self {
+        self.context.push(context.into());
+        self
+    }
+
+    pub fn fatal(self) -> Self {
+        Self {
+            severity: Severity::Fatal,
+            ..self
+        }
+    }
+
+    pub fn is_fatal(&self) -> bool {
+        self.severity == Severity::Fatal
+    }
 
-    #[derive(Error, Debug, Clone)]
-    pub enum Error {
-        #[error("[{line:?}] Could not serialize {msg:?}")]
-        Serialize { msg: String, line: u32 },
-        #[error("[{line:?}] Could not deserialize {msg:?}")]
-        Deserialize { msg: String, line: u32 },
+    /// Errors thrown by js didn't pass through `err_code!`, so they have no location of our own.
+    pub fn is_js(&self) -> bool {
+        self.kind == ErrorKind::Js
     }
 
-    make_errors! {
-        serde,
-        Serialize,
-        Deserialize
+    pub fn location(&self) -> Option<DecodedLocation> {
+        (!self.is_js()).then(|| DecodedLocation::decode(self.code))
+    }
+
+    /// Reads an error previously converted into a `JsValue`, bare codes from `err_code!` become
+    /// [`ErrorKind::Unknown`] and anything else thrown by js becomes [`ErrorKind::Js`].
+    pub fn from_js(value: &JsValue) -> Self {
+        if let Some(code) = value.as_f64() {
+            return Self::new(ErrorKind::Unknown, code as u32);
+        }
+
+        let get = |key: &str| js_sys::Reflect::get(value, &JsValue::from_str(key)).ok();
+        let Some(code) = get("mdma_err").and_then(|code| code.as_f64()) else {
+            let mut error = Self::new(ErrorKind::Js, 0);
+            if let Some(message) = value.dyn_ref::<js_sys::Error>().map(js_sys::Error::message) {
+                error.context.push(Cow::Owned(String::from(message)));
+            } else if let Some(message) = value.as_string() {
+                error.context.push(Cow::Owned(message));
+            }
+
+            return error;
+        };
+        let kind = get("kind")
+            .and_then(|kind| kind.as_string())
+            .map_or(ErrorKind::Unknown, |kind| ErrorKind::from_str(&kind));
+        let severity = match get("fatal").and_then(|fatal| fatal.as_bool()) {
+            Some(true) => Severity::Fatal,
+            _ => Severity::Recoverable,
+        };
+        let context = get("context")
+            .filter(|context| context.is_array())
+            .map(|context| {
+                js_sys::Array::from(&context)
+                    .iter()
+                    .filter_map(|context| context.as_string().map(Cow::Owned))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            kind,
+            code: code as u32,
+            context,
+            severity,
+        }
     }
 }
 
-pub mod promise {
-    pub use thiserror::Error;
+impl fmt::Display for MdmaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_fatal() {
+            write!(f, "[fatal] ")?;
+        }
+        match self.location() {
+            Some(location) => write!(f, "[{}] {location}", self.kind.as_str())?,
+            None => write!(f, "[{}] (v{SYMBOLS_VERSION})", self.kind.as_str())?,
+        }
+        for context in self.context.iter().rev() {
+            write!(f, "\n  {context}")?;
+        }
 
-    #[derive(Error, Debug, Clone)]
-    pub enum Error {
-        #[error("[{line:?}] Could not resolve {msg:?} promise")]
-        Resolve { msg: String, line: u32 },
-        #[error("[{line:?}] Could not reject {msg:?} promise")]
-        Reject { msg: String, line: u32 },
+        Ok(())
     }
+}
 
-    make_errors! {
-        promise,
-        Resolve,
-        Reject
+impl From<MdmaError> for JsValue {
+    fn from(error: MdmaError) -> Self {
+        let object = js_sys::Object::new();
+        let set = |key: &str, value: &JsValue| {
+            let _ = js_sys::Reflect::set(&object, &JsValue::from_str(key), value);
+        };
+        let context: js_sys::Array = error
+            .context
+            .iter()
+            .map(|context| JsValue::from_str(context))
+            .collect();
+
+        set("mdma_err", &JsValue::from_f64(error.code as f64));
+        set("kind", &JsValue::from_str(error.kind.as_str()));
+        set("fatal", &JsValue::from_bool(error.is_fatal()));
+        set("context", &context);
+
+        object.into()
     }
 }
 
-pub mod json {
-    pub use thiserror::Error;
+impl From<JsValue> for MdmaError {
+    fn from(value: JsValue) -> Self {
+        Self::from_js(&value)
+    }
+}
+
+pub type MdmaResult<T> = Result<T, MdmaError>;
+
+/// Adds context to errors while propagating them, e.g.
+/// `hero.rx().ok_or_else(|| err_code!()).context("hero position")?`.
+pub trait ErrorContext<T> {
+    fn context(self, context: impl Into<Cow<'static, str>>) -> MdmaResult<T>;
+    fn fatal(self) -> MdmaResult<T>;
+}
 
-    #[derive(Error, Debug, Clone)]
-    pub enum Error {
-        #[error("[{line:?}] Could not parse {msg:?}")]
-        Parse { msg: String, line: u32 },
-        #[error("[{line:?}] Could not stringify {msg:?}")]
-        Stringify { msg: String, line: u32 },
+impl<T, E: Into<MdmaError>> ErrorContext<T> for Result<T, E> {
+    fn context(self, context: impl Into<Cow<'static, str>>) -> MdmaResult<T> {
+        self.map_err(|error| error.into().context(context))
     }
 
-    make_errors! {
-        json,
-        Parse,
-        Stringify
+    fn fatal(self) -> MdmaResult<T> {
+        self.map_err(|error| error.into().fatal())
     }
 }
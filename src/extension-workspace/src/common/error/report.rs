@@ -0,0 +1,47 @@
+//! Parsing of error reports pasted by users.
+//!
+//! Only depends on `std`, so that `extract_filenames` can include it with `#[path]` instead of
+//! keeping its own copy.
+
+/// Error code found in a report pasted by a user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportedCode<'a> {
+    pub code: u32,
+    /// Release the code was reported from, if the report contains it.
+    pub release: Option<&'a str>,
+}
+
+/// Finds every error code in a copied MDMA console log or a single pasted code.
+///
+/// Log lines have the form `{"mdma_err": 1234, "ev": "...", "v": "0.15.0"}`, any other line is
+/// only accepted if it consists of a single number.
+pub fn parse_error_report(report: &str) -> Vec<ReportedCode<'_>> {
+    report
+        .lines()
+        .filter_map(|line| match json_field(line, "mdma_err") {
+            Some(code) => Some(ReportedCode {
+                code: parse_code(code)?,
+                release: json_field(line, "v"),
+            }),
+            None => Some(ReportedCode {
+                code: parse_code(line.trim())?,
+                release: None,
+            }),
+        })
+        .collect()
+}
+
+/// Parses a single error code, `None` if the value isn't an integer fitting into `u32`.
+pub fn parse_code(value: &str) -> Option<u32> {
+    let code = value.parse::<f64>().ok()?;
+
+    (code.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&code)).then_some(code as u32)
+}
+
+fn json_field<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let (_, value) = line.split_once(&format!("\"{key}\":"))?;
+    let value = value.trim_start();
+    let end = value.find([',', '}']).unwrap_or(value.len());
+
+    Some(value[..end].trim().trim_matches('"'))
+}
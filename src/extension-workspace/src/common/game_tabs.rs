@@ -0,0 +1,75 @@
+//! Signals exchanged between game tabs, e.g. a main character and its alts
+//! played in the same browser. The background only picks the receiving tabs,
+//! every signal is handled by them.
+
+use serde::{Deserialize, Serialize};
+
+/// Identifies a character regardless of the tab it's played in, the data
+/// stored by the background is kept apart for every character.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CharacterId {
+    pub account_id: String,
+    pub char_id: String,
+}
+
+impl CharacterId {
+    pub fn new(account_id: String, char_id: String) -> Self {
+        Self {
+            account_id,
+            char_id,
+        }
+    }
+
+    /// Key of the `prefix` entry stored for this character.
+    pub fn storage_key(&self, prefix: &str) -> String {
+        format!("{prefix}_{}_{}", self.account_id, self.char_id)
+    }
+}
+
+/// Character played in a game tab.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TabHero {
+    pub account_id: String,
+    pub char_id: String,
+    pub nick: String,
+    pub world: String,
+    /// Whether the character leads its party, invite requests are answered by
+    /// the party leader.
+    #[serde(default)]
+    pub leads_party: bool,
+}
+
+impl TabHero {
+    pub fn is_same_world(&self, other: &Self) -> bool {
+        self.world == other.world
+    }
+
+    pub fn character(&self) -> CharacterId {
+        CharacterId::new(self.account_id.clone(), self.char_id.clone())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TabSignal {
+    /// The sender asks to be invited to a party, only one tab on the same
+    /// world receives it.
+    InviteRequest,
+    /// The sender got attacked by another player.
+    UnderAttack { attacker: String },
+}
+
+/// Signal sent to the other identified game tabs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TabBroadcast {
+    /// Overwritten by the background with the character identified by the
+    /// sending tab.
+    pub from: TabHero,
+    pub signal: TabSignal,
+}
+
+impl TabBroadcast {
+    pub fn new(from: TabHero, signal: TabSignal) -> Self {
+        Self { from, signal }
+    }
+}
@@ -0,0 +1,172 @@
+//! Localization of the texts displayed in game and in the popup.
+//!
+//! Messages are kept in the `<locale>.properties` catalogs next to this
+//! module, one `key = text` per line, where `#` starts a comment, `\n` is a
+//! line break, `\ ` is a space kept at either end of the text and `{name}` is
+//! a placeholder filled in by [`tr!`](crate::tr).
+//! The build script generates [`Msg`] from the catalogs and fails if any of
+//! them is missing a key or a placeholder of `pl.properties`.
+
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+include!(concat!(env!("OUT_DIR"), "/messages.rs"));
+
+thread_local! {
+    static CURRENT: Cell<Locale> = const { Cell::new(Locale::Pl) };
+}
+
+/// Order of the variants has to match `LOCALES` in `common/build.rs`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Pl = 0,
+    En = 1,
+}
+
+impl Locale {
+    pub fn current() -> Self {
+        CURRENT.with(Cell::get)
+    }
+
+    pub fn set_current(locale: Self) {
+        CURRENT.with(|current| current.set(locale));
+    }
+
+    /// Worlds on `margonem.pl` are Polish, the ones on `margonem.com` are
+    /// English.
+    pub fn from_domain(is_pl: bool) -> Self {
+        match is_pl {
+            true => Self::Pl,
+            false => Self::En,
+        }
+    }
+
+    /// Locale of a BCP 47 language tag, e.g. `navigator.language`.
+    pub fn from_language_tag(tag: &str) -> Self {
+        match tag.to_ascii_lowercase().starts_with("pl") {
+            true => Self::Pl,
+            false => Self::En,
+        }
+    }
+
+    /// Name of the language in itself, displayed when choosing the language.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pl => "Polski",
+            Self::En => "English",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Pl => "pl",
+            Self::En => "en",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "pl" => Some(Self::Pl),
+            "en" => Some(Self::En),
+            _ => None,
+        }
+    }
+
+    /// Tag passed to the `Intl` APIs, e.g. when formatting dates.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::Pl => "pl-PL",
+            Self::En => "en-GB",
+        }
+    }
+
+    /// Case insensitive comparison following the alphabet of the locale.
+    pub fn compare(self, a: &str, b: &str) -> Ordering {
+        let a = a.to_lowercase();
+        let b = b.to_lowercase();
+        let ranks = |text: &str| {
+            text.chars()
+                .map(|c| self.collation_rank(c))
+                .collect::<Vec<_>>()
+        };
+
+        ranks(&a).cmp(&ranks(&b)).then_with(|| a.cmp(&b))
+    }
+
+    /// Position of a lowercase character in the alphabet, characters outside
+    /// of it are placed after every letter.
+    fn collation_rank(self, c: char) -> u32 {
+        const POLISH_ALPHABET: &str = "aąbcćdeęfghijklłmnńoópqrsśtuvwxyzźż";
+
+        let position = match self {
+            Self::Pl => POLISH_ALPHABET.chars().position(|letter| letter == c),
+            Self::En => match fold_diacritic(c) {
+                c @ 'a'..='z' => Some(c as usize - 'a' as usize),
+                _ => None,
+            },
+        };
+
+        match position {
+            Some(position) => position as u32 + 1,
+            None => 1000 + c as u32,
+        }
+    }
+}
+
+/// Base letter of a Polish letter with a diacritic.
+fn fold_diacritic(c: char) -> char {
+    match c {
+        'ą' => 'a',
+        'ć' => 'c',
+        'ę' => 'e',
+        'ł' => 'l',
+        'ń' => 'n',
+        'ó' => 'o',
+        'ś' => 's',
+        'ź' | 'ż' => 'z',
+        c => c,
+    }
+}
+
+impl Msg {
+    /// Text of the message in the current locale.
+    pub fn text(self) -> &'static str {
+        self.text_in(Locale::current())
+    }
+
+    pub fn text_in(self, locale: Locale) -> &'static str {
+        CATALOGS[locale as usize][self as usize]
+    }
+
+    /// Text of the message in the current locale with the placeholders
+    /// replaced by `args`.
+    pub fn format(self, args: &[(&str, &dyn Display)]) -> String {
+        args.iter()
+            .fold(self.text().to_owned(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), &value.to_string())
+            })
+    }
+}
+
+/// Text of a message from the catalogs in the current locale.
+///
+/// ```ignore
+/// message(tr!(HotkeysUnbound));
+/// message(&tr!(HotkeysDisabled, action = action.describe()));
+/// ```
+#[macro_export]
+macro_rules! tr {
+    ($key:ident) => {
+        $crate::i18n::Msg::$key.text()
+    };
+    ($key:ident, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::Msg::$key.format(&[
+            $((stringify!($name), &$value as &dyn ::std::fmt::Display)),+
+        ])
+    };
+}
@@ -15,8 +15,18 @@ cfg_if::cfg_if! {
 #[cfg(feature = "extension")]
 #[macro_use]
 pub mod log;
-#[cfg(any(feature = "backend", feature = "background"))]
+#[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+pub mod addon_dashboard;
+#[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+pub mod catalog;
+#[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+pub mod clan_roster;
+#[cfg(any(feature = "backend", feature = "background", feature = "popup"))]
 pub mod connection;
+#[cfg(any(feature = "foreground", feature = "background"))]
+pub mod game_tabs;
+#[cfg(any(feature = "foreground", feature = "popup"))]
+pub mod i18n;
 #[cfg(feature = "task")]
 pub mod messaging;
 
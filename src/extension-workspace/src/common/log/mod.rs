@@ -32,6 +32,22 @@ macro_rules! map_err {
             $crate::err_code!()
         }
     };
+    ($kind:ident, $context:expr) => {
+        |_err| {
+            ::web_sys::console::debug_5(
+                &::wasm_bindgen::JsValue::from_str(::obfstr::obfstr!("%c MDMA %c %c Rust ")),
+                &::wasm_bindgen::JsValue::from_str(::obfstr::obfstr!(
+                    "background: #8CAAEE; color: black; font-weight: bold; border-radius: 5px;"
+                )),
+                &::wasm_bindgen::JsValue::from_str(::obfstr::obfstr!("")),
+                &::wasm_bindgen::JsValue::from_str(::obfstr::obfstr!(
+                    "background: #CE412B; color: black; font-weight: bold; border-radius: 5px;"
+                )),
+                &_err.into(),
+            );
+            ::wasm_bindgen::JsValue::from($crate::err!($kind, $context))
+        }
+    };
 }
 
 #[macro_export]
@@ -56,6 +72,21 @@ macro_rules! err_code {
     }};
 }
 
+/// Creates a [`MdmaError`](crate::error::MdmaError) located at the call site, e.g.
+/// `err!(Get, "hero")`.
+#[macro_export]
+macro_rules! err {
+    ($kind:ident) => {
+        $crate::error::MdmaError::new(
+            $crate::error::ErrorKind::$kind,
+            $crate::err_code!(as_num) as u32,
+        )
+    };
+    ($kind:ident, $context:expr) => {
+        $crate::err!($kind).context($context)
+    };
+}
+
 #[macro_export]
 macro_rules! trap {
     ($input:expr) => {{
@@ -17,27 +17,45 @@ use uuid::{Uuid, fmt::Simple};
 #[cfg(feature = "extension")]
 use wasm_bindgen::prelude::*;
 
-#[cfg(any(feature = "backend", feature = "background"))]
+#[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+use crate::addon_dashboard::{AddonDashboard, AddonToggle};
+#[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+use crate::catalog::GameCatalog;
+#[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+use crate::clan_roster::ClanRoster;
+#[cfg(any(feature = "backend", feature = "background", feature = "popup"))]
 use crate::connection::SessionScope;
+#[cfg(any(feature = "foreground", feature = "background"))]
+use crate::game_tabs::{CharacterId, TabBroadcast, TabHero};
+#[cfg(feature = "extension")]
+use crate::i18n::Locale;
 #[cfg(feature = "extension")]
 use crate::map_err;
 #[cfg(feature = "popup")]
 use crate::web_extension_sys::browser;
 #[cfg(any(feature = "foreground", feature = "background"))]
 use crate::web_extension_sys::cookies::{self, CookieDetails};
+#[cfg(feature = "background")]
+use crate::web_extension_sys::tabs::TabId;
 
 pub mod validator;
 pub mod prelude {
-    pub use super::{Message, MessageKind, Premium, Target, Task, validator::MessageValidator};
+    pub use super::{
+        DiagnosticsReport, LogLevel, Message, MessageKind, Premium, ReportedError, Target, Task,
+        validator::MessageValidator,
+    };
 
-    #[cfg(any(feature = "backend", feature = "background"))]
+    #[cfg(any(feature = "backend", feature = "background", feature = "popup"))]
     pub use crate::connection::SessionScope;
 
     #[cfg(any(feature = "popup", feature = "background"))]
     pub use super::{PopupMessage, PopupState, PopupUpdate};
 
     #[cfg(any(feature = "foreground", feature = "background"))]
-    pub use super::Cookie;
+    pub use super::{Cookie, PersistedLog};
+
+    #[cfg(feature = "extension")]
+    pub use super::ConnectionState;
 }
 
 // Whenever adding a new task make sure backend is in sync with extension.
@@ -54,6 +72,16 @@ pub enum Task {
     Cookie,
     InitSession,
     TerminateSession,
+    Diagnostics,
+    ConsoleLogs,
+    ConnectionState,
+    Catalog,
+    ClanRoster,
+    AddonDashboard,
+    SessionScope,
+    GameTab,
+    TabBroadcast,
+    Language,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize_repr, Deserialize_repr)]
@@ -148,15 +176,52 @@ pub struct Message {
     pub settings: Option<Value>,
     #[cfg(any(feature = "foreground", feature = "background"))]
     pub cookie: Option<Cookie>,
-    #[cfg(any(feature = "backend", feature = "background"))]
+    #[cfg(any(feature = "backend", feature = "background", feature = "popup"))]
     pub session_scope: Option<SessionScope>,
     pub log_out: Option<LogOutDetails>,
     /// Code provided after logging in via oauth2, used for establishing an
     /// authorized session.
     pub code: Option<String>,
     pub error: Option<String>,
+    /// Error report sent from the foreground when the user opted into diagnostics.
+    pub diagnostics: Option<DiagnosticsReport>,
+    /// MDMA console entries persisted between page reloads.
+    #[cfg(any(feature = "foreground", feature = "background"))]
+    pub console_logs: Option<Vec<PersistedLog>>,
     #[cfg(any(feature = "popup", feature = "background"))]
     pub popup: Option<PopupUpdate>,
+    /// State of the connection between the background and the backend.
+    #[cfg(feature = "extension")]
+    pub connection: Option<ConnectionState>,
+    /// Game catalog served by the backend, `None` if it doesn't have one.
+    #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+    pub catalog: Option<GameCatalog>,
+    /// Clan roster sent for syncing, the response contains the merged roster.
+    #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+    pub clan_roster: Option<ClanRoster>,
+    /// Addon states reported by the game tab.
+    #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+    pub addon_dashboard: Option<AddonDashboard>,
+    /// Addons toggled from the popup.
+    #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+    pub addon_toggles: Option<Vec<AddonToggle>>,
+    /// Character played in the game tab which sent the message.
+    #[cfg(any(feature = "foreground", feature = "background"))]
+    pub game_tab: Option<TabHero>,
+    /// Character the stored console logs and addon dashboard belong to.
+    #[cfg(any(feature = "foreground", feature = "background"))]
+    pub character: Option<CharacterId>,
+    /// Signal relayed between game tabs.
+    #[cfg(any(feature = "foreground", feature = "background"))]
+    pub tab_broadcast: Option<TabBroadcast>,
+    /// Language chosen in the manager settings, `None` follows the game domain.
+    #[cfg(feature = "extension")]
+    pub language: Option<Locale>,
+    /// Game tab the message was received from or should be sent to, every
+    /// game tab receives it if it's `None`.
+    #[cfg(feature = "background")]
+    #[serde(skip)]
+    pub tab_id: Option<TabId>,
 }
 
 impl Message {
@@ -275,13 +340,36 @@ impl fmt::Debug for Message {
             settings,
             #[cfg(any(feature = "foreground", feature = "background"))]
             cookie,
-            #[cfg(any(feature = "backend", feature = "background"))]
+            #[cfg(any(feature = "backend", feature = "background", feature = "popup"))]
             session_scope,
             log_out,
             code,
             error,
+            diagnostics,
+            #[cfg(any(feature = "foreground", feature = "background"))]
+            console_logs,
             #[cfg(any(feature = "popup", feature = "background"))]
             popup,
+            #[cfg(feature = "extension")]
+            connection,
+            #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+            catalog,
+            #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+            clan_roster,
+            #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+            addon_dashboard,
+            #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+            addon_toggles,
+            #[cfg(any(feature = "foreground", feature = "background"))]
+            game_tab,
+            #[cfg(any(feature = "foreground", feature = "background"))]
+            character,
+            #[cfg(any(feature = "foreground", feature = "background"))]
+            tab_broadcast,
+            #[cfg(feature = "extension")]
+            language,
+            #[cfg(feature = "background")]
+            tab_id,
         } = &self;
 
         debug_struct.field("task", &task);
@@ -307,7 +395,7 @@ impl fmt::Debug for Message {
         if let Some(settings) = settings.as_ref() {
             debug_struct.field("settings", settings);
         }
-        #[cfg(any(feature = "backend", feature = "background"))]
+        #[cfg(any(feature = "backend", feature = "background", feature = "popup"))]
         if let Some(session_scope) = session_scope.as_ref() {
             debug_struct.field("session_scope", session_scope);
         }
@@ -320,6 +408,13 @@ impl fmt::Debug for Message {
         if let Some(error) = error.as_ref() {
             debug_struct.field("error", error);
         }
+        if let Some(diagnostics) = diagnostics.as_ref() {
+            debug_struct.field("diagnostics", diagnostics);
+        }
+        #[cfg(any(feature = "foreground", feature = "background"))]
+        if let Some(console_logs) = console_logs.as_ref() {
+            debug_struct.field("console_logs", &console_logs.len());
+        }
         #[cfg(any(feature = "popup", feature = "background"))]
         if let Some(popup) = popup.as_ref() {
             debug_struct.field("popup", popup);
@@ -328,6 +423,46 @@ impl fmt::Debug for Message {
         if let Some(cookie) = cookie.as_ref() {
             debug_struct.field("cookie", cookie);
         }
+        #[cfg(feature = "extension")]
+        if let Some(connection) = connection.as_ref() {
+            debug_struct.field("connection", connection);
+        }
+        #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+        if let Some(catalog) = catalog.as_ref() {
+            debug_struct.field("catalog", &catalog.version);
+        }
+        #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+        if let Some(clan_roster) = clan_roster.as_ref() {
+            debug_struct.field("clan_roster", &(&clan_roster.world, clan_roster.clan_id));
+        }
+        #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+        if let Some(addon_dashboard) = addon_dashboard.as_ref() {
+            debug_struct.field("addon_dashboard", &addon_dashboard.addons.len());
+        }
+        #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+        if let Some(addon_toggles) = addon_toggles.as_ref() {
+            debug_struct.field("addon_toggles", addon_toggles);
+        }
+        #[cfg(any(feature = "foreground", feature = "background"))]
+        if let Some(game_tab) = game_tab.as_ref() {
+            debug_struct.field("game_tab", game_tab);
+        }
+        #[cfg(any(feature = "foreground", feature = "background"))]
+        if let Some(character) = character.as_ref() {
+            debug_struct.field("character", character);
+        }
+        #[cfg(any(feature = "foreground", feature = "background"))]
+        if let Some(tab_broadcast) = tab_broadcast.as_ref() {
+            debug_struct.field("tab_broadcast", tab_broadcast);
+        }
+        #[cfg(feature = "extension")]
+        if let Some(language) = language.as_ref() {
+            debug_struct.field("language", language);
+        }
+        #[cfg(feature = "background")]
+        if let Some(tab_id) = tab_id.as_ref() {
+            debug_struct.field("tab_id", tab_id);
+        }
 
         debug_struct.finish()
     }
@@ -354,6 +489,59 @@ impl Premium {
     }
 }
 
+/// Errors collected by the foreground since the last report, along with the
+/// context needed to reproduce them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiagnosticsReport {
+    /// Extension version the errors were reported from, required to decode
+    /// the error codes.
+    pub version: String,
+    /// `ni` or `si`.
+    pub interface: String,
+    pub addons: Vec<String>,
+    pub errors: Vec<ReportedError>,
+    /// Last events preceding the errors, oldest first.
+    pub events: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportedError {
+    /// Encoded error location, see `common::error::DecodedLocation`, `0` for errors thrown
+    /// by js.
+    pub code: u32,
+    pub kind: String,
+    pub fatal: bool,
+    /// How many times the error occurred since the last report.
+    pub count: u32,
+}
+
+/// Severity of an MDMA console entry.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize_repr, Deserialize_repr,
+)]
+#[repr(u8)]
+pub enum LogLevel {
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+/// MDMA console entry stored by the background between page reloads.
+#[cfg(any(feature = "foreground", feature = "background"))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedLog {
+    pub level: LogLevel,
+    /// `manager` or the key of the addon which added the entry.
+    pub source: String,
+    pub text: String,
+    /// Game event id the entry was added at.
+    pub ev: Option<f64>,
+    /// Unix timestamp in milliseconds.
+    pub logged_at: f64,
+}
+
 // TODO: Better name ?
 #[cfg(any(feature = "popup", feature = "background"))]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -409,6 +597,18 @@ impl PopupMessage {
     }
 }
 
+/// State of the connection between the background and the backend, sent to
+/// the popup and the foreground whenever it changes.
+#[cfg(feature = "extension")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionState {
+    /// The first connection attempt is in progress.
+    Connecting,
+    Connected,
+    /// The socket was closed, next attempt is made after `retry_in` ms.
+    Reconnecting { attempt: u32, retry_in: u32 },
+}
+
 #[cfg(any(feature = "foreground", feature = "background"))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Cookie {
@@ -461,13 +661,36 @@ pub struct MessageBuilder {
     settings: Option<Value>,
     #[cfg(any(feature = "foreground", feature = "background"))]
     pub cookie: Option<Cookie>,
-    #[cfg(any(feature = "backend", feature = "background"))]
+    #[cfg(any(feature = "backend", feature = "background", feature = "popup"))]
     session_scope: Option<SessionScope>,
     log_out: Option<LogOutDetails>,
     code: Option<String>,
     error: Option<String>,
+    diagnostics: Option<DiagnosticsReport>,
+    #[cfg(any(feature = "foreground", feature = "background"))]
+    console_logs: Option<Vec<PersistedLog>>,
     #[cfg(any(feature = "popup", feature = "background"))]
     popup: Option<PopupUpdate>,
+    #[cfg(feature = "extension")]
+    connection: Option<ConnectionState>,
+    #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+    catalog: Option<GameCatalog>,
+    #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+    clan_roster: Option<ClanRoster>,
+    #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+    addon_dashboard: Option<AddonDashboard>,
+    #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+    addon_toggles: Option<Vec<AddonToggle>>,
+    #[cfg(any(feature = "foreground", feature = "background"))]
+    game_tab: Option<TabHero>,
+    #[cfg(any(feature = "foreground", feature = "background"))]
+    character: Option<CharacterId>,
+    #[cfg(any(feature = "foreground", feature = "background"))]
+    tab_broadcast: Option<TabBroadcast>,
+    #[cfg(feature = "extension")]
+    language: Option<Locale>,
+    #[cfg(feature = "background")]
+    tab_id: Option<TabId>,
 }
 
 impl MessageBuilder {
@@ -490,13 +713,36 @@ impl MessageBuilder {
             settings: None,
             #[cfg(any(feature = "foreground", feature = "background"))]
             cookie: None,
-            #[cfg(any(feature = "backend", feature = "background"))]
+            #[cfg(any(feature = "backend", feature = "background", feature = "popup"))]
             session_scope: None,
             log_out: None,
             code: None,
             error: None,
+            diagnostics: None,
+            #[cfg(any(feature = "foreground", feature = "background"))]
+            console_logs: None,
             #[cfg(any(feature = "popup", feature = "background"))]
             popup: None,
+            #[cfg(feature = "extension")]
+            connection: None,
+            #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+            catalog: None,
+            #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+            clan_roster: None,
+            #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+            addon_dashboard: None,
+            #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+            addon_toggles: None,
+            #[cfg(any(feature = "foreground", feature = "background"))]
+            game_tab: None,
+            #[cfg(any(feature = "foreground", feature = "background"))]
+            character: None,
+            #[cfg(any(feature = "foreground", feature = "background"))]
+            tab_broadcast: None,
+            #[cfg(feature = "extension")]
+            language: None,
+            #[cfg(feature = "background")]
+            tab_id: None,
         }
     }
 
@@ -547,6 +793,72 @@ impl MessageBuilder {
         self
     }
 
+    #[cfg(feature = "extension")]
+    pub const fn connection(mut self, connection: ConnectionState) -> Self {
+        self.connection = Some(connection);
+        self
+    }
+
+    #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+    pub fn maybe_catalog(mut self, catalog: Option<GameCatalog>) -> Self {
+        self.catalog = catalog;
+        self
+    }
+
+    #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+    pub fn clan_roster(mut self, clan_roster: ClanRoster) -> Self {
+        self.clan_roster = Some(clan_roster);
+        self
+    }
+
+    #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+    pub fn addon_dashboard(mut self, addon_dashboard: AddonDashboard) -> Self {
+        self.addon_dashboard = Some(addon_dashboard);
+        self
+    }
+
+    #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+    pub fn addon_toggles(mut self, addon_toggles: Vec<AddonToggle>) -> Self {
+        self.addon_toggles = Some(addon_toggles);
+        self
+    }
+
+    #[cfg(any(feature = "foreground", feature = "background"))]
+    pub fn game_tab(mut self, game_tab: TabHero) -> Self {
+        self.game_tab = Some(game_tab);
+        self
+    }
+
+    #[cfg(any(feature = "foreground", feature = "background"))]
+    pub fn character(mut self, character: CharacterId) -> Self {
+        self.character = Some(character);
+        self
+    }
+
+    #[cfg(any(feature = "foreground", feature = "background"))]
+    pub fn tab_broadcast(mut self, tab_broadcast: TabBroadcast) -> Self {
+        self.tab_broadcast = Some(tab_broadcast);
+        self
+    }
+
+    #[cfg(feature = "extension")]
+    pub const fn maybe_language(mut self, language: Option<Locale>) -> Self {
+        self.language = language;
+        self
+    }
+
+    #[cfg(feature = "background")]
+    pub const fn tab_id(mut self, tab_id: TabId) -> Self {
+        self.tab_id = Some(tab_id);
+        self
+    }
+
+    #[cfg(feature = "background")]
+    pub const fn maybe_tab_id(mut self, tab_id: Option<TabId>) -> Self {
+        self.tab_id = tab_id;
+        self
+    }
+
     pub fn code(mut self, code: String) -> Self {
         self.code = Some(code);
         self
@@ -562,12 +874,29 @@ impl MessageBuilder {
         self
     }
 
-    #[cfg(any(feature = "backend", feature = "background"))]
+    pub fn diagnostics(mut self, diagnostics: DiagnosticsReport) -> Self {
+        self.diagnostics = Some(diagnostics);
+        self
+    }
+
+    #[cfg(any(feature = "foreground", feature = "background"))]
+    pub fn console_logs(mut self, console_logs: Vec<PersistedLog>) -> Self {
+        self.console_logs = Some(console_logs);
+        self
+    }
+
+    #[cfg(any(feature = "backend", feature = "background", feature = "popup"))]
     pub fn session_scope(mut self, session_scope: SessionScope) -> Self {
         self.session_scope = Some(session_scope);
         self
     }
 
+    #[cfg(any(feature = "backend", feature = "background", feature = "popup"))]
+    pub fn maybe_session_scope(mut self, session_scope: Option<SessionScope>) -> Self {
+        self.session_scope = session_scope;
+        self
+    }
+
     pub fn username(mut self, username: String) -> Self {
         self.username = Some(username);
         self
@@ -597,13 +926,36 @@ impl MessageBuilder {
             settings: self.settings,
             #[cfg(any(feature = "foreground", feature = "background"))]
             cookie: self.cookie,
-            #[cfg(any(feature = "backend", feature = "background"))]
+            #[cfg(any(feature = "backend", feature = "background", feature = "popup"))]
             session_scope: self.session_scope,
             log_out: self.log_out,
             code: self.code,
             error: self.error,
+            diagnostics: self.diagnostics,
+            #[cfg(any(feature = "foreground", feature = "background"))]
+            console_logs: self.console_logs,
             #[cfg(any(feature = "popup", feature = "background"))]
             popup: self.popup,
+            #[cfg(feature = "extension")]
+            connection: self.connection,
+            #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+            catalog: self.catalog,
+            #[cfg(any(feature = "foreground", feature = "background", feature = "backend"))]
+            clan_roster: self.clan_roster,
+            #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+            addon_dashboard: self.addon_dashboard,
+            #[cfg(any(feature = "foreground", feature = "background", feature = "popup"))]
+            addon_toggles: self.addon_toggles,
+            #[cfg(any(feature = "foreground", feature = "background"))]
+            game_tab: self.game_tab,
+            #[cfg(any(feature = "foreground", feature = "background"))]
+            character: self.character,
+            #[cfg(any(feature = "foreground", feature = "background"))]
+            tab_broadcast: self.tab_broadcast,
+            #[cfg(feature = "extension")]
+            language: self.language,
+            #[cfg(feature = "background")]
+            tab_id: self.tab_id,
         }
     }
 }
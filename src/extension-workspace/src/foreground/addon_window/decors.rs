@@ -1,3 +1,4 @@
+use common::tr;
 use dominator::{Dom, DomBuilder, EventOptions, apply_methods, events::Click};
 use futures_signals::signal::{Signal, SignalExt, not};
 use proc_macros::{add_class_list, builder, with_class_list};
@@ -33,7 +34,7 @@ impl CloseButton {
                 addon_window.active.set_neq(false);
             }))
             .tip!({
-                .text("Zamknij")
+                .text(tr!(WindowClose))
             })
         });
 
@@ -167,8 +168,8 @@ impl CollapseButton {
             .event(|_: Click| addon_window_data.expanded.set(!addon_window_data.expanded.get()))
             .tip!({
                 .text_signal(addon_window_data.expanded.signal_ref(|expanded| match expanded {
-                    true => "Zwiń",
-                    false => "Rozwiń",
+                    true => tr!(WindowCollapse),
+                    false => tr!(WindowExpand),
                 }))
             })
             .disable_dragging()
@@ -201,7 +202,7 @@ impl OpacityToggle {
     pub(super) fn render(self, addon_window_data: &'static AddonWindowDetails) -> Dom {
         let counter = apply_methods!(self.inner, {
             .tip!({
-                .text("Zmień przezroczystość")
+                .text(tr!(WindowOpacity))
             })
             .event(|_: Click| {
                 addon_window_data.opacity_lvl.replace_with(|old| (*old + 1) % 6);
@@ -239,7 +240,7 @@ impl SizeToggle {
     pub(super) fn render(self, addon_window_data: &'static AddonWindowDetails) -> Dom {
         let counter = apply_methods!(self.inner, {
             .tip!({
-                .text("Zmień rozmiar")
+                .text(tr!(WindowSize))
             })
             .event(move |_: Click| {
                 addon_window_data.size.replace_with(|old| (*old + 1) % (self.max_size + 1));
@@ -349,8 +350,8 @@ impl SettingsButton {
             }))
             .tip!({
                 .text_signal(addon_window.active.signal_ref(|active| match *active {
-                    true => "Zamknij ustawienia",
-                    false => "Otwórz ustawienia",
+                    true => tr!(WindowCloseSettings),
+                    false => tr!(WindowOpenSettings),
                 }))
             })
         });
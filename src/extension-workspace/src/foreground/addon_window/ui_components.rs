@@ -1,7 +1,7 @@
 use std::any::{Any, TypeId};
 use std::ops::Not;
 
-use common::throw_err_code;
+use common::{err, throw_err_code, tr};
 use dominator::events::{Change, Click, Focus, Input as InputEvent, KeyDown};
 use dominator::traits::OptionStr;
 use dominator::{Dom, EventOptions, window_size};
@@ -22,12 +22,6 @@ use crate::utils::{
 
 use super::*;
 
-const INVALID_CHARS_MSG: &str = "Tekst zawiera niedozwolone znaki!";
-const INVALID_CAPITALIZATION_MSG: &str =
-    "Wielka litera może znajdować się jedynie na początku słowa lub po znaku `-`!";
-const REPETITION_MSG: &str = "Żaden znak nie może występować więcej niż dwa razy z rzędu!";
-const SPECIAL_CHARS_MSG: &str = "Nick nie może zaczynać się ani kończyć specjalnymi znakami!";
-
 macro_rules! make_component_container {
     ($container_name:ident) => {
         #[allow(unused)]
@@ -469,7 +463,7 @@ impl Window {
         let try_update_root_on_peak = move |window_type: WindowType| {
             let data_lock = addon_data.get(window_type);
             let root_lock = data_lock.root.borrow();
-            let root = root_lock.as_ref().ok_or_else(|| err_code!())?;
+            let root = root_lock.as_ref().ok_or_else(|| err!(Get, "window root"))?;
             // This approach won't work :(
             //common::debug_log!("HAS ON PEAK:", root.class_list().contains("last-on-peak"));
 
@@ -503,12 +497,12 @@ impl Window {
     }
 
     pub(crate) fn build(self) -> JsResult<Dom> {
-        let header = self.header.ok_or_else(|| err_code!())?;
-        let content = self.content.ok_or_else(|| err_code!())?;
+        let header = self.header.ok_or_else(|| err!(Get, "window header"))?;
+        let content = self.content.ok_or_else(|| err!(Get, "window content"))?;
 
         let Some(addon_data) = Addons::get()[self.addon_name].as_ref() else {
             debug_log!(&format!("Could not init root for: {:?}", self.addon_name));
-            return Err(err_code!());
+            return Err(err!(Get, "addon data").into());
         };
 
         if header.has_settings_button() {
@@ -1881,21 +1875,20 @@ impl NickInput {
                 return custom_validity.set_valid(input_elem);
             }
             if nick.chars().count() < 3 {
-                return custom_validity.set_invalid(input_elem, "Nick jest zbyt krótki.");
+                return custom_validity.set_invalid(input_elem, tr!(InputNickTooShort));
             }
             if nick.starts_with('-') || nick.ends_with('-') {
-                return custom_validity.set_invalid(input_elem, SPECIAL_CHARS_MSG);
+                return custom_validity.set_invalid(input_elem, tr!(InputSpecialChars));
             }
             if nicks.lock_ref().contains(&nick) {
-                return custom_validity
-                    .set_invalid(input_elem, "Ten nick znajduje się już na liście.");
+                return custom_validity.set_invalid(input_elem, tr!(InputNickDuplicated));
             }
 
             let has_invalid_char = nick
                 .chars()
                 .any(|char| !is_from_polish_alphabet(char) && !char.is_whitespace() && char != '-');
             if has_invalid_char {
-                return custom_validity.set_invalid(input_elem, INVALID_CHARS_MSG);
+                return custom_validity.set_invalid(input_elem, tr!(InputInvalidChars));
             }
 
             let correct_capitalization = nick.chars().enumerate().all(|(i, c)| {
@@ -1907,7 +1900,7 @@ impl NickInput {
                         .is_some_and(|c| c == '-' || c.is_whitespace())
             });
             if !correct_capitalization {
-                return custom_validity.set_invalid(input_elem, INVALID_CAPITALIZATION_MSG);
+                return custom_validity.set_invalid(input_elem, tr!(InputInvalidCapitalization));
             }
 
             let has_repetition = nick
@@ -1916,7 +1909,7 @@ impl NickInput {
                 .zip(nick.chars().skip(2))
                 .any(|((a, b), c)| a == b && b == c);
             if has_repetition {
-                return custom_validity.set_invalid(input_elem, REPETITION_MSG);
+                return custom_validity.set_invalid(input_elem, tr!(InputRepetition));
             }
 
             custom_validity.set_valid(input_elem);
@@ -1941,7 +1934,7 @@ impl NickInput {
             let nick = input_elem.value().trim().to_lowercase();
 
             if nick.is_empty() {
-                message("Nick jest zbyt krótki.").unwrap_js();
+                message(tr!(InputNickTooShort)).unwrap_js();
                 return;
             }
 
@@ -2014,14 +2007,10 @@ impl ItemNameInput {
                 return custom_validity.set_valid(input_elem);
             }
             if item_name.chars().count() > 60 {
-                return custom_validity
-                    .set_invalid(input_elem, "Nazwa przedmiotu jest zbyt długa.");
+                return custom_validity.set_invalid(input_elem, tr!(InputItemNameTooLong));
             }
             if item_names.lock_ref().contains(&item_name) {
-                return custom_validity.set_invalid(
-                    input_elem,
-                    "Ta nazwa przedmiotu znajduje się już na liście.",
-                );
+                return custom_validity.set_invalid(input_elem, tr!(InputItemNameDuplicated));
             }
 
             let has_invalid_char = item_name.chars().any(|char| {
@@ -2031,7 +2020,7 @@ impl ItemNameInput {
                     && char != '\''
             });
             if has_invalid_char {
-                return custom_validity.set_invalid(input_elem, INVALID_CHARS_MSG);
+                return custom_validity.set_invalid(input_elem, tr!(InputInvalidChars));
             }
 
             custom_validity.set_valid(input_elem);
@@ -2056,7 +2045,7 @@ impl ItemNameInput {
             let item_name = input_elem.value().trim().to_lowercase();
 
             if item_name.is_empty() {
-                message("Nick jest zbyt krótki.").unwrap_js();
+                message(tr!(InputNickTooShort)).unwrap_js();
                 return;
             }
 
@@ -2193,15 +2182,15 @@ impl ScrollWrapperOptionBuilder {
         Self { inner, ..self }
     }
 
-    //pub fn text_signal<B, C>(self, signal: C) -> Self
-    //where
-    //    B: AsStr,
-    //    C: Signal<Item = B> + 'static,
-    //{
-    //    let inner = self.inner.text_signal(signal);
-    //
-    //    Self { inner, ..self }
-    //}
+    pub fn text_signal<B, C>(self, signal: C) -> Self
+    where
+        B: AsStr,
+        C: Signal<Item = B> + 'static,
+    {
+        let inner = self.inner.text_signal(signal);
+
+        Self { inner, ..self }
+    }
 
     pub fn on_click<F: FnMut(Click) + 'static>(self, clb: F) -> Self {
         let inner = self.inner.event(clb);
@@ -16,30 +16,27 @@ impl Settings {
             .heading(
                 Heading::builder()
                     .class_list("m-top[0]")
-                    .text("Akceptuj zaproszenia od"),
+                    .text(tr!(AcceptGroupHeading)),
             )
             .checkbox_pair(
-                Checkbox::builder(self.none.clone()).text("Nieznajomych"),
-                Checkbox::builder(self.friend.clone()).text("Przyjaciół"),
+                Checkbox::builder(self.none.clone()).text(tr!(AcceptGroupNone)),
+                Checkbox::builder(self.friend.clone()).text(tr!(AcceptGroupFriend)),
             )
             .checkbox_pair(
-                Checkbox::builder(self.clan.clone()).text("Członków klanu"),
-                Checkbox::builder(self.clan_ally.clone()).text("Sojuszników klanu"),
+                Checkbox::builder(self.clan.clone()).text(tr!(AcceptGroupClan)),
+                Checkbox::builder(self.clan_ally.clone()).text(tr!(AcceptGroupClanAlly)),
             )
             .apply_if(WorldConfig::has_fractions(), |builder| {
                 builder.checkbox(
-                    Checkbox::builder(self.fraction_ally.clone()).text("Sojuszników frakcji"),
+                    Checkbox::builder(self.fraction_ally.clone())
+                        .text(tr!(AcceptGroupFractionAlly)),
                 )
             })
             .section(ContentSection::new().class_list("label j-c[left] w[100%] a-i[center]"))
             .heading(
                 Heading::builder()
-                    .text("Nie akceptuj automatycznie zaproszeń od")
-                    .info_bubble(
-                        InfoBubble::builder()
-                            .text("Wielkość liter nie ma znaczenia.")
-                            .build(),
-                    ),
+                    .text(tr!(AcceptGroupExcluded))
+                    .info_bubble(InfoBubble::builder().text(tr!(ExcludedNicksInfo)).build()),
             )
             .excluded_nicks_setting(&self.excluded_nicks);
 
@@ -51,9 +48,7 @@ impl Settings {
 }
 
 pub(super) fn init(settings_window: &'static Settings) -> JsResult<()> {
-    let _handle = WINDOWS_ROOT
-        .try_append_dom(settings_window.render()?)
-        .ok_or_else(|| err_code!())?;
+    let _handle = WINDOWS_ROOT.append_window(settings_window.render()?)?;
 
     Ok(())
 }
@@ -52,12 +52,8 @@ impl Settings {
             .heading(
                 Heading::builder()
                     .class_list("m-top[0]")
-                    .text("Nie akceptuj automatycznie przywołań od")
-                    .info_bubble(
-                        InfoBubble::builder()
-                            .text("Wielkość liter nie ma znaczenia.")
-                            .build(),
-                    ),
+                    .text(tr!(AcceptSummonExcluded))
+                    .info_bubble(InfoBubble::builder().text(tr!(ExcludedNicksInfo)).build()),
             )
             .excluded_nicks_setting(&self.excluded_nicks);
 
@@ -69,9 +65,7 @@ impl Settings {
 }
 
 pub(crate) fn init(settings_window: &'static Settings) -> JsResult<()> {
-    let _handle = WINDOWS_ROOT
-        .try_append_dom(settings_window.render()?)
-        .ok_or_else(|| err_code!())?;
+    let _handle = WINDOWS_ROOT.append_window(settings_window.render()?)?;
 
     Ok(())
 }
@@ -63,10 +63,7 @@ fn handle_summon(question: &str, settings_window: &Settings) -> bool {
                 .starts_with(&excluded_nick.to_lowercase())
         }) {
         true => {
-            message(
-                "Nie akceptuje przywołania, ponieważ nick przywołującego jest na liście wykluczeń.",
-            )
-            .unwrap_js();
+            message(tr!(AcceptSummonExcludedSummon)).unwrap_js();
             false
         }
         false => {
@@ -1,7 +1,9 @@
-use dominator::{html, Dom};
+use dominator::events::KeyDown;
+use dominator::{html, Dom, EventOptions};
 use futures_signals::signal::SignalExt;
 
 use crate::addon_window::prelude::*;
+use crate::hotkeys::HotkeyAction;
 use crate::interface::{ThreadLocalShadowRoot, WINDOWS_ROOT};
 use crate::prelude::*;
 
@@ -21,7 +23,7 @@ impl Settings {
                 .checkbox(
                     Checkbox::builder(self.colossus.active.clone())
                         .class_list("w-s[pre-line] l-h[16]")
-                        .text("Wybieraj zestaw automatycznie\nna mapie z kolosami"),
+                        .text(tr!(AdaptiveBuildsColossus)),
                 )
                 .button(
                     Button::builder()
@@ -31,7 +33,7 @@ impl Settings {
                             self.colossus
                                 .build
                                 .signal()
-                                .map(|curr_build| format!("Zestaw {curr_build}")),
+                                .map(|curr_build| tr!(AdaptiveBuildsBuild, build = curr_build)),
                         )
                         .mixin(|builder| {
                             builder.child(html!("div", {
@@ -44,19 +46,51 @@ impl Settings {
                             ScrollWrapper::builder(|| || self.scroll_active.set(false))
                                 .visible_signal(self.scroll_active.signal())
                                 .class_list("w[90] l[1]")
-                                .option(self.build_scroll_wrapper_option("Zestaw 1", 1))
-                                .option(self.build_scroll_wrapper_option("Zestaw 2", 2))
-                                .option(self.build_scroll_wrapper_option("Zestaw 3", 3))
-                                .option(self.build_scroll_wrapper_option("Zestaw 4", 4))
-                                .option(self.build_scroll_wrapper_option("Zestaw 5", 5))
-                                .option(self.build_scroll_wrapper_option("Zestaw 6", 6))
-                                .option(self.build_scroll_wrapper_option("Zestaw 7", 7))
-                                .option(self.build_scroll_wrapper_option("Zestaw 8", 8))
-                                .option(self.build_scroll_wrapper_option("Zestaw 9", 9))
+                                .option(self.build_scroll_wrapper_option(1))
+                                .option(self.build_scroll_wrapper_option(2))
+                                .option(self.build_scroll_wrapper_option(3))
+                                .option(self.build_scroll_wrapper_option(4))
+                                .option(self.build_scroll_wrapper_option(5))
+                                .option(self.build_scroll_wrapper_option(6))
+                                .option(self.build_scroll_wrapper_option(7))
+                                .option(self.build_scroll_wrapper_option(8))
+                                .option(self.build_scroll_wrapper_option(9))
                                 .build(),
                         ),
                 ),
-        );
+        )
+        .section(
+            ContentSection::new()
+                .class_list("d[flex] f-d[row] j-c[space-between] a-i[center]")
+                .checkbox(
+                    self.colossus
+                        .hotkey
+                        .checkbox(HotkeyAction::SwitchBuild)
+                        .class_list("w-s[pre-line] l-h[16]")
+                        .text(tr!(AdaptiveBuildsHotkey)),
+                )
+                .input(
+                    self.colossus
+                        .hotkey
+                        .input(HotkeyAction::SwitchBuild)
+                        .size(InputSize::Custom("w[100]")),
+                ),
+        )
+        .global_event_with_options(&EventOptions::preventable(), move |event: KeyDown| {
+            if !Addons::is_active(ADDON_NAME) {
+                return;
+            }
+
+            match self.colossus.hotkey.is_pressed(&event) {
+                Ok(true) => {
+                    event.prevent_default();
+                    event.stop_immediate_propagation();
+                    self.colossus.switch_build();
+                }
+                Ok(false) => {}
+                Err(err_code) => console_error!(err_code),
+            }
+        });
 
         SettingsWindow::builder(ADDON_NAME)
             .header(window_header)
@@ -64,22 +98,16 @@ impl Settings {
             .build()
     }
 
-    fn build_scroll_wrapper_option(
-        &'static self,
-        txt: &'static str,
-        build_no: u8,
-    ) -> ScrollWrapperOption {
+    fn build_scroll_wrapper_option(&'static self, build_no: u8) -> ScrollWrapperOption {
         ScrollWrapperOption::builder()
-            .text(txt)
+            .text(&tr!(AdaptiveBuildsBuild, build = build_no))
             .on_click(move |_| self.colossus.build.set_neq(build_no))
             .build()
     }
 }
 
 pub(super) fn init(settings: &'static Settings) -> JsResult<()> {
-    let _settings_window_handle = WINDOWS_ROOT
-        .try_append_dom(settings.render()?)
-        .ok_or_else(|| err_code!())?;
+    let _settings_window_handle = WINDOWS_ROOT.append_window(settings.render()?)?;
 
     Ok(())
 }
@@ -3,6 +3,7 @@ mod html;
 use futures_signals::signal::{Mutable, SignalExt};
 use proc_macros::{Setting, Settings};
 
+use crate::hotkeys::{Hotkey, HotkeyAction, HotkeyValue, Hotkeys};
 use crate::prelude::*;
 
 const ADDON_NAME: AddonName = AddonName::AdaptiveBuilds;
@@ -11,6 +12,8 @@ const ADDON_NAME: AddonName = AddonName::AdaptiveBuilds;
 struct ColossusBuild {
     active: Mutable<bool>,
     build: Mutable<u8>,
+    /// Switches to the colossus build right away.
+    hotkey: Hotkey,
 }
 
 impl Default for ColossusBuild {
@@ -18,10 +21,24 @@ impl Default for ColossusBuild {
         Self {
             active: Mutable::default(),
             build: Mutable::new(1),
+            hotkey: Hotkey::new(HotkeyValue::default(), false),
         }
     }
 }
 
+impl ColossusBuild {
+    fn switch_build(&self) {
+        let collosus_build = self.build.get();
+        // Update skill view if it's open
+        let skills = get_engine().skills().map(|_| "&skillshop=1").unwrap_or_default();
+        let task = format!("builds&action=updateCurrent&id={collosus_build}{skills}");
+        if send_task(&task).is_err() {
+            console_error!()
+        }
+        let _ = message(tr!(AdaptiveBuildsSwitching));
+    }
+}
+
 #[derive(Settings, Default)]
 struct Settings {
     colossus: ColossusBuild,
@@ -31,6 +48,7 @@ struct Settings {
 
 pub(crate) fn init() -> JsResult<()> {
     let settings = Settings::new(ADDON_NAME);
+    Hotkeys::register(HotkeyAction::SwitchBuild, &settings.colossus.hotkey);
 
     let future = settings
         .colossus
@@ -49,14 +67,7 @@ pub(crate) fn init() -> JsResult<()> {
         })
         .for_each(|change_build| {
             if change_build {
-                let collosus_build = settings.colossus.build.get();
-                // Update skill view if it's open
-                let skills = get_engine().skills().map(|_| "&skillshop=1").unwrap_or_default();
-                let task = format!("builds&action=updateCurrent&id={collosus_build}{skills}");
-                if send_task(&task).is_err() {
-                    console_error!()
-                }
-                let _ = message("[MDMA::RS] Zmieniam zestaw na kolosy...");
+                settings.colossus.switch_build();
             }
 
             async {}
@@ -9,8 +9,8 @@ use super::{ADDON_NAME, ActiveSettings};
 
 impl ActiveSettings {
     fn render(&'static self) -> JsResult<Dom> {
-        let window_size =
-            Addons::get_window_size(ADDON_NAME, Self::WINDOW_TYPE).ok_or_else(|| err_code!())?;
+        let window_size = Addons::get_window_size(ADDON_NAME, Self::WINDOW_TYPE)
+            .ok_or_else(|| err!(Get, "window size"))?;
         let decor = HeaderDecor::builder()
             .push_left(decors::OpacityToggle::new())
             .push_left(self.build_attack_state_bubble())
@@ -23,9 +23,17 @@ impl ActiveSettings {
         let window_content = WindowContent::builder()
             .class_list("f-d[column]")
             .section(self.anti_afk_settings(window_size))
-            .heading(Heading::builder().class_list("m[0]").text("Kastrat"))
+            .heading(
+                Heading::builder()
+                    .class_list("m[0]")
+                    .text(tr!(AddonKastrat)),
+            )
             .section(self.kastrat_settings(window_size))
-            .heading(Heading::builder().class_list("m[0]").text("Berserker"))
+            .heading(
+                Heading::builder()
+                    .class_list("m[0]")
+                    .text(tr!(AntyDuchBerserker)),
+            )
             .section(self.berserker_settings(window_size));
 
         AddonWindow::builder(ADDON_NAME)
@@ -47,8 +55,8 @@ impl ActiveSettings {
                 apply_methods!(b, {
                     .tip!({
                         .text_signal(self.kastrat.attack_toggle.signal_ref(|active| match active {
-                            true => "Wyłącz atakowanie graczy",
-                            false => "Włącz atakowanie graczy",
+                            true => tr!(AntyDuchAttackDisable),
+                            false => tr!(AntyDuchAttackEnable),
                         }))
                     })
                 })
@@ -99,7 +107,7 @@ impl ActiveSettings {
             .checkbox(
                 Checkbox::builder(self.anti_afk.return_active.clone())
                     .class_list("p-top[6]")
-                    .text("Wracanie na koordy"),
+                    .text(tr!(AntyDuchReturn)),
             )
     }
 
@@ -113,7 +121,7 @@ impl ActiveSettings {
                         .text_signal(|| {
                             window_size.signal_ref(|size| match size {
                                 0 => "",
-                                _ => "Min",
+                                _ => tr!(KastratMin),
                             })
                         })
                         .input_type(InputType::number(1.0, 500.0))
@@ -130,7 +138,7 @@ impl ActiveSettings {
                         .text_signal(|| {
                             window_size.signal_ref(|size| match size {
                                 0 => "",
-                                _ => "Max",
+                                _ => tr!(KastratMax),
                             })
                         })
                         .input_type(InputType::number(1.0, 500.0))
@@ -186,7 +194,7 @@ impl ActiveSettings {
             .checkbox(
                 Checkbox::builder(self.kastrat.return_active.clone())
                     .class_list("p-top[6]")
-                    .text("Wracanie na koordy"),
+                    .text(tr!(AntyDuchReturn)),
             )
     }
 
@@ -200,7 +208,7 @@ impl ActiveSettings {
                         .text_signal(|| {
                             window_size.signal_ref(|size| match size {
                                 0 => "",
-                                _ => "Min",
+                                _ => tr!(KastratMin),
                             })
                         })
                         .input_type(InputType::number(1.0, 99999.0))
@@ -217,7 +225,7 @@ impl ActiveSettings {
                         .text_signal(|| {
                             window_size.signal_ref(|size| match size {
                                 0 => "",
-                                _ => "Max",
+                                _ => tr!(KastratMax),
                             })
                         })
                         .input_type(InputType::number(2.0, 99999.0))
@@ -247,15 +255,13 @@ impl ActiveSettings {
             .checkbox(
                 Checkbox::builder(self.berserker.fast_fight.clone())
                     .class_list("p-top[6]")
-                    .text("Szybka walka"),
+                    .text(tr!(AntyDuchFastFight)),
             )
     }
 }
 
 pub(super) fn init(active_settings: &'static ActiveSettings) -> JsResult<()> {
-    let _active_settings_window_handle = WINDOWS_ROOT
-        .try_append_dom(active_settings.render()?)
-        .ok_or_else(|| err_code!())?;
+    let _active_settings_window_handle = WINDOWS_ROOT.append_window(active_settings.render()?)?;
 
     Ok(())
 }
@@ -16,7 +16,8 @@ use crate::{
     addons::kastrat::TargetData,
     bindings::engine::types::MapMode,
     globals::port::task::{Targets, Task, Tasks},
-    pathfinder::{Pos, pathfind_to},
+    overlay::{Overlay, OverlayColor, OverlayItem, OverlayKey, OverlayShape},
+    pathfinder::{Pos, find_path_or_closest, pathfind_to},
     prelude::*,
 };
 
@@ -25,6 +26,7 @@ use super::kastrat::Target;
 // use super::kastrat::Target;
 
 const ADDON_NAME: AddonName = AddonName::AntyDuch;
+const TARGET_COLOR: OverlayColor = OverlayColor::named("rgba(220, 20, 60, 0.6)");
 pub const MIN_DIFF: f64 = 101.0;
 
 #[derive(Clone, Setting)]
@@ -239,7 +241,7 @@ pub(crate) fn init() -> JsResult<()> {
     kastrat.target.init();
 
     let future = Addons::active_signal(ADDON_NAME)
-        .ok_or_else(|| err_code!())?
+        .ok_or_else(|| err!(Get, "addon active signal"))?
         .switch(|addon_active| {
             kastrat
                 .attack_toggle
@@ -329,9 +331,9 @@ pub(crate) fn init() -> JsResult<()> {
             future.await;
 
             #[cfg(debug_assertions)]
-            let _ = message(&format!(
-                "[MDMA::RS] Atakuję \"{}\"...",
-                kastrat
+            let _ = message(&tr!(
+                KastratAttacking,
+                nick = kastrat
                     .target
                     .lock_ref()
                     .as_ref()
@@ -383,7 +385,7 @@ pub(crate) fn init() -> JsResult<()> {
     wasm_bindgen_futures::spawn_local(future);
 
     let future = Addons::active_signal(ADDON_NAME)
-        .ok_or_else(|| err_code!())?
+        .ok_or_else(|| err!(Get, "addon active signal"))?
         .to_stream()
         .skip(1)
         .for_each(move |active| {
@@ -557,52 +559,79 @@ pub(crate) fn init() -> JsResult<()> {
             .await;
 
             let Some((id, dest)) = npc_opt else {
+                Overlay::replace(ADDON_NAME, []);
                 return;
             };
 
-            // Walk to npc
+            berserker.attack(id, dest).await;
+            Overlay::replace(ADDON_NAME, []);
+        });
+    wasm_bindgen_futures::spawn_local(future);
+
+    html::init(active_settings)
+}
+
+impl Berserker {
+    async fn attack(&self, id: Id, dest: Pos) {
+        // Walk to npc
+        Self::mark_target(id, dest);
+        if let Err(err_code) = go_to(dest).await {
+            console_error!(err_code);
+            return;
+        };
+
+        // Wait at npc location
+        delay_range(self.delay.min.get(), self.delay.max.get()).await;
+
+        // Max 10 tries idc
+        for _ in 0..=10 {
+            // Go again before attack
+            Self::mark_target(id, dest);
             if let Err(err_code) = go_to(dest).await {
                 console_error!(err_code);
                 return;
             };
 
-            // Wait at npc location
-            delay_range(berserker.delay.min.get(), berserker.delay.max.get()).await;
-
-            // Max 10 tries idc
-            for _ in 0..=10 {
-                // Go again before attack
-                if let Err(err_code) = go_to(dest).await {
-                    console_error!(err_code);
-                    return;
-                };
-
-                debug_log!("attacking:", id);
-                let task = format!(
-                    "fight&a=attack&id=-{id}{}",
-                    match berserker.fast_fight.get() {
-                        true => "&ff=1",
-                        false => "",
-                    }
-                );
-                // Attack npc
-                if let Err(err_code) = send_task(&task) {
-                    console_error!(err_code);
-                    return;
+            debug_log!("attacking:", id);
+            let task = format!(
+                "fight&a=attack&id=-{id}{}",
+                match self.fast_fight.get() {
+                    true => "&ff=1",
+                    false => "",
                 }
+            );
+            // Attack npc
+            if let Err(err_code) = send_task(&task) {
+                console_error!(err_code);
+                return;
+            }
 
-                // Wait for kill
-                delay_range(3_000, 5_000).await;
+            // Wait for kill
+            delay_range(3_000, 5_000).await;
 
-                // If killed return else recurse
-                if !Npcs::get().lock_ref().contains_key(&id) {
-                    return;
-                }
+            // If killed return else recurse
+            if !Npcs::get().lock_ref().contains_key(&id) {
+                return;
             }
-        });
-    wasm_bindgen_futures::spawn_local(future);
+        }
+    }
 
-    html::init(active_settings)
+    /// Marks the attacked npc on the map along with the path the hero takes to reach it.
+    fn mark_target(id: Id, dest: Pos) {
+        let mut items = vec![(
+            OverlayKey::new(id, 0),
+            OverlayItem::new(OverlayShape::NpcMark(id), TARGET_COLOR),
+        )];
+        let path = Pos::current_hero_pos().and_then(|start| find_path_or_closest(start, dest));
+        if let Some(path) = path {
+            items.push((
+                OverlayKey::new(id, 1),
+                OverlayItem::new(OverlayShape::Path(path), TARGET_COLOR),
+            ));
+        }
+
+        Overlay::replace(ADDON_NAME, items);
+    }
 }
 
 async fn go_to(dest: Pos) -> JsResult<()> {
@@ -150,34 +150,29 @@ impl WindowContent {
     fn hotkey_setting(self, hotkey: &'static Hotkey, mass_invite_hotkey: &'static Hotkey) -> Self {
         let hotkey_heading = Heading::builder()
             .class_list("m[0]")
-            .text("Skróty klawiszowe").mixin(|builder| {
-            apply_methods!(builder, {
-                .info_bubble!({
-                    .text("Do dokładniejszego zapraszania (np. według nicku) używaj przycisków z okna dodatku!")
+            .text(tr!(BetterGroupInvitesHotkeys))
+            .mixin(|builder| {
+                apply_methods!(builder, {
+                    .info_bubble!({
+                        .text(tr!(BetterGroupInvitesHotkeysInfo))
+                    })
                 })
-            })
-        });
-        let invite_checkbox = Checkbox::builder(hotkey.active.clone())
-            .text("Klawisz do zapraszania")
-            .on_click(Hotkey::on_click_factory(hotkey, mass_invite_hotkey));
-        let invite_input = Input::builder()
-            .class_list("keybind")
-            .value(hotkey.value.get_cloned())
-            .maxlength("1")
-            .on_key_down(Hotkey::on_key_down_factory(hotkey, mass_invite_hotkey));
+            });
+        let invite_checkbox = hotkey
+            .checkbox(HotkeyAction::Invite)
+            .text(tr!(BetterGroupInvitesInviteKey));
+        let invite_input = hotkey.input(HotkeyAction::Invite).class_list("keybind");
         let invite_section = ContentSection::new()
             .class_list("label j-c[space-between]")
             .checkbox(invite_checkbox)
             .input(invite_input);
 
-        let mass_invite_checkbox = Checkbox::builder(mass_invite_hotkey.active.clone())
-            .text("Klawisz do masowego zapraszania")
-            .on_click(Hotkey::on_click_factory(mass_invite_hotkey, hotkey));
-        let mass_invite_input = Input::builder()
-            .class_list("keybind")
-            .value(mass_invite_hotkey.value.get_cloned())
-            .maxlength("1")
-            .on_key_down(Hotkey::on_key_down_factory(mass_invite_hotkey, hotkey));
+        let mass_invite_checkbox = mass_invite_hotkey
+            .checkbox(HotkeyAction::MassInvite)
+            .text(tr!(BetterGroupInvitesMassInviteKey));
+        let mass_invite_input = mass_invite_hotkey
+            .input(HotkeyAction::MassInvite)
+            .class_list("keybind");
         let mass_invite_section = ContentSection::new()
             .class_list("label j-c[space-between]")
             .checkbox(mass_invite_checkbox)
@@ -193,16 +188,19 @@ impl WindowContent {
         min: &'static LinkedInput<u32>,
         max: &'static LinkedInput<u32>,
     ) -> Self {
-        let delay_heading = Heading::builder()
-            .text("Opóźnienie między zaproszeniami")
-            .mixin(|builder| apply_methods!(builder, {
-                .info_bubble!({
-                    .text("Zakres czasowy w milisekundach między wysyłaniem zaproszeń do grupy.")
-                })
-            }));
+        let delay_heading =
+            Heading::builder()
+                .text(tr!(BetterGroupInvitesDelay))
+                .mixin(|builder| {
+                    apply_methods!(builder, {
+                        .info_bubble!({
+                            .text(tr!(BetterGroupInvitesDelayInfo))
+                        })
+                    })
+                });
         let min_delay_input = Input::builder()
             .input_type(InputType::number(100.0, f64::MAX))
-            .text("Minimalne")
+            .text(tr!(BetterGroupInvitesDelayMin))
             .size(InputSize::Custom("w[64]"))
             .placeholder_signal(min.value.signal_ref(|value| value.to_string()))
             .value(min.value.get().to_string())
@@ -211,7 +209,7 @@ impl WindowContent {
             .on_input(LinkedInput::on_input_factory(min, max));
         let max_delay_input = Input::builder()
             .input_type(InputType::number(100.0, f64::MAX))
-            .text("Maksymalne")
+            .text(tr!(BetterGroupInvitesDelayMax))
             .size(InputSize::Custom("w[64]"))
             .placeholder_signal(max.value.signal_ref(|value| value.to_string()))
             .value(max.value.get().to_string())
@@ -226,19 +224,22 @@ impl WindowContent {
     }
 
     fn invite_setting(self, relations: &Relations) -> Self {
-        let invite_setting_heading = Heading::builder().text("Automatycznie wysyłaj do");
-        let none_checkbox = Checkbox::builder(relations.none.clone()).text("Nieznajomych");
-        let friend_checkbox = Checkbox::builder(relations.friend.clone()).text("Przyjaciół");
-        let clan_checkbox = Checkbox::builder(relations.clan.clone()).text("Członków klanu");
+        let invite_setting_heading = Heading::builder().text(tr!(BetterGroupInvitesAutoInvite));
+        let none_checkbox =
+            Checkbox::builder(relations.none.clone()).text(tr!(BetterGroupInvitesStrangers));
+        let friend_checkbox =
+            Checkbox::builder(relations.friend.clone()).text(tr!(BetterGroupInvitesFriends));
+        let clan_checkbox =
+            Checkbox::builder(relations.clan.clone()).text(tr!(BetterGroupInvitesClanMembers));
         let clan_ally_checkbox =
-            Checkbox::builder(relations.clan_ally.clone()).text("Sojuszników klanu");
+            Checkbox::builder(relations.clan_ally.clone()).text(tr!(BetterGroupInvitesClanAllies));
 
         self.heading(invite_setting_heading)
             .checkbox_pair(none_checkbox, friend_checkbox)
             .checkbox_pair(clan_checkbox, clan_ally_checkbox)
             .apply_if(WorldConfig::has_fractions(), |builder| {
-                let fraction_ally_checkbox =
-                    Checkbox::builder(relations.fraction_ally.clone()).text("Sojuszników frakcji");
+                let fraction_ally_checkbox = Checkbox::builder(relations.fraction_ally.clone())
+                    .text(tr!(BetterGroupInvitesFractionAllies));
                 builder.checkbox(fraction_ally_checkbox)
             })
     }
@@ -246,24 +247,30 @@ impl WindowContent {
     //TODO: Rewrite info bubble builder to require build step and make use of ContentSection
     //possible that way
     fn mass_invite_setting(self, mass_invite_peers: &FromPeers) -> Self {
-        let mass_invite_heading = Heading::builder().text("Masowo zapraszaj");
-        let friend_checkbox =
-            Checkbox::builder(mass_invite_peers.friend.clone()).text("Przyjaciół");
-        let clan_checkbox =
-            Checkbox::builder(mass_invite_peers.clan.clone()).text("Członków klanu");
+        let mass_invite_heading = Heading::builder().text(tr!(BetterGroupInvitesMassInvite));
+        let friend_checkbox = Checkbox::builder(mass_invite_peers.friend.clone())
+            .text(tr!(BetterGroupInvitesFriends));
+        let clan_checkbox = Checkbox::builder(mass_invite_peers.clan.clone())
+            .text(tr!(BetterGroupInvitesClanMembers));
         let all_checkbox = Checkbox::builder(mass_invite_peers.from_location.clone())
-            .text("Graczy z mapy")
-            .info_bubble(InfoBubble::builder().mixin(|builder| apply_methods!(builder, {
-                .tip!({
-                    .child(html!("div", {
-                        .class("m-bottom[3]")
-                        .text("Zaproszenia będą wysyłane do graczy z aktualnej lokacji.")
-                    }))
-                    .child(html!("div", {
-                        .text("Zapraszanie graczy z aktualnej lokacji przebiega według ustawień automatycznego zapraszania.")
-                    }))
-                })
-            })).build());
+            .text(tr!(BetterGroupInvitesMapPlayers))
+            .info_bubble(
+                InfoBubble::builder()
+                    .mixin(|builder| {
+                        apply_methods!(builder, {
+                            .tip!({
+                                .child(html!("div", {
+                                    .class("m-bottom[3]")
+                                    .text(tr!(BetterGroupInvitesMapPlayersInfo))
+                                }))
+                                .child(html!("div", {
+                                    .text(tr!(BetterGroupInvitesMapPlayersRelationsInfo))
+                                }))
+                            })
+                        })
+                    })
+                    .build(),
+            );
 
         self.heading(mass_invite_heading)
             .checkbox_pair(friend_checkbox, clan_checkbox)
@@ -273,7 +280,7 @@ impl WindowContent {
     pub(crate) fn excluded_nicks_setting(self, excluded_nicks: &'static NickInput) -> Self {
         let exclusion_list_input = Input::builder()
             .class_list("m-left[10]")
-            .placeholder("Nick Gracza")
+            .placeholder(tr!(BetterGroupInvitesNickPlaceholder))
             .maxlength("21")
             .text_align(TextAlign::Left)
             .size(InputSize::Big)
@@ -315,47 +322,57 @@ impl WindowContent {
     }
 
     fn invite_with_professions_setting(self, professions: &'static Professions) -> Self {
-        self.checkbox(Checkbox::builder(professions.active.clone()).text("Zaproś według profesji"))
-            .section_signal(professions.active.signal().map(move |active| {
-                if !active {
-                    return None;
-                }
-
-                Some(
-                    ContentSection::new()
-                        .class_list("label h[auto] f-d[column] j-c[space-between] g[3] p-top[6]")
-                        .invite_with_profession_setting(
-                            &professions,
-                            &professions.warrior,
-                            "Wojownicy",
-                        )
-                        .invite_with_profession_setting(&professions, &professions.mage, "Magowie")
-                        .invite_with_profession_setting(&professions, &professions.hunter, "Łowcy")
-                        .invite_with_profession_setting(
-                            &professions,
-                            &professions.paladin,
-                            "Paladyni",
-                        )
-                        .invite_with_profession_setting(
-                            &professions,
-                            &professions.blade_dancer,
-                            "Tancerze ostrzy",
-                        )
-                        .invite_with_profession_setting(
-                            &professions,
-                            &professions.tracker,
-                            "Tropiciele",
-                        ),
-                )
-            }))
+        self.checkbox(
+            Checkbox::builder(professions.active.clone()).text(tr!(BetterGroupInvitesByProfession)),
+        )
+        .section_signal(professions.active.signal().map(move |active| {
+            if !active {
+                return None;
+            }
+
+            Some(
+                ContentSection::new()
+                    .class_list("label h[auto] f-d[column] j-c[space-between] g[3] p-top[6]")
+                    .invite_with_profession_setting(
+                        &professions,
+                        &professions.warrior,
+                        tr!(BetterGroupInvitesWarriors),
+                    )
+                    .invite_with_profession_setting(
+                        &professions,
+                        &professions.mage,
+                        tr!(BetterGroupInvitesMages),
+                    )
+                    .invite_with_profession_setting(
+                        &professions,
+                        &professions.hunter,
+                        tr!(BetterGroupInvitesHunters),
+                    )
+                    .invite_with_profession_setting(
+                        &professions,
+                        &professions.paladin,
+                        tr!(BetterGroupInvitesPaladins),
+                    )
+                    .invite_with_profession_setting(
+                        &professions,
+                        &professions.blade_dancer,
+                        tr!(BetterGroupInvitesBladeDancers),
+                    )
+                    .invite_with_profession_setting(
+                        &professions,
+                        &professions.tracker,
+                        tr!(BetterGroupInvitesTrackers),
+                    ),
+            )
+        }))
     }
 
     fn invite_with_nick_setting(self, nicks: &'static Nicks) -> Self {
         let nick_checkbox = Checkbox::builder(nicks.active.clone())
-            .text("Zaproś według nicków")
+            .text(tr!(BetterGroupInvitesByNick))
             .info_bubble(
                 InfoBubble::builder()
-                    .text("Wielkość liter nie ma znaczenia.")
+                    .text(tr!(BetterGroupInvitesCaseInsensitive))
                     .build(),
             );
 
@@ -368,7 +385,7 @@ impl WindowContent {
                 let with_nicks = &nicks.values;
                 let nicks_input = Input::builder()
                     .class_list("m-left[10] p-left[10]")
-                    .placeholder("Nick Gracza")
+                    .placeholder(tr!(BetterGroupInvitesNickPlaceholder))
                     .maxlength("21")
                     .size(InputSize::Custom("w[127]"))
                     .text_align(TextAlign::Left)
@@ -415,34 +432,36 @@ impl WindowContent {
     }
 
     fn invite_with_lvl_setting(self, lvl_range: &'static LevelRange) -> Self {
-        self.checkbox(Checkbox::builder(lvl_range.active.clone()).text("Zaproś według poziomów"))
-            .input_pair_signal(lvl_range.active.signal_ref(move |&active| {
-                if !active {
-                    return None;
-                }
-
-                let min_lvl_range = &lvl_range.min;
-                let max_lvl_range = &lvl_range.max;
-                let min_lvl_range_input = Input::builder()
-                    .input_type(InputType::number(1.0, 500.0))
-                    .placeholder_signal(min_lvl_range.value.signal_ref(|lvl| lvl.to_string()))
-                    .value(min_lvl_range.value.get().to_string())
-                    .store_root(&min_lvl_range.root)
-                    .with_tooltip(&min_lvl_range.custom_validity)
-                    .on_input(LinkedInput::on_input_factory(min_lvl_range, max_lvl_range));
-                let max_lvl_range_input = Input::builder()
-                    .input_type(InputType::number(1.0, 500.0))
-                    .placeholder_signal(max_lvl_range.value.signal_ref(|lvl| lvl.to_string()))
-                    .value(max_lvl_range.value.get().to_string())
-                    .store_root(&max_lvl_range.root)
-                    .with_tooltip(&max_lvl_range.custom_validity)
-                    .on_input(LinkedInput::on_input_factory(max_lvl_range, min_lvl_range));
-
-                Some(
-                    InputPair::builder(min_lvl_range_input, max_lvl_range_input)
-                        .class_list("j-c[center] f[1]"),
-                )
-            }))
+        self.checkbox(
+            Checkbox::builder(lvl_range.active.clone()).text(tr!(BetterGroupInvitesByLevel)),
+        )
+        .input_pair_signal(lvl_range.active.signal_ref(move |&active| {
+            if !active {
+                return None;
+            }
+
+            let min_lvl_range = &lvl_range.min;
+            let max_lvl_range = &lvl_range.max;
+            let min_lvl_range_input = Input::builder()
+                .input_type(InputType::number(1.0, 500.0))
+                .placeholder_signal(min_lvl_range.value.signal_ref(|lvl| lvl.to_string()))
+                .value(min_lvl_range.value.get().to_string())
+                .store_root(&min_lvl_range.root)
+                .with_tooltip(&min_lvl_range.custom_validity)
+                .on_input(LinkedInput::on_input_factory(min_lvl_range, max_lvl_range));
+            let max_lvl_range_input = Input::builder()
+                .input_type(InputType::number(1.0, 500.0))
+                .placeholder_signal(max_lvl_range.value.signal_ref(|lvl| lvl.to_string()))
+                .value(max_lvl_range.value.get().to_string())
+                .store_root(&max_lvl_range.root)
+                .with_tooltip(&max_lvl_range.custom_validity)
+                .on_input(LinkedInput::on_input_factory(max_lvl_range, min_lvl_range));
+
+            Some(
+                InputPair::builder(min_lvl_range_input, max_lvl_range_input)
+                    .class_list("j-c[center] f[1]"),
+            )
+        }))
     }
 
     fn invite_buttons(
@@ -452,14 +471,14 @@ impl WindowContent {
     ) -> Self {
         let invite_button = Button::builder()
             .class_list("w[80] f-s[11]")
-            .text("Zaproś")
+            .text(tr!(BetterGroupInvitesInvite))
             .on_click(ActiveSettings::invite_button_onclick_factory(
                 active_settings,
                 settings,
             ));
         let mass_invite_button = Button::builder()
             .class_list("w[105] f-s[11]")
-            .text("Zaproś masowo")
+            .text(tr!(BetterGroupInvitesInviteMass))
             .on_click(ActiveSettings::mass_invite_button_onclick_factory(
                 active_settings,
                 settings,
@@ -469,6 +488,21 @@ impl WindowContent {
 
         self.button_pair(invite_button_pair)
     }
+
+    fn request_invite_button(self) -> Self {
+        let request_invite_button = Button::builder()
+            .class_list("w[190] f-s[11] m-top[6]")
+            .text(tr!(BetterGroupInvitesRequestInvite))
+            .on_click(|_| {
+                wasm_bindgen_futures::spawn_local(async {
+                    if let Err(err_code) = request_invite_from_other_tabs().await {
+                        console_error!(err_code);
+                    }
+                })
+            });
+
+        self.button(request_invite_button)
+    }
 }
 
 impl ContentSection {
@@ -515,17 +549,19 @@ impl Settings {
             .build();
         let settings_window_header = WindowHeader::new(decor);
 
-        let exclusion_list_heading = Heading::builder().text("Lista wykluczeń").mixin(|builder| {
-            apply_methods!(builder, {
-                .info_bubble!({
-                    .child(html!("div", {
-                        .class("m-bottom[3]")
-                        .text("Gracze z tej listy nie będą automatycznie zapraszani do grupy.")
-                    }))
-                    .text("Wielkość liter nie ma znaczenia.")
+        let exclusion_list_heading = Heading::builder()
+            .text(tr!(BetterGroupInvitesExclusionList))
+            .mixin(|builder| {
+                apply_methods!(builder, {
+                    .info_bubble!({
+                        .child(html!("div", {
+                            .class("m-bottom[3]")
+                            .text(tr!(BetterGroupInvitesExclusionListInfo))
+                        }))
+                        .text(tr!(BetterGroupInvitesCaseInsensitive))
+                    })
                 })
-            })
-        });
+            });
 
         let settings_window_content = WindowContent::builder()
             .hotkey_setting(&self.hotkey, &self.mass_invite.hotkey)
@@ -563,7 +599,8 @@ impl ActiveSettings {
             .invite_with_professions_setting(&self.with_prof)
             .invite_with_nick_setting(&self.with_nicks)
             .invite_with_lvl_setting(&self.with_lvl)
-            .invite_buttons(&self, &settings);
+            .invite_buttons(&self, &settings)
+            .request_invite_button();
 
         AddonWindow::builder(AddonName::BetterGroupInvites)
             .header(addon_window_header)
@@ -576,13 +613,9 @@ pub(super) fn init(
     settings: &'static Settings,
     active_settings: &'static ActiveSettings,
 ) -> JsResult<()> {
-    let _settings_window_handle = WINDOWS_ROOT
-        .try_append_dom(settings.render()?)
-        .ok_or_else(|| err_code!())?;
+    let _settings_window_handle = WINDOWS_ROOT.append_window(settings.render()?)?;
 
-    let _addon_window_handle = WINDOWS_ROOT
-        .try_append_dom(active_settings.render(settings)?)
-        .ok_or_else(|| err_code!())?;
+    let _addon_window_handle = WINDOWS_ROOT.append_window(active_settings.render(settings)?)?;
 
     Ok(())
 }
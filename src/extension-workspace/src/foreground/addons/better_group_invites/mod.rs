@@ -14,6 +14,7 @@ use std::fmt::Display;
 use std::iter::Filter;
 use std::ops::Deref;
 
+use common::game_tabs::TabSignal;
 use dominator::events::{Click, Input as InputEvent, KeyDown};
 use futures_signals::signal::{Mutable, Signal, SignalExt};
 use proc_macros::{ActiveSettings, Setting, Settings};
@@ -23,10 +24,10 @@ use web_sys::{Element, HtmlInputElement};
 
 use crate::addon_window::ui_components::{ChangeValidity, NickInput};
 use crate::bindings::engine::communication;
+use crate::hotkeys::{Hotkey, HotkeyAction, HotkeyValue, Hotkeys};
 use crate::prelude::*;
 
 const ADDON_NAME: AddonName = AddonName::BetterGroupInvites;
-const ACTIVE_MESSAGE: &str = "Skróty klawiszowe nie mogą być jednocześnie aktywne, jeśli mają przypisane takie same wartości!";
 
 // TODO: Maybe make the fields clones of Mutable<T> ?
 struct Candidate {
@@ -194,21 +195,21 @@ where
         let value = first_root.value();
 
         if value.starts_with("0") && value.len() > 1 {
-            first_validity.set_invalid(first_root, String::from("Nieprawidłowa wartość!"));
+            first_validity.set_invalid(first_root, String::from(tr!(InputInvalidValue)));
             return None;
         }
 
         let validation_error = match first_root.validity() {
             validity if validity.range_overflow() => Some(match first_root.max() {
-                max if max.is_empty() => "Przekroczono maksymalną wartość!".to_string(),
-                max => format!("Maksymalna wartość wynosi {}!", max),
+                max if max.is_empty() => tr!(InputMaxExceeded).to_owned(),
+                max => tr!(InputMaxValue, max = max),
             }),
             validity if validity.range_underflow() => Some(match first_root.min() {
-                min if min.is_empty() => "Przekroczono minimalną wartość!".to_string(),
-                min => format!("Minimalna wartość wynosi {}!", min),
+                min if min.is_empty() => tr!(InputMinExceeded).to_owned(),
+                min => tr!(InputMinValue, min = min),
             }),
             validity if validity.valid() => None,
-            _ => Some(String::from("Nieprawidłowa wartość!")),
+            _ => Some(String::from(tr!(InputInvalidValue))),
         };
 
         if let Some(validation_error) = validation_error {
@@ -222,13 +223,15 @@ where
         }
 
         let value = T::from_f64(first_root.value_as_number());
-        let error_msg = match first_range {
-            Range::Maximum if value < second_value => "mniejsza od wartości minimalnej",
-            Range::Minimum if value > second_value => "większa od wartości maksymalnej",
+        let validation_error = match first_range {
+            Range::Maximum if value < second_value => {
+                tr!(InputBelowMinimum, minimum = second_value)
+            }
+            Range::Minimum if value > second_value => {
+                tr!(InputAboveMaximum, maximum = second_value)
+            }
             _ => return Some(value), // value passed validation checks
         };
-
-        let validation_error = format!("Wartość nie może być {} ({})!", error_msg, second_value);
         first_validity.set_invalid(first_root, validation_error);
 
         None
@@ -250,75 +253,6 @@ impl Delay {
     }
 }
 
-#[derive(Setting)]
-struct Hotkey {
-    value: Mutable<String>,
-    active: Mutable<bool>,
-}
-
-impl Hotkey {
-    fn new(value: &str, active: bool) -> Self {
-        Self {
-            value: Mutable::new(value.to_owned()),
-            active: Mutable::new(active),
-        }
-    }
-
-    #[inline]
-    fn on_key_down_factory(
-        first: &'static Self,
-        second: &'static Self,
-    ) -> impl FnMut(KeyDown, &HtmlInputElement) + 'static {
-        #[inline]
-        move |event: KeyDown, input_elem: &HtmlInputElement| {
-            let value = event.key().to_ascii_uppercase();
-
-            if value.chars().count() > 1 {
-                if value == "ESCAPE" {
-                    if let Err(_err) = input_elem.blur() {
-                        debug_log!(_err);
-                        console_error!();
-                    }
-                }
-
-                event.prevent_default();
-                event.stop_propagation();
-                return;
-            }
-            if value.is_empty() || value.chars().any(|c| !c.is_ascii_alphabetic()) {
-                return;
-            }
-            if value == second.value.get_cloned() && second.active.get() && first.active.get() {
-                second.active.set(false);
-                message(ACTIVE_MESSAGE).unwrap_js();
-            }
-            if let Err(_err) = input_elem.blur() {
-                debug_log!(_err);
-                console_error!();
-            }
-
-            event.prevent_default();
-            event.stop_propagation();
-            input_elem.set_value(&value);
-            first.value.set(value);
-        }
-    }
-
-    fn on_click_factory(
-        first: &'static Self,
-        second: &'static Self,
-    ) -> impl FnMut(Click) + 'static {
-        move |_| {
-            if second.value.get_cloned() == first.value.get_cloned() {
-                message(ACTIVE_MESSAGE).unwrap_js();
-                second.active.set(false);
-            }
-
-            first.active.set(!first.active.get());
-        }
-    }
-}
-
 #[derive(Setting)]
 struct Relations {
     none: Mutable<bool>,
@@ -385,12 +319,12 @@ struct Settings {
 
 impl Default for Settings {
     fn default() -> Self {
-        let mass_invite_hotkey = Hotkey::new(s!("B"), true);
+        let mass_invite_hotkey = Hotkey::new(HotkeyValue::new(s!("B"), false), true);
         let mass_invite_peers = FromPeers::new(false, true, true);
         Self {
             delay: Delay::new(150, 200),
             excluded_nicks: NickInput::default(),
-            hotkey: Hotkey::new(s!("V"), true),
+            hotkey: Hotkey::new(HotkeyValue::new(s!("V"), false), true),
             inviting: Mutable::new(false),
             interrupt: RefCell::new(Vec::new()),
             mass_invite: MassInvite::new(mass_invite_hotkey, mass_invite_peers),
@@ -480,20 +414,20 @@ impl ProfessionSetting {
             if input_value.starts_with("0") && input_value.len() > 1 {
                 professions.turn_off_checkboxes();
                 return custom_validity
-                    .set_invalid(input_elem, String::from("Nieprawidłowa wartość!"));
+                    .set_invalid(input_elem, String::from(tr!(InputInvalidValue)));
             }
 
             let validation_error = match input_elem.validity() {
                 validity if validity.range_overflow() => Some(match input_elem.max() {
-                    max if max.is_empty() => "Przekroczono maksymalną wartość!".to_string(),
-                    max => format!("Maksymalna wartość wynosi {}!", max),
+                    max if max.is_empty() => tr!(InputMaxExceeded).to_owned(),
+                    max => tr!(InputMaxValue, max = max),
                 }),
                 validity if validity.range_underflow() => Some(match input_elem.min() {
-                    min if min.is_empty() => "Przekroczono minimalną wartość!".to_string(),
-                    min => format!("Minimalna wartość wynosi {}!", min),
+                    min if min.is_empty() => tr!(InputMinExceeded).to_owned(),
+                    min => tr!(InputMinValue, min = min),
                 }),
                 validity if validity.valid() => None,
-                _ => Some(String::from("Nieprawidłowa wartość!")),
+                _ => Some(String::from(tr!(InputInvalidValue))),
             };
 
             if let Some(validation_error) = validation_error {
@@ -511,7 +445,7 @@ impl ProfessionSetting {
 
             if active.get() && !professions.below_party_limit(&target_prof) {
                 professions.turn_off_checkboxes();
-                message("Maksymalna liczba zaproszeń to 9!").unwrap_js();
+                message(tr!(BetterGroupInvitesLimit)).unwrap_js();
             }
         }
     }
@@ -615,7 +549,7 @@ impl Professions {
                 false => {
                     event.prevent_default();
                     event.stop_immediate_propagation();
-                    message("Maksymalna liczba zaproszeń to 9!").unwrap_js();
+                    message(tr!(BetterGroupInvitesLimit)).unwrap_js();
                 }
             }
         }
@@ -817,6 +751,8 @@ impl ActiveSettings {
 pub(crate) fn init() -> JsResult<()> {
     let settings = Settings::new(ADDON_NAME);
     let active_settings = ActiveSettings::new(ADDON_NAME);
+    Hotkeys::register(HotkeyAction::Invite, &settings.hotkey);
+    Hotkeys::register(HotkeyAction::MassInvite, &settings.mass_invite.hotkey);
 
     try_add_listener(settings)?;
     html::init(settings, active_settings)
@@ -830,17 +766,11 @@ enum InviteType {
 }
 
 impl InviteType {
-    fn get(event: &web_sys::KeyboardEvent, settings: &Settings) -> JsResult<Self> {
-        use crate::utils::window_events::verify_keyboard_event;
-
-        if settings.hotkey.active.get()
-            && verify_keyboard_event(event, &settings.hotkey.value.get_cloned())?
-        {
+    fn get(event: &KeyDown, settings: &Settings) -> JsResult<Self> {
+        if settings.hotkey.is_pressed(event)? {
             return Ok(Self::Regular);
         }
-        if settings.mass_invite.hotkey.active.get()
-            && verify_keyboard_event(event, &settings.mass_invite.hotkey.value.get_cloned())?
-        {
+        if settings.mass_invite.hotkey.is_pressed(event)? {
             return Ok(Self::Mass);
         }
 
@@ -853,12 +783,15 @@ impl InviteType {
 fn try_add_listener(settings: &'static Settings) -> JsResult<()> {
     use crate::utils::window;
     use common::closure;
+    use dominator::traits::StaticEvent;
 
-    let better_group_invites_listener = closure!(move |event: web_sys::KeyboardEvent| async move {
+    let better_group_invites_listener = closure!(move |event: web_sys::Event| async move {
         if !Addons::is_active(ADDON_NAME) {
             return;
         }
 
+        let event = KeyDown::unchecked_from_event(event);
+
         match InviteType::get(&event, &settings).unwrap_js() {
             InviteType::Regular => {
                 event.prevent_default();
@@ -880,7 +813,7 @@ fn try_add_listener(settings: &'static Settings) -> JsResult<()> {
 
     window()
         .add_event_listener_with_callback("keydown", &better_group_invites_listener)
-        .map_err(map_err!())
+        .map_err(map_err!(Call, "invite hotkey listener"))
 }
 
 trait FilterCanInvite {
@@ -1023,13 +956,13 @@ fn get_regular_invite_candidates(settings: &Settings) -> HashSet<OtherId> {
 
 async fn send_regular_invite_invites(mut candidates: Iter<'_, OtherId>, settings: &Settings) {
     if settings.inviting.get() {
-        message("Usuwam zakolejkowane zaproszenia...").unwrap_js();
+        message(tr!(BetterGroupInvitesClearing)).unwrap_js();
 
         settings.interrupt.borrow_mut().push(());
     }
 
     let Some(candidate_id) = candidates.next() else {
-        message("Brak graczy do zapraszania!").unwrap_js();
+        message(tr!(BetterGroupInvitesNoPlayers)).unwrap_js();
         return;
     };
 
@@ -1120,13 +1053,13 @@ async fn send_mass_invite_invites(mut candidates: Iter<'_, OtherId>, settings: &
     use crate::utils::delay_range;
 
     if settings.inviting.get() {
-        message("Usuwam zakolejkowane zaproszenia...").unwrap_js();
+        message(tr!(BetterGroupInvitesClearing)).unwrap_js();
 
         settings.interrupt.borrow_mut().push(());
     }
 
     let Some(candidate_id) = candidates.next() else {
-        message("Brak graczy do zapraszania!").unwrap_js();
+        message(tr!(BetterGroupInvitesNoPlayers)).unwrap_js();
         return;
     };
 
@@ -1167,3 +1100,12 @@ async fn send_mass_invite_invites(mut candidates: Iter<'_, OtherId>, settings: &
 
     settings.inviting.set(false);
 }
+
+/// Asks another game tab on the same world, the party leader if it's played in
+/// one, to invite the hero.
+async fn request_invite_from_other_tabs() -> JsResult<()> {
+    GameTabs::broadcast(TabSignal::InviteRequest).await?;
+    message(tr!(BetterGroupInvitesRequested))?;
+
+    Ok(())
+}
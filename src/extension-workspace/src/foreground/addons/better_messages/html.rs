@@ -1,9 +1,10 @@
 use std::ops::Deref;
 
 use dominator::events::Input as InputEvent;
-use dominator::{clone, stylesheet, Dom};
+use dominator::{clone, html, stylesheet, Dom};
 use futures_signals::map_ref;
-use futures_signals::signal::SignalExt;
+use futures_signals::signal::{Mutable, SignalExt};
+use futures_signals::signal_vec::SignalVecExt;
 use web_sys::HtmlInputElement;
 
 use crate::addon_window::prelude::*;
@@ -11,6 +12,9 @@ use crate::interface::{ThreadLocalShadowRoot, WINDOWS_ROOT};
 use crate::prelude::*;
 
 use super::*;
+use super::rules::compile_regex;
+use crate::addons::chat_history::{CHANNELS, channel_label};
+use crate::bindings::engine::communication::ChatChannelName;
 
 impl WindowContent {
     fn font_size_setting(self, font: &Font) -> Self {
@@ -18,7 +22,8 @@ impl WindowContent {
             size,
             active: checked,
         } = font;
-        let font_size_checkbox = Checkbox::builder(checked.clone()).text("Rozmiar tekstu");
+        let font_size_checkbox =
+            Checkbox::builder(checked.clone()).text(tr!(BetterMessagesFontSize));
         let font_size_input = Input::builder()
             .value(font.size.get().to_string())
             .input_type(InputType::number(0.0, 999.0))
@@ -54,7 +59,7 @@ impl WindowContent {
             code,
             active: checked,
         } = color;
-        let color_checkbox = Checkbox::builder(checked.clone()).text("Kolor tekstu");
+        let color_checkbox = Checkbox::builder(checked.clone()).text(tr!(BetterMessagesColor));
         let color_input = Input::builder()
             .input_type(InputType::color())
             .size(InputSize::Color)
@@ -72,7 +77,7 @@ impl WindowContent {
 
     fn pointer_events_setting(self, pointer_events: &Mutable<bool>) -> Self {
         let pointer_events_checkbox =
-            Checkbox::builder(pointer_events.clone()).text("Interakcja z tekstem");
+            Checkbox::builder(pointer_events.clone()).text(tr!(BetterMessagesPointerEvents));
         let pointer_events_section = ContentSection::new()
             .class_list("label j-c[space-between]")
             .checkbox(pointer_events_checkbox);
@@ -88,8 +93,8 @@ impl WindowContent {
                     .testing
                     .active
                     .signal_ref(|&active| match active {
-                        true => "Stop test",
-                        false => "Test",
+                        true => tr!(BetterMessagesStopTest),
+                        false => tr!(BetterMessagesTest),
                     }),
             )
             .on_click(move |_| {
@@ -144,13 +149,17 @@ impl ActiveSettings {
         let addon_window_content = WindowContent::builder()
             .heading(
                 Heading::builder()
-                    .text("Ustawienia tekstu")
+                    .text(tr!(BetterMessagesTextSettings))
                     .class_list("m[0]"),
             )
             .font_size_setting(&self.font)
             .color_setting(&self.color)
             .pointer_events_setting(&self.pointer_events)
-            .test_button(self);
+            .test_button(self)
+            .heading(Heading::builder().text(tr!(BetterMessagesPinned)))
+            .section(self.render_pinned())
+            .heading(Heading::builder().text(tr!(BetterMessagesForwarded)))
+            .section(self.render_forwarded());
 
         AddonWindow::builder(AddonName::BetterMessages)
             .header(addon_window_header)
@@ -159,8 +168,330 @@ impl ActiveSettings {
     }
 }
 
-pub(super) fn init(active_settings: &'static ActiveSettings) -> JsResult<()> {
-    let addon_data = Addons::get_addon(ADDON_NAME).ok_or_else(|| err_code!())?;
+impl FeedEntry {
+    fn render(&self) -> ContentSection {
+        let color = self.color.clone();
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] w-s[pre-line]")
+            .apply_if(color.is_some(), |section| {
+                section.mixin(|builder| builder.style("color", color.unwrap_or_default()))
+            })
+            .section(ContentSection::new().class_list("o[60%]").text(&self.entry.header()))
+            .section(ContentSection::new().text(&self.entry.text))
+    }
+}
+
+impl ActiveSettings {
+    fn render_pinned(&'static self) -> ContentSection {
+        let pinned = self.feed.pinned.signal_vec_cloned().map(move |feed_entry| {
+            let entry = feed_entry.entry.clone();
+
+            ContentSection::new()
+                .class_list("d[flex] f-d[row] j-c[space-between] a-i[center] g[5]")
+                .section(feed_entry.render())
+                .button(
+                    Button::builder()
+                        .text(tr!(BetterMessagesUnpin))
+                        .on_click(move |_| self.feed.unpin(&entry)),
+                )
+        });
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[5] max-h[150] scroll-y")
+            .section_signal_vec(pinned)
+    }
+
+    fn render_forwarded(&'static self) -> ContentSection {
+        let forwarded = self
+            .feed
+            .forwarded
+            .signal_vec_cloned()
+            .map(|feed_entry| feed_entry.render());
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[5] max-h[250] scroll-y b-f[glassy-blur]")
+            .section_signal_vec(forwarded)
+    }
+}
+
+impl Settings {
+    fn draft_input(
+        &'static self,
+        label: &str,
+        placeholder: &str,
+        value: &'static Mutable<String>,
+    ) -> ContentSection {
+        let input = Input::builder()
+            .placeholder(placeholder)
+            .maxlength("100")
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .value(value.lock_ref().as_str())
+            .on_input(move |_event, input_elem| value.set(input_elem.value()));
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between] a-i[center] g[10]")
+            .section(ContentSection::new().text(label))
+            .input(input)
+    }
+
+    fn channel_option(&'static self, channel: Option<ChatChannelName>) -> ScrollWrapperOption {
+        ScrollWrapperOption::builder()
+            .text(channel.map(channel_label).unwrap_or(tr!(ChatChannelAll)))
+            .on_click(move |_| self.draft.channel.set_neq(channel))
+            .build()
+    }
+
+    fn draft_channel_setting(&'static self) -> ContentSection {
+        // TODO: Figure out a better solution.
+        let wrapper_state: &'static _ = Box::leak(Box::new(Mutable::default()));
+        let scroll_wrapper = CHANNELS.iter().fold(
+            ScrollWrapper::builder(|| {
+                || {
+                    wrapper_state.set_neq(false);
+                }
+            })
+            .class_list("w[150] l[1]")
+            .visible_signal(wrapper_state.signal())
+            .option(self.channel_option(None)),
+            |scroll_wrapper, (channel, _)| scroll_wrapper.option(self.channel_option(Some(*channel))),
+        );
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between]")
+            .section(
+                ContentSection::new()
+                    .class_list("a-c[center]")
+                    .text(tr!(ChatChannel)),
+            )
+            .button(
+                Button::builder()
+                    .class_list("w[158] t-a[left]")
+                    .no_hover()
+                    .text_signal(
+                        self.draft.channel.signal().map(|channel| {
+                            channel.map(channel_label).unwrap_or(tr!(ChatChannelAll))
+                        }),
+                    )
+                    .on_click(|_| wrapper_state.set_neq(true))
+                    .on_mousedown(|event| event.stop_propagation())
+                    .mixin(|builder| {
+                        builder.child(html!("div", {
+                            .class!(pos[absolute] r[8] align-center menu-arrow)
+                        }))
+                    })
+                    .scroll_wrapper(scroll_wrapper.build()),
+            )
+    }
+
+    fn render_draft(&'static self) -> ContentSection {
+        let draft = &self.draft;
+        let color_input = Input::builder()
+            .input_type(InputType::color())
+            .size(InputSize::Color)
+            .value(draft.color.lock_ref().deref())
+            .on_input(move |_, input_elem: &HtmlInputElement| {
+                draft.color.set(input_elem.value());
+            });
+        let color_section = ContentSection::new()
+            .class_list("label j-c[space-between]")
+            .checkbox(Checkbox::builder(draft.recolor.clone()).text(tr!(BetterMessagesRecolor)))
+            .input(color_input);
+        let add_button = Button::builder()
+            .text(tr!(BetterMessagesAddRule))
+            .on_click(move |_| self.add_draft());
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[5]")
+            .section(self.draft_input(
+                tr!(BetterMessagesName),
+                tr!(BetterMessagesNamePlaceholder),
+                &draft.name,
+            ))
+            .section(self.draft_channel_setting())
+            .section(self.draft_input(
+                tr!(BetterMessagesNick),
+                tr!(BetterMessagesNickPlaceholder),
+                &draft.nick,
+            ))
+            .section(self.draft_input(
+                tr!(BetterMessagesKeyword),
+                tr!(BetterMessagesKeywordPlaceholder),
+                &draft.keyword,
+            ))
+            .section(self.draft_input(
+                tr!(BetterMessagesRegex),
+                tr!(BetterMessagesRegexPlaceholder),
+                &draft.regex,
+            ))
+            .section(color_section)
+            .checkbox(Checkbox::builder(draft.pin.clone()).text(tr!(BetterMessagesPin)))
+            .checkbox(Checkbox::builder(draft.mute.clone()).text(tr!(BetterMessagesMute)))
+            .checkbox(Checkbox::builder(draft.forward.clone()).text(tr!(BetterMessagesForward)))
+            .button(add_button)
+    }
+
+    fn add_draft(&'static self) {
+        let rule = self.draft.to_rule();
+
+        if !rule.has_action() {
+            let _ = message(tr!(BetterMessagesRuleNoAction));
+            return;
+        }
+        if !rule.regex.is_empty() && compile_regex(&rule.regex).is_err() {
+            let _ = message(tr!(BetterMessagesRuleInvalidRegex));
+            return;
+        }
+
+        self.add_rule(rule);
+    }
+
+    fn render_rules(&'static self) -> ContentSection {
+        let rules = self.rules.entries_cloned().map(move |(id, rule)| {
+            let name = match rule.name.is_empty() {
+                true => tr!(BetterMessagesRuleNumber, number = id + 1),
+                false => rule.name.clone(),
+            };
+            let toggle_button = Button::builder()
+                .text(match rule.enabled {
+                    true => tr!(BetterMessagesDisable),
+                    false => tr!(BetterMessagesEnable),
+                })
+                .on_click(move |_| self.toggle_rule(id));
+            let remove_button = Button::builder()
+                .text(tr!(BetterMessagesRemove))
+                .on_click(move |_| self.remove_rule(id));
+
+            ContentSection::new()
+                .class_list("d[flex] f-d[column] g[3]")
+                .apply_if(!rule.enabled, |section| section.class_list("o[40%]"))
+                .section(ContentSection::new().class_list("mdma-text").text(&name))
+                .section(ContentSection::new().class_list("w-s[pre-line]").text(&rule.describe()))
+                .button_pair(ButtonPair::builder(toggle_button, remove_button))
+        });
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[6] max-h[200] scroll-y")
+            .section_signal_vec(rules)
+    }
+
+    fn render_preview(&'static self, active_settings: &'static ActiveSettings) -> ContentSection {
+        let preview = map_ref! {
+            let rule = self.draft.rule_signal(),
+            let recent = active_settings.testing.recent_messages.signal_vec_cloned().to_signal_cloned() => {
+                recent
+                    .iter()
+                    .filter(|entry| rule.matches(entry))
+                    .map(|entry| FeedEntry { entry: entry.clone(), color: rule.color.clone() })
+                    .collect::<Vec<_>>()
+            }
+        }
+        .to_signal_vec()
+        .map(|feed_entry| feed_entry.render());
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[5] max-h[200] scroll-y b-f[glassy-blur]")
+            .section_signal_vec(preview)
+    }
+
+    fn render_rule_transfer(&'static self) -> ContentSection {
+        let import_input = Input::builder()
+            .placeholder(tr!(BetterMessagesRulesPlaceholder))
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .confirm_button(
+                InputButton::builder()
+                    .on_click(move |_event, input_elem| {
+                        let imported = match self.import_rules(&input_elem.value()) {
+                            Ok(imported) => imported,
+                            Err(_err) => {
+                                debug_log!(@f "{_err:?}");
+                                if message(tr!(BetterMessagesRulesInvalid)).is_err() {
+                                    console_error!()
+                                }
+                                return;
+                            }
+                        };
+                        input_elem.set_value("");
+                        let msg = tr!(BetterMessagesRulesImported, imported = imported);
+                        if message(&msg).is_err() {
+                            console_error!()
+                        }
+                    })
+                    .tip(tr!(BetterMessagesImport)),
+            );
+        let export_button = Button::builder()
+            .text(tr!(BetterMessagesExport))
+            .on_click(move |_event| {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let rules = match self.export_rules() {
+                        Ok(rules) => rules,
+                        Err(err) => return console_error!(err),
+                    };
+                    let clipboard = window().navigator().clipboard();
+                    if let Err(err) = wasm_bindgen_futures::JsFuture::from(clipboard.write_text(&rules))
+                        .await
+                        .map_err(map_err!())
+                    {
+                        return console_error!(err);
+                    }
+                    if message(tr!(BetterMessagesExported)).is_err() {
+                        console_error!()
+                    }
+                });
+            });
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[5]")
+            .input(import_input)
+            .button(export_button)
+    }
+
+    fn render(&'static self, active_settings: &'static ActiveSettings) -> JsResult<Dom> {
+        let decor = HeaderDecor::builder()
+            .push_left(decors::OpacityToggle::new())
+            .push_right(decors::CloseButton::new())
+            .push_right(decors::CollapseButton::new())
+            .build();
+        let header = WindowHeader::new(decor);
+        let content = WindowContent::builder()
+            .class_list("f-d[column]")
+            .heading(
+                Heading::builder()
+                    .text(tr!(BetterMessagesRules))
+                    .class_list("first-heading")
+                    .info_bubble(
+                        InfoBubble::builder()
+                            .text(tr!(BetterMessagesRulesInfo))
+                            .build(),
+                    ),
+            )
+            .section(self.render_rules())
+            .heading(Heading::builder().text(tr!(BetterMessagesNewRule)))
+            .section(self.render_draft())
+            .heading(
+                Heading::builder()
+                    .text(tr!(BetterMessagesPreview))
+                    .info_bubble(
+                        InfoBubble::builder()
+                            .text(tr!(BetterMessagesPreviewInfo))
+                            .build(),
+                    ),
+            )
+            .section(self.render_preview(active_settings))
+            .heading(Heading::builder().text(tr!(BetterMessagesTransfer)))
+            .section(self.render_rule_transfer());
+
+        SettingsWindow::builder(ADDON_NAME)
+            .header(header)
+            .content(content)
+            .build()
+    }
+}
+
+pub(super) fn init(active_settings: &'static ActiveSettings, settings: &'static Settings) -> JsResult<()> {
+    let addon_data = Addons::get_addon(ADDON_NAME).ok_or_else(|| err!(Get, "addon data"))?;
     let font_size_signal = map_ref! {
         let addon_active = addon_data.active.signal(),
         let font_size_active = active_settings.font.active.signal(),
@@ -228,9 +559,8 @@ pub(super) fn init(active_settings: &'static ActiveSettings) -> JsResult<()> {
     }
 
     //TODO: Remove from DOM if hidden?
-    let _handle = WINDOWS_ROOT
-        .try_append_dom(active_settings.render()?)
-        .ok_or_else(|| err_code!())?;
+    let _handle = WINDOWS_ROOT.append_window(active_settings.render()?)?;
+    let _settings_window_handle = WINDOWS_ROOT.append_window(settings.render(active_settings)?)?;
 
     Ok(())
 }
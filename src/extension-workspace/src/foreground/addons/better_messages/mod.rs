@@ -1,14 +1,23 @@
 mod html;
+mod recolor;
+mod rules;
 
 use std::cell::RefCell;
 use std::rc::Rc;
 
 use futures_signals::signal::Mutable;
-use proc_macros::{ActiveSettings, Setting};
+use futures_signals::signal_map::MutableBTreeMap;
+use futures_signals::signal_vec::MutableVec;
+use proc_macros::{ActiveSettings, Setting, Settings};
 
+use crate::bindings::engine::communication::Chat;
 use crate::prelude::*;
 
+use rules::{ChatEntry, ChatRule, Feed, FeedEntry, RuleDraft};
+
 const ADDON_NAME: AddonName = AddonName::BetterMessages;
+/// Amount of recent chat messages the rules can be tested against.
+const RECENT_MESSAGES_CAPACITY: usize = 50;
 
 #[derive(ActiveSettings)]
 struct ActiveSettings {
@@ -17,6 +26,8 @@ struct ActiveSettings {
     pointer_events: Mutable<bool>,
     #[setting(skip)]
     testing: Testing,
+    #[setting(skip)]
+    feed: Feed,
 }
 
 impl Default for ActiveSettings {
@@ -28,7 +39,9 @@ impl Default for ActiveSettings {
             testing: Testing {
                 interval_id: Mutable::new(None),
                 active: Mutable::new(false),
+                recent_messages: MutableVec::new(),
             },
+            feed: Feed::default(),
         }
     }
 }
@@ -66,9 +79,117 @@ impl Default for Color {
 struct Testing {
     interval_id: Mutable<Option<i32>>,
     active: Mutable<bool>,
+    /// Chat messages the rule preview is evaluated against.
+    recent_messages: MutableVec<ChatEntry>,
+}
+
+impl Testing {
+    fn push_recent(&self, entry: ChatEntry) {
+        let mut recent_lock = self.recent_messages.lock_mut();
+        if recent_lock.len() >= RECENT_MESSAGES_CAPACITY {
+            recent_lock.remove(0);
+        }
+
+        recent_lock.push_cloned(entry);
+    }
+}
+
+#[derive(Settings, Default)]
+struct Settings {
+    rules: MutableBTreeMap<u32, ChatRule>,
+    #[setting(skip)]
+    draft: RuleDraft,
+}
+
+impl Settings {
+    fn add_rule(&self, rule: ChatRule) {
+        let mut rules_lock = self.rules.lock_mut();
+        let id = rules_lock.keys().last().map_or(0, |id| id + 1);
+
+        rules_lock.insert_cloned(id, rule);
+    }
+
+    fn toggle_rule(&self, id: u32) {
+        let mut rules_lock = self.rules.lock_mut();
+        let Some(mut rule) = rules_lock.get(&id).cloned() else {
+            return console_error!();
+        };
+
+        rule.enabled = !rule.enabled;
+        rules_lock.insert_cloned(id, rule);
+    }
+
+    fn remove_rule(&self, id: u32) {
+        self.rules.lock_mut().remove(&id);
+    }
+
+    fn export_rules(&self) -> JsResult<String> {
+        let rules: Vec<_> = self.rules.lock_ref().values().cloned().collect();
+
+        serde_json::to_string(&rules).map_err(map_err!(from))
+    }
+
+    /// Appends the rules to the existing ones, returns the amount of imported rules.
+    fn import_rules(&self, value: &str) -> JsResult<usize> {
+        let rules: Vec<ChatRule> = serde_json::from_str(value).map_err(map_err!(from))?;
+        let imported = rules.len();
+
+        rules.into_iter().for_each(|rule| self.add_rule(rule));
+
+        Ok(imported)
+    }
 }
 
 impl ActiveSettings {
+    fn init(&'static self, settings: &'static Settings) -> JsResult<()> {
+        Emitter::intercept_on(EmitterEvent::Chat, move |socket_response| {
+            if Addons::is_active(ADDON_NAME)
+                && let Some(chat) = socket_response.chat.as_mut()
+            {
+                self.apply_rules(chat, settings);
+            }
+            Box::pin(async { Ok(()) })
+        })?;
+
+        recolor::init()
+    }
+
+    /// Forwards, pins and recolors matching messages, removes the muted ones so the game never
+    /// displays them.
+    fn apply_rules(&self, chat: &mut Chat, settings: &Settings) {
+        // Iterated in place so the rules keep their compiled regexes.
+        let rules_lock = settings.rules.lock_ref();
+        let rules: Vec<_> = rules_lock.values().filter(|rule| rule.enabled).collect();
+
+        chat.retain_messages(|channel, message| {
+            let Some(entry) = ChatEntry::new(channel, message) else {
+                return true;
+            };
+            self.testing.push_recent(entry.clone());
+
+            let matched: Vec<_> = rules.iter().filter(|rule| rule.matches(&entry)).collect();
+            if matched.is_empty() {
+                return true;
+            }
+
+            let muted = matched.iter().any(|rule| rule.mute);
+            let color = matched.iter().find_map(|rule| rule.color.clone());
+            if !muted && let Some(color) = color.clone() {
+                recolor::queue(entry.text.clone(), color);
+            }
+
+            let feed_entry = FeedEntry { color, entry };
+            if matched.iter().any(|rule| rule.pin) {
+                self.feed.pin(feed_entry.clone());
+            }
+            if matched.iter().any(|rule| rule.forward) {
+                self.feed.forward(feed_entry);
+            }
+
+            !muted
+        });
+    }
+
     fn init_tests(&self) -> JsResult<()> {
         use crate::bindings::message;
         use js_sys::Function;
@@ -140,6 +261,8 @@ impl ActiveSettings {
 
 pub(crate) fn init() -> JsResult<()> {
     let addon_window = ActiveSettings::new(ADDON_NAME);
+    let settings = Settings::new(ADDON_NAME);
+    addon_window.init(settings)?;
 
-    html::init(addon_window)
+    html::init(addon_window, settings)
 }
@@ -0,0 +1,110 @@
+//! Recolors the chat lines rendered by the game. Messages matched by a rule
+//! are queued when the chat response gets intercepted and recolored once the
+//! game appends them to the chat.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, MutationObserver, MutationObserverInit, MutationRecord};
+
+use crate::prelude::*;
+
+/// Messages which never got rendered, e.g. because the chat was closed, are
+/// dropped above this amount.
+const PENDING_CAPACITY: usize = 50;
+/// Chat line element, the same on both interfaces.
+const MESSAGE_SELECTOR: &str = ".new-chat-message";
+
+thread_local! {
+    /// Texts of the matched messages along with their color, oldest first.
+    static PENDING: RefCell<VecDeque<(String, String)>> = const { RefCell::new(VecDeque::new()) };
+}
+
+pub(super) fn queue(text: String, color: String) {
+    PENDING.with_borrow_mut(|pending| {
+        if pending.len() >= PENDING_CAPACITY {
+            pending.pop_front();
+        }
+        pending.push_back((text, color));
+    });
+}
+
+pub(super) fn init() -> JsResult<()> {
+    let mutation_callback = closure!(move |mutation_list: Vec<MutationRecord>| {
+        if PENDING.with_borrow(VecDeque::is_empty) {
+            return;
+        }
+
+        mutation_list
+            .iter()
+            .flat_map(|mutation| mutation.added_nodes().values())
+            .filter_map(|node| node.ok()?.dyn_into::<Element>().ok())
+            .for_each(|element| {
+                if element.matches(MESSAGE_SELECTOR).unwrap_or_default() {
+                    recolor(&element);
+                    return;
+                }
+
+                let Ok(messages) = element.query_selector_all(MESSAGE_SELECTOR) else {
+                    return;
+                };
+                messages
+                    .values()
+                    .into_iter()
+                    .filter_map(|node| node.ok()?.dyn_into::<Element>().ok())
+                    .for_each(|message| recolor(&message));
+            });
+    });
+    let mutation_observer = MutationObserver::new(&mutation_callback)
+        .map_err(map_err!(Call, "chat mutation observer"))?;
+    let mutation_observer_init = MutationObserverInit::new();
+    mutation_observer_init.set_child_list(true);
+    mutation_observer_init.set_subtree(true);
+
+    mutation_observer
+        .observe_with_options(
+            &document()
+                .body()
+                .ok_or_else(|| err!(Get, "document body"))?,
+            &mutation_observer_init,
+        )
+        .map_err(map_err!(Call, "observe chat"))
+}
+
+/// Colors the message and every element inside it, since the game styles
+/// the author and the text separately.
+fn recolor(message: &Element) {
+    let Some(text) = message.text_content() else {
+        return;
+    };
+    let Some(color) = PENDING.with_borrow_mut(|pending| {
+        let index = pending
+            .iter()
+            .position(|(pending_text, _)| text.contains(pending_text))?;
+
+        pending.remove(index).map(|(_, color)| color)
+    }) else {
+        return;
+    };
+
+    let descendants = message
+        .query_selector_all("*")
+        .map(|nodes| {
+            nodes
+                .values()
+                .into_iter()
+                .filter_map(Result::ok)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    std::iter::once(message.clone().into())
+        .chain(descendants)
+        .filter_map(|node: JsValue| node.dyn_into::<HtmlElement>().ok())
+        .for_each(|element| {
+            if element.style().set_property("color", &color).is_err() {
+                console_error!();
+            }
+        });
+}
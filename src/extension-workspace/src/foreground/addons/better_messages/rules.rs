@@ -0,0 +1,295 @@
+use std::cell::OnceCell;
+
+use futures_signals::map_ref;
+use futures_signals::signal::{Mutable, Signal};
+use futures_signals::signal_vec::MutableVec;
+use js_sys::{Array, Function, RegExp};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::addons::chat_history::channel_label;
+use crate::bindings::engine::communication::{ChatChannelName, ChatMessage};
+use crate::prelude::*;
+
+/// Amount of forwarded messages kept in the addon window.
+const FORWARDED_CAPACITY: usize = 100;
+const DEFAULT_RULE_COLOR: &str = "#ff8c00";
+
+/// Chat message as seen by the rules.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct ChatEntry {
+    pub(super) channel: ChatChannelName,
+    /// Unix timestamp in seconds.
+    pub(super) ts: f64,
+    pub(super) author: Option<String>,
+    pub(super) text: String,
+}
+
+impl ChatEntry {
+    pub(super) fn new(channel: ChatChannelName, message: &ChatMessage) -> Option<Self> {
+        Some(Self {
+            channel,
+            ts: message.ts()?,
+            author: message.author_nick().map(str::to_owned),
+            text: message.text()?.to_owned(),
+        })
+    }
+
+    pub(super) fn header(&self) -> String {
+        let date = js_sys::Date::new(&(self.ts * 1_000.0).into());
+        let time = format!(
+            "{:02}:{:02}:{:02}",
+            date.get_hours(),
+            date.get_minutes(),
+            date.get_seconds()
+        );
+
+        match self.author.as_deref() {
+            Some(author) => format!("{time} [{}] {author}", channel_label(self.channel)),
+            None => format!("{time} [{}]", channel_label(self.channel)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(super) struct ChatRule {
+    pub(super) name: String,
+    pub(super) enabled: bool,
+    /// `None` matches every channel.
+    pub(super) channel: Option<ChatChannelName>,
+    /// Conditions below are ignored when empty.
+    pub(super) nick: String,
+    pub(super) keyword: String,
+    pub(super) regex: String,
+    /// Color of the message in the game chat and in the addon window.
+    pub(super) color: Option<String>,
+    pub(super) pin: bool,
+    pub(super) mute: bool,
+    pub(super) forward: bool,
+    #[serde(skip)]
+    compiled: CompiledRegex,
+}
+
+impl Default for ChatRule {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            enabled: true,
+            channel: None,
+            nick: String::new(),
+            keyword: String::new(),
+            regex: String::new(),
+            color: None,
+            pin: false,
+            mute: false,
+            forward: false,
+            compiled: CompiledRegex::default(),
+        }
+    }
+}
+
+impl ChatRule {
+    pub(super) fn matches(&self, entry: &ChatEntry) -> bool {
+        if self.channel.is_some_and(|channel| channel != entry.channel) {
+            return false;
+        }
+        if !self.nick.is_empty()
+            && !entry
+                .author
+                .as_deref()
+                .is_some_and(|author| author.to_lowercase() == self.nick.to_lowercase())
+        {
+            return false;
+        }
+        if !self.keyword.is_empty()
+            && !entry
+                .text
+                .to_lowercase()
+                .contains(&self.keyword.to_lowercase())
+        {
+            return false;
+        }
+        if !self.regex.is_empty() {
+            // Invalid patterns are rejected when adding a rule, imported ones just never match.
+            let Some(regex) = self
+                .compiled
+                .0
+                .get_or_init(|| compile_regex(&self.regex).ok())
+            else {
+                return false;
+            };
+            if !regex.test(&entry.text) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Whether the rule does anything at all.
+    pub(super) fn has_action(&self) -> bool {
+        self.color.is_some() || self.pin || self.mute || self.forward
+    }
+
+    pub(super) fn describe(&self) -> String {
+        let channel = self
+            .channel
+            .map(channel_label)
+            .unwrap_or(tr!(ChatChannelAll));
+        let mut conditions = vec![tr!(BetterMessagesConditionChannel, channel = channel)];
+        if !self.nick.is_empty() {
+            conditions.push(tr!(BetterMessagesConditionNick, nick = self.nick));
+        }
+        if !self.keyword.is_empty() {
+            conditions.push(tr!(BetterMessagesConditionKeyword, keyword = self.keyword));
+        }
+        if !self.regex.is_empty() {
+            conditions.push(tr!(BetterMessagesConditionRegex, regex = self.regex));
+        }
+
+        let actions: Vec<_> = [
+            (self.color.is_some(), tr!(BetterMessagesActionRecolor)),
+            (self.pin, tr!(BetterMessagesActionPin)),
+            (self.mute, tr!(BetterMessagesActionMute)),
+            (self.forward, tr!(BetterMessagesActionForward)),
+        ]
+        .into_iter()
+        .filter_map(|(active, action)| active.then_some(action))
+        .collect();
+
+        format!("{} → {}", conditions.join(", "), actions.join(", "))
+    }
+}
+
+/// Regex of a rule, compiled on the first match instead of for every message.
+#[derive(Debug, Clone, Default)]
+struct CompiledRegex(OnceCell<Option<RegExp>>);
+
+/// The pattern itself is compared through [`ChatRule::regex`].
+impl PartialEq for CompiledRegex {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// Case insensitive JS regex, constructed through `Reflect` so invalid patterns don't throw.
+pub(super) fn compile_regex(pattern: &str) -> JsResult<RegExp> {
+    let constructor: Function = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("RegExp"))
+        .map_err(map_err!())?
+        .unchecked_into();
+    let args = Array::of2(&JsValue::from_str(pattern), &JsValue::from_str("i"));
+
+    js_sys::Reflect::construct(&constructor, &args)
+        .map(JsCast::unchecked_into)
+        .map_err(map_err!())
+}
+
+/// Rule being edited in the settings window.
+pub(super) struct RuleDraft {
+    pub(super) name: Mutable<String>,
+    pub(super) channel: Mutable<Option<ChatChannelName>>,
+    pub(super) nick: Mutable<String>,
+    pub(super) keyword: Mutable<String>,
+    pub(super) regex: Mutable<String>,
+    pub(super) recolor: Mutable<bool>,
+    pub(super) color: Mutable<String>,
+    pub(super) pin: Mutable<bool>,
+    pub(super) mute: Mutable<bool>,
+    pub(super) forward: Mutable<bool>,
+}
+
+impl Default for RuleDraft {
+    fn default() -> Self {
+        Self {
+            name: Mutable::default(),
+            channel: Mutable::default(),
+            nick: Mutable::default(),
+            keyword: Mutable::default(),
+            regex: Mutable::default(),
+            recolor: Mutable::new(true),
+            color: Mutable::new(DEFAULT_RULE_COLOR.to_owned()),
+            pin: Mutable::default(),
+            mute: Mutable::default(),
+            forward: Mutable::new(true),
+        }
+    }
+}
+
+impl RuleDraft {
+    pub(super) fn to_rule(&self) -> ChatRule {
+        ChatRule {
+            name: self.name.lock_ref().trim().to_owned(),
+            enabled: true,
+            channel: self.channel.get(),
+            nick: self.nick.lock_ref().trim().to_owned(),
+            keyword: self.keyword.lock_ref().trim().to_owned(),
+            regex: self.regex.lock_ref().trim().to_owned(),
+            color: self.recolor.get().then(|| self.color.get_cloned()),
+            pin: self.pin.get(),
+            mute: self.mute.get(),
+            forward: self.forward.get(),
+            compiled: CompiledRegex::default(),
+        }
+    }
+
+    pub(super) fn rule_signal(&self) -> impl Signal<Item = ChatRule> {
+        map_ref! {
+            let channel = self.channel.signal(),
+            let nick = self.nick.signal_cloned(),
+            let keyword = self.keyword.signal_cloned(),
+            let regex = self.regex.signal_cloned(),
+            let recolor = self.recolor.signal(),
+            let color = self.color.signal_cloned(),
+            let pin = self.pin.signal(),
+            let mute = self.mute.signal(),
+            let forward = self.forward.signal() => {
+                ChatRule {
+                    channel: *channel,
+                    nick: nick.trim().to_owned(),
+                    keyword: keyword.trim().to_owned(),
+                    regex: regex.trim().to_owned(),
+                    color: recolor.then(|| color.clone()),
+                    pin: *pin,
+                    mute: *mute,
+                    forward: *forward,
+                    ..ChatRule::default()
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct FeedEntry {
+    pub(super) entry: ChatEntry,
+    pub(super) color: Option<String>,
+}
+
+/// Messages forwarded or pinned by the rules, displayed in the addon window.
+#[derive(Default)]
+pub(super) struct Feed {
+    pub(super) pinned: MutableVec<FeedEntry>,
+    pub(super) forwarded: MutableVec<FeedEntry>,
+}
+
+impl Feed {
+    pub(super) fn forward(&self, feed_entry: FeedEntry) {
+        let mut forwarded_lock = self.forwarded.lock_mut();
+        if forwarded_lock.len() >= FORWARDED_CAPACITY {
+            forwarded_lock.remove(0);
+        }
+
+        forwarded_lock.push_cloned(feed_entry);
+    }
+
+    pub(super) fn pin(&self, feed_entry: FeedEntry) {
+        self.pinned.lock_mut().push_cloned(feed_entry);
+    }
+
+    pub(super) fn unpin(&self, entry: &ChatEntry) {
+        self.pinned
+            .lock_mut()
+            .retain(|feed_entry| &feed_entry.entry != entry);
+    }
+}
@@ -6,7 +6,7 @@ use dominator::events::{ContextMenu, DoubleClick, MouseButton, MouseDown, MouseE
 use dominator::{apply_methods, html, with_node, Dom, DomBuilder, EventOptions };
 use futures::FutureExt;
 use futures_signals::map_ref;
-use futures_signals::signal::{self, Mutable, Signal, SignalExt};
+use futures_signals::signal::{self, Signal, SignalExt};
 use futures_signals::signal_vec::{SignalVec, SignalVecExt};
 use itertools::Itertools;
 use web_sys::{HtmlDivElement, HtmlImageElement};
@@ -14,15 +14,16 @@ use web_sys::{HtmlDivElement, HtmlImageElement};
 use crate::addon_window::{prelude::*, MdmaAddonWindow};
 use crate::bindings::engine::iframe_window::PlayerProfileOptions;
 use crate::interface::tips_parser::tip;
-use crate::color_mark::{Color, ColorMark};
+use crate::{color_mark::ColorMark, overlay::OverlayColor};
 use crate::interface::{ThreadLocalShadowRoot, WINDOWS_ROOT};
 use crate::prelude::*;
 
-use super::{get_emotion_source, ActiveSettings, LevelDisplay, Ordering, Settings, SortBy, ADDON_NAME, SRC};
+use super::sightings::{Sighting, Sightings, MAX_DISPLAYED_SIGHTINGS};
+use super::{get_emotion_source, ActiveSettings, Settings, ADDON_NAME, SRC};
 
 const TOP_FADE_MAX: f64 = 0.10;
 const BOTTOM_FADE_MIN: f64 = 1.0 - TOP_FADE_MAX;
-const HIGHLIGHT_COLOR: Color = Color::Lime;
+const HIGHLIGHT_COLOR: OverlayColor = OverlayColor::named("lime");
 
 impl Settings {
     fn render(&'static self) -> JsResult<Dom> {
@@ -33,10 +34,10 @@ impl Settings {
         let settings_window_header = WindowHeader::new(decor);
         let settings_window_content = WindowContent::builder()
             .class_list("f-d[column]")
-            .heading(Heading::builder().class_list("first-heading").text("Ustawienia ogólne"))
+            .heading(Heading::builder().class_list("first-heading").text(tr!(GeneralSettings)))
             .section(self.common_settings())
-            .heading(Heading::builder().text("Lista graczy"))
-            .section(self.player_list_settings());
+            .heading(Heading::builder().text(tr!(BetterWhoIsHerePlayerList)))
+            .section(self.player_list.render());
 
         SettingsWindow::builder(ADDON_NAME)
             .header(settings_window_header)
@@ -49,16 +50,16 @@ impl Settings {
             .class_list("d[flex] f-d[column]")
             .apply_if(Premium::active(), |b| b.checkbox(
                 Checkbox::builder(self.clear_target.clone())
-                    .text("Automatyczne przerywanie dobijania")
+                    .text(tr!(BetterWhoIsHereClearTarget))
                     .info_bubble(
                         InfoBubble::builder()
                             .apply(|b| {
                                 b.class(["w[240]", "max-w[none]"])
-                                    .text("Dobijanie zostanie przerwane, jeśli:")
+                                    .text(tr!(BetterWhoIsHereClearTargetInfo))
                                     .child(html!("div", {
                                         .class!(p-top[6] t-a[left])
-                                        .text("- zmienimy trasę przed dojściem do celu,")
-                                        .text("\n- cel wyjdzie spoza zasięgu ataku.")
+                                        .text(tr!(BetterWhoIsHereClearTargetRoute))
+                                        .text(tr!(BetterWhoIsHereClearTargetRange))
                                     }))
                             })
                             .build()
@@ -66,150 +67,22 @@ impl Settings {
             ))
             .checkbox(
                 Checkbox::builder(self.replace_widget.clone())
-                    .text("Otwieraj za pomocą widgetu ")
+                    .text(tr!(BetterWhoIsHereReplaceWidget))
                     .label_mixin(|b| b.child(html!("span", {
                         .class("c[darkorange]")
-                        .text("Gracze na mapie")
+                        .text(tr!(BetterWhoIsHereMapPlayersWidget))
                     })))
             )
-    }
-
-    fn player_list_settings(&'static self) -> ContentSection {
-        ContentSection::new()
-            .class_list("d[flex] f-d[column] g[6]")
-            .section(self.sort_by_setting())
-            .section(self.sort_order_setting())
-            .section(self.level_display_setting())
-        
-    }
-
-    fn level_display_setting(&'static self) -> ContentSection {
-        // TODO: Figure out a better solution.
-        let wrapper_state: &'static _ = Box::leak(Box::new(Mutable::default()));
-
-        ContentSection::new()
-            .class_list("d[flex] f-d[row] g[10] j-c[space-between]")
-            .section(ContentSection::new().class_list("a-c[center]").text("Wyświetlanie poziomów"))
-            .button(
-                Button::builder()
-                    .class_list("w[208] t-a[left]")
-                    .no_hover()
-                    .text_signal(self.level_display_signal())
-                    .on_click(|_| wrapper_state.set_neq(true))
-                    .on_mousedown(|event| event.stop_propagation())
-                    .mixin(|builder| {
-                        builder.child(html!("div", {
-                            .class!(pos[absolute] r[8] align-center menu-arrow)
-                        }))
-                    })
-                    .scroll_wrapper(
-                        ScrollWrapper::builder(|| {
-                                || {
-                                    wrapper_state.set_neq(false);
-                                }
-                            })
-                            .class_list("w[200] l[1]")
-                            .visible_signal(wrapper_state.signal())
-                            .option(self.level_display_option(LevelDisplay::First))
-                            .option(self.level_display_option(LevelDisplay::Last))
-                            .option(self.level_display_option(LevelDisplay::None))
-                            .option(self.level_display_option(LevelDisplay::Only))
-                            .build()
-                    ),
-            )
-    }
-
-    fn level_display_option(&'static self, level_display: LevelDisplay) -> ScrollWrapperOption {
-        ScrollWrapperOption::builder()
-            .text(level_display.as_str())
-            .on_click(move |_| self.level_display.set_neq(level_display))
-            .build()
-    }
-
-    fn sort_by_setting(&'static self) -> ContentSection {
-        // TODO: Figure out a better solution.
-        let wrapper_state: &'static _ = Box::leak(Box::new(Mutable::default()));
-
-        ContentSection::new()
-            .class_list("d[flex] f-d[row] j-c[space-between]")
-            .section(ContentSection::new().class_list("a-c[center]").text("Sortowanie względem"))
-            .button(
-                Button::builder()
-                    .class_list("w[98] t-a[left]")
-                    .no_hover()
-                    .text_signal(self.sort_by_signal())
-                    .on_click(|_| wrapper_state.set_neq(true))
-                    .on_mousedown(|event| event.stop_propagation())
-                    .mixin(|builder| {
-                        builder.child(html!("div", {
-                            .class!(pos[absolute] r[8] align-center menu-arrow)
-                        }))
-                    })
-                    .scroll_wrapper(
-                        ScrollWrapper::builder(|| {
-                                || {
-                                    wrapper_state.set_neq(false);
-                                }
-                            })
-                            .class_list("w[90] l[1]")
-                            .visible_signal(wrapper_state.signal())
-                            .option(self.sort_by_option(SortBy::Lvl))
-                            .option(self.sort_by_option(SortBy::Nick))
-                            .option(self.sort_by_option(SortBy::Prof))
-                            .build()
-                    ),
-            )
-    }
-
-    fn sort_by_option(&'static self, sort_by: SortBy) -> ScrollWrapperOption {
-        ScrollWrapperOption::builder()
-            .text(sort_by.as_str())
-            .on_click(move |_| self.sort_by.set_neq(sort_by))
-            .build()
-    }
-
-    fn sort_order_setting(&'static self) -> ContentSection {
-        // TODO: Figure out a better solution.
-        let wrapper_state: &'static _ = Box::leak(Box::new(Mutable::default()));
-
-        ContentSection::new()
-            .class_list("d[flex] f-d[row] j-c[space-between]")
-            .section(ContentSection::new().class_list("a-c[center]").text("Kolejność"))
-            .button(
-                Button::builder()
-                    .class_list("w[98] t-a[left]")
-                    .no_hover()
-                    .text_signal(self.sort_ordering_signal())
-                    .on_click(|_| wrapper_state.set_neq(true))
-                    .on_mousedown(|event| event.stop_propagation())
-                    .mixin(move |builder| {
-                        builder
-                            .child(html!("div", {
-                                .class!(pos[absolute] r[8] align-center menu-arrow)
-                            }))
-                    })
-                    .scroll_wrapper(
-                    ScrollWrapper::builder(|| {
-                                || {
-                                    wrapper_state.set_neq(false);
-                                }
-                            })
-                            .class_list("w[90] l[1]")
-                            .visible_signal(wrapper_state.signal())
-                            .option(self.sort_ordering_option(Ordering::Descending))
-                            .option(self.sort_ordering_option(Ordering::Ascending))
+            .checkbox(
+                Checkbox::builder(self.record_sightings.clone())
+                    .text(tr!(BetterWhoIsHereRecordSightings))
+                    .info_bubble(
+                        InfoBubble::builder()
+                            .text(tr!(BetterWhoIsHereRecordSightingsInfo))
                             .build()
-                    ),
+                    )
             )
     }
-
-    fn sort_ordering_option(&'static self, ordering: Ordering) -> ScrollWrapperOption {
-        ScrollWrapperOption::builder()
-            .text(ordering.as_str())
-            .on_click(move |_| self.ordering.set_neq(ordering))
-            .build()
-    }
-
 }
 
 impl ActiveSettings {
@@ -231,7 +104,8 @@ impl ActiveSettings {
         let bottom_shadow_animation: &'static _ = Box::leak(Box::new(
             MutableAnimation::new_with_initial(0.0, Percentage::END),
         ));
-        let window_size = Addons::get_window_size(ADDON_NAME, Self::WINDOW_TYPE).ok_or_else(|| err_code!())?;
+        let window_size = Addons::get_window_size(ADDON_NAME, Self::WINDOW_TYPE)
+            .ok_or_else(|| err!(Get, "window size"))?;
         let addon_window_content = WindowContent::builder()
             .class_list("p[1-4-4-4] f-d[column]")
             .section_signal(self.scroll_target_signal().map(move |visible| {
@@ -252,6 +126,7 @@ impl ActiveSettings {
                     )
                     .mixin(|b| {
                         let sorted_signal = settings
+                            .player_list
                             .sort_signal()
                             .switch_signal_vec(move |_| {
                                 self.sorted_list_signal_vec(settings)
@@ -295,18 +170,8 @@ impl ActiveSettings {
                         })
                     }),
             )
-            .input(
-                Input::builder()
-                    .class_list("b-s[none] b[none] bg[none] cursor[auto]")
-                    .size(InputSize::Big)
-                    .placeholder("Szukaj gracza...")
-                    .text_align(TextAlign::Left)
-                    .on_input(|_, input_elem| {
-                        let mut value = input_elem.value();
-                        value.make_ascii_lowercase();
-                        self.search_text.set(value);
-                    })
-            );
+            .section(settings.player_list.render_filter())
+            .section(self.sightings.render(settings));
 
         AddonWindow::builder(ADDON_NAME)
             .header(addon_window_header)
@@ -328,27 +193,20 @@ impl ActiveSettings {
         }
     }
 
-    fn prepare_list_signal_vec(&'static self) -> impl SignalVec<Item = Other> + 'static {
+    fn prepare_list_signal_vec(&'static self, settings: &'static Settings) -> impl SignalVec<Item = Other> + 'static {
         Others::get().signal_vec_keys()
             .filter_signal_cloned(|id| signal::not(self.is_target_signal(*id)))
             .filter_map(|other_id| {
                 Others::get().lock_ref().get(&other_id).cloned()
             })
-            .filter_signal_cloned(|other_data| {
-                other_data.nick
-                    .signal_cloned()
-                    .switch(|mut nick| {
-                        nick.make_ascii_lowercase();
-                        self.search_text.signal_ref(move |search_text| nick.contains(search_text))
-                    })
-            })
+            .filter_signal_cloned(move |other_data| settings.player_list.filter_signal(other_data))
     }
 
     fn sorted_list_signal_vec(&'static self, settings: &'static Settings) -> impl SignalVec<Item = Dom> + 'static {
-        self.prepare_list_signal_vec()
+        self.prepare_list_signal_vec(settings)
             .to_signal_cloned()
             .map(|mut entries| {
-                entries.sort_by(|a,b| settings.compare(a, b));
+                entries.sort_by(|a,b| settings.player_list.compare(a, b));
                 entries
             })
             .to_signal_vec()
@@ -364,20 +222,20 @@ impl ActiveSettings {
                 apply_methods!(b, {
                     .tip!({
                         .class!(d[flex] f-d[column] g[2])
-                        .child(player_count_tip_text("Zwykli gracze", Relation::None))
-                        .child(player_count_tip_text("Przyjaciele", Relation::Friend))
-                        .child(player_count_tip_text("Wrogowie", Relation::Enemy))
-                        .child(player_count_tip_text("Klanowicze", Relation::Clan))
-                        .child(player_count_tip_text("Sojusznicy klanowi", Relation::ClanAlly))
-                        .child(player_count_tip_text("Wrogowie klanowi", Relation::ClanEnemy))
+                        .child(player_count_tip_text(tr!(BetterWhoIsHereCountNone), Relation::None))
+                        .child(player_count_tip_text(tr!(BetterWhoIsHereCountFriend), Relation::Friend))
+                        .child(player_count_tip_text(tr!(BetterWhoIsHereCountEnemy), Relation::Enemy))
+                        .child(player_count_tip_text(tr!(BetterWhoIsHereCountClan), Relation::Clan))
+                        .child(player_count_tip_text(tr!(BetterWhoIsHereCountClanAlly), Relation::ClanAlly))
+                        .child(player_count_tip_text(tr!(BetterWhoIsHereCountClanEnemy), Relation::ClanEnemy))
                         .apply_if(WorldConfig::has_fractions(), |builder| {
                             builder
-                                .child(player_count_tip_text("Sojusznicy frakcji", Relation::FractionAlly))
-                                .child(player_count_tip_text("Wrogowie frakcji", Relation::FractionEnemy))
+                                .child(player_count_tip_text(tr!(BetterWhoIsHereCountFractionAlly), Relation::FractionAlly))
+                                .child(player_count_tip_text(tr!(BetterWhoIsHereCountFractionEnemy), Relation::FractionEnemy))
                         })
                         .child(html!("div", {
                             .class!(d[flex] f-d[row] j-c[space-between] group)
-                            .child(html!("span", { .text("Grupowicze") }))
+                            .child(html!("span", { .text(tr!(BetterWhoIsHereCountParty)) }))
                             .child(html!("span", { 
                                 .text_signal(Others::get().entries_cloned().to_signal_cloned().map(|entries| {
                                     let party_lock = Party::get().lock_ref();
@@ -424,17 +282,11 @@ impl ActiveSettings {
                             .child(html!("div", {
                                 .class("nick")
                                 .text_signal(map_ref!{
-                                    let level_display = settings.level_display.signal(),
+                                    let level_display = settings.player_list.level_display.signal(),
                                     let lvl = lvl_mutable.signal(),
                                     let oplvl = oplvl_mutable.signal(),
                                     let prof = prof_mutable.signal() => {
-                                        match level_display {
-                                            _ if *lvl <= 300 => format!("({}{})", *lvl, *prof),
-                                            LevelDisplay::First => format!("({}{}|{}{})", *lvl, *prof, *oplvl, *prof),
-                                            LevelDisplay::Last => format!("({}{}|{}{})", *oplvl, *prof, *lvl, *prof),
-                                            LevelDisplay::None => format!("({}{})", *oplvl, *prof),
-                                            LevelDisplay::Only => format!("({}{})",*lvl, *prof),
-                                        }
+                                        level_display.format(*lvl, *oplvl, *prof)
                                     }
                                 })
                             }))
@@ -447,8 +299,8 @@ impl ActiveSettings {
                             .child(html!("div", {
                                 .style("margin-top", "3px")
                                 .text(match has_premium {
-                                    true => "PPM aby przerwać dobijanie gracza.",
-                                    false => "PPM aby przerwać atak na gracza.",
+                                    true => tr!(BetterWhoIsHereClearTargetTip),
+                                    false => tr!(BetterWhoIsHereStopAttackTip),
                                 })
                             }))
                         })
@@ -489,17 +341,11 @@ impl ActiveSettings {
                     ContentSection::new()
                         .class_list("other-lvl")
                         .text_signal(map_ref!{
-                            let level_display = settings.level_display.signal(),
+                            let level_display = settings.player_list.level_display.signal(),
                             let lvl = target.lvl.signal(),
                             let oplvl = target.operational_lvl.signal(),
                             let prof = target.prof.signal() => {
-                                match level_display {
-                                    _ if *lvl <= 300 => format!("({}{})", *lvl, *prof),
-                                    LevelDisplay::First => format!("({}{}|{}{})", *lvl, *prof, *oplvl, *prof),
-                                    LevelDisplay::Last => format!("({}{}|{}{})", *oplvl, *prof, *lvl, *prof),
-                                    LevelDisplay::None => format!("({}{})", *oplvl, *prof),
-                                    LevelDisplay::Only => format!("({}{})",*lvl, *prof),
-                                }
+                                level_display.format(*lvl, *oplvl, *prof)
                             }
                         }),
                 )
@@ -541,20 +387,20 @@ impl ActiveSettings {
                         .unwrap_js();
                 }
 
-                ColorMark::remove(HIGHLIGHT_COLOR, ADDON_NAME, old_scroll_target_id);
+                ColorMark::remove(&HIGHLIGHT_COLOR, ADDON_NAME, old_scroll_target_id);
                 //globals.others.remove_color_mark(old_target);
             }
         })
             .class_list("w[130] t-a[center] l[100%+4] align-center")
             .option_if(!Premium::active(), || ScrollWrapperOption::builder()
-                .text("Atakuj")
+                .text(tr!(BetterWhoIsHereAttack))
                 .on_click(move |_| {
                     self.target.set(Others::get().lock_ref().get(&other_id).unwrap_js().clone(), ADDON_NAME);
                 })
                 .build()
             )
             .option_if(Premium::active(), || ScrollWrapperOption::builder()
-                .text("Dobijaj")
+                .text(tr!(BetterWhoIsHereFinishOff))
                 .on_click(move |_| {
                     self.target.set(Others::get().lock_ref().get(&other_id).unwrap_js().clone(), ADDON_NAME);
                     match cfg!(feature = "antyduch") {
@@ -576,7 +422,7 @@ impl ActiveSettings {
             )
             .option(
                 ScrollWrapperOption::builder()
-                    .text("Handluj")
+                    .text(tr!(BetterWhoIsHereTrade))
                     .on_click(move |_| {
                         wasm_bindgen_futures::spawn_local(async move {
                             let success = get_engine().hero().unwrap_js().auto_go_to_other(other_id).await.unwrap_js();
@@ -598,7 +444,7 @@ impl ActiveSettings {
             )
             .option_if(Hero::get().lvl.get() > 29,
                 || ScrollWrapperOption::builder()
-                    .text("Pocałuj")
+                    .text(tr!(BetterWhoIsHereKiss))
                     .on_click(move |_| {
                         wasm_bindgen_futures::spawn_local(async move {
                             let success = get_engine().hero().unwrap_js().auto_go_to_other(other_id).await.unwrap_js();
@@ -620,7 +466,7 @@ impl ActiveSettings {
             )
             .option_if(Hero::get().vip.get(),
                 || ScrollWrapperOption::builder()
-                    .text("Karmazynowe błogosławieństwo")
+                    .text(tr!(BetterWhoIsHereBless))
                     .on_click(move |_| {
                         wasm_bindgen_futures::spawn_local(async move {
                             let success = get_engine().hero().unwrap_js().auto_go_to_other(other_id).await.unwrap_js();
@@ -645,7 +491,7 @@ impl ActiveSettings {
                     .get(&other_id)
                     .is_some_and(|other_data| other_data.relation.get() != Relation::Enemy),
                 || ScrollWrapperOption::builder()
-                    .text("Wyślij wiadomość")
+                    .text(tr!(BetterWhoIsHereSendMessage))
                     .on_click(move |_| {
                         get_engine()
                             .chat_controller()
@@ -667,7 +513,7 @@ impl ActiveSettings {
             )
             .option(
                 ScrollWrapperOption::builder()
-                    .text("Pokaż ekwipunek")
+                    .text(tr!(BetterWhoIsHereShowEquipment))
                     .on_click(move |_| {
                         get_engine().others().unwrap_js().get_by_id(other_id).unwrap_js().show_eq().unwrap_js();
                     })
@@ -675,7 +521,7 @@ impl ActiveSettings {
             )
             .option(
                 ScrollWrapperOption::builder()
-                    .text("Zaproś do przyjaciół")
+                    .text(tr!(BetterWhoIsHereInviteFriend))
                     .on_click(move |_| {
                         send_task(&format!(
                             "friends&a=finvite&nick={}",
@@ -695,7 +541,7 @@ impl ActiveSettings {
             )
             .option(
                 ScrollWrapperOption::builder()
-                    .text("Zaproś do drużyny")
+                    .text(tr!(BetterWhoIsHereInviteParty))
                     .on_click(move |_| {
                         send_task(&format!("party&a=inv&id={other_id}")).unwrap_js();
                     })
@@ -703,7 +549,7 @@ impl ActiveSettings {
             )
             .option(
                 ScrollWrapperOption::builder()
-                    .text("Pokaż profil")
+                    .text(tr!(BetterWhoIsHereShowProfile))
                     .on_click(move |_| {
                         let others_lock = Others::get().lock_ref();
                         let other_data = others_lock.get(&other_id).unwrap_js();
@@ -751,6 +597,136 @@ impl ActiveSettings {
     }
 }
 
+impl Sightings {
+    fn render(&'static self, settings: &'static Settings) -> ContentSection {
+        let toggle_button = Button::builder()
+            .class_list("w[100%]")
+            .text(tr!(BetterWhoIsHereSightings))
+            .selected_signal(self.visible.signal())
+            .on_click(move |_| self.visible.set(!self.visible.get()));
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] m-top[6]")
+            .button(toggle_button)
+            .section(
+                ContentSection::new()
+                    .class_list("d[flex] f-d[column] g[5] m-top[6]")
+                    .visible_signal(self.visible.signal())
+                    .section(self.render_search())
+                    .section(ContentSection::new().class_list("mdma-text").text_signal(
+                        self.results.signal_ref(|results| {
+                            match results.len() > MAX_DISPLAYED_SIGHTINGS {
+                                true => tr!(
+                                    BetterWhoIsHereSightingsTruncated,
+                                    count = results.len(),
+                                    displayed = MAX_DISPLAYED_SIGHTINGS,
+                                ),
+                                false => tr!(BetterWhoIsHereSightingsFound, count = results.len()),
+                            }
+                        }),
+                    ))
+                    .section(self.render_results(settings))
+                    .section(self.render_controls()),
+            )
+    }
+
+    fn render_search(&'static self) -> ContentSection {
+        let query_input = Input::builder()
+            .placeholder(tr!(BetterWhoIsHereQueryPlaceholder))
+            .maxlength("200")
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .with_tooltip(&self.query_error)
+            .on_input(move |_, input_elem| match self.set_query(input_elem.value()) {
+                Ok(()) => self.query_error.set_valid(input_elem),
+                Err(err) => self.query_error.set_invalid(input_elem, err.to_string()),
+            });
+        let map_input = Input::builder()
+            .placeholder(tr!(BetterWhoIsHereMapName))
+            .maxlength("60")
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .on_input(move |_, input_elem| {
+                self.map_name.set(input_elem.value().trim().to_lowercase())
+            });
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[5]")
+            .input(query_input)
+            .input(map_input)
+    }
+
+    fn render_results(&'static self, settings: &'static Settings) -> ContentSection {
+        let results = self
+            .results
+            .signal_ref(|results| results[..results.len().min(MAX_DISPLAYED_SIGHTINGS)].to_vec())
+            .to_signal_vec()
+            .map(move |sighting| Self::render_one_result(settings, sighting));
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[5] max-h[300] scroll-y b-f[glassy-blur]")
+            .section_signal_vec(results)
+    }
+
+    fn render_one_result(settings: &'static Settings, sighting: Sighting) -> ContentSection {
+        let level = settings.player_list.level_display.get().format(
+            sighting.lvl,
+            sighting.operational_lvl,
+            sighting.prof,
+        );
+        let clan = sighting
+            .clan
+            .as_deref()
+            .map(|clan| format!(" [{clan}]"))
+            .unwrap_or_default();
+        let seen = format!(
+            "{} | {} - {}",
+            sighting.map_name.as_deref().unwrap_or("-"),
+            format_ts(sighting.first_seen),
+            format_ts(sighting.last_seen),
+        );
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column]")
+            .section(ContentSection::new().text(&format!("{} {level}{clan}", sighting.nick)))
+            .section(ContentSection::new().class_list("o[60%] f-s[11]").text(&seen))
+    }
+
+    fn render_controls(&'static self) -> ContentSection {
+        let csv_button = Button::builder()
+            .text(tr!(BetterWhoIsHereExportCsv))
+            .on_click(move |_| self.export_to_clipboard(false));
+        let json_button = Button::builder()
+            .text(tr!(BetterWhoIsHereExportJson))
+            .on_click(move |_| self.export_to_clipboard(true));
+
+        ContentSection::new().button_pair(ButtonPair::builder(csv_button, json_button))
+    }
+
+    fn export_to_clipboard(&'static self, json: bool) {
+        let text = match json {
+            true => match self.to_json() {
+                Ok(text) => text,
+                Err(err) => return console_error!(err),
+            },
+            false => self.to_csv(),
+        };
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let clipboard = window().navigator().clipboard();
+            if let Err(err) = wasm_bindgen_futures::JsFuture::from(clipboard.write_text(&text))
+                .await
+                .map_err(map_err!())
+            {
+                return console_error!(err);
+            }
+            if message(tr!(BetterWhoIsHereExported)).is_err() {
+                console_error!()
+            }
+        });
+    }
+}
+
 fn related_players_count_signal(
     relation: Relation,
 ) -> impl Signal<Item = String> {
@@ -787,10 +763,7 @@ fn build_one_other(
     settings: &'static Settings,
 ) -> Dom {
     let is_wanted = other_data.is_wanted();
-    let nick_mutable = other_data.nick;
-    let lvl_mutable = other_data.lvl.clone();
-    let oplvl_mutable = other_data.operational_lvl.clone();
-    let prof_mutable = other_data.prof.clone();
+    let nick_mutable = other_data.nick.clone();
     let nick_cell = nick_mutable.signal_ref(|nick| {
         Some(html!("div", {
             .class("other-nick")
@@ -804,8 +777,7 @@ fn build_one_other(
         //.class(.unwrap_or(Relation::None).to_str())
         // TODO: Instead of `signal_vec_keys` create a signal that responds with the last added id ?
         .dynamic_class_signal(
-            other_data.relation
-                .signal()
+            other_data.relation_signal()
                 .switch(move |relation| {
                     Party::get()
                         .signal_vec_keys()
@@ -825,18 +797,9 @@ fn build_one_other(
             .child(html!("div", {
                 .class("nick")
                 .text_signal(map_ref!{
-                    let level_display = settings.level_display.signal(),
                     let nick = nick_mutable.signal_cloned(),
-                    let lvl = lvl_mutable.signal(),
-                    let oplvl = oplvl_mutable.signal(),
-                    let prof = prof_mutable.signal() => {
-                        match level_display {
-                            _ if *lvl <= 300 => format!("{nick} ({}{})", *lvl, *prof),
-                            LevelDisplay::First => format!("{nick} ({}{}|{}{})", *lvl, *prof, *oplvl, *prof),
-                            LevelDisplay::Last => format!("{nick} ({}{}|{}{})", *oplvl, *prof, *lvl, *prof),
-                            LevelDisplay::None => format!("{nick} ({}{})", *oplvl, *prof),
-                            LevelDisplay::Only => format!("{nick} ({}{})",*lvl, *prof),
-                        }
+                    let level_text = settings.player_list.level_text_signal(&other_data) => {
+                        format!("{nick} {level_text}")
                     }
                 })
             }))
@@ -880,22 +843,19 @@ fn build_one_other(
         .apply_if(is_wanted, |b| b.child(html!("div", {
             .class("skull")
         })))
+        .child_signal(settings.player_list.clan_column_signal(&other_data).map(|clan_opt| {
+            clan_opt.map(|clan| html!("div", {
+                .class("other-clan")
+                .class!(f-s[11] overflow[hidden] t-o[ellipsis])
+                .text(&clan)
+            }))
+        }))
         .child_signal(map_ref!{
-            let level_display = settings.level_display.signal(),
-            let lvl = other_data.lvl.signal(),
-            let oplvl = other_data.operational_lvl.signal(),
-            let prof = other_data.prof.signal() => {
-                let txt = match level_display {
-                    _ if *lvl <= 300 => format!("({}{})", *lvl, *prof),
-                    LevelDisplay::First => format!("({}{}|{}{})", *lvl, *prof, *oplvl, *prof),
-                    LevelDisplay::Last => format!("({}{}|{}{})", *oplvl, *prof, *lvl, *prof),
-                    LevelDisplay::None => format!("({}{})", *oplvl, *prof),
-                    LevelDisplay::Only => format!("({}{})",*lvl, *prof),
-                };
-
-                Some(html!("div", {
+            let level_column = settings.player_list.level_column.signal(),
+            let level_text = settings.player_list.level_text_signal(&other_data) => {
+                level_column.then(|| html!("div", {
                     .class("other-lvl")
-                    .text(&txt)
+                    .text(level_text)
                 }))
             }
         })
@@ -917,8 +877,8 @@ fn build_one_other(
         })
         .event_with_options(&EventOptions::preventable(), move |_: MouseDown| {
             //if !globals.others.has_color_mark(other_id) {
-            //common::debug_log!("HAS COLOR MARK:", ColorMark::has_mark(HIGHLIGHT_COLOR, ADDON_NAME, &other_id));
-            if ColorMark::has_mark(HIGHLIGHT_COLOR, ADDON_NAME, &other_id) && !state.is_scroll_target(other_id) {
+            //common::debug_log!("HAS COLOR MARK:", ColorMark::has_mark(&HIGHLIGHT_COLOR, ADDON_NAME, &other_id));
+            if ColorMark::has_mark(&HIGHLIGHT_COLOR, ADDON_NAME, &other_id) && !state.is_scroll_target(other_id) {
                 return
             }
 
@@ -957,7 +917,7 @@ fn build_one_other(
             {
                 get_engine().targets().unwrap_js().delete_arrow(&format!("Other-{other_id}")).unwrap_js();
             }
-            ColorMark::remove(HIGHLIGHT_COLOR, ADDON_NAME, other_id);
+            ColorMark::remove(&HIGHLIGHT_COLOR, ADDON_NAME, other_id);
             //globals.others.remove_color_mark(other_id);
         })
         .after_inserted(move |_| {
@@ -982,7 +942,7 @@ fn build_one_other(
                 //common::debug_log!("is target...");
                 return;
             }
-            if !ColorMark::has_mark(HIGHLIGHT_COLOR, ADDON_NAME, &other_id) {
+            if !ColorMark::has_mark(&HIGHLIGHT_COLOR, ADDON_NAME, &other_id) {
                 return;
             }
 
@@ -990,7 +950,7 @@ fn build_one_other(
             {
                 get_engine().targets().unwrap_js().delete_arrow(&format!("Other-{other_id}")).unwrap_js();
             }
-            ColorMark::remove(HIGHLIGHT_COLOR, ADDON_NAME, other_id);
+            ColorMark::remove(&HIGHLIGHT_COLOR, ADDON_NAME, other_id);
         })
     })
 }
@@ -999,14 +959,8 @@ pub(super) fn init(
     settings_window: &'static Settings,
     addon_window: &'static ActiveSettings,
 ) -> JsResult<()> {
-    let _settings_window_handle = WINDOWS_ROOT
-        .try_append_dom(settings_window.render()?)
-        .ok_or_else(|| err_code!())?;
-    let _addon_window_handle = WINDOWS_ROOT
-        .try_append_dom(addon_window.render(
-            settings_window,
-        )?)
-        .ok_or_else(|| err_code!())?;
+    let _settings_window_handle = WINDOWS_ROOT.append_window(settings_window.render()?)?;
+    let _addon_window_handle = WINDOWS_ROOT.append_window(addon_window.render(settings_window)?)?;
 
     Ok(())
 }
@@ -1,185 +1,63 @@
 mod html;
+mod sightings;
 
 use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
-use std::ops::Deref;
 
 use futures_signals::signal::{Mutable, Signal, SignalExt};
 use futures_signals::signal_vec::{SignalVecExt, VecDiff};
 use futures_signals::{map_ref, signal};
 use js_sys::JsString;
 use proc_macros::Settings;
-use serde_repr::{Deserialize_repr, Serialize_repr};
 use wasm_bindgen::{intern, prelude::*};
 use wasm_bindgen_futures::JsFuture;
 
 use crate::addons::kastrat::{MIN_DIFF, TargetData};
 use crate::bindings::engine::types::MapMode;
+use crate::player_list::PlayerListSettings;
 #[cfg(feature = "antyduch")]
 use crate::pathfinder::{Pos, pathfind_to};
 use crate::prelude::*;
 
 use super::kastrat::Target;
 
+use sightings::Sightings;
+
 thread_local! {
     static EMOTION_SOURCES: RefCell<HashMap<EmotionName, JsString>> = RefCell::new(HashMap::with_capacity(20));
 }
 
 const ADDON_NAME: AddonName = AddonName::BetterWhoIsHere;
 
-#[derive(Default, Copy, Clone, PartialEq, Eq, Debug, Serialize_repr, Deserialize_repr)]
-#[repr(u8)]
-enum SortBy {
-    #[default]
-    Lvl = 0,
-    Nick,
-    Prof,
-}
-
-impl SortBy {
-    fn as_str(self) -> &'static str {
-        match self {
-            Self::Lvl => "Poziomu",
-            Self::Nick => "Nicku",
-            Self::Prof => "Profesji",
-        }
-    }
-}
-
-#[derive(Default, Copy, Clone, PartialEq, Eq, Debug, Serialize_repr, Deserialize_repr)]
-#[repr(u8)]
-enum Ordering {
-    Ascending = 0,
-    #[default]
-    Descending,
-}
-
-impl Ordering {
-    fn as_str(self) -> &'static str {
-        match self {
-            Self::Descending => "Malejąco",
-            Self::Ascending => "Rosnąco",
-        }
-    }
-}
-
-#[derive(Default, Copy, Clone, PartialEq, Eq, Debug, Serialize_repr, Deserialize_repr)]
-#[repr(u8)]
-pub enum LevelDisplay {
-    First = 0,
-    Last,
-    None,
-    #[default]
-    Only,
-}
-
-impl LevelDisplay {
-    pub fn as_str(self) -> &'static str {
-        use LevelDisplay::*;
-
-        match self {
-            First => "Poziom | Poziom operacyjny",
-            Last => "Poziom operacyjny | Poziom",
-            None => "Tylko poziom operacyjny",
-            Only => "Tylko poziom",
-        }
-    }
-}
-
 #[derive(Settings)]
 struct Settings {
-    sort_by: Mutable<SortBy>,
-    ordering: Mutable<Ordering>,
     clear_target: Mutable<bool>,
-    level_display: Mutable<LevelDisplay>,
     replace_widget: Mutable<bool>,
+    record_sightings: Mutable<bool>,
+    player_list: PlayerListSettings,
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
-            sort_by: Mutable::default(),
-            ordering: Mutable::default(),
             clear_target: Mutable::default(),
-            level_display: Mutable::default(),
             replace_widget: Mutable::new(true),
+            record_sightings: Mutable::new(true),
+            player_list: PlayerListSettings::default(),
         }
     }
 }
 
-/// Map a character to its position in the Polish alphabet
-fn polish_char_value(c: char) -> u32 {
-    match c {
-        'a' => 1,
-        'ą' => 2,
-        'b' => 3,
-        'c' => 4,
-        'ć' => 5,
-        'd' => 6,
-        'e' => 7,
-        'ę' => 8,
-        'f' => 9,
-        'g' => 10,
-        'h' => 11,
-        'i' => 12,
-        'j' => 13,
-        'k' => 14,
-        'l' => 15,
-        'ł' => 16,
-        'm' => 17,
-        'n' => 18,
-        'ń' => 19,
-        'o' => 20,
-        'ó' => 21,
-        'p' => 22,
-        'q' => 23, // Not in Polish alphabet but included for completeness
-        'r' => 24,
-        's' => 25,
-        'ś' => 26,
-        't' => 27,
-        'u' => 28,
-        'v' => 29, // Not in native Polish alphabet but included for completeness
-        'w' => 30,
-        'x' => 31, // Not in Polish alphabet but included for completeness
-        'y' => 32,
-        'z' => 33,
-        'ź' => 34,
-        'ż' => 35,
-        // For characters not in the Polish alphabet, assign a higher value
-        _ => 1000 + (c as u32),
-    }
-}
-
-/// Compare two strings according to Polish alphabetical order
-pub fn compare_polish_strings(a: &str, b: &str) -> std::cmp::Ordering {
-    let a_chars: Vec<char> = a.chars().collect();
-    let b_chars: Vec<char> = b.chars().collect();
-
-    let len = std::cmp::min(a_chars.len(), b_chars.len());
-
-    for i in 0..len {
-        let a_val = polish_char_value(a_chars[i]);
-        let b_val = polish_char_value(b_chars[i]);
-
-        match a_val.cmp(&b_val) {
-            std::cmp::Ordering::Equal => continue,
-            other => return other,
-        }
-    }
-
-    // If we've compared all characters up to the minimum length and they're equal,
-    // then the shorter string comes first
-    a_chars.len().cmp(&b_chars.len())
-}
-
 impl Settings {
     #[cfg(feature = "ni")]
     fn init(&'static self) -> JsResult<()> {
-        let who_is_here = get_engine().who_is_here().ok_or_else(|| err_code!())?;
+        let who_is_here = get_engine()
+            .who_is_here()
+            .ok_or_else(|| err!(Get, "who is here"))?;
 
         let original_manage_panel_visible = who_is_here
             .get_manage_panel_visible()
-            .ok_or_else(|| err_code!())?;
+            .ok_or_else(|| err!(Get, "manage panel visible"))?;
         let new_manage_panel_visible = closure!(
             { let who_is_here = who_is_here.clone() },
             move || -> JsResult<()> {
@@ -198,143 +76,16 @@ impl Settings {
 
         Ok(())
     }
-
-    fn sort_signal(&'static self) -> impl Signal<Item = SortBy> {
-        self.ordering
-            .signal()
-            .dedupe()
-            .switch(|_| self.sort_by.signal().dedupe())
-    }
-
-    fn sort_ordering_signal(&self) -> impl Signal<Item = &'static str> {
-        self.ordering.signal().map(|ordering| ordering.as_str())
-    }
-
-    fn sort_by_signal(&self) -> impl Signal<Item = &'static str> {
-        self.sort_by.signal().map(|sort| sort.as_str())
-    }
-
-    fn level_display_signal(&self) -> impl Signal<Item = &'static str> {
-        self.level_display
-            .signal()
-            .map(|level_display| level_display.as_str())
-    }
-
-    fn compare(&self, a: &Other, b: &Other) -> std::cmp::Ordering {
-        //common::debug_log!("START CMP");
-        let sort_by = self.sort_by.get();
-        let ordering = self.ordering.get();
-
-        match sort_by {
-            SortBy::Nick => match ordering {
-                Ordering::Ascending => Self::compare_nick(a, b),
-                Ordering::Descending => Self::compare_nick(a, b).reverse(),
-            },
-            SortBy::Lvl => {
-                let lvl_order = match ordering {
-                    Ordering::Ascending => Self::compare_lvl(a, b),
-                    Ordering::Descending => Self::compare_lvl(a, b).reverse(),
-                };
-
-                lvl_order.then_with(|| Self::compare_nick(a, b))
-            }
-            SortBy::Prof => {
-                let prof_order = match ordering {
-                    Ordering::Ascending => Self::compare_prof(a, b),
-                    Ordering::Descending => Self::compare_prof(a, b).reverse(),
-                };
-
-                prof_order
-                    .then_with(|| Self::compare_lvl(a, b).reverse())
-                    .then_with(|| Self::compare_nick(a, b))
-            }
-        }
-
-        //common::debug_log!(
-        //    format!("{ord:?}"),
-        //    serde_wasm_bindgen::to_value(a).unwrap_js(),
-        //    serde_wasm_bindgen::to_value(b).unwrap_js(),
-        //);
-        //common::debug_log!("END CMP");
-    }
-
-    /// Implemented for ascending order, where
-    /// the topmost value is the first rendered cell.
-    fn compare_nick(a: &Other, b: &Other) -> std::cmp::Ordering {
-        let nick_a = &a.nick;
-        let nick_b = &b.nick;
-
-        compare_polish_strings(
-            nick_a.lock_ref().to_lowercase().as_str(),
-            nick_b.lock_ref().to_lowercase().as_str(),
-        )
-        //nick_a
-        //    .lock_ref()
-        //    .to_lowercase()
-        //    .cmp(&nick_b.lock_ref().to_lowercase())
-    }
-
-    /// Implemented for ascending order, where
-    /// the topmost value is the first rendered cell.
-    fn compare_lvl(a: &Other, b: &Other) -> std::cmp::Ordering {
-        let lvl_a = &a.lvl;
-        let lvl_b = &b.lvl;
-
-        lvl_a.lock_ref().cmp(lvl_b.lock_ref().deref())
-    }
-
-    // TODO: Store the profession counts globally instead of recounting on every update.
-    // FIXME: Errogenous search.
-    /// Implemented for ascending order, where
-    /// the topmost value is the first rendered cell.
-    fn compare_prof(a: &Other, b: &Other) -> std::cmp::Ordering {
-        let others_lock = Others::get().lock_ref();
-        //common::debug_log!(@f "others_length {}", others_lock.len());
-
-        let prof_a = &a.prof;
-        let prof_b = &b.prof;
-        let prof_a = prof_a.get();
-        let prof_b = prof_b.get();
-
-        if prof_a == prof_b {
-            //common::debug_log!("SAME PROF COUNT");
-            return std::cmp::Ordering::Equal;
-        }
-
-        let mut prof_a_count = 1;
-        let mut prof_b_count = 1;
-
-        //if !others_lock.contains_key(&a.char_id) || !others_lock.contains_key(&b.char_id) {
-        //    common::debug_log!(match others_lock.contains_key(&a.char_id) {
-        //        true => "B HAS BEEN REMOVED ALREADY",
-        //        false => "A HAS BEEN REMOVED ALREADY",
-        //    });
-        //}
-        //
-        others_lock
-            .iter()
-            .filter(|(id, _)| **id != a.char_id && **id != b.char_id)
-            .for_each(|(_, other_data)| match other_data.prof.get() {
-                prof if prof == prof_a => prof_a_count += 1,
-                prof if prof == prof_b => prof_b_count += 1,
-                _ => {}
-            });
-        //common::debug_log!(@f "prof_a_count: {prof_a_count} prof_b_count: {prof_b_count}");
-
-        prof_a_count
-            .cmp(&prof_b_count)
-            .then_with(|| prof_a.cmp(&prof_b))
-    }
 }
 
 #[derive(Default)]
 struct ActiveSettings {
     // TODO: Rename to `scroll_target` ?
     scroll_visible: Mutable<Option<Id>>,
-    search_text: Mutable<String>,
     //#[setting(skip)]
     target: Target,
     after_follow: Cell<bool>,
+    sightings: Sightings,
 }
 
 impl ActiveSettings {
@@ -463,14 +214,31 @@ pub(crate) fn init() -> JsResult<()> {
     use futures_signals::signal_map::{MapDiff, SignalMapExt};
 
     let settings = Settings::new(ADDON_NAME);
+    settings.player_list.migrate(ADDON_NAME);
     #[cfg(feature = "ni")]
     settings.init()?;
     let active_settings: &'static ActiveSettings = Box::leak(Box::new(ActiveSettings::default()));
     active_settings.init()?;
 
     let on_other = Others::get().signal_map_cloned().for_each(move |change| {
+        let record_sightings = settings.record_sightings.get() && Addons::is_active(ADDON_NAME);
+
         match change {
+            MapDiff::Insert { value, .. } => {
+                if record_sightings {
+                    active_settings.sightings.on_enter(&value);
+                }
+            }
+            MapDiff::Replace { entries } => {
+                active_settings.sightings.on_clear();
+                if record_sightings {
+                    entries
+                        .iter()
+                        .for_each(|(_, other)| active_settings.sightings.on_enter(other));
+                }
+            }
             MapDiff::Remove { key } => {
+                active_settings.sightings.on_leave(key);
                 if (!Premium::active() || settings.clear_target.get())
                     && active_settings.is_target(key)
                 {
@@ -478,6 +246,7 @@ pub(crate) fn init() -> JsResult<()> {
                 }
             }
             MapDiff::Clear {} => {
+                active_settings.sightings.on_clear();
                 if !Premium::active() || settings.clear_target.get() {
                     active_settings.clear_target();
                 }
@@ -489,6 +258,7 @@ pub(crate) fn init() -> JsResult<()> {
     });
 
     wasm_bindgen_futures::spawn_local(on_other);
+    wasm_bindgen_futures::spawn_local(active_settings.sightings.update_results());
 
     let future = async move {
         loop {
@@ -574,9 +344,9 @@ pub(crate) fn init() -> JsResult<()> {
                         .to_string()
                         .as_str(),
                 );
-                let _ = message(&format!(
-                    "[MDMA::RS] Atakuję \"{}\"",
-                    &target.nick.get_cloned()
+                let _ = message(&tr!(
+                    BetterWhoIsHereAttacking,
+                    nick = target.nick.get_cloned()
                 ));
                 drop(target_lock);
                 if send_request(attack_req).await.is_err() {
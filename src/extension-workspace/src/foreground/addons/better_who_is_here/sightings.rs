@@ -0,0 +1,386 @@
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeSet;
+
+use futures_signals::map_ref;
+use futures_signals::signal::{Mutable, SignalExt};
+use futures_signals::signal_map::{MutableBTreeMap, MutableBTreeMapLockMut};
+use futures_signals::signal_vec::SignalVecExt;
+use js_sys::Array;
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
+use wasm_bindgen::JsValue;
+use web_sys::{
+    IdbDatabase, IdbKeyRange, IdbObjectStore, IdbObjectStoreParameters, IdbTransactionMode,
+};
+
+use crate::bindings::engine::communication::{Profession, Relation};
+use crate::player_list::{PlayerFilter, PlayerSnapshot, QueryError};
+use crate::prelude::*;
+
+const DB_NAME: &str = "mdma-sightings";
+const DB_VERSION: u32 = 1;
+const STORE_NAME: &str = "sightings";
+/// Compound `[world, last_seen]` index used when loading a single world.
+const WORLD_LAST_SEEN_INDEX: &str = "world_last_seen";
+/// Least recently seen players above this count get removed from the world's history.
+const MAX_SIGHTINGS: usize = 3_000;
+/// Search results above this count are not displayed, keeping the most recently seen ones.
+pub(super) const MAX_DISPLAYED_SIGHTINGS: usize = 100;
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct Sighting {
+    pub(super) world: String,
+    pub(super) char_id: OtherId,
+    pub(super) nick: String,
+    pub(super) lvl: u16,
+    pub(super) operational_lvl: u16,
+    #[serde_as(as = "DisplayFromStr")]
+    pub(super) prof: Profession,
+    pub(super) clan: Option<String>,
+    /// Map the player was last seen on.
+    pub(super) map_name: Option<String>,
+    /// Unix timestamp in seconds.
+    pub(super) first_seen: f64,
+    /// Unix timestamp in seconds.
+    pub(super) last_seen: f64,
+}
+
+impl Sighting {
+    fn new(other: &Other, now: f64) -> Self {
+        Self {
+            world: WorldConfig::world_name(),
+            char_id: other.char_id,
+            nick: other.nick.get_cloned(),
+            lvl: other.lvl.get(),
+            operational_lvl: other.operational_lvl.get(),
+            prof: other.prof.get(),
+            clan: other.clan.lock_ref().as_ref().map(|clan| clan.name.clone()),
+            map_name: Town::get().lock_ref().name.clone(),
+            first_seen: now,
+            last_seen: now,
+        }
+    }
+
+    /// Relation is not recorded, it can change long after the player was seen.
+    pub(super) fn snapshot(&self) -> PlayerSnapshot {
+        PlayerSnapshot {
+            nick: self.nick.clone(),
+            lvl: self.lvl,
+            operational_lvl: self.operational_lvl,
+            prof: self.prof,
+            relation: Relation::None,
+            clan: self.clan.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SightingsStorage(IdbDatabase);
+
+impl SightingsStorage {
+    async fn open() -> JsResult<Self> {
+        idb_open(DB_NAME, DB_VERSION, Self::create_schema)
+            .await
+            .map(Self)
+    }
+
+    fn create_schema(db: &IdbDatabase) -> JsResult<()> {
+        let key_path = Array::of2(&JsValue::from_str("world"), &JsValue::from_str("char_id"));
+        let params = IdbObjectStoreParameters::new();
+        params.set_key_path(&key_path);
+
+        let store = db
+            .create_object_store_with_optional_parameters(STORE_NAME, &params)
+            .map_err(map_err!())?;
+        let world_last_seen_key_path =
+            Array::of2(&JsValue::from_str("world"), &JsValue::from_str("last_seen"));
+        store
+            .create_index_with_str_sequence(WORLD_LAST_SEEN_INDEX, &world_last_seen_key_path)
+            .map_err(map_err!())?;
+
+        Ok(())
+    }
+
+    fn store(&self, mode: IdbTransactionMode) -> JsResult<IdbObjectStore> {
+        idb_store(&self.0, STORE_NAME, mode)
+    }
+
+    /// Every sighting recorded on the world, least recently seen first.
+    async fn load_world(&self, world: &str) -> JsResult<Vec<Sighting>> {
+        let world = JsValue::from_str(world);
+        let range = IdbKeyRange::bound(
+            &Array::of2(&world, &JsValue::from_f64(0.0)),
+            &Array::of2(&world, &JsValue::from_f64(f64::MAX)),
+        )
+        .map_err(map_err!())?;
+        let request = self
+            .store(IdbTransactionMode::Readonly)?
+            .index(WORLD_LAST_SEEN_INDEX)
+            .map_err(map_err!())?
+            .get_all_with_key(&range)
+            .map_err(map_err!())?;
+
+        serde_wasm_bindgen::from_value(idb_request_result(&request).await?).map_err(map_err!(from))
+    }
+
+    /// Stores the sightings and removes the `removed` players of the same world.
+    async fn update(
+        &self,
+        sightings: &[Sighting],
+        world: &str,
+        removed: &[OtherId],
+    ) -> JsResult<()> {
+        let store = self.store(IdbTransactionMode::Readwrite)?;
+        let mut last_request = None;
+
+        for sighting in sightings {
+            let value = serde_wasm_bindgen::to_value(sighting).map_err(map_err!(from))?;
+            last_request = Some(store.put(&value).map_err(map_err!())?);
+        }
+        for char_id in removed {
+            let key = Array::of2(&JsValue::from_str(world), &JsValue::from(*char_id));
+            last_request = Some(store.delete(&key).map_err(map_err!())?);
+        }
+
+        // Requests within a transaction finish in order, waiting for the last one is enough.
+        if let Some(request) = last_request {
+            idb_request_result(&request).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// History of the players seen on the current world.
+#[derive(Default)]
+pub(super) struct Sightings {
+    storage: RefCell<Option<SightingsStorage>>,
+    opening: Cell<bool>,
+    /// Sightings recorded before the storage got opened.
+    pending: RefCell<Vec<Sighting>>,
+    /// Players currently on the hero's map, their `last_seen` gets updated once they leave.
+    present: RefCell<BTreeSet<OtherId>>,
+    pub(super) entries: MutableBTreeMap<OtherId, Sighting>,
+    pub(super) query: Mutable<String>,
+    pub(super) query_error: Mutable<String>,
+    pub(super) filter: Mutable<PlayerFilter>,
+    /// Lowercase part of the map name.
+    pub(super) map_name: Mutable<String>,
+    pub(super) visible: Mutable<bool>,
+    /// Matching sightings, most recently seen first, see [`Sightings::update_results`].
+    pub(super) results: Mutable<Vec<Sighting>>,
+}
+
+impl Sightings {
+    fn storage(&self) -> Option<SightingsStorage> {
+        self.storage.borrow().clone()
+    }
+
+    fn now() -> f64 {
+        js_sys::Date::now() / 1_000.0
+    }
+
+    pub(super) fn on_enter(&'static self, other: &Other) {
+        if WorldConfig::world_name().is_empty() {
+            return;
+        }
+
+        self.present.borrow_mut().insert(other.char_id);
+        self.save(vec![Sighting::new(other, Self::now())]);
+    }
+
+    pub(super) fn on_leave(&'static self, char_id: OtherId) {
+        if self.present.borrow_mut().remove(&char_id) {
+            self.touch(vec![char_id]);
+        }
+    }
+
+    /// Called when every player leaves at once, e.g. after the hero changes the map.
+    pub(super) fn on_clear(&'static self) {
+        let present = std::mem::take(&mut *self.present.borrow_mut());
+        self.touch(present.into_iter().collect());
+    }
+
+    fn touch(&'static self, char_ids: Vec<OtherId>) {
+        let now = Self::now();
+        let sightings = {
+            let entries_lock = self.entries.lock_ref();
+            char_ids
+                .iter()
+                .filter_map(|char_id| entries_lock.get(char_id).cloned())
+                .map(|sighting| Sighting {
+                    last_seen: now,
+                    ..sighting
+                })
+                .collect()
+        };
+
+        self.save(sightings);
+    }
+
+    fn save(&'static self, mut sightings: Vec<Sighting>) {
+        let Some(storage) = self.storage() else {
+            let mut pending = self.pending.borrow_mut();
+            let free = MAX_SIGHTINGS.saturating_sub(pending.len());
+            pending.extend(sightings.into_iter().take(free));
+            drop(pending);
+
+            return self.open();
+        };
+        if sightings.is_empty() {
+            return;
+        }
+
+        let mut entries_lock = self.entries.lock_mut();
+        sightings.iter_mut().for_each(|sighting| {
+            if let Some(old_sighting) = entries_lock.get(&sighting.char_id) {
+                sighting.first_seen = old_sighting.first_seen;
+            }
+            entries_lock.insert_cloned(sighting.char_id, sighting.clone());
+        });
+        let removed = Self::trim(&mut entries_lock);
+        drop(entries_lock);
+
+        let world = WorldConfig::world_name();
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(err_code) = storage.update(&sightings, &world, &removed).await {
+                console_error!(err_code);
+            }
+        });
+    }
+
+    /// Removes the least recently seen players above [`MAX_SIGHTINGS`].
+    fn trim(entries_lock: &mut MutableBTreeMapLockMut<'_, OtherId, Sighting>) -> Vec<OtherId> {
+        let excess = entries_lock.len().saturating_sub(MAX_SIGHTINGS);
+        if excess == 0 {
+            return Vec::new();
+        }
+
+        let mut by_last_seen: Vec<_> = entries_lock
+            .iter()
+            .map(|(char_id, sighting)| (sighting.last_seen, *char_id))
+            .collect();
+        by_last_seen.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        by_last_seen
+            .into_iter()
+            .take(excess)
+            .map(|(_, char_id)| {
+                entries_lock.remove(&char_id);
+                char_id
+            })
+            .collect()
+    }
+
+    fn open(&'static self) {
+        if self.opening.replace(true) {
+            return;
+        }
+
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(err_code) = self.load().await {
+                // The next sighting tries again.
+                self.opening.set(false);
+                console_error!(err_code);
+            }
+        });
+    }
+
+    async fn load(&'static self) -> JsResult<()> {
+        let storage = SightingsStorage::open().await?;
+        let sightings = storage.load_world(&WorldConfig::world_name()).await?;
+
+        self.entries.lock_mut().replace_cloned(
+            sightings
+                .into_iter()
+                .map(|sighting| (sighting.char_id, sighting))
+                .collect(),
+        );
+        self.storage.replace(Some(storage));
+        let pending = self.pending.take();
+        self.save(pending);
+
+        Ok(())
+    }
+
+    pub(super) fn set_query(&self, query: String) -> Result<(), QueryError> {
+        let filter = query.parse()?;
+
+        self.query.set_neq(query);
+        self.filter.set(filter);
+
+        Ok(())
+    }
+
+    fn matches(&self, sighting: &Sighting) -> bool {
+        let map_name = self.map_name.lock_ref();
+        let map_matches = map_name.is_empty()
+            || sighting
+                .map_name
+                .as_ref()
+                .is_some_and(|name| name.to_lowercase().contains(map_name.as_str()));
+
+        map_matches && self.filter.lock_ref().matches(&sighting.snapshot())
+    }
+
+    /// Keeps `results` up to date whenever the history or the search changes,
+    /// the search only runs while the history is visible.
+    pub(super) async fn update_results(&'static self) {
+        map_ref! {
+            let visible = self.visible.signal(),
+            let _entries = self.entries.entries_cloned().to_signal_map(|_| ()),
+            let _filter = self.filter.signal_ref(|_| ()),
+            let _map_name = self.map_name.signal_ref(|_| ()) => *visible
+        }
+        .for_each(|visible| {
+            if visible {
+                self.results.set(self.search());
+            }
+            async {}
+        })
+        .await
+    }
+
+    /// Matching sightings, most recently seen first.
+    pub(super) fn search(&self) -> Vec<Sighting> {
+        let mut results: Vec<_> = self
+            .entries
+            .lock_ref()
+            .values()
+            .filter(|sighting| self.matches(sighting))
+            .cloned()
+            .collect();
+        results.sort_by(|a, b| b.last_seen.total_cmp(&a.last_seen));
+
+        results
+    }
+
+    pub(super) fn to_json(&self) -> JsResult<String> {
+        serde_json::to_string_pretty(&self.search()).map_err(map_err!(from))
+    }
+
+    pub(super) fn to_csv(&self) -> String {
+        let mut lines = vec![
+            "world,char_id,nick,lvl,operational_lvl,prof,clan,map_name,first_seen,last_seen"
+                .to_owned(),
+        ];
+        lines.extend(self.search().into_iter().map(|sighting| {
+            format!(
+                "{},{},{},{},{},{},{},{},{},{}",
+                csv_escape(&sighting.world),
+                sighting.char_id,
+                csv_escape(&sighting.nick),
+                sighting.lvl,
+                sighting.operational_lvl,
+                sighting.prof,
+                csv_escape(sighting.clan.as_deref().unwrap_or_default()),
+                csv_escape(sighting.map_name.as_deref().unwrap_or_default()),
+                format_ts(sighting.first_seen),
+                format_ts(sighting.last_seen),
+            )
+        }));
+
+        lines.join("\n")
+    }
+}
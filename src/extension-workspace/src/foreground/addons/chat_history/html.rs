@@ -0,0 +1,300 @@
+use dominator::{Dom, html};
+use futures_signals::signal::{Mutable, SignalExt};
+use futures_signals::signal_vec::SignalVecExt;
+
+use crate::addon_window::prelude::*;
+use crate::bindings::engine::communication::ChatChannelName;
+use crate::interface::{ThreadLocalShadowRoot, WINDOWS_ROOT};
+use crate::prelude::*;
+
+use super::storage::StoredMessage;
+use super::{ADDON_NAME, ActiveSettings, CHANNELS, Settings, channel_label};
+
+impl ActiveSettings {
+    fn filter_input(
+        &'static self,
+        label: &str,
+        placeholder: &str,
+        value: &'static Mutable<String>,
+    ) -> ContentSection {
+        let input = Input::builder()
+            .placeholder(placeholder)
+            .maxlength("60")
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .on_input(move |_event, input_elem| value.set(input_elem.value()));
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between] a-i[center] g[10]")
+            .section(ContentSection::new().text(label.text()))
+            .input(input)
+    }
+
+    fn channel_option(&'static self, channel: Option<ChatChannelName>) -> ScrollWrapperOption {
+        ScrollWrapperOption::builder()
+            .text(channel.map(channel_label).unwrap_or(tr!(ChatChannelAll)))
+            .on_click(move |_| self.search.channel.set_neq(channel))
+            .build()
+    }
+
+    fn channel_setting(&'static self) -> ContentSection {
+        // TODO: Figure out a better solution.
+        let wrapper_state: &'static _ = Box::leak(Box::new(Mutable::default()));
+        let scroll_wrapper = CHANNELS.iter().fold(
+            ScrollWrapper::builder(|| {
+                || {
+                    wrapper_state.set_neq(false);
+                }
+            })
+            .class_list("w[150] l[1]")
+            .visible_signal(wrapper_state.signal())
+            .option(self.channel_option(None)),
+            |scroll_wrapper, (channel, _)| scroll_wrapper.option(self.channel_option(Some(*channel))),
+        );
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between]")
+            .section(
+                ContentSection::new()
+                    .class_list("a-c[center]")
+                    .text(tr!(ChatChannel)),
+            )
+            .button(
+                Button::builder()
+                    .class_list("w[158] t-a[left]")
+                    .no_hover()
+                    .text_signal(
+                        self.search.channel.signal().map(|channel| {
+                            channel.map(channel_label).unwrap_or(tr!(ChatChannelAll))
+                        }),
+                    )
+                    .on_click(|_| wrapper_state.set_neq(true))
+                    .on_mousedown(|event| event.stop_propagation())
+                    .mixin(|builder| {
+                        builder.child(html!("div", {
+                            .class!(pos[absolute] r[8] align-center menu-arrow)
+                        }))
+                    })
+                    .scroll_wrapper(scroll_wrapper.build()),
+            )
+    }
+
+    fn render_filters(&'static self) -> ContentSection {
+        let search = &self.search;
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[5]")
+            .section(self.channel_setting())
+            .section(self.filter_input(
+                tr!(ChatHistoryText),
+                tr!(ChatHistoryTextPlaceholder),
+                &search.text,
+            ))
+            .section(self.filter_input(
+                tr!(ChatHistoryNick),
+                tr!(ChatHistoryNickPlaceholder),
+                &search.nick,
+            ))
+            .section(self.filter_input(
+                tr!(ChatHistoryFrom),
+                tr!(ChatHistoryDatePlaceholder),
+                &search.from,
+            ))
+            .section(self.filter_input(
+                tr!(ChatHistoryTo),
+                tr!(ChatHistoryDatePlaceholder),
+                &search.to,
+            ))
+    }
+
+    fn render_one_result(
+        &'static self,
+        settings: &'static Settings,
+        message: StoredMessage,
+    ) -> ContentSection {
+        let nick = match (&message.author, &message.receiver) {
+            (Some(author), Some(receiver)) => format!("{author} -> {receiver}"),
+            (Some(author), None) => author.clone(),
+            _ => channel_label(message.channel).to_owned(),
+        };
+        let header = format!(
+            "{} [{}] {nick}",
+            format_ts(message.ts),
+            channel_label(message.channel)
+        );
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] w-s[pre-line]")
+            .apply_if(settings.is_highlighted(&message.text), |section| {
+                section.mixin(|builder| builder.style("color", "orange"))
+            })
+            .section(ContentSection::new().class_list("o[60%]").text(&header))
+            .section(ContentSection::new().text(&message.text))
+    }
+
+    fn render_results(&'static self, settings: &'static Settings) -> ContentSection {
+        let results = self
+            .search
+            .results
+            .signal_vec_cloned()
+            .map(move |message| self.render_one_result(settings, message));
+        let truncated_info = ContentSection::new()
+            .class_list("mdma-text")
+            .visible_signal(self.search.truncated.signal())
+            .text(tr!(ChatHistoryTruncated));
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[5]")
+            .section(truncated_info)
+            .section(
+                ContentSection::new()
+                    .class_list("d[flex] f-d[column] g[5] max-h[300] scroll-y b-f[glassy-blur]")
+                    .section_signal_vec(results),
+            )
+    }
+
+    fn render_controls(&'static self) -> ContentSection {
+        let search_button = Button::builder()
+            .text(tr!(ChatHistorySearch))
+            .on_click(move |_| self.search());
+        let export_button = Button::builder()
+            .text(tr!(ChatHistoryExport))
+            .on_click(move |_| self.export_to_clipboard());
+
+        ContentSection::new()
+            .class_list("m-top[6]")
+            .button_pair(ButtonPair::builder(search_button, export_button))
+    }
+
+    fn export_to_clipboard(&'static self) {
+        let text = self.to_export();
+        if text.is_empty() {
+            let _ = message(tr!(ChatHistoryExportEmpty));
+            return;
+        }
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let clipboard = window().navigator().clipboard();
+            if let Err(err) = wasm_bindgen_futures::JsFuture::from(clipboard.write_text(&text))
+                .await
+                .map_err(map_err!())
+            {
+                return console_error!(err);
+            }
+            if message(tr!(ChatHistoryExported)).is_err() {
+                console_error!()
+            }
+        });
+    }
+
+    fn render(&'static self, settings: &'static Settings) -> JsResult<Dom> {
+        let decor = HeaderDecor::builder()
+            .push_left(decors::OpacityToggle::new())
+            .push_right(decors::CloseButton::new())
+            .push_right(decors::CollapseButton::new())
+            .build();
+        let header = WindowHeader::new(decor);
+        let content = WindowContent::builder()
+            .class_list("f-d[column]")
+            .section(self.render_filters())
+            .section(self.render_controls())
+            .heading(Heading::builder().text(tr!(ChatHistoryResults)))
+            .section(self.render_results(settings));
+
+        AddonWindow::builder(ADDON_NAME)
+            .header(header)
+            .content(content)
+            .build()
+    }
+}
+
+impl Settings {
+    fn render(&'static self) -> JsResult<Dom> {
+        let decor = HeaderDecor::builder()
+            .push_left(decors::OpacityToggle::new())
+            .push_right(decors::CloseButton::new())
+            .build();
+        let header = WindowHeader::new(decor);
+
+        let channels = CHANNELS.iter().fold(
+            ContentSection::new().class_list("d[flex] f-d[column] g[3]"),
+            |section, (channel, label)| {
+                section.checkbox(
+                    Checkbox::builder(self.channels.get(*channel).clone()).text(label.text()),
+                )
+            },
+        );
+        let keywords_input = Input::builder()
+            .placeholder(tr!(ChatHistoryKeywordsPlaceholder))
+            .maxlength("200")
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .value_signal(self.keywords.signal_ref(|keywords| Some(keywords.join(", "))))
+            .confirm_button(
+                InputButton::builder()
+                    .tip(tr!(ChatHistorySave))
+                    .on_click(move |_event, input_elem| {
+                        self.set_keywords(&input_elem.value());
+                    }),
+            );
+        let retention_input = Input::builder()
+            .value(self.retention_days.get().to_string())
+            .input_type(InputType::number(0.0, 365.0))
+            .maxlength("3")
+            .on_input(move |event, elem| {
+                event.prevent_default();
+                event.stop_immediate_propagation();
+
+                let value = elem.value_as_number() as u16;
+                self.retention_days.set_neq(value);
+            });
+        let retention_section = ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between] a-i[center]")
+            .section(ContentSection::new().text(tr!(ChatHistoryRetentionDays)))
+            .input(retention_input);
+
+        let content = WindowContent::builder()
+            .class_list("f-d[column]")
+            .heading(
+                Heading::builder()
+                    .text(tr!(ChatHistoryChannels))
+                    .class_list("first-heading"),
+            )
+            .section(channels)
+            .heading(
+                Heading::builder()
+                    .text(tr!(ChatHistoryKeywords))
+                    .info_bubble(
+                        InfoBubble::builder()
+                            .text(tr!(ChatHistoryKeywordsInfo))
+                            .build(),
+                    ),
+            )
+            .section(ContentSection::new().input(keywords_input))
+            .heading(
+                Heading::builder()
+                    .text(tr!(ChatHistoryRetention))
+                    .info_bubble(
+                        InfoBubble::builder()
+                            .text(tr!(ChatHistoryRetentionInfo))
+                            .build(),
+                    ),
+            )
+            .section(retention_section);
+
+        SettingsWindow::builder(ADDON_NAME)
+            .header(header)
+            .content(content)
+            .build()
+    }
+}
+
+pub(super) fn init(
+    active_settings: &'static ActiveSettings,
+    settings: &'static Settings,
+) -> JsResult<()> {
+    let _settings_window_handle = WINDOWS_ROOT.append_window(settings.render()?)?;
+    let _addon_window_handle = WINDOWS_ROOT.append_window(active_settings.render(settings)?)?;
+
+    Ok(())
+}
@@ -0,0 +1,353 @@
+mod html;
+mod storage;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use common::i18n::Msg;
+use futures_signals::signal::Mutable;
+use futures_signals::signal_vec::MutableVec;
+use proc_macros::{ActiveSettings, Setting, Settings};
+
+use crate::bindings::engine::communication::{Chat, ChatChannelName};
+use crate::prelude::*;
+
+use storage::{ChatStorage, StoredMessage};
+
+const ADDON_NAME: AddonName = AddonName::ChatHistory;
+const MS_PER_DAY: f64 = 86_400_000.0;
+/// The search stops after this many results, keeping the newest ones.
+const MAX_SEARCH_RESULTS: usize = 300;
+/// Messages received above this count before the storage gets opened are
+/// dropped, e.g. when it fails to open.
+const MAX_PENDING: usize = 1_000;
+
+pub(crate) const CHANNELS: [(ChatChannelName, Msg); 7] = [
+    (ChatChannelName::Global, Msg::ChatChannelGlobal),
+    (ChatChannelName::Local, Msg::ChatChannelLocal),
+    (ChatChannelName::Trade, Msg::ChatChannelTrade),
+    (ChatChannelName::Group, Msg::ChatChannelGroup),
+    (ChatChannelName::Clan, Msg::ChatChannelClan),
+    (ChatChannelName::Personal, Msg::ChatChannelPersonal),
+    (ChatChannelName::System, Msg::ChatChannelSystem),
+];
+
+pub(crate) fn channel_label(channel: ChatChannelName) -> &'static str {
+    CHANNELS
+        .iter()
+        .find_map(|(name, label)| (*name == channel).then(|| label.text()))
+        .unwrap_or_default()
+}
+
+#[derive(Setting, Clone)]
+struct RecordedChannels {
+    global: Mutable<bool>,
+    local: Mutable<bool>,
+    trade: Mutable<bool>,
+    group: Mutable<bool>,
+    clan: Mutable<bool>,
+    personal: Mutable<bool>,
+    system: Mutable<bool>,
+}
+
+impl Default for RecordedChannels {
+    fn default() -> Self {
+        Self {
+            global: Mutable::new(true),
+            local: Mutable::new(true),
+            trade: Mutable::new(true),
+            group: Mutable::new(true),
+            clan: Mutable::new(true),
+            personal: Mutable::new(true),
+            system: Mutable::new(false),
+        }
+    }
+}
+
+impl RecordedChannels {
+    fn get(&self, channel: ChatChannelName) -> &Mutable<bool> {
+        match channel {
+            ChatChannelName::Global => &self.global,
+            ChatChannelName::Local => &self.local,
+            ChatChannelName::Trade => &self.trade,
+            ChatChannelName::Group => &self.group,
+            ChatChannelName::Clan => &self.clan,
+            ChatChannelName::Personal => &self.personal,
+            ChatChannelName::System => &self.system,
+        }
+    }
+}
+
+#[derive(Settings)]
+struct Settings {
+    channels: RecordedChannels,
+    /// Messages containing any of these get highlighted in the search results.
+    keywords: Mutable<Vec<String>>,
+    /// Messages older than this get removed on startup, 0 keeps them forever.
+    retention_days: Mutable<u16>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            channels: RecordedChannels::default(),
+            keywords: Mutable::default(),
+            retention_days: Mutable::new(30),
+        }
+    }
+}
+
+impl Settings {
+    fn set_keywords(&self, value: &str) {
+        let keywords = value
+            .split(',')
+            .map(|keyword| keyword.trim().to_lowercase())
+            .filter(|keyword| !keyword.is_empty())
+            .collect();
+
+        self.keywords.set(keywords);
+    }
+
+    fn is_highlighted(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+
+        self.keywords
+            .lock_ref()
+            .iter()
+            .any(|keyword| text.contains(keyword.as_str()))
+    }
+}
+
+#[derive(Default)]
+struct Search {
+    /// `None` searches through every channel.
+    channel: Mutable<Option<ChatChannelName>>,
+    text: Mutable<String>,
+    nick: Mutable<String>,
+    /// Date in the `YYYY-MM-DD` format.
+    from: Mutable<String>,
+    /// Date in the `YYYY-MM-DD` format, inclusive.
+    to: Mutable<String>,
+    results: MutableVec<StoredMessage>,
+    truncated: Mutable<bool>,
+}
+
+impl Search {
+    /// Parses a `YYYY-MM-DD` date into a unix timestamp in seconds of its local midnight, or of
+    /// its last second if `end_of_day` is set.
+    fn parse_date(value: &str, end_of_day: bool) -> Option<f64> {
+        let mut parts = value.trim().splitn(3, '-');
+        let year = parts.next()?.parse::<u32>().ok()?;
+        let month = parts.next()?.parse::<i32>().ok()?;
+        let day = parts.next()?.parse::<i32>().ok()?;
+
+        // Unlike `Date.parse`, which reads date only strings as UTC, the constructor uses local
+        // time like `format_ts` does. Months start at 0 and overflowing days roll over.
+        let next_day = end_of_day as i32;
+        let ms = js_sys::Date::new_with_year_month_day(year, month - 1, day + next_day).get_time();
+
+        (!ms.is_nan()).then(|| ms / 1_000.0 - next_day as f64)
+    }
+
+    fn matches(&self, message: &StoredMessage) -> bool {
+        let text = self.text.lock_ref().trim().to_lowercase();
+        let nick = self.nick.lock_ref().trim().to_lowercase();
+
+        let text_matches = text.is_empty() || message.text.to_lowercase().contains(&text);
+        let nick_matches = nick.is_empty()
+            || [&message.author, &message.receiver]
+                .into_iter()
+                .flatten()
+                .any(|message_nick| message_nick.to_lowercase().contains(&nick));
+
+        text_matches && nick_matches
+    }
+}
+
+#[derive(Default)]
+struct Recorder {
+    storage: RefCell<Option<ChatStorage>>,
+    /// Messages received before the storage got opened, up to [`MAX_PENDING`].
+    pending: RefCell<Vec<StoredMessage>>,
+    /// Timestamp of the newest recorded message of each channel along with the
+    /// recorded messages sharing it, the engine resends the recent history after a
+    /// reload so these would get duplicated otherwise. Channels are tracked
+    /// separately as their messages don't arrive in order with each other.
+    newest: RefCell<HashMap<ChatChannelName, (f64, Vec<StoredMessage>)>>,
+}
+
+impl Recorder {
+    fn storage(&self) -> Option<ChatStorage> {
+        self.storage.borrow().clone()
+    }
+
+    fn record(&'static self, messages: Vec<StoredMessage>) {
+        let Some(storage) = self.storage() else {
+            let mut pending = self.pending.borrow_mut();
+            let free = MAX_PENDING.saturating_sub(pending.len());
+            pending.extend(messages.into_iter().take(free));
+
+            return;
+        };
+
+        let messages: Vec<_> = messages
+            .into_iter()
+            .filter(|message| self.is_new(message))
+            .collect();
+        if messages.is_empty() {
+            return;
+        }
+
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(err_code) = storage.add(&messages).await {
+                console_error!(err_code);
+            }
+        });
+    }
+
+    fn is_new(&self, message: &StoredMessage) -> bool {
+        let mut newest = self.newest.borrow_mut();
+        let (last_ts, last_ts_messages) = newest.entry(message.channel).or_default();
+
+        if message.ts < *last_ts || last_ts_messages.contains(message) {
+            return false;
+        }
+        if message.ts > *last_ts {
+            *last_ts = message.ts;
+            last_ts_messages.clear();
+        }
+        last_ts_messages.push(message.clone());
+
+        true
+    }
+
+    async fn open(&'static self, retention_days: u16) -> JsResult<()> {
+        let storage = ChatStorage::open().await?;
+
+        if retention_days > 0 {
+            let min_ts = (js_sys::Date::now() - retention_days as f64 * MS_PER_DAY) / 1_000.0;
+            storage.remove_older_than(min_ts).await?;
+        }
+        for (channel, _) in CHANNELS {
+            if let Some(last) = storage.last(channel).await? {
+                self.newest
+                    .borrow_mut()
+                    .insert(channel, (last.ts, vec![last]));
+            }
+        }
+
+        self.storage.replace(Some(storage));
+        let pending = self.pending.take();
+        self.record(pending);
+
+        Ok(())
+    }
+}
+
+#[derive(ActiveSettings, Default)]
+struct ActiveSettings {
+    #[setting(skip)]
+    search: Search,
+    #[setting(skip)]
+    recorder: Recorder,
+}
+
+impl ActiveSettings {
+    fn init(&'static self, settings: &'static Settings) -> JsResult<()> {
+        let retention_days = settings.retention_days.get();
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(err_code) = self.recorder.open(retention_days).await {
+                console_error!(err_code);
+            }
+        });
+
+        Emitter::register_on(EmitterEvent::Chat, move |socket_response| {
+            if let Some(chat) = socket_response.chat.as_ref()
+                && Addons::is_active(ADDON_NAME)
+            {
+                self.recorder.record(Self::collect_messages(chat, settings));
+            }
+            Box::pin(async { Ok(()) })
+        })?;
+
+        Ok(())
+    }
+
+    fn collect_messages(chat: &Chat, settings: &Settings) -> Vec<StoredMessage> {
+        let mut messages: Vec<_> = enum_iterator::all::<ChatChannelName>()
+            .filter(|channel| settings.channels.get(*channel).get())
+            .filter_map(|channel| Some((channel, chat.get_messages(channel)?)))
+            .flat_map(|(channel, channel_messages)| {
+                channel_messages.iter().filter_map(move |message| {
+                    Some(StoredMessage {
+                        channel,
+                        ts: message.ts()?,
+                        author: message.author_nick().map(str::to_owned),
+                        receiver: message.receiver_nick().map(str::to_owned),
+                        text: message.text()?.to_owned(),
+                    })
+                })
+            })
+            .collect();
+        messages.sort_by(|a, b| a.ts.total_cmp(&b.ts));
+
+        messages
+    }
+
+    fn search(&'static self) {
+        let Some(storage) = self.recorder.storage() else {
+            if message(tr!(ChatHistoryNotLoaded)).is_err() {
+                console_error!();
+            }
+            return;
+        };
+        let search = &self.search;
+        let from_ts = Search::parse_date(&search.from.lock_ref(), false).unwrap_or(0.0);
+        // Include the whole end day.
+        let to_ts = Search::parse_date(&search.to.lock_ref(), true).unwrap_or(f64::MAX);
+        let channel = search.channel.get();
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let query = storage.query(channel, from_ts, to_ts, MAX_SEARCH_RESULTS, |message| {
+                search.matches(message)
+            });
+            let (results, truncated) = match query.await {
+                Ok(results) => results,
+                Err(err_code) => return console_error!(err_code),
+            };
+
+            search.truncated.set_neq(truncated);
+            search.results.lock_mut().replace_cloned(results);
+        });
+    }
+
+    fn to_export(&self) -> String {
+        self.search
+            .results
+            .lock_ref()
+            .iter()
+            .map(|message| {
+                let nick = match (&message.author, &message.receiver) {
+                    (Some(author), Some(receiver)) => format!("{author} -> {receiver}: "),
+                    (Some(author), None) => format!("{author}: "),
+                    _ => String::new(),
+                };
+                format!(
+                    "[{}] [{}] {nick}{}",
+                    format_ts(message.ts),
+                    channel_label(message.channel),
+                    message.text
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+pub(crate) fn init() -> JsResult<()> {
+    let settings = Settings::new(ADDON_NAME);
+    let active_settings = ActiveSettings::new(ADDON_NAME);
+    active_settings.init(settings)?;
+
+    html::init(active_settings, settings)
+}
@@ -0,0 +1,193 @@
+use js_sys::Array;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    IdbCursorDirection, IdbCursorWithValue, IdbDatabase, IdbIndex, IdbKeyRange, IdbObjectStore,
+    IdbObjectStoreParameters, IdbTransactionMode,
+};
+
+use crate::bindings::engine::communication::ChatChannelName;
+use crate::prelude::*;
+
+const DB_NAME: &str = "mdma-chat-history";
+const DB_VERSION: u32 = 1;
+const STORE_NAME: &str = "messages";
+const KEY_PATH: &str = "id";
+/// Compound `[channel, ts]` index used when searching a single channel.
+const CHANNEL_TS_INDEX: &str = "channel_ts";
+const TS_INDEX: &str = "ts";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct StoredMessage {
+    pub(super) channel: ChatChannelName,
+    /// Unix timestamp in seconds.
+    pub(super) ts: f64,
+    pub(super) author: Option<String>,
+    /// Only present in private messages.
+    pub(super) receiver: Option<String>,
+    pub(super) text: String,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct ChatStorage(IdbDatabase);
+
+impl ChatStorage {
+    pub(super) async fn open() -> JsResult<Self> {
+        idb_open(DB_NAME, DB_VERSION, Self::create_schema)
+            .await
+            .map(Self)
+    }
+
+    fn create_schema(db: &IdbDatabase) -> JsResult<()> {
+        let params = IdbObjectStoreParameters::new();
+        params.set_auto_increment(true);
+        params.set_key_path(&JsValue::from_str(KEY_PATH));
+
+        let store = db
+            .create_object_store_with_optional_parameters(STORE_NAME, &params)
+            .map_err(map_err!())?;
+        let channel_ts_key_path = Array::of2(&JsValue::from_str("channel"), &JsValue::from_str("ts"));
+        store
+            .create_index_with_str_sequence(CHANNEL_TS_INDEX, &channel_ts_key_path)
+            .map_err(map_err!())?;
+        store
+            .create_index_with_str(TS_INDEX, "ts")
+            .map_err(map_err!())?;
+
+        Ok(())
+    }
+
+    fn store(&self, mode: IdbTransactionMode) -> JsResult<IdbObjectStore> {
+        idb_store(&self.0, STORE_NAME, mode)
+    }
+
+    pub(super) async fn add(&self, messages: &[StoredMessage]) -> JsResult<()> {
+        let store = self.store(IdbTransactionMode::Readwrite)?;
+        let mut last_request = None;
+
+        for message in messages {
+            let value = serde_wasm_bindgen::to_value(message).map_err(map_err!(from))?;
+            last_request = Some(store.add(&value).map_err(map_err!())?);
+        }
+
+        // Requests within a transaction finish in order, waiting for the last one is enough.
+        if let Some(request) = last_request {
+            idb_request_result(&request).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Newest stored message of the channel, used to skip messages resent by the engine after a
+    /// reload.
+    pub(super) async fn last(&self, channel: ChatChannelName) -> JsResult<Option<StoredMessage>> {
+        let (mut messages, _) = self
+            .query(Some(channel), f64::NEG_INFINITY, f64::INFINITY, 1, |_| true)
+            .await?;
+
+        Ok(messages.pop())
+    }
+
+    /// Newest messages with `from_ts <= ts <= to_ts` accepted by `filter`, oldest first.
+    ///
+    /// Walks the range with a cursor and stops after `limit` messages, so that a wide range
+    /// doesn't get loaded at once. Also returns whether older messages got cut off.
+    pub(super) async fn query(
+        &self,
+        channel: Option<ChatChannelName>,
+        from_ts: f64,
+        to_ts: f64,
+        limit: usize,
+        filter: impl Fn(&StoredMessage) -> bool,
+    ) -> JsResult<(Vec<StoredMessage>, bool)> {
+        let (index, range) = self.index_range(channel, from_ts, to_ts)?;
+        let request = index
+            .open_cursor_with_range_and_direction(&range, IdbCursorDirection::Prev)
+            .map_err(map_err!())?;
+        let mut messages = Vec::new();
+        let mut truncated = false;
+
+        // The request succeeds again after every `continue_`, with a null cursor at the end.
+        loop {
+            let cursor = idb_request_result(&request).await?;
+            if cursor.is_null() {
+                break;
+            }
+
+            let cursor: IdbCursorWithValue = cursor.unchecked_into();
+            let message: StoredMessage =
+                serde_wasm_bindgen::from_value(cursor.value().map_err(map_err!())?)
+                    .map_err(map_err!(from))?;
+
+            if filter(&message) {
+                if messages.len() == limit {
+                    truncated = true;
+                    break;
+                }
+                messages.push(message);
+            }
+            cursor.continue_().map_err(map_err!())?;
+        }
+        messages.reverse();
+
+        Ok((messages, truncated))
+    }
+
+    fn index_range(
+        &self,
+        channel: Option<ChatChannelName>,
+        from_ts: f64,
+        to_ts: f64,
+    ) -> JsResult<(IdbIndex, IdbKeyRange)> {
+        let store = self.store(IdbTransactionMode::Readonly)?;
+
+        match channel {
+            Some(channel) => {
+                let channel = JsValue::from_str(channel.as_str());
+                let range = IdbKeyRange::bound(
+                    &Array::of2(&channel, &JsValue::from_f64(from_ts)),
+                    &Array::of2(&channel, &JsValue::from_f64(to_ts)),
+                )
+                .map_err(map_err!())?;
+
+                Ok((store.index(CHANNEL_TS_INDEX).map_err(map_err!())?, range))
+            }
+            None => {
+                let range =
+                    IdbKeyRange::bound(&JsValue::from_f64(from_ts), &JsValue::from_f64(to_ts))
+                        .map_err(map_err!())?;
+
+                Ok((store.index(TS_INDEX).map_err(map_err!())?, range))
+            }
+        }
+    }
+
+    /// Remove every message older than `ts`.
+    ///
+    /// Messages of different channels aren't stored in timestamp order, so the outdated ones are
+    /// deleted by their keys instead of a key range.
+    pub(super) async fn remove_older_than(&self, ts: f64) -> JsResult<()> {
+        let range = IdbKeyRange::upper_bound_with_open(&JsValue::from_f64(ts), true)
+            .map_err(map_err!())?;
+        let request = self
+            .store(IdbTransactionMode::Readonly)?
+            .index(TS_INDEX)
+            .map_err(map_err!())?
+            .get_all_keys_with_key(&range)
+            .map_err(map_err!())?;
+        let keys: Array = idb_request_result(&request).await?.unchecked_into();
+        let store = self.store(IdbTransactionMode::Readwrite)?;
+        let mut last_request = None;
+
+        for key in keys.iter() {
+            last_request = Some(store.delete(&key).map_err(map_err!())?);
+        }
+
+        // Requests within a transaction finish in order, waiting for the last one is enough.
+        if let Some(request) = last_request {
+            idb_request_result(&request).await?;
+        }
+
+        Ok(())
+    }
+}
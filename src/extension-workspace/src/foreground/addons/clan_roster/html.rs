@@ -0,0 +1,277 @@
+use common::clan_roster::{RosterEvent, RosterMember};
+use dominator::Dom;
+use futures_signals::map_ref;
+use futures_signals::signal::{Signal, SignalExt};
+use futures_signals::signal_vec::SignalVecExt;
+
+use crate::addon_window::prelude::*;
+use crate::interface::{ThreadLocalShadowRoot, WINDOWS_ROOT};
+use crate::prelude::*;
+
+use super::{
+    ADDON_NAME, ActiveSettings, MAX_DISPLAYED_EVENTS, RosterTracker, Settings, event_label, now,
+};
+
+impl RosterTracker {
+    /// Members along with whether they're inactive, online ones first and
+    /// then the most recently seen ones.
+    fn members_signal(
+        &'static self,
+        settings: &'static Settings,
+    ) -> impl Signal<Item = Vec<(RosterMember, bool)>> {
+        map_ref! {
+            let roster = self.roster.signal_cloned(),
+            let inactivity_days = settings.inactivity_days.signal() => {
+                let now = now();
+                let mut members: Vec<_> = roster
+                    .iter()
+                    .flat_map(|roster| roster.members.iter())
+                    .map(|member| {
+                        let inactive = Settings::is_inactive(member, now, *inactivity_days);
+                        (member.clone(), inactive)
+                    })
+                    .collect();
+                members.sort_by(|(a, _), (b, _)| {
+                    b.online
+                        .cmp(&a.online)
+                        .then_with(|| {
+                            let a = a.last_online.unwrap_or_default();
+                            let b = b.last_online.unwrap_or_default();
+                            b.total_cmp(&a)
+                        })
+                        .then_with(|| a.nick.cmp(&b.nick))
+                });
+
+                members
+            }
+        }
+    }
+
+    fn render_summary(&'static self, settings: &'static Settings) -> ContentSection {
+        let summary_signal = self.members_signal(settings).map(|members| {
+            let online = members.iter().filter(|(member, _)| member.online).count();
+            let inactive = members.iter().filter(|(_, inactive)| *inactive).count();
+
+            tr!(
+                ClanRosterSummary,
+                members = members.len(),
+                online = online,
+                inactive = inactive,
+            )
+        });
+        let empty_info = ContentSection::new()
+            .class_list("mdma-text")
+            .visible_signal(self.roster.signal_ref(Option::is_none))
+            .text(tr!(ClanRosterEmpty));
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[5]")
+            .section(empty_info)
+            .section(ContentSection::new().text_signal(summary_signal))
+    }
+
+    fn render_members(&'static self, settings: &'static Settings) -> ContentSection {
+        let members = self
+            .members_signal(settings)
+            .to_signal_vec()
+            .map(|(member, inactive)| Self::render_one_member(member, inactive));
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[3] max-h[250] scroll-y b-f[glassy-blur]")
+            .section_signal_vec(members)
+    }
+
+    fn render_one_member(member: RosterMember, inactive: bool) -> ContentSection {
+        let last_online = match (member.online, member.last_online) {
+            (true, _) => tr!(ClanRosterOnline).to_owned(),
+            (false, Some(last_online)) => tr!(ClanRosterSeenOnline, ts = format_ts(last_online)),
+            (false, None) => tr!(ClanRosterNoData).to_owned(),
+        };
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between] g[10]")
+            .apply_if(inactive, |section| {
+                section.mixin(|builder| builder.style("color", "orange"))
+            })
+            .section(
+                ContentSection::new()
+                    .text(&format!("{} ({}{})", member.nick, member.lvl, member.prof)),
+            )
+            .section(
+                ContentSection::new()
+                    .class_list("o[60%]")
+                    .text(&last_online),
+            )
+    }
+
+    fn render_events(&'static self) -> ContentSection {
+        let events = self
+            .roster
+            .signal_ref(|roster| {
+                roster
+                    .iter()
+                    .flat_map(|roster| roster.events.iter().rev())
+                    .take(MAX_DISPLAYED_EVENTS)
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .to_signal_vec()
+            .map(Self::render_one_event);
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[3] max-h[150] scroll-y b-f[glassy-blur]")
+            .section_signal_vec(events)
+    }
+
+    fn render_one_event(event: RosterEvent) -> ContentSection {
+        ContentSection::new()
+            .class_list("d[flex] f-d[column]")
+            .section(
+                ContentSection::new()
+                    .class_list("o[60%] f-s[11]")
+                    .text(&format_ts(event.ts)),
+            )
+            .section(ContentSection::new().text(&format!(
+                "{} {}",
+                event.nick,
+                event_label(event.kind, event.lvl)
+            )))
+    }
+
+    fn render_controls(&'static self, settings: &'static Settings) -> ContentSection {
+        let csv_button = Button::builder()
+            .text(tr!(ClanRosterExportCsv))
+            .on_click(move |_| self.export_to_clipboard(settings, false));
+        let json_button = Button::builder()
+            .text(tr!(ClanRosterExportJson))
+            .on_click(move |_| self.export_to_clipboard(settings, true));
+
+        ContentSection::new()
+            .class_list("m-top[6]")
+            .button_pair(ButtonPair::builder(csv_button, json_button))
+    }
+
+    fn export_to_clipboard(&'static self, settings: &'static Settings, json: bool) {
+        if self
+            .roster
+            .lock_ref()
+            .as_ref()
+            .is_none_or(|roster| roster.members.is_empty())
+        {
+            if message(tr!(ClanRosterExportEmpty)).is_err() {
+                console_error!()
+            }
+            return;
+        }
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let text = match json {
+                true => match self.to_json() {
+                    Ok(text) => text,
+                    Err(err) => return console_error!(err),
+                },
+                false => self.to_csv(settings),
+            };
+            let clipboard = window().navigator().clipboard();
+            if let Err(err) = wasm_bindgen_futures::JsFuture::from(clipboard.write_text(&text))
+                .await
+                .map_err(map_err!())
+            {
+                return console_error!(err);
+            }
+            if message(tr!(ClanRosterExported)).is_err() {
+                console_error!()
+            }
+        });
+    }
+}
+
+impl ActiveSettings {
+    fn render(&'static self, settings: &'static Settings) -> JsResult<Dom> {
+        let decor = HeaderDecor::builder()
+            .push_left(decors::OpacityToggle::new())
+            .push_right(decors::CloseButton::new())
+            .push_right(decors::CollapseButton::new())
+            .build();
+        let header = WindowHeader::new(decor);
+        let tracker = &self.tracker;
+        let content = WindowContent::builder()
+            .class_list("f-d[column]")
+            .section(tracker.render_summary(settings))
+            .heading(Heading::builder().text(tr!(ClanRosterMembers)))
+            .section(tracker.render_members(settings))
+            .heading(Heading::builder().text(tr!(ClanRosterEvents)))
+            .section(tracker.render_events())
+            .section(tracker.render_controls(settings));
+
+        AddonWindow::builder(ADDON_NAME)
+            .header(header)
+            .content(content)
+            .build()
+    }
+}
+
+impl Settings {
+    fn render(&'static self) -> JsResult<Dom> {
+        let decor = HeaderDecor::builder()
+            .push_left(decors::OpacityToggle::new())
+            .push_right(decors::CloseButton::new())
+            .build();
+        let header = WindowHeader::new(decor);
+        let inactivity_input = Input::builder()
+            .value(self.inactivity_days.get().to_string())
+            .input_type(InputType::number(0.0, 365.0))
+            .maxlength("3")
+            .on_input(move |event, elem| {
+                event.prevent_default();
+                event.stop_immediate_propagation();
+
+                let value = elem.value_as_number() as u16;
+                self.inactivity_days.set_neq(value);
+            });
+        let inactivity_section = ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between] a-i[center]")
+            .section(ContentSection::new().text(tr!(ClanRosterInactivityDays)))
+            .input(inactivity_input);
+
+        let content = WindowContent::builder()
+            .class_list("f-d[column]")
+            .heading(
+                Heading::builder()
+                    .text(tr!(ClanRosterSyncHeading))
+                    .class_list("first-heading"),
+            )
+            .section(
+                ContentSection::new().checkbox(
+                    Checkbox::builder(self.sync.clone())
+                        .text(tr!(ClanRosterSync))
+                        .info_bubble(InfoBubble::builder().text(tr!(ClanRosterSyncInfo)).build()),
+                ),
+            )
+            .heading(
+                Heading::builder()
+                    .text(tr!(ClanRosterInactivity))
+                    .info_bubble(
+                        InfoBubble::builder()
+                            .text(tr!(ClanRosterInactivityInfo))
+                            .build(),
+                    ),
+            )
+            .section(inactivity_section);
+
+        SettingsWindow::builder(ADDON_NAME)
+            .header(header)
+            .content(content)
+            .build()
+    }
+}
+
+pub(super) fn init(
+    active_settings: &'static ActiveSettings,
+    settings: &'static Settings,
+) -> JsResult<()> {
+    let _settings_window_handle = WINDOWS_ROOT.append_window(settings.render()?)?;
+    let _addon_window_handle = WINDOWS_ROOT.append_window(active_settings.render(settings)?)?;
+
+    Ok(())
+}
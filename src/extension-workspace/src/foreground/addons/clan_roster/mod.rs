@@ -0,0 +1,258 @@
+mod html;
+mod storage;
+
+use std::cell::{OnceCell, RefCell};
+
+use common::clan_roster::{ClanRoster, RosterEventKind, RosterMember};
+use common::messaging::prelude::*;
+use futures_signals::signal::Mutable;
+use proc_macros::{ActiveSettings, Settings};
+
+use crate::prelude::*;
+
+use storage::RosterStorage;
+
+const ADDON_NAME: AddonName = AddonName::ClanRoster;
+const SECS_PER_DAY: f64 = 60.0 * 60.0 * 24.0;
+/// Events above this count are not displayed, keeping the most recent ones.
+const MAX_DISPLAYED_EVENTS: usize = 100;
+
+thread_local! {
+    /// Set once the addon is initialized, rosters synced before are dropped.
+    static TRACKER: OnceCell<&'static RosterTracker> = const { OnceCell::new() };
+}
+
+fn now() -> f64 {
+    js_sys::Date::now() / 1_000.0
+}
+
+fn event_label(kind: RosterEventKind, lvl: u16) -> String {
+    match kind {
+        RosterEventKind::Joined => tr!(ClanRosterJoined).to_owned(),
+        RosterEventKind::Left => tr!(ClanRosterLeft).to_owned(),
+        RosterEventKind::LevelUp => tr!(ClanRosterLevelUp, lvl = lvl),
+    }
+}
+
+#[derive(Settings)]
+struct Settings {
+    /// Sends the roster to the backend after every update and merges it with
+    /// the rosters recorded by other members of the clan.
+    sync: Mutable<bool>,
+    /// Members not seen online for at least this many days are reported as
+    /// inactive.
+    inactivity_days: Mutable<u16>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            sync: Mutable::default(),
+            inactivity_days: Mutable::new(7),
+        }
+    }
+}
+
+impl Settings {
+    /// Days since the member was last seen online between two snapshots,
+    /// `None` if they are online right now or were never seen online.
+    fn offline_days(member: &RosterMember, now: f64) -> Option<f64> {
+        match member.online {
+            true => None,
+            false => member
+                .last_online
+                .map(|last_online| ((now - last_online) / SECS_PER_DAY).max(0.0)),
+        }
+    }
+
+    /// Members never seen online aren't reported, the roster might have
+    /// been tracked for too short to tell.
+    fn is_inactive(member: &RosterMember, now: f64, inactivity_days: u16) -> bool {
+        Self::offline_days(member, now).is_some_and(|days| days >= inactivity_days as f64)
+    }
+}
+
+#[derive(Default)]
+struct RosterTracker {
+    storage: RefCell<Option<RosterStorage>>,
+    /// Roster of the hero's current clan.
+    roster: Mutable<Option<ClanRoster>>,
+}
+
+impl RosterTracker {
+    async fn storage(&self) -> JsResult<RosterStorage> {
+        if let Some(storage) = self.storage.borrow().clone() {
+            return Ok(storage);
+        }
+
+        let storage = RosterStorage::open().await?;
+        self.storage.replace(Some(storage.clone()));
+
+        Ok(storage)
+    }
+
+    /// Roster of the clan, loaded from the storage if it's not the one
+    /// currently tracked, e.g. after the hero joined another clan.
+    async fn load(&self, world: String, clan_id: Id) -> JsResult<ClanRoster> {
+        if let Some(roster) = self.roster.get_cloned()
+            && roster.world == world
+            && roster.clan_id == clan_id
+        {
+            return Ok(roster);
+        }
+
+        let roster = self.storage().await?.load(&world, clan_id).await?;
+
+        Ok(roster.unwrap_or_else(|| ClanRoster::new(world, clan_id)))
+    }
+
+    async fn save(&self, roster: ClanRoster) -> JsResult<()> {
+        self.storage().await?.put(&roster).await?;
+        self.roster.set(Some(roster));
+
+        Ok(())
+    }
+
+    async fn on_members(&self, members: Vec<RosterMember>, sync: bool) -> JsResult<()> {
+        let world = WorldConfig::world_name();
+        let Some(clan_id) = Hero::get().clan.get().and_then(|clan| clan.id) else {
+            return Ok(());
+        };
+        if world.is_empty() {
+            return Ok(());
+        }
+
+        let mut roster = self.load(world, clan_id).await?;
+        roster.record(members, now());
+        self.save(roster.clone()).await?;
+
+        if !sync {
+            return Ok(());
+        }
+
+        Port::send(
+            &Message::builder(Task::ClanRoster, Target::Background, MessageKind::Request)
+                .clan_roster(roster)
+                .character(Hero::get().character())
+                .build(),
+        )
+        .await
+    }
+
+    async fn on_sync(&self, shared_roster: ClanRoster) -> JsResult<()> {
+        let Some(mut roster) = self.roster.get_cloned() else {
+            return Ok(());
+        };
+        if !roster.is_same_clan(&shared_roster) {
+            return Ok(());
+        }
+
+        roster.merge(shared_roster);
+        self.save(roster).await
+    }
+
+    fn to_csv(&self, settings: &Settings) -> String {
+        let now = now();
+        let inactivity_days = settings.inactivity_days.get();
+        let mut lines =
+            vec!["char_id,nick,lvl,prof,online,seen_online,days_not_seen,inactive".to_owned()];
+        let roster_lock = self.roster.lock_ref();
+        let Some(roster) = roster_lock.as_ref() else {
+            return lines.join("\n");
+        };
+
+        lines.extend(roster.members.iter().map(|member| {
+            format!(
+                "{},{},{},{},{},{},{},{}",
+                member.char_id,
+                csv_escape(&member.nick),
+                member.lvl,
+                member.prof,
+                member.online,
+                member.last_online.map(format_ts).unwrap_or_default(),
+                Settings::offline_days(member, now)
+                    .map(|days| format!("{days:.1}"))
+                    .unwrap_or_default(),
+                Settings::is_inactive(member, now, inactivity_days),
+            )
+        }));
+
+        lines.join("\n")
+    }
+
+    fn to_json(&self) -> JsResult<String> {
+        serde_json::to_string_pretty(&*self.roster.lock_ref()).map_err(map_err!(from))
+    }
+}
+
+impl From<&ClanMember> for RosterMember {
+    fn from(member: &ClanMember) -> Self {
+        Self {
+            char_id: member.id,
+            nick: member.nick.clone(),
+            lvl: member.lvl,
+            prof: member.prof.to_string(),
+            online: member.is_online(),
+            last_online: None,
+        }
+    }
+}
+
+#[derive(ActiveSettings, Default)]
+struct ActiveSettings {
+    #[setting(skip)]
+    tracker: RosterTracker,
+}
+
+impl ActiveSettings {
+    fn init(&'static self, settings: &'static Settings) -> JsResult<()> {
+        let tracker = &self.tracker;
+        TRACKER
+            .with(|cell| cell.set(tracker))
+            .map_err(|_| err!(Set, "roster tracker"))?;
+
+        Emitter::register_on(EmitterEvent::Members, move |socket_response| {
+            let members: Option<Vec<_>> = match Addons::is_active(ADDON_NAME) {
+                true => socket_response
+                    .members
+                    .as_ref()
+                    .map(|members| members.iter().map(RosterMember::from).collect()),
+                false => None,
+            };
+            let sync = settings.sync.get();
+
+            Box::pin(async move {
+                match members {
+                    Some(members) => tracker.on_members(members, sync).await,
+                    None => Ok(()),
+                }
+            })
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Merges the roster synced by the backend into the tracked one.
+pub(crate) fn on_sync(shared_roster: Option<ClanRoster>) {
+    let Some(shared_roster) = shared_roster else {
+        return;
+    };
+    let Some(tracker) = TRACKER.with(|cell| cell.get().copied()) else {
+        return;
+    };
+
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(err_code) = tracker.on_sync(shared_roster).await {
+            console_error!(err_code);
+        }
+    });
+}
+
+pub(crate) fn init() -> JsResult<()> {
+    let settings = Settings::new(ADDON_NAME);
+    let active_settings = ActiveSettings::new(ADDON_NAME);
+    active_settings.init(settings)?;
+
+    html::init(active_settings, settings)
+}
@@ -0,0 +1,62 @@
+use common::clan_roster::ClanRoster;
+use js_sys::Array;
+use wasm_bindgen::JsValue;
+use web_sys::{IdbDatabase, IdbObjectStore, IdbObjectStoreParameters, IdbTransactionMode};
+
+use crate::prelude::*;
+
+const DB_NAME: &str = "mdma-clan-rosters";
+const DB_VERSION: u32 = 1;
+const STORE_NAME: &str = "rosters";
+
+/// Rosters of every clan the hero was a member of, keyed by `[world, clan_id]`.
+#[derive(Debug, Clone)]
+pub(super) struct RosterStorage(IdbDatabase);
+
+impl RosterStorage {
+    pub(super) async fn open() -> JsResult<Self> {
+        idb_open(DB_NAME, DB_VERSION, Self::create_schema)
+            .await
+            .map(Self)
+    }
+
+    fn create_schema(db: &IdbDatabase) -> JsResult<()> {
+        let key_path = Array::of2(&JsValue::from_str("world"), &JsValue::from_str("clan_id"));
+        let params = IdbObjectStoreParameters::new();
+        params.set_key_path(&key_path);
+
+        db.create_object_store_with_optional_parameters(STORE_NAME, &params)
+            .map_err(map_err!())?;
+
+        Ok(())
+    }
+
+    fn store(&self, mode: IdbTransactionMode) -> JsResult<IdbObjectStore> {
+        idb_store(&self.0, STORE_NAME, mode)
+    }
+
+    pub(super) async fn load(&self, world: &str, clan_id: Id) -> JsResult<Option<ClanRoster>> {
+        let key = Array::of2(&JsValue::from_str(world), &JsValue::from(clan_id));
+        let request = self
+            .store(IdbTransactionMode::Readonly)?
+            .get(&key)
+            .map_err(map_err!())?;
+        let value = idb_request_result(&request).await?;
+
+        if value.is_undefined() {
+            return Ok(None);
+        }
+
+        serde_wasm_bindgen::from_value(value).map_err(map_err!(from))
+    }
+
+    pub(super) async fn put(&self, roster: &ClanRoster) -> JsResult<()> {
+        let value = serde_wasm_bindgen::to_value(roster).map_err(map_err!(from))?;
+        let request = self
+            .store(IdbTransactionMode::Readwrite)?
+            .put(&value)
+            .map_err(map_err!())?;
+
+        idb_request_result(&request).await.map(|_| ())
+    }
+}
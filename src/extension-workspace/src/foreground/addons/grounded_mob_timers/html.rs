@@ -2,9 +2,10 @@ use dominator::Dom;
 
 use crate::addon_window::prelude::*;
 use crate::interface::{ThreadLocalShadowRoot, WINDOWS_ROOT};
+use crate::overlay::Overlay;
 use crate::prelude::*;
 
-use super::{ADDON_NAME, Settings};
+use super::{ADDON_NAME, Settings, TIMERS, TimerData};
 
 impl Settings {
     fn render(&'static self) -> JsResult<Dom> {
@@ -30,12 +31,11 @@ trait WindowContentExt {
 
 impl WindowContentExt for WindowContent {
     fn auto_remove_setting(self, settings: &'static Settings) -> Self {
-        let auto_remove_checkbox =
-            Checkbox::builder(settings.auto_remove.clone())
-            .text("Usuwaj po [s]")
+        let auto_remove_checkbox = Checkbox::builder(settings.auto_remove.clone())
+            .text(tr!(GroundedMobTimersAutoRemove))
             .info_bubble(
                 InfoBubble::builder()
-                    .text("Wpis zostanie usunięty po upływie podanego okresu czasu liczonego od maksymalnego respawnu.")
+                    .text(tr!(GroundedMobTimersAutoRemoveInfo))
                     .build(),
             );
         let auto_remove_input = Input::builder()
@@ -58,42 +58,34 @@ impl WindowContentExt for WindowContent {
     }
 }
 
-    // #[cfg(not(feature = "ni"))]
-    // fn init_timers(&'static self) -> JsResult<()> {
-    //     use std::ops::Not;
+impl Settings {
+    /// Renders the timers into the map overlay, refreshed every second.
+    pub(super) fn init_timers(&'static self) {
+        wasm_bindgen_futures::spawn_local(async move {
+            loop {
+                self.update_timers();
+                delay(1000).await;
+            }
+        });
+    }
 
-    //     use dominator::DomBuilder;
+    fn update_timers(&self) {
+        let items = TIMERS.with_borrow_mut(|timers_map| {
+            self.retain_timers(timers_map);
 
-    //     let neon = DomBuilder::<web_sys::HtmlDivElement>::new_html("div");
-    //     let shadow = neon
-    //         .__internal_shadow_root(web_sys::ShadowRootMode::Closed)
-    //         .child(
-    //             DomBuilder::<web_sys::HtmlDivElement>::new_html("div")
-    //                 .style("position", "absolute")
-    //                 .style_signal("display", Addons::active_signal(ADDON_NAME).ok_or_else(|| err_code!())?.map(|active| active.not().then_some("none")))
-    //                 .style("z-index", "2")
-    //                 .style_signal("left", self.left.signal_ref(|left| format!("{:.0}px", left)))
-    //                 .style_signal("top", self.top.signal_ref(|top| format!("{:.0}px", top)))
-    //                 .style_signal("width", self.radius.signal_ref(|radius| format!("{:.0}px", radius * 2.0)))
-    //                 .style_signal("height", self.radius.signal_ref(|radius| format!("{:.0}px", radius * 2.0)))
-    //                 .style_signal("background", self.offset.signal().switch(|offset| self.color_signal().map(move |color| {
-    //                     format!("radial-gradient(circle, rgba({}, {}, {}, 1) {:.0}%, transparent 70%)", color.red, color.green, color.blue, offset * 100.0)
-    //                 })))
-    //                 .into_dom(),
-    //         );
-    //     let neon = neon.__internal_transfer_callbacks(shadow).into_dom();
-    //     let base_div = document()
-    //         .get_element_by_id("base")
-    //         .ok_or_else(|| err_code!())?;
-    //     let _neon_handle = dominator::append_dom(&base_div, neon);
+            timers_map
+                .values()
+                .filter(|timer_data| timer_data.is_on_current_map())
+                .flat_map(TimerData::overlay_items)
+                .collect::<Vec<_>>()
+        });
 
-    //     Ok(())
-    // }
+        Overlay::replace(ADDON_NAME, items);
+    }
+}
 
 pub(super) fn init(settings: &'static Settings) -> JsResult<()> {
-    let _settings_window_handle = WINDOWS_ROOT
-        .try_append_dom(settings.render()?)
-        .ok_or_else(|| err_code!())?;
+    let _settings_window_handle = WINDOWS_ROOT.append_window(settings.render()?)?;
 
     Ok(())
 }
@@ -1,4 +1,3 @@
-// TODO: SI (, collision on hover ?)
 // TODO: Usuwaj po x s od maxa.
 mod html;
 
@@ -8,15 +7,16 @@ use futures_signals::{
     signal::Mutable,
     signal_map::{MapDiff, SignalMapExt},
 };
-use js_sys::Function;
-use serde::Serialize;
-use wasm_bindgen::prelude::*;
-use web_sys::CanvasRenderingContext2d;
 
-use crate::{globals::npcs::Npc, pathfinder::Pos, prelude::*};
+use crate::{
+    globals::npcs::Npc,
+    overlay::{OverlayColor, OverlayItem, OverlayKey, OverlayShape},
+    pathfinder::Pos,
+    prelude::*,
+};
 
 const ADDON_NAME: AddonName = AddonName::GroundedMobTimers;
-const TILE_SIZE: f64 = 32.0;
+const TILE_COLOR: OverlayColor = OverlayColor::named("rgba(206, 206, 206, 0.4)");
 
 #[derive(Settings)]
 struct Settings {
@@ -43,7 +43,7 @@ impl Default for Settings {
 
 impl Settings {
     fn init(&'static self) -> JsResult<()> {
-        self.add_to_renderer()?;
+        self.init_timers();
 
         let future = Npcs::get()
             .signal_map_cloned()
@@ -70,14 +70,12 @@ impl Settings {
             return;
         };
 
-        // TODO: Helper method for checking npc type using wt.
-        //       In order to do that you need to rewrite the Values of NpcTemplates.
         let colossus = NpcTemplates::get()
             .lock_ref()
             .get(&npc.template_id)
             .unwrap_js()
             .warrior_type
-            .is_some_and(|wt| wt > 89 && wt < 100);
+            .is_some_and(Catalog::is_colossus);
         if colossus {
             return;
         }
@@ -113,55 +111,13 @@ impl Settings {
     }
 }
 
-#[cfg(not(feature = "ni"))]
-impl Settings {
-    fn add_to_renderer(&'static self) -> JsResult<()> {
-        Ok(())
-    }
-}
-
-#[cfg(feature = "ni")]
 impl Settings {
-    fn add_to_renderer(&'static self) -> JsResult<()> {
-        let api_data = get_engine().api_data().ok_or_else(|| err_code!())?;
-
-        let renderer = get_engine().renderer().ok_or_else(|| err_code!())?;
-        let after_call_draw_add_to_renderer =
-            closure!(move || -> JsResult<()> { self.on_call_draw_add_to_renderer(&renderer) });
-        get_game_api()
-            .add_callback_to_event(
-                &api_data.call_draw_add_to_renderer(),
-                &after_call_draw_add_to_renderer,
-            )
-            .map_err(map_err!())?;
-
-        Ok(())
-    }
-
-    pub fn on_call_draw_add_to_renderer(
-        &self,
-        renderer: &crate::bindings::engine::renderer::Renderer,
-    ) -> JsResult<()> {
-        if !Addons::is_active(ADDON_NAME) {
-            return Ok(());
+    /// Removes timers which exceeded their timeout by more than `auto_remove_sec`.
+    fn retain_timers(&self, timers_map: &mut BTreeMap<Id, TimerData>) {
+        if self.auto_remove.get() {
+            let removal_threshold = -1 * self.auto_remove_sec.get() as i32;
+            timers_map.retain(|_, timer_data| timer_data.sec_left() >= removal_threshold);
         }
-
-        TIMERS.with_borrow_mut(|timers_map| {
-            if self.auto_remove.get() {
-                let removal_threshold = -1 * self.auto_remove_sec.get() as i32;
-                timers_map.retain(|_, timer_data| timer_data.sec_left() >= removal_threshold);
-            }
-
-            timers_map
-                .values()
-                .for_each(|timer_data| match timer_data.get_drawable_obj() {
-                    Ok(Some(drawable_obj)) => renderer.add_1(&drawable_obj),
-                    Ok(None) => {}
-                    Err(err_code) => console_error!(err_code),
-                });
-        });
-
-        Ok(())
     }
 }
 
@@ -173,7 +129,7 @@ struct TimerData {
     timeout: u32,
     /// Specifies from when the mob can spawn.
     start_highlight: u32,
-    /// Npc id used for draw ordering.
+    /// Npc id used as the overlay key.
     npc_id: Id,
     /// Id of the map which the timer is going to be displayed on.
     map_id: Id,
@@ -319,125 +275,53 @@ impl TimerData {
         let now = (js_sys::Date::now() / 1000.0).round() as i32;
         self.timeout as i32 - now
     }
-}
 
-#[cfg(feature = "ni")]
-impl TimerData {
-    fn draw(&self, ctx: &CanvasRenderingContext2d) -> JsResult<()> {
-        ctx.save();
-
-        let rx = self.pos.x as f64;
-        let ry = self.pos.y as f64;
-        let offset = get_engine()
-            .map()
-            .ok_or_else(|| err_code!())?
-            .get_offset()
-            .ok_or_else(|| err_code!())?;
-        let left = rx * 32.0 - offset[0];
-        let top = ry * 32.0 - offset[1];
-
-        Self::draw_tile(ctx, left, top);
-        self.prepare_font(ctx);
-        self.draw_text(ctx, left, top)?;
-
-        ctx.restore();
-
-        Ok(())
-    }
-
-    fn draw_tile(ctx: &CanvasRenderingContext2d, left: f64, top: f64) {
-        ctx.set_line_width(3.0);
-        ctx.set_stroke_style_str("rgba(206, 206, 206, 0.4");
-        ctx.stroke_rect(left, top, TILE_SIZE, TILE_SIZE);
-        ctx.set_fill_style_str("rgba(206, 206, 206, 0.4");
-        ctx.fill_rect(left, top, TILE_SIZE, TILE_SIZE);
-    }
-
-    fn prepare_font(&self, ctx: &CanvasRenderingContext2d) {
-        ctx.set_font("11px Arimo");
-        ctx.set_line_cap("round");
-        ctx.set_line_join("round");
-        ctx.set_text_align("center");
-
-        let should_highlight = self.should_highlight();
-
-        ctx.set_fill_style_str(if self.sec_left() <= 0 {
+    fn text_color(&self) -> &'static str {
+        if self.sec_left() <= 0 {
             "red"
-        } else if should_highlight {
+        } else if self.should_highlight() {
             "darkorange"
         } else {
             "rgba(206, 206, 206, 0.8)"
-        });
-
-        ctx.set_stroke_style_str(if should_highlight {
-            "black"
-        } else {
-            "rgba(0, 0, 0, 0.8)"
-        });
+        }
     }
 
-    fn draw_text(&self, ctx: &CanvasRenderingContext2d, left: f64, top: f64) -> JsResult<()> {
-        let parsed_time = self.to_parsed_time();
-        let x = left + TILE_SIZE / 2.0;
-        let y = top + TILE_SIZE / 2.0 + 3.0;
-
-        ctx.stroke_text_with_max_width(&parsed_time, x, y, TILE_SIZE)
-            .map_err(map_err!())?;
-        ctx.fill_text_with_max_width(&parsed_time, x, y, TILE_SIZE)
-            .map_err(map_err!())
+    fn is_on_current_map(&self) -> bool {
+        Town::get().lock_ref().id == Some(self.map_id)
     }
+}
 
-    fn get_drawable_obj(self) -> JsResult<Option<JsValue>> {
-        #[derive(Serialize)]
-        struct ColorMarkExport {
-            rx: f64,
-            ry: f64,
-            #[serde(with = "serde_wasm_bindgen::preserve")]
-            draw: Function,
-            #[serde(rename = "getOrder", with = "serde_wasm_bindgen::preserve")]
-            get_order: Function,
-            d: ColorMarkData,
-        }
-
-        #[derive(Serialize)]
-        struct ColorMarkData {
-            id: f64,
-        }
-
-        if Town::get().lock_ref().id != Some(self.map_id) {
-            return Ok(None);
-        }
-
-        let draw = closure!(@once move |ctx: &CanvasRenderingContext2d| {
-            if let Err(err_code) = self.draw(ctx) {
-                console_error!(err_code);
-            }
-        });
-        let rx = self.pos.x as f64;
-        let ry = self.pos.y as f64;
-        let export = ColorMarkExport {
-            rx,
-            ry,
-            draw,
-            get_order: self.get_order_factory(),
-            d: ColorMarkData {
-                id: self.npc_id as f64,
-            },
-        };
-
-        let value = serde_wasm_bindgen::to_value(&export).map_err(map_err!(from))?;
-
-        Ok(Some(value))
+impl TimerData {
+    fn overlay_items(&self) -> [(OverlayKey, OverlayItem); 2] {
+        let tip = tr!(
+            GroundedMobTimersRespawn,
+            start = Self::format_time(self.start_highlight),
+            end = Self::format_time(self.timeout),
+        );
+        let tile = OverlayItem::new(OverlayShape::Tile(self.pos), TILE_COLOR).tip(tip.clone());
+        let label = OverlayItem::new(
+            OverlayShape::Label(self.pos, self.to_parsed_time()),
+            OverlayColor::named(self.text_color()),
+        )
+        .z_index(1)
+        .tip(tip);
+
+        [
+            (OverlayKey::new(self.npc_id, 0), tile),
+            (OverlayKey::new(self.npc_id, 1), label),
+        ]
     }
 
-    fn get_order_factory(self) -> Function {
-        let closure = move || {
-            0.001 // Just above the map (0)
-        };
+    /// Formats a unix timestamp in seconds as `HH:MM:SS`.
+    fn format_time(timestamp: u32) -> String {
+        let date = js_sys::Date::new(&(timestamp as f64 * 1000.0).into());
 
-        Closure::<dyn Fn() -> f64>::wrap(Box::new(closure))
-            .into_js_value()
-            .unchecked_into()
+        format!(
+            "{:02}:{:02}:{:02}",
+            date.get_hours(),
+            date.get_minutes(),
+            date.get_seconds()
+        )
     }
 }
 
@@ -2,13 +2,20 @@ use std::ops::Deref;
 
 use dominator::events::{ContextMenu, MouseButton};
 use dominator::{Dom, apply_methods, html, with_node};
-use futures_signals::signal::{self, SignalExt};
+use futures_signals::{
+    map_ref,
+    signal::{self, SignalExt},
+    signal_vec::SignalVecExt,
+};
 
 use crate::addon_window::prelude::*;
 use crate::interface::{ThreadLocalShadowRoot, WINDOWS_ROOT, tips_parser::tip};
 use crate::prelude::*;
 
-use super::{ADDON_NAME, ActiveSettings, DEFAULT_OFFSET, DEFAULT_RADIUS, DEFAULT_ROTATION_STEP};
+use super::{
+    ADDON_NAME, ActiveSettings, DEFAULT_OFFSET, DEFAULT_RADIUS, DEFAULT_ROTATION_STEP,
+    style::{ColorStop, GradientKind, MAX_COLOR_STOPS, NeonAnimation},
+};
 
 trait WindowContentExt {
     fn size_setting(self, active_settings: &'static ActiveSettings) -> Self;
@@ -16,6 +23,9 @@ trait WindowContentExt {
     fn color_setting(self, active_settings: &'static ActiveSettings) -> Self;
     fn mode_setting(self, active_settings: &'static ActiveSettings) -> Self;
     fn interpolation_speed_setting(self, active_settings: &'static ActiveSettings) -> Self;
+    fn gradient_setting(self, active_settings: &'static ActiveSettings) -> Self;
+    fn animation_setting(self, active_settings: &'static ActiveSettings) -> Self;
+    fn presets_setting(self, active_settings: &'static ActiveSettings) -> Self;
 }
 
 impl WindowContentExt for WindowContent {
@@ -34,7 +44,7 @@ impl WindowContentExt for WindowContent {
                             })
                     )
                     .tip!({
-                        .text_signal(active_settings.radius.signal().map(|size| format!("Rozmiar neonu: {size}px\n\nPPM aby przywrócić do wartości domyślnej")))
+                        .text_signal(active_settings.radius.signal().map(|size| tr!(HeroNeonRadiusTip, size = size)))
                     })
                     .with_node!(input_elem => {
                         .event(move |event: ContextMenu| {
@@ -54,7 +64,7 @@ impl WindowContentExt for WindowContent {
             });
         let size_slider_setting = ContentSection::new()
             .class_list("d[flex] f-d[row] a-i[center] j-c[space-between]")
-            .section(ContentSection::new().text("Rozmiar"))
+            .section(ContentSection::new().text(tr!(HeroNeonRadius)))
             .input(size_input);
 
         self.section(
@@ -80,7 +90,7 @@ impl WindowContentExt for WindowContent {
                             })
                     )
                     .tip!({
-                        .text_signal(active_settings.offset.signal().map(|offset| format!("Zanikanie neonu od: {:.0}%\n\nPPM aby przywrócić do wartości domyślnej", offset * 100.0)))
+                        .text_signal(active_settings.offset.signal().map(|offset| tr!(HeroNeonOffsetTip, percent = (offset * 100.0).round())))
                     })
                     .with_node!(input_elem => {
                         .event(move |event: ContextMenu| {
@@ -100,7 +110,7 @@ impl WindowContentExt for WindowContent {
             });
         let offset_slider_setting = ContentSection::new()
             .class_list("d[flex] f-d[row] a-i[center] j-c[space-between] p-top[6] g[5]")
-            .section(ContentSection::new().text("Zanikanie od"))
+            .section(ContentSection::new().text(tr!(HeroNeonOffset)))
             .input(offset_input);
 
         self.section(
@@ -120,37 +130,38 @@ impl WindowContentExt for WindowContent {
             });
         let start_color_section = ContentSection::new()
             .class_list("d[flex] f-d[row] a-i[center] j-c[space-between] p-top[6]")
+            .visible_signal(signal::not(active_settings.gradient.signal()))
             .section(ContentSection::new().text_signal(
                 active_settings.interpolate.signal().dedupe().map(
                     |interpolate| match interpolate {
-                        true => "Kolor 1",
-                        false => "Kolor",
+                        true => tr!(HeroNeonColorFirst),
+                        false => tr!(HeroNeonColor),
                     },
                 ),
             ))
             .input(start_color_input);
 
-        let end_color_section_signal =
-            active_settings
-                .interpolate
-                .signal()
-                .dedupe()
-                .map(move |interpolate| {
-                    interpolate.then(|| {
-                        let end_color_input = Input::builder()
-                            .input_type(InputType::color())
-                            .size(InputSize::Color)
-                            .value(active_settings.end_color.lock_ref().deref())
-                            .on_input(move |_, input_elem| {
-                                active_settings.end_color.set_neq(input_elem.value());
-                            });
+        let end_color_section_signal = map_ref! {
+            let interpolate = active_settings.interpolate.signal(),
+            let gradient = active_settings.gradient.signal() => *interpolate && !*gradient
+        }
+        .dedupe()
+        .map(move |visible| {
+            visible.then(|| {
+                let end_color_input = Input::builder()
+                    .input_type(InputType::color())
+                    .size(InputSize::Color)
+                    .value(active_settings.end_color.lock_ref().deref())
+                    .on_input(move |_, input_elem| {
+                        active_settings.end_color.set_neq(input_elem.value());
+                    });
 
-                        ContentSection::new()
-                            .class_list("d[flex] f-d[row] a-i[center] j-c[space-between] p-top[6]")
-                            .section(ContentSection::new().text("Kolor 2"))
-                            .input(end_color_input)
-                    })
-                });
+                ContentSection::new()
+                    .class_list("d[flex] f-d[row] a-i[center] j-c[space-between] p-top[6]")
+                    .section(ContentSection::new().text(tr!(HeroNeonColorSecond)))
+                    .input(end_color_input)
+            })
+        });
 
         self.section(start_color_section)
             .section_signal(end_color_section_signal)
@@ -159,17 +170,39 @@ impl WindowContentExt for WindowContent {
     fn mode_setting(self, active_settings: &'static ActiveSettings) -> Self {
         let mono_button = Button::builder()
             .no_hover()
-            .text("Mono")
-            .selected_signal(signal::not(active_settings.interpolate.signal()))
+            .text(tr!(HeroNeonMono))
+            .selected_signal(map_ref! {
+                let interpolate = active_settings.interpolate.signal(),
+                let gradient = active_settings.gradient.signal() => !*interpolate && !*gradient
+            })
             .on_click(move |_| {
                 active_settings.interpolation_progress.set(0);
                 active_settings.interpolate.set_neq(false);
+                active_settings.gradient.set_neq(false);
             });
         let duo_button = Button::builder()
             .no_hover()
-            .text("Duo")
-            .selected_signal(active_settings.interpolate.signal())
-            .on_click(move |_| active_settings.interpolate.set_neq(true));
+            .text(tr!(HeroNeonDuo))
+            .selected_signal(map_ref! {
+                let interpolate = active_settings.interpolate.signal(),
+                let gradient = active_settings.gradient.signal() => *interpolate && !*gradient
+            })
+            .on_click(move |_| {
+                active_settings.interpolate.set_neq(true);
+                active_settings.gradient.set_neq(false);
+            });
+        let gradient_button = Button::builder()
+            .no_hover()
+            .text(tr!(HeroNeonGradient))
+            .disabled_signal(signal::always(!Premium::neon()))
+            .selected_signal(active_settings.gradient.signal())
+            .on_click(move |_| {
+                if !Premium::neon() {
+                    return;
+                }
+                active_settings.animation_progress.set(0);
+                active_settings.gradient.set_neq(true);
+            });
 
         // TODO: Happy pride month
         // let special_button = Button::builder()
@@ -178,18 +211,27 @@ impl WindowContentExt for WindowContent {
         //     .selected_signal(active_settings.interpolate.signal())
         //     .on_click(move |_| active_settings.interpolate.set_neq(true));
 
-        self.heading(Heading::builder().text("Tryb wyświetlania"))
+        self.heading(Heading::builder().text(tr!(HeroNeonDisplayMode)))
             .section(
                 ContentSection::new()
                     .class_list("d[flex] f-d[row] j-c[space-around] a-i[center]")
                     .button(mono_button)
-                    .button(duo_button), // .button(special_button),
+                    .button(duo_button)
+                    .button(gradient_button), // .button(special_button),
             )
     }
 
     fn interpolation_speed_setting(self, active_settings: &'static ActiveSettings) -> Self {
-        let speed_slider_setting = active_settings.interpolate.signal().dedupe().map(move |interpolate| {
-            interpolate.then(|| {
+        let speed_visible = map_ref! {
+            let interpolate = active_settings.interpolate.signal(),
+            let gradient = active_settings.gradient.signal(),
+            let animation = active_settings.animation.signal() => match *gradient {
+                true => *animation != NeonAnimation::None && Premium::animation(),
+                false => *interpolate,
+            }
+        };
+        let speed_slider_setting = speed_visible.dedupe().map(move |visible| {
+            visible.then(|| {
                 let step_input = Input::builder()
                     .class_list("w[150]")
                     .input_type(InputType::slider(1.0, 50.0))
@@ -205,7 +247,7 @@ impl WindowContentExt for WindowContent {
                                     })
                             )
                             .tip!({
-                                .text_signal(active_settings.step.signal().map(|step| format!("Prędkość zmiany koloru: {:.0}%\n\nPPM aby przywrócić do wartości domyślnej", step as f64 / DEFAULT_ROTATION_STEP as f64 * 100.0)))
+                                .text_signal(active_settings.step.signal().map(|step| tr!(HeroNeonStepTip, percent = (step as f64 / DEFAULT_ROTATION_STEP as f64 * 100.0).round())))
                             })
                             .with_node!(input_elem => {
                                 .event(move |event: ContextMenu| {
@@ -226,7 +268,7 @@ impl WindowContentExt for WindowContent {
 
                 ContentSection::new()
                     .class_list("d[flex] f-d[row] a-i[center] j-c[space-between]")
-                    .section(ContentSection::new().text("Prędkość"))
+                    .section(ContentSection::new().text(tr!(HeroNeonStep)))
                     .input(step_input)
             })
         });
@@ -237,6 +279,270 @@ impl WindowContentExt for WindowContent {
                 .section_signal(speed_slider_setting),
         )
     }
+
+    fn gradient_setting(self, active_settings: &'static ActiveSettings) -> Self {
+        let kind_button = |gradient_kind: GradientKind| {
+            Button::builder()
+                .no_hover()
+                .text(gradient_kind.into())
+                .selected_signal(
+                    active_settings
+                        .gradient_kind
+                        .signal()
+                        .map(move |selected| selected == gradient_kind),
+                )
+                .on_click(move |_| active_settings.gradient_kind.set_neq(gradient_kind))
+        };
+        let kind_section = ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-around] a-i[center]")
+            .button(kind_button(GradientKind::Radial))
+            .button(kind_button(GradientKind::Linear))
+            .button(kind_button(GradientKind::Conic));
+        // Only rebuild the list when stops are added or removed,
+        // otherwise dragging a slider would recreate it on every input.
+        let stops = active_settings
+            .stops
+            .signal_ref(Vec::len)
+            .dedupe()
+            .map(move |len| (0..len).map(|index| color_stop_section(active_settings, index)).collect())
+            .to_signal_vec();
+        let add_button = Button::builder()
+            .text(tr!(HeroNeonAddColor))
+            .disabled_signal(
+                active_settings
+                    .stops
+                    .signal_ref(|stops| stops.len() >= MAX_COLOR_STOPS),
+            )
+            .on_click(move |_| {
+                let mut stops_lock = active_settings.stops.lock_mut();
+                if stops_lock.len() >= MAX_COLOR_STOPS {
+                    return;
+                }
+                let color = stops_lock
+                    .last()
+                    .map_or_else(|| String::from("#8caaee"), |stop| stop.color.clone());
+                stops_lock.push(ColorStop::new(&color, 1.0, 0.0));
+            });
+
+        self.section_signal(active_settings.gradient.signal().dedupe().map(move |gradient| {
+            (gradient && Premium::neon()).then(|| {
+                ContentSection::new()
+                    .class_list("d[flex] f-d[column] g[5] p-top[6]")
+                    .section(kind_section)
+                    .section_signal_vec(stops)
+                    .button(add_button)
+            })
+        }))
+    }
+
+    fn animation_setting(self, active_settings: &'static ActiveSettings) -> Self {
+        if !Premium::animation() {
+            return self;
+        }
+
+        let animation_button = |animation: NeonAnimation| {
+            Button::builder()
+                .no_hover()
+                .text(animation.into())
+                .selected_signal(
+                    active_settings
+                        .animation
+                        .signal()
+                        .map(move |selected| selected == animation),
+                )
+                .on_click(move |_| {
+                    active_settings.animation_progress.set(0);
+                    active_settings.animation.set_neq(animation);
+                })
+        };
+
+        self.heading(
+            Heading::builder()
+                .text(tr!(HeroNeonAnimation))
+                .mixin(|b| b.visible_signal(active_settings.gradient.signal())),
+        )
+        .section(
+            ContentSection::new()
+                .class_list("d[flex] f-d[row] j-c[space-around] a-i[center]")
+                .visible_signal(active_settings.gradient.signal())
+                .button(animation_button(NeonAnimation::None))
+                .button(animation_button(NeonAnimation::Pulse))
+                .button(animation_button(NeonAnimation::Breathe))
+                .button(animation_button(NeonAnimation::Rotate)),
+        )
+    }
+
+    fn presets_setting(self, active_settings: &'static ActiveSettings) -> Self {
+        if !Premium::neon() {
+            return self;
+        }
+
+        let save_input = Input::builder()
+            .placeholder(tr!(HeroNeonPresetName))
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .confirm_button(
+                InputButton::builder()
+                    .on_click(move |_event, input_elem| {
+                        let name = input_elem.value().trim().to_string();
+                        if name.is_empty() {
+                            return;
+                        }
+                        input_elem.set_value("");
+                        active_settings.save_preset(name);
+                    })
+                    .tip(tr!(HeroNeonSavePreset)),
+            );
+        let presets = active_settings
+            .presets
+            .signal_cloned()
+            .to_signal_vec()
+            .map(move |preset| {
+                let name = preset.name.clone();
+                let load_button = Button::builder()
+                    .text(tr!(HeroNeonLoadPreset))
+                    .on_click(move |_| active_settings.load_preset(&preset));
+                let remove_button = Button::builder()
+                    .text(tr!(HeroNeonRemovePreset))
+                    .on_click(move |_| active_settings.remove_preset(&name));
+
+                ContentSection::new()
+                    .class_list("d[flex] f-d[row] a-i[center] j-c[space-between] g[5]")
+                    .section(ContentSection::new().class_list("mdma-text").text(&name))
+                    .button_pair(ButtonPair::builder(load_button, remove_button))
+            });
+        let import_input = Input::builder()
+            .placeholder(tr!(HeroNeonImportPlaceholder))
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .confirm_button(
+                InputButton::builder()
+                    .on_click(move |_event, input_elem| {
+                        let imported = match active_settings.import_presets(&input_elem.value()) {
+                            Ok(imported) => imported,
+                            Err(_err) => {
+                                debug_log!(@f "{_err:?}");
+                                if message(tr!(HeroNeonImportInvalid)).is_err() {
+                                    console_error!()
+                                }
+                                return;
+                            }
+                        };
+                        input_elem.set_value("");
+                        if message(&tr!(HeroNeonImported, imported = imported)).is_err() {
+                            console_error!()
+                        }
+                    })
+                    .tip(tr!(HeroNeonImport)),
+            );
+        let export_button = Button::builder()
+            .text(tr!(HeroNeonExport))
+            .on_click(move |_event| {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let presets = match active_settings.export_presets() {
+                        Ok(presets) => presets,
+                        Err(err) => return console_error!(err),
+                    };
+                    let clipboard = window().navigator().clipboard();
+                    if let Err(err) =
+                        wasm_bindgen_futures::JsFuture::from(clipboard.write_text(&presets))
+                            .await
+                            .map_err(map_err!())
+                    {
+                        return console_error!(err);
+                    }
+                    if message(tr!(HeroNeonExported)).is_err() {
+                        console_error!()
+                    }
+                });
+            });
+
+        self.heading(Heading::builder().text(tr!(HeroNeonPresets)))
+            .section(
+                ContentSection::new()
+                    .class_list("d[flex] f-d[column] g[5]")
+                    .input(save_input)
+                    .section(
+                        ContentSection::new()
+                            .class_list("d[flex] f-d[column] g[5] max-h[200] scroll-y")
+                            .section_signal_vec(presets),
+                    )
+                    .input(import_input)
+                    .button(export_button),
+            )
+    }
+}
+
+fn color_stop_section(active_settings: &'static ActiveSettings, index: usize) -> ContentSection {
+    let Some(stop) = active_settings.stops.lock_ref().get(index).cloned() else {
+        return ContentSection::new();
+    };
+    let color_input = Input::builder()
+        .input_type(InputType::color())
+        .size(InputSize::Color)
+        .value(&stop.color)
+        .on_input(move |_, input_elem| {
+            if let Some(stop) = active_settings.stops.lock_mut().get_mut(index) {
+                stop.color = input_elem.value();
+            }
+        });
+    let position_input = Input::builder()
+        .class_list("w[80]")
+        .input_type(InputType::slider(0.0, 100.0))
+        .mixin(|b, _| {
+            apply_methods!(b, {
+                .tip!({
+                    .text_signal(active_settings.stops.signal_ref(move |stops| {
+                        let position = stops.get(index).map_or(0.0, |stop| stop.position);
+                        tr!(HeroNeonStopPosition, percent = (position * 100.0).round())
+                    }))
+                })
+            })
+        })
+        .value((stop.position * 100.0).to_string())
+        .on_input(move |_event, input| {
+            let value = input.value_as_number();
+            if let Some(stop) = active_settings.stops.lock_mut().get_mut(index) {
+                stop.position = (value / 100.0).clamp(0.0, 1.0) as f32;
+            }
+        });
+    let opacity_input = Input::builder()
+        .class_list("w[80]")
+        .input_type(InputType::slider(0.0, 100.0))
+        .mixin(|b, _| {
+            apply_methods!(b, {
+                .tip!({
+                    .text_signal(active_settings.stops.signal_ref(move |stops| {
+                        let opacity = stops.get(index).map_or(0.0, |stop| stop.opacity);
+                        tr!(HeroNeonStopOpacity, percent = (opacity * 100.0).round())
+                    }))
+                })
+            })
+        })
+        .value((stop.opacity * 100.0).to_string())
+        .on_input(move |_event, input| {
+            let value = input.value_as_number();
+            if let Some(stop) = active_settings.stops.lock_mut().get_mut(index) {
+                stop.opacity = (value / 100.0).clamp(0.0, 1.0) as f32;
+            }
+        });
+    let remove_button = Button::builder()
+        .text("✕")
+        .disabled_signal(active_settings.stops.signal_ref(|stops| stops.len() <= 2))
+        .on_click(move |_| {
+            let mut stops_lock = active_settings.stops.lock_mut();
+            // A gradient needs at least two colors.
+            if stops_lock.len() > 2 && index < stops_lock.len() {
+                stops_lock.remove(index);
+            }
+        });
+
+    ContentSection::new()
+        .class_list("d[flex] f-d[row] a-i[center] j-c[space-between] g[5]")
+        .input(color_input)
+        .input(position_input)
+        .input(opacity_input)
+        .button(remove_button)
 }
 
 impl ActiveSettings {
@@ -252,7 +558,7 @@ impl ActiveSettings {
             .class_list("d[flex] f-d[column]")
             .heading(
                 Heading::builder()
-                    .text("Ustawienia ogólne")
+                    .text(tr!(GeneralSettings))
                     .class_list("first-heading"),
             )
             .section(ContentSection::new())
@@ -260,7 +566,10 @@ impl ActiveSettings {
             .offset_setting(self)
             .mode_setting(self)
             .color_setting(self)
-            .interpolation_speed_setting(self);
+            .gradient_setting(self)
+            .animation_setting(self)
+            .interpolation_speed_setting(self)
+            .presets_setting(self);
 
         AddonWindow::builder(ADDON_NAME)
             .header(window_header)
@@ -274,38 +583,30 @@ impl ActiveSettings {
 
         use dominator::DomBuilder;
 
-        let neon = DomBuilder::<web_sys::HtmlDivElement>::new_html("div");
-        let shadow = neon
-            .__internal_shadow_root(web_sys::ShadowRootMode::Closed)
-            .child(
-                DomBuilder::<web_sys::HtmlDivElement>::new_html("div")
-                    .style("position", "absolute")
-                    .style_signal("display", Addons::active_signal(ADDON_NAME).ok_or_else(|| err_code!())?.map(|active| active.not().then_some("none")))
-                    .style("border-radius", "50%")
-                    .style("z-index", "2")
-                    .style_signal("left", self.left.signal_ref(|left| format!("{:.0}px", left)))
-                    .style_signal("top", self.top.signal_ref(|top| format!("{:.0}px", top)))
-                    .style_signal("width", self.radius.signal_ref(|radius| format!("{:.0}px", radius * 2.0)))
-                    .style_signal("height", self.radius.signal_ref(|radius| format!("{:.0}px", radius * 2.0)))
-                    .style_signal("background", self.offset.signal().switch(|offset| self.color_signal().map(move |color| {
-                        format!("radial-gradient(circle, rgba({}, {}, {}, 1) {:.0}%, transparent 70%)", color.red, color.green, color.blue, offset * 100.0)
-                    })))
-                    .into_dom(),
-            );
-        let neon = neon.__internal_transfer_callbacks(shadow).into_dom();
-        let base_div = document()
-            .get_element_by_id("base")
-            .ok_or_else(|| err_code!())?;
-        let _neon_handle = dominator::append_dom(&base_div, neon);
+        let active_signal =
+            Addons::active_signal(ADDON_NAME).ok_or_else(|| err!(Get, "addon active signal"))?;
+        let neon = DomBuilder::<web_sys::HtmlDivElement>::new_html("div")
+            .style("position", "absolute")
+            .style_signal(
+                "display",
+                active_signal.map(|active| active.not().then_some("none")),
+            )
+            .style("border-radius", "50%")
+            .style("z-index", "2")
+            .style_signal("left", self.left.signal_ref(|left| format!("{:.0}px", left)))
+            .style_signal("top", self.top.signal_ref(|top| format!("{:.0}px", top)))
+            .style_signal("width", self.radius.signal_ref(|radius| format!("{:.0}px", radius * 2.0)))
+            .style_signal("height", self.radius.signal_ref(|radius| format!("{:.0}px", radius * 2.0)))
+            .style_signal("background", self.background_signal())
+            .style_signal("filter", self.filter_signal())
+            .into_dom();
 
-        Ok(())
+        crate::si_layer::append_to_map(neon)
     }
 }
 
 pub(super) fn init(active_settings: &'static ActiveSettings) -> JsResult<()> {
-    let _settings_window_handle = WINDOWS_ROOT
-        .try_append_dom(active_settings.render()?)
-        .ok_or_else(|| err_code!())?;
+    let _settings_window_handle = WINDOWS_ROOT.append_window(active_settings.render()?)?;
 
     #[cfg(not(feature = "ni"))]
     active_settings.init_neon_div()?;
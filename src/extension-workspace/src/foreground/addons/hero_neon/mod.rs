@@ -1,7 +1,7 @@
 // TODO(maybe): Below/over hero's whoisHereGlow, transparency (check if needs additional color step for initial transparency to not be 1)
 
-// TODO: Add linear/radial gradient like in discord's new roles.
 mod html;
+mod style;
 
 use std::{cell::Cell, ops::Deref};
 
@@ -18,12 +18,16 @@ use web_sys::CanvasRenderingContext2d;
 
 use crate::prelude::*;
 
+use style::{
+    AnimationFrame, ColorStop, GradientKind, MAX_COLOR_STOPS, NeonAnimation, NeonPreset,
+    default_color_stops,
+};
+
 const ADDON_NAME: AddonName = AddonName::HeroNeon;
 const DEFAULT_RADIUS: f64 = 28.0;
 const DEFAULT_ROTATION_STEP: u16 = 10;
 const DEFAULT_OFFSET: f32 = 0.3;
 
-#[derive(Default)]
 struct Rgb {
     red: u8,
     green: u8,
@@ -38,6 +42,9 @@ impl Rgb {
 
 fn hex_to_rgb(hex: &str) -> JsResult<Rgb> {
     let hex = hex.strip_prefix('#').ok_or_else(|| err_code!())?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return Err(err_code!());
+    }
     let red = u8::from_str_radix(&hex[0..2], 16).map_err(map_err!(from))?;
     let green = u8::from_str_radix(&hex[2..4], 16).map_err(map_err!(from))?;
     let blue = u8::from_str_radix(&hex[4..6], 16).map_err(map_err!(from))?;
@@ -59,14 +66,26 @@ struct ActiveSettings {
     interpolate: Mutable<bool>,
     /// Step of color interpolation animation.
     step: Mutable<u16>,
+    /// Whether to draw the multi-stop gradient instead of the start/end colors.
+    gradient: Mutable<bool>,
+    gradient_kind: Mutable<GradientKind>,
+    stops: Mutable<Vec<ColorStop>>,
+    animation: Mutable<NeonAnimation>,
+    presets: Mutable<Vec<NeonPreset>>,
     #[cfg(not(feature = "ni"))]
     #[setting(skip)]
     left: Mutable<f64>,
     #[cfg(not(feature = "ni"))]
     #[setting(skip)]
     top: Mutable<f64>,
+    /// Notified on every animation frame while the gradient is animated.
+    #[cfg(not(feature = "ni"))]
+    #[setting(skip)]
+    animation_tick: Mutable<()>,
     #[setting(skip)]
     interpolation_progress: Cell<u16>,
+    #[setting(skip)]
+    animation_progress: Cell<u16>,
 }
 
 impl Default for ActiveSettings {
@@ -78,11 +97,19 @@ impl Default for ActiveSettings {
             end_color: Mutable::new(String::from("#000000")),
             interpolate: Mutable::default(),
             step: Mutable::new(DEFAULT_ROTATION_STEP),
+            gradient: Mutable::default(),
+            gradient_kind: Mutable::default(),
+            stops: Mutable::new(default_color_stops()),
+            animation: Mutable::default(),
+            presets: Mutable::default(),
             #[cfg(not(feature = "ni"))]
             left: Mutable::default(),
             #[cfg(not(feature = "ni"))]
             top: Mutable::default(),
+            #[cfg(not(feature = "ni"))]
+            animation_tick: Mutable::default(),
             interpolation_progress: Cell::default(),
+            animation_progress: Cell::default(),
         }
     }
 }
@@ -120,6 +147,94 @@ impl ActiveSettings {
     }
 }
 
+impl ActiveSettings {
+    /// Gradients are only available with the neon premium.
+    fn gradient_active(&self) -> bool {
+        self.gradient.get() && Premium::neon()
+    }
+
+    fn active_animation(&self) -> NeonAnimation {
+        match Premium::animation() {
+            true => self.animation.get(),
+            false => NeonAnimation::None,
+        }
+    }
+
+    /// Moves the animation forward by one step, should be called once per
+    /// rendered frame.
+    fn advance_animation(&self) {
+        let progress = (self.animation_progress.get() + self.step.get()) % 1000;
+        self.animation_progress.set(progress);
+    }
+
+    fn animation_frame(&self) -> AnimationFrame {
+        self.active_animation().frame(self.animation_progress.get())
+    }
+
+    /// Color stops sorted by their position, with opacity scaled by `intensity`.
+    fn gradient_stops(&self, intensity: f64) -> JsResult<Vec<(f32, String)>> {
+        let mut stops = self.stops.get_cloned();
+        stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+
+        stops
+            .iter()
+            .map(|stop| Ok((stop.position.clamp(0.0, 1.0), stop.to_rgba(intensity)?)))
+            .collect()
+    }
+
+    /// Saves the current gradient, replacing a preset with the same name.
+    fn save_preset(&self, name: String) {
+        let preset = NeonPreset {
+            name,
+            gradient_kind: self.gradient_kind.get(),
+            stops: self.stops.get_cloned(),
+            animation: self.animation.get(),
+            radius: self.radius.get(),
+            step: self.step.get(),
+        };
+        let mut presets_lock = self.presets.lock_mut();
+
+        match presets_lock.iter_mut().find(|saved| saved.name == preset.name) {
+            Some(saved) => *saved = preset,
+            None => presets_lock.push(preset),
+        }
+    }
+
+    fn load_preset(&self, preset: &NeonPreset) {
+        self.gradient.set_neq(true);
+        self.gradient_kind.set_neq(preset.gradient_kind);
+        self.stops.set(preset.stops.iter().take(MAX_COLOR_STOPS).cloned().collect());
+        self.animation.set_neq(preset.animation);
+        self.radius.set_neq(preset.radius.clamp(0.0, 100.0));
+        self.step.set_neq(preset.step.clamp(1, 50));
+        self.animation_progress.set(0);
+    }
+
+    fn remove_preset(&self, name: &str) {
+        self.presets.lock_mut().retain(|preset| preset.name != name);
+    }
+
+    fn export_presets(&self) -> JsResult<String> {
+        serde_json::to_string(&*self.presets.lock_ref()).map_err(map_err!(from))
+    }
+
+    /// Appends the presets to the saved ones, returns the amount of imported presets.
+    /// Nothing is imported if any of the presets is invalid.
+    fn import_presets(&self, value: &str) -> JsResult<usize> {
+        let mut presets: Vec<NeonPreset> = serde_json::from_str(value).map_err(map_err!(from))?;
+        presets.iter_mut().try_for_each(NeonPreset::validate)?;
+        let imported = presets.len();
+
+        presets.into_iter().for_each(|preset| {
+            let mut presets_lock = self.presets.lock_mut();
+            presets_lock.retain(|saved| saved.name != preset.name);
+            presets_lock.push(preset);
+        });
+
+        Ok(imported)
+    }
+}
+
 #[cfg(feature = "ni")]
 impl ActiveSettings {
     fn draw(&self, ctx: &CanvasRenderingContext2d) -> JsResult<()> {
@@ -139,6 +254,13 @@ impl ActiveSettings {
         let center_y = ry * 32.0 + 24.0 - offset[1];
         let radius = self.radius.get() * std::f64::consts::SQRT_2;
 
+        if self.gradient_active() {
+            let res = self.draw_gradient(ctx, center_x, center_y, radius);
+            ctx.restore();
+
+            return res;
+        }
+
         let gradient = ctx
             .create_radial_gradient(center_x, center_y, 0.0, center_x, center_y, radius)
             .map_err(map_err!())?;
@@ -172,6 +294,56 @@ impl ActiveSettings {
         Ok(())
     }
 
+    fn draw_gradient(
+        &self,
+        ctx: &CanvasRenderingContext2d,
+        center_x: f64,
+        center_y: f64,
+        radius: f64,
+    ) -> JsResult<()> {
+        self.advance_animation();
+        let frame = self.animation_frame();
+        let gradient_kind = self.gradient_kind.get();
+        let gradient = match gradient_kind {
+            GradientKind::Radial => ctx
+                .create_radial_gradient(center_x, center_y, 0.0, center_x, center_y, radius)
+                .map_err(map_err!())?,
+            GradientKind::Linear => {
+                let dx = radius * frame.angle.cos();
+                let dy = radius * frame.angle.sin();
+
+                ctx.create_linear_gradient(
+                    center_x - dx,
+                    center_y - dy,
+                    center_x + dx,
+                    center_y + dy,
+                )
+            }
+            GradientKind::Conic => ctx
+                .create_conic_gradient(frame.angle, center_x, center_y)
+                .map_err(map_err!())?,
+        };
+
+        for (position, color) in self.gradient_stops(frame.intensity)? {
+            gradient
+                .add_color_stop(position, &color)
+                .map_err(map_err!())?;
+        }
+
+        // Soften the edges of gradients which don't fade out with the distance.
+        if gradient_kind != GradientKind::Radial {
+            ctx.set_filter(&format!("blur({:.0}px)", radius / 4.0));
+        }
+
+        ctx.set_fill_style_canvas_gradient(&gradient);
+        ctx.begin_path();
+        ctx.arc(center_x, center_y, radius, 0.0, 2.0 * std::f64::consts::PI)
+            .map_err(map_err!())?;
+        ctx.fill();
+
+        Ok(())
+    }
+
     fn get_order_factory() -> Function {
         let closure = move || {
             // let epsilon =   1.1; // The order needs to be below the pet.
@@ -232,8 +404,12 @@ impl ActiveSettings {
     }
 
     fn add_to_renderer(&'static self) -> JsResult<()> {
-        let api_data = get_engine().api_data().ok_or_else(|| err_code!())?;
-        let renderer = get_engine().renderer().ok_or_else(|| err_code!())?;
+        let api_data = get_engine()
+            .api_data()
+            .ok_or_else(|| err!(Get, "api data"))?;
+        let renderer = get_engine()
+            .renderer()
+            .ok_or_else(|| err!(Get, "renderer"))?;
         let after_call_draw_add_to_renderer = closure!(move || {
             if !Addons::is_active(ADDON_NAME) {
                 return;
@@ -251,7 +427,7 @@ impl ActiveSettings {
                 &api_data.call_draw_add_to_renderer(),
                 &after_call_draw_add_to_renderer,
             )
-            .map_err(map_err!())?;
+            .map_err(map_err!(Call, "draw callback"))?;
 
         Ok(())
     }
@@ -262,7 +438,7 @@ impl ActiveSettings {
     fn add_to_renderer(&'static self) -> JsResult<()> {
         use futures_signals::signal::SignalExt;
 
-        let hero = get_engine().hero().ok_or_else(|| err_code!())?;
+        let hero = get_engine().hero().ok_or_else(|| err!(Get, "hero"))?;
 
         let hero_clone = hero.clone();
         let future = self.radius.signal().for_each(move |radius| {
@@ -274,7 +450,7 @@ impl ActiveSettings {
         });
         wasm_bindgen_futures::spawn_local(future);
 
-        let original_run = hero.get_run().ok_or_else(|| err_code!())?;
+        let original_run = hero.get_run().ok_or_else(|| err!(Get, "hero run"))?;
         let new_run = closure!(
             { let hero = hero.clone() },
             move || -> JsResult<JsValue> {
@@ -292,7 +468,29 @@ impl ActiveSettings {
 
         hero.set_run(&new_run);
 
-        Ok(())
+        self.request_animation_frame()
+    }
+
+    /// The neon is a plain element on SI, which isn't redrawn by the game, so
+    /// the animation is driven by its own loop.
+    fn request_animation_frame(&'static self) -> JsResult<()> {
+        let on_frame = closure!(@once move || {
+            if Addons::is_active(ADDON_NAME)
+                && self.gradient_active()
+                && self.active_animation() != NeonAnimation::None
+            {
+                self.advance_animation();
+                self.animation_tick.set(());
+            }
+            if let Err(err_code) = self.request_animation_frame() {
+                console_error!(err_code);
+            }
+        });
+
+        window()
+            .request_animation_frame(&on_frame)
+            .map(|_| ())
+            .map_err(map_err!(Call, "animation frame"))
     }
 
     fn update_pos_no_draw(
@@ -328,27 +526,72 @@ impl ActiveSettings {
         Ok(())
     }
 
-    fn color_signal(&'static self) -> impl futures_signals::signal::Signal<Item = Rgb> {
+    fn background_signal(&'static self) -> impl futures_signals::signal::Signal<Item = String> {
         futures_signals::map_ref! {
-            let start_color = self.start_color.signal_cloned(),
+            let offset = self.offset.signal(),
+            let _ = self.start_color.signal_cloned(),
             let _ = self.end_color.signal_cloned(),
-            let interpolate = self.interpolate.signal() => {
-                let res = match interpolate {
-                    true => self.interpolate_color(),
-                    false => hex_to_rgb(start_color),
-                };
-
-                match res {
-                    Ok(res) => res,
+            let _ = self.interpolate.signal(),
+            let _ = self.gradient.signal(),
+            let _ = self.gradient_kind.signal(),
+            let _ = self.stops.signal_ref(|_| ()),
+            let _ = self.animation.signal(),
+            let _ = self.animation_tick.signal() => {
+                match self.background(*offset) {
+                    Ok(background) => background,
                     Err(err_code) => {
                         console_error!(err_code);
 
-                        Rgb::default()
+                        String::new()
                     }
                 }
             }
         }
     }
+
+    fn background(&self, offset: f32) -> JsResult<String> {
+        if !self.gradient_active() {
+            let color = match self.interpolate.get() {
+                true => self.interpolate_color()?,
+                false => hex_to_rgb(self.start_color.lock_ref().deref())?,
+            };
+
+            return Ok(format!(
+                "radial-gradient(circle, rgba({}, {}, {}, 1) {:.0}%, transparent 70%)",
+                color.red,
+                color.green,
+                color.blue,
+                offset * 100.0
+            ));
+        }
+
+        let frame = self.animation_frame();
+        let stops = self
+            .gradient_stops(frame.intensity)?
+            .into_iter()
+            .map(|(position, color)| format!("{color} {:.0}%", position * 100.0))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let degrees = frame.angle.to_degrees();
+
+        Ok(match self.gradient_kind.get() {
+            GradientKind::Radial => format!("radial-gradient(circle, {stops})"),
+            GradientKind::Linear => format!("linear-gradient({degrees:.0}deg, {stops})"),
+            GradientKind::Conic => format!("conic-gradient(from {degrees:.0}deg, {stops})"),
+        })
+    }
+
+    /// Soften the edges of gradients which don't fade out with the distance.
+    fn filter_signal(&'static self) -> impl futures_signals::signal::Signal<Item = Option<String>> {
+        futures_signals::map_ref! {
+            let _ = self.gradient.signal(),
+            let gradient_kind = self.gradient_kind.signal(),
+            let radius = self.radius.signal() => {
+                (self.gradient_active() && *gradient_kind != GradientKind::Radial)
+                    .then(|| format!("blur({:.0}px)", radius / 4.0))
+            }
+        }
+    }
 }
 
 pub(crate) fn init() -> JsResult<()> {
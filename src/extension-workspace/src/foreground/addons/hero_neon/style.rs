@@ -0,0 +1,145 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+use super::hex_to_rgb;
+
+/// Highest amount of color stops in a single gradient.
+pub(super) const MAX_COLOR_STOPS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub(super) enum GradientKind {
+    #[default]
+    Radial,
+    Linear,
+    Conic,
+}
+
+impl From<GradientKind> for &'static str {
+    fn from(value: GradientKind) -> Self {
+        match value {
+            GradientKind::Radial => tr!(HeroNeonGradientRadial),
+            GradientKind::Linear => tr!(HeroNeonGradientLinear),
+            GradientKind::Conic => tr!(HeroNeonGradientConic),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub(super) enum NeonAnimation {
+    #[default]
+    None,
+    /// Sharp flash fading out until the next cycle.
+    Pulse,
+    /// Smooth fade in and out.
+    Breathe,
+    /// Rotates linear and conic gradients.
+    Rotate,
+}
+
+impl From<NeonAnimation> for &'static str {
+    fn from(value: NeonAnimation) -> Self {
+        match value {
+            NeonAnimation::None => tr!(HeroNeonAnimationNone),
+            NeonAnimation::Pulse => tr!(HeroNeonAnimationPulse),
+            NeonAnimation::Breathe => tr!(HeroNeonAnimationBreathe),
+            NeonAnimation::Rotate => tr!(HeroNeonAnimationRotate),
+        }
+    }
+}
+
+/// State of an animation at a given point of its cycle.
+pub(super) struct AnimationFrame {
+    /// Multiplier of every color stop opacity.
+    pub(super) intensity: f64,
+    /// Gradient rotation in radians.
+    pub(super) angle: f64,
+}
+
+impl NeonAnimation {
+    /// `progress` is the position in the animation cycle (0 to 1000).
+    pub(super) fn frame(self, progress: u16) -> AnimationFrame {
+        let t = progress as f64 / 1000.0;
+
+        match self {
+            Self::None => AnimationFrame {
+                intensity: 1.0,
+                angle: 0.0,
+            },
+            Self::Pulse => AnimationFrame {
+                intensity: 0.3 + 0.7 * (1.0 - t).powi(3),
+                angle: 0.0,
+            },
+            Self::Breathe => AnimationFrame {
+                intensity: 0.3 + 0.7 * (0.5 - 0.5 * (2.0 * PI * t).cos()),
+                angle: 0.0,
+            },
+            Self::Rotate => AnimationFrame {
+                intensity: 1.0,
+                angle: 2.0 * PI * t,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct ColorStop {
+    /// Hex color, e.g. `#8caaee`.
+    pub(super) color: String,
+    /// Position of the stop in the gradient (0 to 1).
+    pub(super) position: f32,
+    /// Opacity of the color (0 to 1).
+    pub(super) opacity: f32,
+}
+
+impl ColorStop {
+    pub(super) fn new(color: &str, position: f32, opacity: f32) -> Self {
+        Self {
+            color: color.to_string(),
+            position,
+            opacity,
+        }
+    }
+
+    /// Css color of the stop with it's opacity scaled by `intensity`.
+    pub(super) fn to_rgba(&self, intensity: f64) -> JsResult<String> {
+        let rgb = hex_to_rgb(&self.color)?;
+        let alpha = (self.opacity as f64 * intensity).clamp(0.0, 1.0);
+
+        Ok(format!(
+            "rgba({}, {}, {}, {alpha:.3})",
+            rgb.red, rgb.green, rgb.blue
+        ))
+    }
+}
+
+pub(super) fn default_color_stops() -> Vec<ColorStop> {
+    vec![
+        ColorStop::new("#8caaee", 0.3, 1.0),
+        ColorStop::new("#ce412b", 0.7, 0.0),
+    ]
+}
+
+/// Saved gradient style which can be shared with other players.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct NeonPreset {
+    pub(super) name: String,
+    pub(super) gradient_kind: GradientKind,
+    pub(super) stops: Vec<ColorStop>,
+    pub(super) animation: NeonAnimation,
+    pub(super) radius: f64,
+    pub(super) step: u16,
+}
+
+impl NeonPreset {
+    /// Drops the stops above [`MAX_COLOR_STOPS`] and makes sure the remaining
+    /// ones can be drawn, since presets can be imported from other players.
+    pub(super) fn validate(&mut self) -> JsResult<()> {
+        self.stops.truncate(MAX_COLOR_STOPS);
+        self.stops
+            .iter()
+            .try_for_each(|stop| hex_to_rgb(&stop.color).map(|_| ()))
+    }
+}
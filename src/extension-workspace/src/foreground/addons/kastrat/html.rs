@@ -1,25 +1,23 @@
-use std::iter;
 use std::ops::Deref;
 
-use common::err_code;
 use dominator::events::KeyDown;
 use dominator::{apply_methods, clone, html, Dom, EventOptions};
 use futures_signals::map_ref;
 use futures_signals::signal::SignalExt;
-use wasm_bindgen::intern;
 
 use crate::addon_window::prelude::*;
 use crate::bindings::engine::hero::AutoGoToData;
+use crate::hotkeys::HotkeyAction;
 use crate::interface::tips_parser::tip;
-use crate::interface::{ThreadLocalShadowRoot, ALLOWED_CHARS, WINDOWS_ROOT};
+use crate::interface::{ThreadLocalShadowRoot, WINDOWS_ROOT};
 use crate::prelude::*;
 
-use super::{get_engine, message, ActiveSettings, HotkeyValue, Settings, ADDON_NAME};
+use super::{get_engine, message, ActiveSettings, Settings, ADDON_NAME};
 
 impl ActiveSettings {
     fn render(&'static self, settings: &'static Settings) -> JsResult<Dom> {
-        let window_size =
-            Addons::get_window_size(ADDON_NAME, Self::WINDOW_TYPE).ok_or_else(|| err_code!())?;
+        let window_size = Addons::get_window_size(ADDON_NAME, Self::WINDOW_TYPE)
+            .ok_or_else(|| err!(Get, "window size"))?;
         let decor = HeaderDecor::builder()
             .push_left(decors::OpacityToggle::new())
             .push_left(decors::SettingsButton::new())
@@ -40,7 +38,7 @@ impl ActiveSettings {
                         .text_signal(|| {
                             window_size.signal_ref(|size| match size {
                                 0 => "",
-                                _ => "Min",
+                                _ => tr!(KastratMin),
                             })
                         })
                         .input_type(InputType::number(1.0, 500.0))
@@ -57,7 +55,7 @@ impl ActiveSettings {
                         .text_signal(|| {
                             window_size.signal_ref(|size| match size {
                                 0 => "",
-                                _ => "Max",
+                                _ => tr!(KastratMax),
                             })
                         })
                         .input_type(InputType::number(1.0, 500.0))
@@ -71,6 +69,7 @@ impl ActiveSettings {
                 )
                 .class_list("j-c[space-around]"),
             )
+            .checkbox(Checkbox::builder(self.enemies_only.clone()).text(tr!(KastratEnemiesOnly)))
             .section(self.target_setting(settings)?);
 
         AddonWindow::builder(ADDON_NAME)
@@ -90,8 +89,8 @@ impl ActiveSettings {
                 apply_methods!(b, {
                     .tip!({
                         .text_signal(self.attack_toggle.signal_ref(|active| match active {
-                            true => "Wyłącz atakowanie",
-                            false => "Włącz atakowanie",
+                            true => tr!(KastratAttackDisable),
+                            false => tr!(KastratAttackEnable),
                         }))
                     })
                 })
@@ -100,11 +99,11 @@ impl ActiveSettings {
 
     fn target_setting(&'static self, settings: &Settings) -> JsResult<ContentSection> {
         let button_text_signal = Addons::get_window_size(ADDON_NAME, Self::WINDOW_TYPE)
-            .ok_or_else(|| err_code!())?
+            .ok_or_else(|| err!(Get, "window size"))?
             .signal()
             .map(|window_size| match window_size {
-                0 => "Podejdź",
-                _ => "Podejdź do celu",
+                0 => tr!(KastratTrack),
+                _ => tr!(KastratTrackTarget),
             });
         let target_button = Button::builder()
             .class_list("w[120] overflow[hidden] t-o[ellipsis] w-s[nowrap]")
@@ -120,10 +119,7 @@ impl ActiveSettings {
                     && let Some(target_y) = target.y.get()
                 {
                     let dest = AutoGoToData::new(target_x, target_y);
-                    let _ = message(&format!(
-                        "[MDMA::RS] Podchodzę do \"{}\"...",
-                        target.nick.lock_ref().deref()
-                    ));
+                    let _ = message(&tr!(KastratTracking, nick = target.nick.lock_ref().deref()));
 
                     get_engine()
                         .hero()
@@ -148,36 +144,36 @@ impl Settings {
             .build();
         let header = WindowHeader::new(decor);
         let window_size = Addons::get_window_size(ADDON_NAME, ActiveSettings::WINDOW_TYPE)
-            .ok_or_else(|| err_code!())?;
+            .ok_or_else(|| err!(Get, "window size"))?;
         let content = WindowContent::builder()
             .heading(
                 Heading::builder()
                     .class_list("first-heading")
-                    .text("Ustawienia ogólne"),
+                    .text(tr!(GeneralSettings)),
             )
             .section(
                 ContentSection::new()
                     .checkbox(
                         Checkbox::builder(self.track_button.clone()).label_mixin(|builder| {
-                            builder.text("Wyświetlaj przycisk ").child(html!("b", {
+                            builder.text(tr!(KastratShowButton)).child(html!("b", {
                                 .text_signal(
                                     window_size.signal()
                                         .map(|window_size| match window_size {
-                                            0 => "Podejdź",
-                                            _ => "Podejdź do celu",
+                                            0 => tr!(KastratTrack),
+                                            _ => tr!(KastratTrackTarget),
                                         })
                                 )
                             }))
                         }),
                     )
-                    .checkbox(Checkbox::builder(self.msg.clone()).text(s!("Wiadomość o ataku")))
+                    .checkbox(Checkbox::builder(self.msg.clone()).text(tr!(KastratAttackMessage)))
                     .checkbox(
                         Checkbox::builder(self.wanted_targetting.clone())
                             .class_list("w-s[pre-line] l-h[16]")
-                            .text("Atakuj poszukiwanych na\nmapach z warunkowym PvP"),
+                            .text(tr!(KastratWanted)),
                     ),
             )
-            .heading(Heading::builder().text("Skróty klawiszowe"))
+            .heading(Heading::builder().text(tr!(KastratHotkeys)))
             .section(self.hotkey_setting())
             .global_event_with_options(&EventOptions::preventable(), move |event: KeyDown| {
                 self.init(active_settings, event)
@@ -189,197 +185,53 @@ impl Settings {
             .build()
     }
     fn hotkey_setting(&'static self) -> ContentSection {
-        let attack_toggle_checkbox = Checkbox::builder(self.attack_toggle_hotkey.active.clone())
+        let attack_toggle_checkbox = self
+            .attack_toggle_hotkey
+            .checkbox(HotkeyAction::AttackToggle)
             .class_list("w-s[pre-line] l-h[16]")
-            .text("Klawisz do przełączania\nautomatycznego ataku");
-
-        let hotkey_lock = self.attack_toggle_hotkey.value.lock_ref();
-        let display_value = hotkey_lock
-            .ctrl_key
-            .then_some("Ctrl")
-            .into_iter()
-            .chain(hotkey_lock.alt_key.then_some("Alt"))
-            .chain(hotkey_lock.shift_key.then_some("Shift"))
-            .chain(iter::once(hotkey_lock.value.as_str()))
-            .map(ToString::to_string)
-            .collect::<Vec<String>>()
-            .join(" + ");
-        drop(hotkey_lock);
-
-        let attack_toggle_input = Input::builder()
-            .input_type(InputType::keybind())
-            .value(display_value)
-            .maxlength("1")
-            .size(InputSize::Custom("w[100]"))
-            .on_key_down(move |event, input_elem| {
-                if event.repeat() {
-                    return;
-                }
-
-                let value = event.key();
-                let mut chars = value.chars();
-
-                if chars
-                    .next()
-                    .is_none_or(|c| !is_from_polish_alphabet(c) && !ALLOWED_CHARS.contains(c))
-                {
-                    event.prevent_default();
-                    event.stop_propagation();
-                    input_elem.blur().unwrap_js();
-                    return;
-                }
-                if chars.next().is_some() {
-                    event.prevent_default();
-                    event.stop_propagation();
-
-                    if value != intern("Tab") {
-                        if value == intern("Escape")
-                            || !matches!(value.as_str(), "Control" | "Alt" | "Shift")
-                        {
-                            input_elem.blur().unwrap_js();
-                        }
-                        return;
-                    }
-                }
-
-                let value = value
-                    .chars()
-                    .map(polish_to_ascii)
-                    .collect::<String>()
-                    .to_ascii_uppercase();
-                let mut new_hotkey = HotkeyValue::default();
-                let display_value = event
-                    .ctrl_key()
-                    .then(|| {
-                        new_hotkey.ctrl_key = true;
-                        "Ctrl"
-                    })
-                    .into_iter()
-                    .chain(event.alt_key().then(|| {
-                        new_hotkey.alt_key = true;
-                        "Alt"
-                    }))
-                    .chain(event.shift_key().then(|| {
-                        new_hotkey.shift_key = true;
-                        "Shift"
-                    }))
-                    .chain(iter::once(value.as_str()))
-                    .map(ToString::to_string)
-                    .collect::<Vec<String>>()
-                    .join(" + ");
-
-                event.prevent_default();
-                event.stop_propagation();
-                input_elem.blur().unwrap_js();
-                input_elem.set_value(&display_value);
-                new_hotkey.value = value;
-                self.attack_toggle_hotkey.value.set(new_hotkey);
-            });
+            .text(tr!(KastratAttackToggleHotkey));
+        let attack_toggle_input = self
+            .attack_toggle_hotkey
+            .input(HotkeyAction::AttackToggle)
+            .size(InputSize::Custom("w[100]"));
         let attack_toggle_section = ContentSection::new()
             .class_list("d[flex] f-d[row] g[10] a-i[center]")
             .checkbox(attack_toggle_checkbox)
             .input(attack_toggle_input);
 
-        let track_checkbox = Checkbox::builder(self.track_hotkey.active.clone())
+        let track_checkbox = self
+            .track_hotkey
+            .checkbox(HotkeyAction::TrackTarget)
             .class_list("w-s[pre-line] l-h[16]")
-            .text("Klawisz od podchodzenia\ndo celu")
-            .on_click(|_| {
-                self.track_hotkey
-                    .active
-                    .set(!self.track_hotkey.active.get())
-            });
-
-        let hotkey_lock = self.track_hotkey.value.lock_ref();
-        let display_value = hotkey_lock
-            .ctrl_key
-            .then_some("Ctrl")
-            .into_iter()
-            .chain(hotkey_lock.alt_key.then_some("Alt"))
-            .chain(hotkey_lock.shift_key.then_some("Shift"))
-            .chain(iter::once(hotkey_lock.value.as_str()))
-            .map(ToString::to_string)
-            .collect::<Vec<String>>()
-            .join(" + ");
-        drop(hotkey_lock);
-
-        let track_input = Input::builder()
-            .input_type(InputType::keybind())
-            .value(display_value)
-            .maxlength("1")
-            .size(InputSize::Custom("w[100]"))
-            .on_key_down(move |event, input_elem| {
-                if event.repeat() {
-                    return;
-                }
-
-                let value = event.key();
-                let mut chars = value.chars();
-
-                if chars
-                    .next()
-                    .is_none_or(|c| !is_from_polish_alphabet(c) && !ALLOWED_CHARS.contains(c))
-                {
-                    event.prevent_default();
-                    event.stop_propagation();
-                    input_elem.blur().unwrap_js();
-                    return;
-                }
-                if chars.next().is_some() {
-                    event.prevent_default();
-                    event.stop_propagation();
-
-                    if value != intern("Tab") {
-                        if value == intern("Escape")
-                            || !matches!(value.as_str(), "Control" | "Alt" | "Shift")
-                        {
-                            input_elem.blur().unwrap_js();
-                        }
-                        return;
-                    }
-                }
-
-                let value = value
-                    .chars()
-                    .map(polish_to_ascii)
-                    .collect::<String>()
-                    .to_ascii_uppercase();
-                let mut new_hotkey = HotkeyValue::default();
-                let display_value = event
-                    .ctrl_key()
-                    .then(|| {
-                        new_hotkey.ctrl_key = true;
-                        "Ctrl"
-                    })
-                    .into_iter()
-                    .chain(event.alt_key().then(|| {
-                        new_hotkey.alt_key = true;
-                        "Alt"
-                    }))
-                    .chain(event.shift_key().then(|| {
-                        new_hotkey.shift_key = true;
-                        "Shift"
-                    }))
-                    .chain(iter::once(value.as_str()))
-                    .map(ToString::to_string)
-                    .collect::<Vec<String>>()
-                    .join(" + ");
-
-                event.prevent_default();
-                event.stop_propagation();
-                input_elem.blur().unwrap_js();
-                input_elem.set_value(&display_value);
-                new_hotkey.value = value;
-                self.track_hotkey.value.set(new_hotkey);
-            });
+            .text(tr!(KastratTrackHotkey));
+        let track_input = self
+            .track_hotkey
+            .input(HotkeyAction::TrackTarget)
+            .size(InputSize::Custom("w[100]"));
         let track_hotkey_section = ContentSection::new()
             .class_list("d[flex] f-d[row] j-c[space-between] a-i[center]")
             .checkbox(track_checkbox)
             .input(track_input);
 
+        let clear_target_checkbox = self
+            .clear_target_hotkey
+            .checkbox(HotkeyAction::ClearTarget)
+            .class_list("w-s[pre-line] l-h[16]")
+            .text(tr!(KastratClearTargetHotkey));
+        let clear_target_input = self
+            .clear_target_hotkey
+            .input(HotkeyAction::ClearTarget)
+            .size(InputSize::Custom("w[100]"));
+        let clear_target_section = ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between] a-i[center]")
+            .checkbox(clear_target_checkbox)
+            .input(clear_target_input);
+
         ContentSection::new()
             .class_list("d[flex] f-d[column] g[6]")
             .section(attack_toggle_section)
             .section(track_hotkey_section)
+            .section(clear_target_section)
     }
 }
 
@@ -387,12 +239,8 @@ pub(super) fn init(
     settings: &'static Settings,
     active_settings: &'static ActiveSettings,
 ) -> JsResult<()> {
-    let _addon_window_handle = WINDOWS_ROOT
-        .try_append_dom(active_settings.render(settings)?)
-        .ok_or_else(|| err_code!())?;
+    let _addon_window_handle = WINDOWS_ROOT.append_window(active_settings.render(settings)?)?;
 
-    let _settings_window_handle = WINDOWS_ROOT
-        .try_append_dom(settings.render(active_settings)?)
-        .ok_or_else(|| err_code!())?;
+    let _settings_window_handle = WINDOWS_ROOT.append_window(settings.render(active_settings)?)?;
     Ok(())
 }
@@ -7,17 +7,16 @@ use futures_signals::signal::{self, Mutable, MutableLockRef, Signal, SignalExt};
 use futures_signals::signal_map::SignalMapExt;
 use futures_signals::signal_vec::SignalVecExt;
 use proc_macros::{ActiveSettings, Setting, Settings};
-use serde::{Deserialize, Serialize};
-
 use crate::bindings::engine::hero::AutoGoToData;
 use crate::bindings::engine::types::MapMode;
-use crate::color_mark::{Color, ColorMark};
+use crate::hotkeys::{Hotkey, HotkeyAction, HotkeyValue, Hotkeys};
+use crate::{color_mark::ColorMark, overlay::OverlayColor};
 use crate::prelude::*;
 
 mod html;
 
 const ADDON_NAME: AddonName = AddonName::Kastrat;
-const TARGET_COLOR: Color = Color::Red;
+const TARGET_COLOR: OverlayColor = OverlayColor::named("red");
 pub const MIN_DIFF: f64 = 120.0;
 
 #[derive(Clone, Setting)]
@@ -274,7 +273,7 @@ impl Target {
                 .unwrap_js()
                 .delete_arrow(&format!("Other-{}", old_target.char_id))
                 .unwrap_js();
-            ColorMark::remove(TARGET_COLOR, addon_name, old_target.char_id);
+            ColorMark::remove(&TARGET_COLOR, addon_name, old_target.char_id);
         }
 
         #[cfg(feature = "ni")]
@@ -311,7 +310,7 @@ impl Target {
                 .unwrap_js()
                 .delete_arrow(&format!("Other-{}", old_target.char_id))
                 .unwrap_js();
-            ColorMark::remove(TARGET_COLOR, addon_name, old_target.char_id);
+            ColorMark::remove(&TARGET_COLOR, addon_name, old_target.char_id);
         }
 
         old_target
@@ -382,64 +381,13 @@ impl Target {
     }
 }
 
-#[derive(Default, Deserialize, Serialize)]
-struct HotkeyValue {
-    value: String,
-    alt_key: bool,
-    ctrl_key: bool,
-    shift_key: bool,
-}
-
-impl HotkeyValue {
-    fn new(value: &str) -> Self {
-        Self {
-            value: value.to_owned(),
-            alt_key: false,
-            ctrl_key: false,
-            shift_key: true,
-        }
-    }
-}
-
-impl window_events::Hotkey for HotkeyValue {
-    fn value(&self) -> &str {
-        &self.value
-    }
-
-    fn alt_key(&self) -> bool {
-        self.alt_key
-    }
-
-    fn ctrl_key(&self) -> bool {
-        self.ctrl_key
-    }
-
-    fn shift_key(&self) -> bool {
-        self.shift_key
-    }
-}
-
-#[derive(Clone, Setting)]
-struct Hotkey {
-    value: Mutable<HotkeyValue>,
-    active: Mutable<bool>,
-}
-
-impl Hotkey {
-    fn new(value: &str) -> Self {
-        Self {
-            value: Mutable::new(HotkeyValue::new(value)),
-            active: Mutable::new(false),
-        }
-    }
-}
-
 #[derive(Settings)]
 struct Settings {
     /// Determines whether wanted players are valid targets on MapMode::AgreePvp.
     wanted_targetting: Mutable<bool>,
     attack_toggle_hotkey: Hotkey,
     track_hotkey: Hotkey,
+    clear_target_hotkey: Hotkey,
     msg: Mutable<bool>,
     /// Whether track button is visible.
     track_button: Mutable<bool>,
@@ -450,8 +398,9 @@ impl Default for Settings {
     fn default() -> Self {
         Self {
             wanted_targetting: Mutable::new(false),
-            attack_toggle_hotkey: Hotkey::new("X"),
-            track_hotkey: Hotkey::new("L"),
+            attack_toggle_hotkey: Hotkey::new(HotkeyValue::new("X", true), false),
+            track_hotkey: Hotkey::new(HotkeyValue::new("L", true), false),
+            clear_target_hotkey: Hotkey::new(HotkeyValue::new("K", true), false),
             msg: Mutable::new(false),
             track_button: Mutable::new(true),
         }
@@ -464,59 +413,59 @@ impl Settings {
             return;
         }
 
-        if self.track_hotkey.active.get() {
-            match window_events::validate_keydown_event(
-                &event,
-                self.track_hotkey.value.lock_ref().deref(),
-            ) {
-                Ok(true) => {
-                    let target_lock = active_settings.target.lock_ref();
-                    let Some(target) = target_lock.as_ref() else {
-                        return;
-                    };
-
-                    if let Some(target_x) = target.x.get()
-                        && let Some(target_y) = target.y.get()
-                    {
-                        let dest = AutoGoToData::new(target_x, target_y);
-                        let _ = message(&format!(
-                            "[MDMA::RS] Podchodzę do \"{}\"...",
-                            target.nick.lock_ref().deref()
-                        ));
-
-                        get_engine()
-                            .hero()
-                            .unwrap_js()
-                            .auto_go_to(&dest)
-                            .unwrap_js();
-                    }
-
-                    event.prevent_default();
-                    event.stop_immediate_propagation();
+        match self.track_hotkey.is_pressed(&event) {
+            Ok(true) => {
+                let target_lock = active_settings.target.lock_ref();
+                let Some(target) = target_lock.as_ref() else {
                     return;
+                };
+
+                if let Some(target_x) = target.x.get()
+                    && let Some(target_y) = target.y.get()
+                {
+                    let dest = AutoGoToData::new(target_x, target_y);
+                    let _ = message(&tr!(KastratTracking, nick = target.nick.lock_ref().deref()));
+
+                    get_engine()
+                        .hero()
+                        .unwrap_js()
+                        .auto_go_to(&dest)
+                        .unwrap_js();
                 }
-                Ok(false) => {}
-                Err(err_code) => console_error!(err_code),
+
+                event.prevent_default();
+                event.stop_immediate_propagation();
+                return;
             }
+            Ok(false) => {}
+            Err(err_code) => console_error!(err_code),
         }
-        if self.attack_toggle_hotkey.active.get() {
-            match window_events::validate_keydown_event(
-                &event,
-                self.attack_toggle_hotkey.value.lock_ref().deref(),
-            ) {
-                Ok(true) => {
-                    let old = active_settings.attack_toggle.replace_with(|old| !*old);
-                    let msg = match old {
-                        true => "[MDMA::RS] Wyłączono automatyczne atakowanie...",
-                        false => "[MDMA::RS] Włączono automatyczne atakowanie...",
-                    };
-                    let _ = message(msg);
-                    event.prevent_default();
-                    event.stop_immediate_propagation();
+        match self.clear_target_hotkey.is_pressed(&event) {
+            Ok(true) => {
+                if active_settings.target.clear(ADDON_NAME).is_some() {
+                    let _ = message(tr!(KastratTargetCleared));
                 }
-                Ok(false) => {}
-                Err(err_code) => console_error!(err_code),
+
+                event.prevent_default();
+                event.stop_immediate_propagation();
+                return;
             }
+            Ok(false) => {}
+            Err(err_code) => console_error!(err_code),
+        }
+        match self.attack_toggle_hotkey.is_pressed(&event) {
+            Ok(true) => {
+                let old = active_settings.attack_toggle.replace_with(|old| !*old);
+                let msg = match old {
+                    true => tr!(KastratAttackDisabled),
+                    false => tr!(KastratAttackEnabled),
+                };
+                let _ = message(msg);
+                event.prevent_default();
+                event.stop_immediate_propagation();
+            }
+            Ok(false) => {}
+            Err(err_code) => console_error!(err_code),
         }
     }
 
@@ -559,12 +508,20 @@ impl Targetable for Other {
             .filter(move |&member_id| member_id == char_id)
             .is_empty();
 
+        let from_enemies = map_ref! {
+            let enemies_only = active_settings.enemies_only.signal(),
+            let enemy = self.enemy_signal() => {
+                !*enemies_only || *enemy
+            }
+        };
+
         map_ref! {
             let from_lvl = active_settings.lvl.contains_signal(self.lvl.signal()),
+            let from_enemies = from_enemies,
             let not_in_battle = not_in_battle,
             let not_friendly = signal::not(self.friendly_signal()),
             let not_from_party = not_from_party => {
-                *from_lvl && *not_in_battle && *not_friendly && *not_from_party
+                *from_lvl && *from_enemies && *not_in_battle && *not_friendly && *not_from_party
             }
         }
     }
@@ -574,6 +531,8 @@ impl Targetable for Other {
 struct ActiveSettings {
     lvl: Level,
     attack_toggle: Mutable<bool>,
+    /// Only players from the hero's enemy list are targeted.
+    enemies_only: Mutable<bool>,
     #[setting(skip)]
     target: Target,
 }
@@ -583,6 +542,7 @@ impl Default for ActiveSettings {
         Self {
             attack_toggle: Mutable::new(true),
             lvl: Level::default(),
+            enemies_only: Mutable::new(false),
             //exclusion_list: ExclusionList::default(),
             target: Target::default(),
             //msg: Mutable::new(true),
@@ -594,6 +554,9 @@ pub(crate) fn init() -> JsResult<()> {
     let active_settings = ActiveSettings::new(ADDON_NAME);
     active_settings.target.init();
     let settings = Settings::new(ADDON_NAME);
+    Hotkeys::register(HotkeyAction::AttackToggle, &settings.attack_toggle_hotkey);
+    Hotkeys::register(HotkeyAction::TrackTarget, &settings.track_hotkey);
+    Hotkeys::register(HotkeyAction::ClearTarget, &settings.clear_target_hotkey);
 
     // PRECEDENCE:
     // 1. settings/active_settings change
@@ -602,7 +565,7 @@ pub(crate) fn init() -> JsResult<()> {
     // 4. filter out untargetable
     // 5. min_by distance
     let future = Addons::active_signal(ADDON_NAME)
-        .ok_or_else(|| err_code!())?
+        .ok_or_else(|| err!(Get, "addon active signal"))?
         .switch(|addon_active| {
             active_settings
                 .attack_toggle
@@ -701,9 +664,9 @@ pub(crate) fn init() -> JsResult<()> {
             future.await;
 
             if settings.msg.get() {
-                let _ = message(&format!(
-                    "[MDMA::RS] Atakuję \"{}\"...",
-                    active_settings
+                let _ = message(&tr!(
+                    KastratAttacking,
+                    nick = active_settings
                         .target
                         .lock_ref()
                         .as_ref()
@@ -752,7 +715,7 @@ pub(crate) fn init() -> JsResult<()> {
     wasm_bindgen_futures::spawn_local(future);
 
     let future = Addons::active_signal(ADDON_NAME)
-        .ok_or_else(|| err_code!())?
+        .ok_or_else(|| err!(Get, "addon active signal"))?
         .to_stream()
         .skip(1)
         .for_each(move |active| {
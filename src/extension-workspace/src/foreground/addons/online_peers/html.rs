@@ -21,18 +21,20 @@ use web_sys::HtmlDivElement;
 
 use crate::{
     addon_window::prelude::*,
-    color_mark::{Color, ColorMark},
+    color_mark::ColorMark,
     bindings::engine::{iframe_window::PlayerProfileOptions, show_eq::ShowEqPlayerData},
     interface::{tips_parser::tip, ThreadLocalShadowRoot, WINDOWS_ROOT},
+    overlay::OverlayColor,
 };
-use crate::{addons::better_who_is_here::LevelDisplay, prelude::*};
+use crate::prelude::*;
 
-use super::{ActiveSettings, DisplayTab, Ordering, Settings, SortBy, ADDON_NAME};
+use super::notifications::{PresenceNotifications, WatchedPeer};
+use super::{ActiveSettings, DisplayTab, Settings, ADDON_NAME};
 
 const TOP_FADE_MAX: f64 = 0.10;
 const BOTTOM_FADE_MIN: f64 = 1.0 - TOP_FADE_MAX;
 const MAX_OVERSCROLL: f64 = 50.0;
-const HIGHLIGHT_COLOR: Color = Color::Lime;
+const HIGHLIGHT_COLOR: OverlayColor = OverlayColor::named("lime");
 
 impl Settings {
     fn render(&'static self) -> JsResult<Dom> {
@@ -45,10 +47,20 @@ impl Settings {
             .class_list("f-d[column]")
             .heading(
                 Heading::builder()
-                    .text("Lista rówieśników")
+                    .text(tr!(OnlinePeersPeerList))
                     .class_list("first-heading"),
             )
-            .section(self.player_list_settings());
+            .section(self.player_list_settings())
+            .heading(
+                Heading::builder()
+                    .text(tr!(OnlinePeersNotifications))
+                    .info_bubble(
+                        InfoBubble::builder()
+                            .text(tr!(OnlinePeersNotificationsInfo))
+                            .build(),
+                    ),
+            )
+            .section(self.notifications.render());
 
         SettingsWindow::builder(ADDON_NAME)
             .header(settings_window_header)
@@ -61,170 +73,136 @@ impl Settings {
             .class_list("d[flex] f-d[column] g[6]")
             .checkbox(
                 Checkbox::builder(self.always_show_tip.clone())
-                    .text("Zawsze wyświetlaj tip")
+                    .text(tr!(OnlinePeersAlwaysShowTip))
                     .info_bubble(
                         InfoBubble::builder()
-                            .text("Gdy ta opcja jest wyłączona tip rówieśnika wyświetli się po najechaniu na jego komórkę myszką, tylko jeżeli jego opis się w niej nie mieści.")
-                            .build()
-                    )
+                            .text(tr!(OnlinePeersAlwaysShowTipInfo))
+                            .build(),
+                    ),
+            )
+            .checkbox(
+                Checkbox::builder(self.show_location.clone()).text(tr!(OnlinePeersShowLocation)),
             )
-            .checkbox(Checkbox::builder(self.show_location.clone()).text("Wyświetlaj lokację rówieśnika"))
             .checkbox(
                 Checkbox::builder(self.show_alias.clone())
                     .class_list("w-s[pre-line] l-h[16]")
-                    .text("Wyświetlaj informację o kolosie\nlub tytanie w lokacji rówieśnika")
+                    .text(tr!(OnlinePeersShowBoss))
                     .info_bubble(
                         InfoBubble::builder()
-                            .text("Jeśli rówieśnik znajduje się w przedsionku lub lokacji z kolosem/tytanem, wyświetli się o tym informacja w jego komórce. Np. K-114 lub T-285")
-                            .build()
-                    )
+                            .text(tr!(OnlinePeersShowBossInfo))
+                            .build(),
+                    ),
             )
-            .section(self.sort_by_setting())
-            .section(self.sort_order_setting())
-            .section(self.level_display_setting())
+            .section(self.player_list.render())
     }
-
-    fn level_display_setting(&'static self) -> ContentSection {
-        // TODO: Figure out a better solution.
-        let wrapper_state: &'static _ = Box::leak(Box::new(Mutable::default()));
+}
+impl PresenceNotifications {
+    fn render(&'static self) -> ContentSection {
+        let aliases_input = Input::builder()
+            .placeholder(tr!(OnlinePeersWatchedLocationsPlaceholder))
+            .maxlength("200")
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .value_signal(self.watched_aliases.signal_ref(|aliases| Some(aliases.join(", "))))
+            .confirm_button(InputButton::builder().tip(tr!(OnlinePeersSave)).on_click(
+                move |_event, input_elem| {
+                    self.set_watched_aliases(&input_elem.value());
+                },
+            ));
+        let watched_list = self
+            .watched_peers
+            .entries_cloned()
+            .map(move |(peer_id, watched_peer)| self.render_one(peer_id, &watched_peer));
 
         ContentSection::new()
-            .class_list("d[flex] f-d[row] g[10] j-c[space-between]")
-            .section(
-                ContentSection::new()
-                    .class_list("a-c[center]")
-                    .text("Wyświetlanie poziomów"),
+            .class_list("d[flex] f-d[column] g[6]")
+            .checkbox(
+                Checkbox::builder(self.in_game_message.clone()).text(tr!(OnlinePeersInGameMessage)),
             )
-            .button(
-                Button::builder()
-                    .class_list("w[208] t-a[left]")
-                    .no_hover()
-                    .text_signal(self.level_display_signal())
-                    .on_click(|_| wrapper_state.set_neq(true))
-                    .on_mousedown(|event| event.stop_propagation())
-                    .mixin(|builder| {
-                        builder.child(html!("div", {
-                            .class!(pos[absolute] r[8] align-center menu-arrow)
-                        }))
-                    })
-                    .scroll_wrapper(
-                        ScrollWrapper::builder(|| {
-                            || {
-                                wrapper_state.set_neq(false);
-                            }
-                        })
-                        .class_list("w[200] l[1]")
-                        .visible_signal(wrapper_state.signal())
-                        .option(self.level_display_option(LevelDisplay::First))
-                        .option(self.level_display_option(LevelDisplay::Last))
-                        .option(self.level_display_option(LevelDisplay::None))
-                        .option(self.level_display_option(LevelDisplay::Only))
-                        .build(),
-                    ),
+            .checkbox(
+                Checkbox::builder(self.console_entry.clone()).text(tr!(OnlinePeersConsoleEntry)),
             )
-    }
-
-    fn level_display_option(&'static self, level_display: LevelDisplay) -> ScrollWrapperOption {
-        ScrollWrapperOption::builder()
-            .text(level_display.as_str())
-            .on_click(move |_| self.level_display.set_neq(level_display))
-            .build()
-    }
-
-    fn sort_by_setting(&'static self) -> ContentSection {
-        let wrapper_state: &'static _ = Box::leak(Box::new(Mutable::default()));
-
-        ContentSection::new()
-            .class_list("d[flex] f-d[row] j-c[space-between] g[10]")
+            .checkbox(Checkbox::builder(self.sound.clone()).text(tr!(OnlinePeersSound)))
             .section(
                 ContentSection::new()
-                    .class_list("a-c[center]")
-                    .text("Sortowanie względem"),
+                    .class_list("d[flex] f-d[column] g[3]")
+                    .text(tr!(OnlinePeersWatchedLocations))
+                    .input(aliases_input),
             )
-            .button(
-                Button::builder()
-                    .class_list("w[98] t-a[left]")
-                    .no_hover()
-                    .text_signal(self.sort_by_signal())
-                    .on_click(|_| wrapper_state.set_neq(true))
-                    .on_mousedown(|event| event.stop_propagation())
-                    .mixin(move |builder| {
-                        builder.child(html!("div", {
-                            .class!(pos[absolute] r[8] align-center menu-arrow)
-                        }))
-                    })
-                    .scroll_wrapper(
-                        ScrollWrapper::builder(|| {
-                            || {
-                                wrapper_state.set_neq(false);
-                            }
-                        })
-                        .class_list("w[90] l[1]")
-                        .visible_signal(wrapper_state.signal())
-                        .option(self.sort_by_option(SortBy::Lvl))
-                        .option(self.sort_by_option(SortBy::Nick))
-                        .option(self.sort_by_option(SortBy::Prof))
-                        .build(),
-                    ),
+            .section(
+                ContentSection::new()
+                    .class_list("g[5] d[flex] f-d[column] max-h[150] scroll-y b-f[glassy-blur]")
+                    .section_signal_vec(watched_list),
             )
     }
 
-    fn sort_by_option(&'static self, sort_by: SortBy) -> ScrollWrapperOption {
-        ScrollWrapperOption::builder()
-            .text(sort_by.as_str())
-            .on_click(move |_| self.sort_by.set_neq(sort_by))
-            .build()
-    }
-
-    fn sort_order_setting(&'static self) -> ContentSection {
-        let wrapper_state: &'static _ = Box::leak(Box::new(Mutable::default()));
+    fn render_one(&'static self, peer_id: PeerId, watched_peer: &WatchedPeer) -> ContentSection {
+        let flag_checkbox = |flag: &Mutable<bool>, text: &str| {
+            let flag = flag.clone();
+            Checkbox::builder(flag.clone())
+                .text(text)
+                .on_click(move |_| {
+                    flag.set(!flag.get());
+                    self.save_one(peer_id);
+                })
+        };
 
         ContentSection::new()
-            .class_list("d[flex] f-d[row] j-c[space-between]")
+            .class_list("d[flex] f-d[column] g[3]")
             .section(
                 ContentSection::new()
-                    .class_list("a-c[center]")
-                    .text("Kolejność"),
-            )
-            .button(
-                Button::builder()
-                    .class_list("w[98] t-a[left]")
-                    .no_hover()
-                    .text_signal(self.sort_ordering_signal())
-                    .on_click(|_| wrapper_state.set_neq(true))
-                    .on_mousedown(|event| event.stop_propagation())
-                    .mixin(move |builder| {
-                        builder.child(html!("div", {
-                            .class!(pos[absolute] r[8] align-center menu-arrow)
-                        }))
-                    })
-                    .scroll_wrapper(
-                        ScrollWrapper::builder(|| {
-                            || {
-                                wrapper_state.set_neq(false);
-                            }
-                        })
-                        .class_list("w[90] l[1]")
-                        .visible_signal(wrapper_state.signal())
-                        .option(self.sort_ordering_option(Ordering::Descending))
-                        .option(self.sort_ordering_option(Ordering::Ascending))
-                        .build(),
+                    .class_list("d[flex] f-d[row] j-c[space-between]")
+                    .section(
+                        ContentSection::new()
+                            .class_list("a-c[center]")
+                            .text_signal(watched_peer.nick.signal_cloned()),
+                    )
+                    .button(
+                        Button::builder()
+                            .text(tr!(OnlinePeersRemove))
+                            .on_click(move |_| self.toggle_watch(peer_id)),
                     ),
             )
-    }
-
-    fn sort_ordering_option(&'static self, ordering: Ordering) -> ScrollWrapperOption {
-        ScrollWrapperOption::builder()
-            .text(ordering.as_str())
-            .on_click(move |_| self.ordering.set_neq(ordering))
-            .build()
+            .checkbox_pair(
+                flag_checkbox(&watched_peer.login, tr!(OnlinePeersLogin)),
+                flag_checkbox(&watched_peer.logout, tr!(OnlinePeersLogout)),
+            )
+            .checkbox_pair(
+                flag_checkbox(&watched_peer.enter_map, tr!(OnlinePeersEnterMap)),
+                flag_checkbox(&watched_peer.alias, tr!(OnlinePeersAlias)),
+            )
     }
 }
+
 impl ActiveSettings {
     fn render(
         &'static self,
         settings: &'static Settings,
     ) -> JsResult<Dom> {
+        let counter_tip_signal = map_ref! {
+            let entries = Peers::get().online_entries_signal(),
+            let friends_max = Peers::get().friends_max.signal(),
+            let enemies_max = Peers::get().enemies_max.signal() => {
+                let online_count = |relation| {
+                    entries
+                        .iter()
+                        .filter(|(_, peer_data)| peer_data.relation.get() == relation)
+                        .count()
+                };
+                let with_max = |count: usize, max: Option<u8>| match max {
+                    Some(max) => format!("{count} / {max}"),
+                    None => count.to_string(),
+                };
+
+                tr!(
+                    OnlinePeersCounter,
+                    clan = online_count(Relation::Clan),
+                    friends = with_max(online_count(Relation::Friend), *friends_max),
+                    enemies = with_max(online_count(Relation::Enemy), *enemies_max),
+                )
+            }
+        }
+        .dedupe_cloned();
         let decor = HeaderDecor::builder()
             .push_left(decors::OpacityToggle::new())
             .push_left(decors::SettingsButton::new())
@@ -235,28 +213,7 @@ impl ActiveSettings {
                 apply_methods!(b, {
                     .text_signal(Peers::get().online_len_signal().map(|online_count| format!("({online_count})")))
                     .tip!({
-                        .text_signal(Peers::get().online_entries_signal().map(|entries| {
-                            let clan_members_count = entries
-                                .iter()
-                                .filter(|(_, peer_data)| {
-                                    peer_data
-                                        .relation.get()
-                                         == Relation::Clan
-                                })
-                                .count();
-                            let friends_count = entries
-                                .iter()
-                                .filter(|(_, peer_data)| {
-                                    peer_data
-                                        .relation.get()
-                                         == Relation::Friend
-                                })
-                                .count();
-                            format!(
-                                "Klanowicze online: {}\n\n Przyjaciele online: {} / 50",
-                                clan_members_count, friends_count
-                            )
-                        }).dedupe_cloned())
+                        .text_signal(counter_tip_signal)
                     })
                 })
             }).build())
@@ -266,11 +223,11 @@ impl ActiveSettings {
         #[cfg(not(debug_assertions))]
         let scroll_wrapper_signal = self
             .scroll_target_signal()
-            .map(move |visible| visible.map(|peer_id| self.build_scroll_wrapper(peer_id)));
+            .map(move |visible| visible.map(|peer_id| self.build_scroll_wrapper(peer_id, settings)));
         #[cfg(debug_assertions)]
         let scroll_wrapper_signal = self
             .scroll_target_signal()
-            .map(move |visible| visible.map(|peer_id| self.build_scroll_wrapper(peer_id)));
+            .map(move |visible| visible.map(|peer_id| self.build_scroll_wrapper(peer_id, settings)));
         let content = WindowContent::builder()
             .class_list("f-d[column] p[1-4-4-4]")
             .section_signal(scroll_wrapper_signal)
@@ -278,33 +235,16 @@ impl ActiveSettings {
                 ContentSection::new()
                     .class_list("d[flex] f-d[row] m[0--2]")
                     .section(
-                        ContentSection::new()
-                            .class_list("card-header")
-                            .text("Klanowicze")
-                            .class_signal(
-                                "active",
-                                self.current_tab
-                                    .signal_ref(|tab| *tab == DisplayTab::ClanMembers),
-                            )
-                            .event(|_: Click| {
-                                self.current_tab.set(!self.current_tab.get());
-                            }),
+                        self.render_tab_header(
+                            DisplayTab::ClanMembers,
+                            tr!(OnlinePeersClanMembers),
+                        ),
                     )
-                    .section(
-                        ContentSection::new()
-                            .class_list("card-header")
-                            .text("Przyjaciele")
-                            .class_signal(
-                                "active",
-                                self.current_tab
-                                    .signal_ref(|tab| *tab == DisplayTab::Friends),
-                            )
-                            .event(|_: Click| {
-                                self.current_tab.set(!self.current_tab.get());
-                            }),
-                    ),
+                    .section(self.render_tab_header(DisplayTab::Friends, tr!(OnlinePeersFriends)))
+                    .section(self.render_tab_header(DisplayTab::Enemies, tr!(OnlinePeersEnemies))),
             )
-            .section(self.render_list(settings)?);
+            .section(self.render_list(settings)?)
+            .section(settings.player_list.render_filter());
 
         AddonWindow::builder(ADDON_NAME)
             .header(header)
@@ -315,8 +255,16 @@ impl ActiveSettings {
     fn build_scroll_wrapper(
         &'static self,
         peer_id: Id,
-        //settings: &'static Settings,
+        settings: &'static Settings,
     ) -> ScrollWrapper {
+        let watch_text_signal = settings
+            .notifications
+            .is_watched_signal(peer_id)
+            .map(|watched| match watched {
+                true => tr!(OnlinePeersUnwatch),
+                false => tr!(OnlinePeersWatch),
+            });
+
         ScrollWrapper::builder(|| {
             || {
                 let Some(old_scroll_target_id) = self.scroll_visible.replace(None) else {
@@ -329,14 +277,14 @@ impl ActiveSettings {
                     .unwrap_js()
                     .delete_arrow(&format!("Other-{old_scroll_target_id}"))
                     .unwrap_js();
-                ColorMark::remove(HIGHLIGHT_COLOR, ADDON_NAME, old_scroll_target_id);
+                ColorMark::remove(&HIGHLIGHT_COLOR, ADDON_NAME, old_scroll_target_id);
                 //globals.others.remove_color_mark(old_target);
             }
         })
         .class_list("w[130] t-a[center] l[100%+4] align-center")
         .option(
             ScrollWrapperOption::builder()
-                .text("Wyślij wiadomość")
+                .text(tr!(OnlinePeersSendMessage))
                 .on_click(move |_| {
                     get_engine()
                         .chat_controller()
@@ -358,7 +306,7 @@ impl ActiveSettings {
         )
         .option(
             ScrollWrapperOption::builder()
-                .text("Pokaż ekwipunek")
+                .text(tr!(OnlinePeersShowEquipment))
                 .on_click(move |_| {
                     let Some(player_data) = 
                         Peers::get()
@@ -367,7 +315,7 @@ impl ActiveSettings {
                             .and_then(|peer| ShowEqPlayerData::new_with_peer(peer))
                             .or_else(|| Others::get().lock_ref().get(&peer_id).and_then(|other| ShowEqPlayerData::new_with_other(other)))
                      else {
-                        let _ = message("[MDMA::RS] Nie udało się wczytać ekwipunku gracza.");
+                        let _ = message(tr!(OnlinePeersEquipmentError));
                         return;
                     };
                     get_engine()
@@ -380,7 +328,7 @@ impl ActiveSettings {
         )
         .option(
             ScrollWrapperOption::builder()
-                .text("Zaproś do przyjaciół")
+                .text(tr!(OnlinePeersInviteFriend))
                 .on_click(move |_| {
                     send_task(&format!(
                         "friends&a=finvite&nick={}",
@@ -401,7 +349,7 @@ impl ActiveSettings {
         )
         .option(
             ScrollWrapperOption::builder()
-                .text("Dodaj do wrogów")
+                .text(tr!(OnlinePeersAddEnemy))
                 .on_click(move |_| {
                     send_task(&format!(
                         "friends&a=eadd&nick={}",
@@ -422,7 +370,7 @@ impl ActiveSettings {
         )
         .option(
             ScrollWrapperOption::builder()
-                .text("Zaproś do drużyny")
+                .text(tr!(OnlinePeersInviteParty))
                 .on_click(move |_| {
                     send_task(&format!("party&a=inv&id={peer_id}")).unwrap_js();
                 })
@@ -430,7 +378,13 @@ impl ActiveSettings {
         )
         .option(
             ScrollWrapperOption::builder()
-                .text("Pokaż profil")
+                .text_signal(watch_text_signal)
+                .on_click(move |_| settings.notifications.toggle_watch(peer_id))
+                .build(),
+        )
+        .option(
+            ScrollWrapperOption::builder()
+                .text(tr!(OnlinePeersShowProfile))
                 .on_click(move |_| {
                     let Some((account_id, character_id)) = Peers::get()
                         .lock_ref()
@@ -451,7 +405,7 @@ impl ActiveSettings {
                                 })
                         })
                     else {
-                        let _ = message("[MDMA::RS] Nie udało się wczytać profilu gracza.");
+                        let _ = message(tr!(OnlinePeersProfileError));
                         return;
                     };
                     let options = PlayerProfileOptions::new(account_id, character_id);
@@ -597,7 +551,7 @@ impl ActiveSettings {
         //    }
         //};
         let window_size = Addons::get_window_size(ADDON_NAME, Self::WINDOW_TYPE)
-            .ok_or_else(|| err_code!())?;
+            .ok_or_else(|| err!(Get, "window size"))?;
 
         Ok(ContentSection::new()
             .class_list("scroll-y min-h[50] fade-top-bottom d[flex] f-d[column] pos[relative]")
@@ -794,6 +748,7 @@ impl ActiveSettings {
             )
             .section_signal_vec(
                 settings
+                    .player_list
                     .sort_signal()
                     .switch(|_| Others::get().len())
                     .switch_signal_vec(move |_| {
@@ -801,11 +756,14 @@ impl ActiveSettings {
                             .online_from_keys_signal()
                             .map(|mut entries| {
                                 entries.sort_by(|(_, a), (_, b)| {
-                                    settings.compare(a, b)
+                                    settings.player_list.compare(a, b)
                                 });
                                 entries
                             })
                             .to_signal_vec()
+                            .filter_signal_cloned(move |(_, peer_data)| {
+                                settings.player_list.filter_signal(peer_data)
+                            })
                             .filter_map(move |(peer_id, peer_data)| {
                                 self.render_one_peer(peer_id, peer_data, settings)
                             })
@@ -839,6 +797,14 @@ impl ActiveSettings {
         //}))
     }
 
+    fn render_tab_header(&'static self, tab: DisplayTab, text: &str) -> ContentSection {
+        ContentSection::new()
+            .class_list("card-header")
+            .text(text)
+            .class_signal("active", self.current_tab.signal_ref(move |current| *current == tab))
+            .event(move |_: Click| self.current_tab.set_neq(tab))
+    }
+
     fn calculate_responsiveness(current_overscroll: f64) -> f64 {
         let progress = current_overscroll / MAX_OVERSCROLL;
         let factor = 1.0 - progress;
@@ -852,13 +818,14 @@ impl ActiveSettings {
             return;
         }
 
+        // The enemy list comes along with the friend list.
         let emitter_event = match current_tab {
             DisplayTab::ClanMembers => EmitterEvent::Members,
-            DisplayTab::Friends => EmitterEvent::Friends,
+            DisplayTab::Friends | DisplayTab::Enemies => EmitterEvent::Friends,
         };
         let task = match current_tab {
             DisplayTab::ClanMembers => clan::get_members,
-            DisplayTab::Friends => friends::get_friends,
+            DisplayTab::Friends | DisplayTab::Enemies => friends::get_friends,
         };
 
         fn callback<'a>(
@@ -915,13 +882,10 @@ impl ActiveSettings {
         settings: &'static Settings,
     ) -> Option<ContentSection> {
         let relation = peer_data.relation.get();
-        let nick = peer_data.nick;
-        let lvl = peer_data.lvl;
-        let oplvl = peer_data.operational_lvl;
-        let prof = peer_data.prof;
-        let map_name = peer_data.map_name;
-        let x_mutable = peer_data.x;
-        let y_mutable = peer_data.y;
+        let nick = peer_data.nick.clone();
+        let map_name = peer_data.map_name.clone();
+        let x_mutable = peer_data.x.clone();
+        let y_mutable = peer_data.y.clone();
 
         #[cfg(debug_assertions)]
         let is_scroll_target_signal =
@@ -938,22 +902,18 @@ impl ActiveSettings {
             )
             .section(
                 ContentSection::new()
-                    .class_list("other-lvl m-right[auto]")
-                    .text_signal(map_ref! {
-                        let level_display = settings.level_display.signal(),
-                        let lvl = lvl.signal(),
-                        let oplvl = oplvl.signal(),
-                        let prof = prof.signal() => {
-                            match level_display {
-                                _ if *lvl <= 300 => format!("({}{})", *lvl, *prof),
-                                LevelDisplay::First => format!("({}{}|{}{})", *lvl, *prof, *oplvl, *prof),
-                                LevelDisplay::Last => format!("({}{}|{}{})", *oplvl, *prof, *lvl, *prof),
-                                LevelDisplay::None => format!("({}{})", *oplvl, *prof),
-                                LevelDisplay::Only => format!("({}{})",*lvl, *prof),
-                            }
-                        }
-                    }),
+                    .class_list("other-lvl")
+                    .visible_signal(settings.player_list.level_column.signal())
+                    .text_signal(settings.player_list.level_text_signal(&peer_data)),
             )
+            .section_signal(settings.player_list.clan_column_signal(&peer_data).map(|clan_opt| {
+                clan_opt.map(|clan| {
+                    ContentSection::new()
+                        .class_list("other-clan f-s[11] overflow[hidden] t-o[ellipsis]")
+                        .text(&clan)
+                })
+            }))
+            .section(ContentSection::new().class_list("m-right[auto]"))
             .section_signal(settings.show_alias
                 .signal()
                 .switch(clone!(map_name => move |show_alias| {
@@ -1037,7 +997,7 @@ impl ActiveSettings {
                 if !Others::get().lock_ref().contains_key(&peer_id) {
                     return;
                 }
-                if ColorMark::has_mark(HIGHLIGHT_COLOR, ADDON_NAME, &peer_id)
+                if ColorMark::has_mark(&HIGHLIGHT_COLOR, ADDON_NAME, &peer_id)
                     && !self.is_scroll_target(peer_id)
                 {
                     return;
@@ -1088,7 +1048,7 @@ impl ActiveSettings {
                 if self.is_scroll_target(peer_id) {
                     return;
                 }
-                if !ColorMark::has_mark(HIGHLIGHT_COLOR, ADDON_NAME, &peer_id) {
+                if !ColorMark::has_mark(&HIGHLIGHT_COLOR, ADDON_NAME, &peer_id) {
                     return;
                 }
 
@@ -1097,7 +1057,7 @@ impl ActiveSettings {
                     .unwrap_js()
                     .delete_arrow(&format!("Other-{peer_id}"))
                     .unwrap_js();
-                ColorMark::remove(HIGHLIGHT_COLOR, ADDON_NAME, peer_id);
+                ColorMark::remove(&HIGHLIGHT_COLOR, ADDON_NAME, peer_id);
             })
             .after_inserted(move |_| {
                 if !self.is_scroll_target(peer_id) {
@@ -1122,7 +1082,7 @@ impl ActiveSettings {
                 if !Others::get().lock_ref().contains_key(&peer_id) {
                     return;
                 }
-                if !ColorMark::has_mark(HIGHLIGHT_COLOR, ADDON_NAME, &peer_id) {
+                if !ColorMark::has_mark(&HIGHLIGHT_COLOR, ADDON_NAME, &peer_id) {
                     return;
                 }
 
@@ -1131,7 +1091,7 @@ impl ActiveSettings {
                     .unwrap_js()
                     .delete_arrow(&format!("Other-{peer_id}"))
                     .unwrap_js();
-                ColorMark::remove(HIGHLIGHT_COLOR, ADDON_NAME, peer_id);
+                ColorMark::remove(&HIGHLIGHT_COLOR, ADDON_NAME, peer_id);
             })
             .mixin(move |b| {
                 let overflow_signal_options = dominator_helpers::OverflowSignalOptions::builder()
@@ -1157,18 +1117,9 @@ impl ActiveSettings {
                         .child(html!("div", {
                             .class("nick")
                             .text_signal(map_ref!{
-                                let level_display = settings.level_display.signal(),
                                 let nick = nick.signal_cloned(),
-                                let lvl = lvl.signal(),
-                                let oplvl = oplvl.signal(),
-                                let prof = prof.signal() => {
-                                    match level_display {
-                                        _ if *lvl <= 300 => format!("{nick} ({}{})", *lvl, *prof),
-                                        LevelDisplay::First => format!("{nick} ({}{}|{}{})", *lvl, *prof, *oplvl, *prof),
-                                        LevelDisplay::Last => format!("{nick} ({}{}|{}{})", *oplvl, *prof, *lvl, *prof),
-                                        LevelDisplay::None => format!("{nick} ({}{})", *oplvl, *prof),
-                                        LevelDisplay::Only => format!("{nick} ({}{})",*lvl, *prof),
-                                    }
+                                let level_text = settings.player_list.level_text_signal(&peer_data) => {
+                                    format!("{nick} {level_text}")
                                 }
                             })
                         }))
@@ -1199,7 +1150,7 @@ impl ActiveSettings {
             .mixin(|b| {
                 apply_methods!(b, {
                     .tip!({
-                        .text("Zaproś do grupy")
+                        .text(tr!(OnlinePeersInviteGroup))
                     })
                 })
             })
@@ -1215,10 +1166,7 @@ impl ActiveSettings {
                         return true;
                     }
 
-                    match current_tab {
-                        DisplayTab::ClanMembers => Relation::Clan == relation,
-                        DisplayTab::Friends => Relation::Friend == relation,
-                    }
+                    current_tab.relation() == relation
                 }))
                 .section(peer)
                 .section(inv_button),
@@ -1244,12 +1192,8 @@ pub(super) fn init(
     active_settings: &'static ActiveSettings,
     settings: &'static Settings,
 ) -> JsResult<()> {
-    let _settings_window_handle = WINDOWS_ROOT
-        .try_append_dom(settings.render()?)
-        .ok_or_else(|| err_code!())?;
-    let _addon_window_handle = WINDOWS_ROOT
-        .try_append_dom(active_settings.render(settings)?)
-        .ok_or_else(|| err_code!())?;
+    let _settings_window_handle = WINDOWS_ROOT.append_window(settings.render()?)?;
+    let _addon_window_handle = WINDOWS_ROOT.append_window(active_settings.render(settings)?)?;
 
     Ok(())
 }
@@ -0,0 +1,321 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use common::messaging::prelude::LogLevel;
+use futures_signals::map_ref;
+use futures_signals::signal::{Mutable, Signal, SignalExt};
+use futures_signals::signal_map::{Entry, MutableBTreeMap, SignalMapExt};
+use futures_signals::signal_vec::SignalVecExt;
+use proc_macros::Setting;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+use web_sys::{AudioContext, OscillatorType};
+
+use crate::prelude::*;
+use crate::interface::console::LogSource;
+use crate::utils::logging::console_log_with;
+
+use super::{ADDON_NAME, ActiveSettings};
+
+/// Length of the notification sound in seconds.
+const SOUND_DURATION: f64 = 0.35;
+const SOUND_FREQUENCY: f32 = 880.0;
+
+thread_local! {
+    static AUDIO_CONTEXT: RefCell<Option<AudioContext>> = const { RefCell::new(None) };
+}
+
+/// Presence events the peer is being watched for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct WatchedPeer {
+    /// Nick of the peer at the time it started being watched,
+    /// used to display the peer after it disappears from the peers list.
+    pub(super) nick: Mutable<String>,
+    pub(super) login: Mutable<bool>,
+    pub(super) logout: Mutable<bool>,
+    pub(super) enter_map: Mutable<bool>,
+    pub(super) alias: Mutable<bool>,
+}
+
+impl WatchedPeer {
+    fn new(nick: String) -> Self {
+        Self {
+            nick: Mutable::new(nick),
+            login: Mutable::new(true),
+            logout: Mutable::new(true),
+            enter_map: Mutable::new(true),
+            alias: Mutable::new(true),
+        }
+    }
+
+    fn is_watching(&self, event: &PresenceEvent) -> bool {
+        match event {
+            PresenceEvent::Login => self.login.get(),
+            PresenceEvent::Logout => self.logout.get(),
+            PresenceEvent::EnterMap => self.enter_map.get(),
+            PresenceEvent::Alias { .. } => self.alias.get(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PresenceSnapshot {
+    online: bool,
+    map_name: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum PresenceEvent {
+    Login,
+    Logout,
+    EnterMap,
+    Alias {
+        alias: &'static str,
+        tip: &'static str,
+    },
+}
+
+impl PresenceEvent {
+    fn to_message(&self, nick: &str) -> String {
+        match self {
+            Self::Login => tr!(OnlinePeersNotifyLogin, nick = nick),
+            Self::Logout => tr!(OnlinePeersNotifyLogout, nick = nick),
+            Self::EnterMap => tr!(OnlinePeersNotifyEnterMap, nick = nick),
+            Self::Alias { alias, tip } if tip.is_empty() => {
+                tr!(OnlinePeersNotifyAlias, nick = nick, alias = alias)
+            }
+            Self::Alias { alias, tip } => {
+                tr!(
+                    OnlinePeersNotifyAliasTip,
+                    nick = nick,
+                    alias = alias,
+                    tip = tip
+                )
+            }
+        }
+    }
+}
+
+#[derive(Setting, Clone)]
+pub(super) struct PresenceNotifications {
+    pub(super) watched_peers: MutableBTreeMap<PeerId, WatchedPeer>,
+    /// Location aliases (e.g. T-285, K-114) that trigger a notification
+    /// when a watched peer enters one of them.
+    pub(super) watched_aliases: Mutable<Vec<String>>,
+    pub(super) in_game_message: Mutable<bool>,
+    pub(super) console_entry: Mutable<bool>,
+    pub(super) sound: Mutable<bool>,
+}
+
+impl Default for PresenceNotifications {
+    fn default() -> Self {
+        Self {
+            watched_peers: MutableBTreeMap::new(),
+            watched_aliases: Mutable::default(),
+            in_game_message: Mutable::new(true),
+            console_entry: Mutable::new(true),
+            sound: Mutable::default(),
+        }
+    }
+}
+
+impl PresenceNotifications {
+    pub(super) fn init(&'static self) {
+        let previous_snapshots = RefCell::new(BTreeMap::new());
+        let future = Peers::get()
+            .entries_cloned()
+            .map_signal(|(peer_id, peer)| {
+                map_ref! {
+                    let online = peer.online.signal(),
+                    let map_name = peer.map_name.signal_cloned() => {
+                        (peer_id, PresenceSnapshot { online: *online, map_name: map_name.clone() })
+                    }
+                }
+            })
+            .to_signal_cloned()
+            .for_each(move |snapshots| {
+                let snapshots: BTreeMap<_, _> = snapshots.into_iter().collect();
+                let previous_snapshots = previous_snapshots.replace(snapshots.clone());
+
+                if Addons::is_active(ADDON_NAME) {
+                    self.notify_changes(&previous_snapshots, &snapshots);
+                }
+
+                async {}
+            });
+
+        wasm_bindgen_futures::spawn_local(future);
+    }
+
+    pub(super) fn is_watched_signal(&self, peer_id: PeerId) -> impl Signal<Item = bool> {
+        self.watched_peers
+            .signal_map_cloned()
+            .key_cloned(peer_id)
+            .map(|watched_peer| watched_peer.is_some())
+    }
+
+    pub(super) fn toggle_watch(&self, peer_id: PeerId) {
+        let mut watched_lock = self.watched_peers.lock_mut();
+
+        if watched_lock.remove(&peer_id).is_some() {
+            return;
+        }
+
+        let Some(nick) = Peers::get()
+            .lock_ref()
+            .get(&peer_id)
+            .map(|peer| peer.nick.get_cloned())
+        else {
+            return console_error!();
+        };
+
+        watched_lock.insert_cloned(peer_id, WatchedPeer::new(nick));
+    }
+
+    /// Trigger `MapDiff::Update` so the changes made to the peer's flags get saved.
+    pub(super) fn save_one(&self, peer_id: PeerId) {
+        let mut watched_lock = self.watched_peers.lock_mut();
+        let Entry::Occupied(mut entry) = watched_lock.entry(peer_id) else {
+            return console_error!();
+        };
+
+        entry.modify_cloned(|_| {});
+    }
+
+    pub(super) fn set_watched_aliases(&self, value: &str) {
+        let aliases = value
+            .split([',', ' '])
+            .map(|alias| alias.trim().to_uppercase())
+            .filter(|alias| !alias.is_empty())
+            .collect();
+
+        self.watched_aliases.set(aliases);
+    }
+
+    fn notify_changes(
+        &self,
+        previous_snapshots: &BTreeMap<PeerId, PresenceSnapshot>,
+        snapshots: &BTreeMap<PeerId, PresenceSnapshot>,
+    ) {
+        let watched_lock = self.watched_peers.lock_ref();
+
+        for (peer_id, watched_peer) in watched_lock.iter() {
+            // Peers seen for the first time (e.g. during the initial fetch) don't trigger anything.
+            let (Some(old), Some(new)) = (previous_snapshots.get(peer_id), snapshots.get(peer_id))
+            else {
+                continue;
+            };
+            if old == new {
+                continue;
+            }
+
+            let nick = Peers::get()
+                .lock_ref()
+                .get(peer_id)
+                .map(|peer| peer.nick.get_cloned())
+                .unwrap_or_else(|| watched_peer.nick.get_cloned());
+
+            self.detect_events(old, new)
+                .into_iter()
+                .filter(|event| watched_peer.is_watching(event))
+                .for_each(|event| self.notify(&event.to_message(&nick)));
+        }
+    }
+
+    fn detect_events(&self, old: &PresenceSnapshot, new: &PresenceSnapshot) -> Vec<PresenceEvent> {
+        let mut events = Vec::new();
+
+        match (old.online, new.online) {
+            (false, true) => events.push(PresenceEvent::Login),
+            (true, false) => {
+                events.push(PresenceEvent::Logout);
+                return events;
+            }
+            _ => {}
+        }
+
+        if old.map_name == new.map_name || !new.online {
+            return events;
+        }
+        let Some(map_name) = new.map_name.as_deref() else {
+            return events;
+        };
+
+        if Town::get().lock_ref().name.as_deref() == Some(map_name) {
+            events.push(PresenceEvent::EnterMap);
+        }
+
+        let alias = ActiveSettings::to_map_alias(map_name);
+        let old_alias = old
+            .map_name
+            .as_deref()
+            .map(ActiveSettings::to_map_alias)
+            .unwrap_or_default();
+        let is_watched_alias = self
+            .watched_aliases
+            .lock_ref()
+            .iter()
+            .any(|watched| watched.eq_ignore_ascii_case(alias));
+
+        if !alias.is_empty() && alias != old_alias && is_watched_alias {
+            events.push(PresenceEvent::Alias {
+                alias,
+                tip: ActiveSettings::to_map_alias_tip(map_name),
+            });
+        }
+
+        events
+    }
+
+    fn notify(&self, text: &str) {
+        if self.in_game_message.get() && message(text).is_err() {
+            console_error!()
+        }
+        if self.console_entry.get() {
+            console_log_with(
+                LogLevel::Info,
+                LogSource::Addon(ADDON_NAME),
+                JsValue::from_str(text),
+            );
+        }
+        if self.sound.get() {
+            if let Err(err_code) = play_sound() {
+                console_error!(err_code);
+            }
+        }
+    }
+}
+
+fn play_sound() -> JsResult<()> {
+    AUDIO_CONTEXT.with_borrow_mut(|audio_context| {
+        let audio_context = match audio_context {
+            Some(audio_context) => audio_context,
+            None => audio_context.insert(AudioContext::new().map_err(map_err!())?),
+        };
+        // Browsers keep the context suspended until the user interacts with the page.
+        let _ = audio_context.resume().map_err(map_err!())?;
+
+        let oscillator = audio_context.create_oscillator().map_err(map_err!())?;
+        let gain = audio_context.create_gain().map_err(map_err!())?;
+        let now = audio_context.current_time();
+
+        oscillator.set_type(OscillatorType::Sine);
+        oscillator.frequency().set_value(SOUND_FREQUENCY);
+        gain.gain()
+            .set_value_at_time(0.2, now)
+            .map_err(map_err!())?;
+        gain.gain()
+            .exponential_ramp_to_value_at_time(0.001, now + SOUND_DURATION)
+            .map_err(map_err!())?;
+
+        oscillator
+            .connect_with_audio_node(&gain)
+            .map_err(map_err!())?;
+        gain.connect_with_audio_node(&audio_context.destination())
+            .map_err(map_err!())?;
+        oscillator.start().map_err(map_err!())?;
+        oscillator
+            .stop_with_when(now + SOUND_DURATION)
+            .map_err(map_err!())
+    })
+}
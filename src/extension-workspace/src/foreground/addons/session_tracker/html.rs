@@ -0,0 +1,178 @@
+use dominator::Dom;
+use futures_signals::map_ref;
+use futures_signals::signal::{Signal, SignalExt};
+use futures_signals::signal_vec::SignalVecExt;
+
+use crate::addon_window::prelude::*;
+use crate::interface::{ThreadLocalShadowRoot, WINDOWS_ROOT};
+use crate::prelude::*;
+
+use super::{ADDON_NAME, ActiveSettings, KillEntry, RARITIES, Session};
+
+impl Session {
+    fn stat_row<A>(&'static self, label: &str, value_signal: A) -> ContentSection
+    where
+        A: Signal<Item = String> + 'static,
+    {
+        ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between] g[10]")
+            .section(ContentSection::new().text(label))
+            .section(ContentSection::new().text_signal(value_signal))
+    }
+
+    fn rate_signal<A>(&'static self, value_signal: A) -> impl Signal<Item = String>
+    where
+        A: Signal<Item = f64> + 'static,
+    {
+        map_ref! {
+            let value = value_signal,
+            let elapsed_ms = self.elapsed_ms.signal() => {
+                format!("{value:.0} ({})", Self::format_rate(*value, *elapsed_ms))
+            }
+        }
+    }
+
+    fn render_summary(&'static self) -> ContentSection {
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[3]")
+            .section(self.stat_row(
+                tr!(SessionTrackerDuration),
+                self.elapsed_ms.signal().map(Session::format_elapsed),
+            ))
+            .section(self.stat_row(
+                tr!(SessionTrackerKills),
+                self.rate_signal(
+                    self.kills
+                        .entries_cloned()
+                        .to_signal_map(|entries| {
+                            entries
+                                .iter()
+                                .map(|(_, kill_entry)| kill_entry.count as f64)
+                                .sum::<f64>()
+                        }),
+                ),
+            ))
+            .section(self.stat_row(
+                tr!(SessionTrackerExp),
+                self.rate_signal(self.exp.signal().map(|exp| exp as f64)),
+            ))
+            .section(self.stat_row(
+                tr!(SessionTrackerGold),
+                self.rate_signal(self.gold.signal().map(|gold| gold as f64)),
+            ))
+            .section(self.stat_row(
+                tr!(SessionTrackerLoot),
+                self.rate_signal(self.loot.signal_ref(|loot| loot.total() as f64)),
+            ))
+    }
+
+    fn render_loot(&'static self) -> ContentSection {
+        RARITIES.iter().fold(
+            ContentSection::new().class_list("d[flex] f-d[column] g[3] p-left[6]"),
+            |section, (rarity, label)| {
+                let rarity = *rarity;
+                section.section(self.stat_row(
+                    label.text(),
+                    self.rate_signal(self.loot.signal_ref(move |loot| loot.get(rarity) as f64)),
+                ))
+            },
+        )
+    }
+
+    fn render_kills(&'static self) -> ContentSection {
+        let kills_list = self
+            .kills
+            .entries_cloned()
+            .map(move |(_, kill_entry)| self.render_one_kill(kill_entry));
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[3] max-h[150] scroll-y b-f[glassy-blur]")
+            .section_signal_vec(kills_list)
+    }
+
+    fn render_one_kill(&'static self, kill_entry: KillEntry) -> ContentSection {
+        let label = match kill_entry.lvl {
+            Some(lvl) => format!("{} ({lvl})", kill_entry.nick),
+            None => kill_entry.nick.clone(),
+        };
+        let count = kill_entry.count;
+
+        self.stat_row(
+            &label,
+            self.elapsed_ms.signal().map(move |elapsed_ms| {
+                format!("{count} ({})", Self::format_rate(count as f64, elapsed_ms))
+            }),
+        )
+    }
+
+    fn render_controls(&'static self) -> ContentSection {
+        let reset_button = Button::builder()
+            .text(tr!(SessionTrackerReset))
+            .on_click(move |_| self.reset());
+        let csv_button = Button::builder()
+            .text(tr!(SessionTrackerExportCsv))
+            .on_click(move |_| self.export_to_clipboard(false));
+        let json_button = Button::builder()
+            .text(tr!(SessionTrackerExportJson))
+            .on_click(move |_| self.export_to_clipboard(true));
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[5] m-top[6]")
+            .button(reset_button)
+            .button_pair(ButtonPair::builder(csv_button, json_button))
+    }
+
+    fn export_to_clipboard(&'static self, json: bool) {
+        wasm_bindgen_futures::spawn_local(async move {
+            let session_export = self.to_export();
+            let text = match json {
+                true => match session_export.to_json() {
+                    Ok(text) => text,
+                    Err(err) => return console_error!(err),
+                },
+                false => session_export.to_csv(),
+            };
+            let clipboard = window().navigator().clipboard();
+            if let Err(err) = wasm_bindgen_futures::JsFuture::from(clipboard.write_text(&text))
+                .await
+                .map_err(map_err!())
+            {
+                return console_error!(err);
+            }
+            if message(tr!(SessionTrackerExported)).is_err() {
+                console_error!()
+            }
+        });
+    }
+}
+
+impl ActiveSettings {
+    fn render(&'static self) -> JsResult<Dom> {
+        let decor = HeaderDecor::builder()
+            .push_left(decors::OpacityToggle::new())
+            .push_right(decors::CloseButton::new())
+            .push_right(decors::CollapseButton::new())
+            .build();
+        let header = WindowHeader::new(decor);
+        let session = &self.session;
+        let content = WindowContent::builder()
+            .class_list("f-d[column]")
+            .section(session.render_summary())
+            .heading(Heading::builder().text(tr!(SessionTrackerLootByRarity)))
+            .section(session.render_loot())
+            .heading(Heading::builder().text(tr!(SessionTrackerKillsByMonster)))
+            .section(session.render_kills())
+            .section(session.render_controls());
+
+        AddonWindow::builder(ADDON_NAME)
+            .header(header)
+            .content(content)
+            .build()
+    }
+}
+
+pub(super) fn init(active_settings: &'static ActiveSettings) -> JsResult<()> {
+    let _active_settings_window_handle = WINDOWS_ROOT.append_window(active_settings.render()?)?;
+
+    Ok(())
+}
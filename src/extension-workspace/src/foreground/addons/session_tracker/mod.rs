@@ -0,0 +1,430 @@
+mod html;
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
+
+use common::i18n::Msg;
+use futures_signals::signal::Mutable;
+use futures_signals::signal_map::MutableBTreeMap;
+use proc_macros::ActiveSettings;
+use serde::Serialize;
+
+use crate::bindings::engine::communication::{FightData, Rarity};
+use crate::prelude::*;
+
+const ADDON_NAME: AddonName = AddonName::SessionTracker;
+const MS_PER_HOUR: f64 = 3_600_000.0;
+/// Rates aren't displayed until the session lasts at least this long,
+/// otherwise the first kill would result in absurd values.
+const MIN_RATE_ELAPSED_MS: f64 = 60_000.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+struct LootCounts {
+    common: u32,
+    unique: u32,
+    heroic: u32,
+    upgraded: u32,
+    legendary: u32,
+    artifact: u32,
+}
+
+impl LootCounts {
+    fn add(&mut self, rarity: Rarity) {
+        match rarity {
+            Rarity::Common => self.common += 1,
+            Rarity::Unique => self.unique += 1,
+            Rarity::Heroic => self.heroic += 1,
+            Rarity::Upgraded => self.upgraded += 1,
+            Rarity::Legendary => self.legendary += 1,
+            Rarity::Artifact => self.artifact += 1,
+        }
+    }
+
+    fn get(&self, rarity: Rarity) -> u32 {
+        match rarity {
+            Rarity::Common => self.common,
+            Rarity::Unique => self.unique,
+            Rarity::Heroic => self.heroic,
+            Rarity::Upgraded => self.upgraded,
+            Rarity::Legendary => self.legendary,
+            Rarity::Artifact => self.artifact,
+        }
+    }
+
+    fn total(&self) -> u32 {
+        self.common + self.unique + self.heroic + self.upgraded + self.legendary + self.artifact
+    }
+}
+
+const RARITIES: [(Rarity, Msg); 6] = [
+    (Rarity::Common, Msg::SessionTrackerRarityCommon),
+    (Rarity::Unique, Msg::SessionTrackerRarityUnique),
+    (Rarity::Heroic, Msg::SessionTrackerRarityHeroic),
+    (Rarity::Upgraded, Msg::SessionTrackerRarityUpgraded),
+    (Rarity::Legendary, Msg::SessionTrackerRarityLegendary),
+    (Rarity::Artifact, Msg::SessionTrackerRarityArtifact),
+];
+
+#[derive(Debug, Clone, Serialize)]
+struct KillEntry {
+    nick: String,
+    lvl: Option<u16>,
+    count: u32,
+}
+
+/// Npc of the opposing team in the current battle.
+struct BattleNpc {
+    template_id: Id,
+    /// Health in percent, npcs with none left when the battle ends count as
+    /// kills.
+    hpp: u8,
+}
+
+#[derive(Default)]
+struct Session {
+    /// Timestamp in ms of when the session started.
+    started_at: Mutable<f64>,
+    /// Updated every second.
+    elapsed_ms: Mutable<f64>,
+    /// Kills per npc template id.
+    kills: MutableBTreeMap<Id, KillEntry>,
+    loot: Mutable<LootCounts>,
+    exp: Mutable<u64>,
+    /// Gold can be spent during the session so it's a signed delta.
+    gold: Mutable<i64>,
+    last_exp: Cell<Option<u64>>,
+    last_gold: Cell<Option<u64>>,
+    /// Ids of items which have already been counted as loot.
+    seen_loot: RefCell<BTreeSet<ItemId>>,
+    /// Npcs fought in the current battle per warrior id.
+    battle_npcs: RefCell<BTreeMap<Id, BattleNpc>>,
+}
+
+impl Session {
+    fn reset(&self) {
+        let now = js_sys::Date::now();
+
+        self.started_at.set(now);
+        self.elapsed_ms.set(0.0);
+        self.kills.lock_mut().clear();
+        self.loot.set(LootCounts::default());
+        self.exp.set(0);
+        self.gold.set(0);
+        self.seen_loot.borrow_mut().clear();
+    }
+
+    fn per_hour(value: f64, elapsed_ms: f64) -> Option<f64> {
+        (elapsed_ms >= MIN_RATE_ELAPSED_MS).then(|| value / (elapsed_ms / MS_PER_HOUR))
+    }
+
+    fn format_rate(value: f64, elapsed_ms: f64) -> String {
+        match Self::per_hour(value, elapsed_ms) {
+            Some(rate) => format!("{rate:.1}/h"),
+            None => "-/h".to_owned(),
+        }
+    }
+
+    fn format_elapsed(elapsed_ms: f64) -> String {
+        let total_sec = (elapsed_ms / 1000.0) as u64;
+
+        format!(
+            "{:02}:{:02}:{:02}",
+            total_sec / 3600,
+            total_sec % 3600 / 60,
+            total_sec % 60
+        )
+    }
+
+    fn on_hero(&self, hero_data: &HeroData) {
+        let active = Addons::is_active(ADDON_NAME);
+
+        // Keep updating the last values even when inactive, so that turning
+        // the addon back on doesn't count everything in between.
+        if let Some(exp) = hero_data.exp
+            && let Some(last_exp) = self.last_exp.replace(Some(exp))
+            && active
+            && exp > last_exp
+        {
+            self.exp.replace_with(|total| *total + (exp - last_exp));
+        }
+        if let Some(gold) = hero_data.gold
+            && let Some(last_gold) = self.last_gold.replace(Some(gold))
+            && active
+            && gold != last_gold
+        {
+            self.gold
+                .replace_with(|total| *total + gold as i64 - last_gold as i64);
+        }
+    }
+
+    fn on_loot(&self, socket_response: &Response) -> JsResult<()> {
+        if !Addons::is_active(ADDON_NAME) {
+            return Ok(());
+        }
+
+        let loot = socket_response.loot.as_ref().ok_or_else(|| err_code!())?;
+        if loot.init.is_none() {
+            return Ok(());
+        }
+        if loot
+            .source
+            .as_deref()
+            .is_none_or(|source| source != "fight")
+        {
+            return Ok(());
+        }
+        let Some(states) = loot.states.as_ref() else {
+            return Ok(());
+        };
+
+        let items_lock = Items::get().lock_ref();
+        let mut seen_loot = self.seen_loot.borrow_mut();
+        let mut loot_lock = self.loot.lock_mut();
+
+        for item_id in states.keys() {
+            if !seen_loot.insert(*item_id) {
+                continue;
+            }
+
+            let Some(item_stats) = socket_response
+                .item
+                .as_ref()
+                .and_then(|items| items.get(item_id))
+                .or_else(|| items_lock.get(item_id))
+                .and_then(|item| item.parse_stats())
+            else {
+                debug_log!("No item data for loot", *item_id);
+                continue;
+            };
+
+            loot_lock.add(item_stats.rarity);
+        }
+
+        Ok(())
+    }
+
+    fn on_fight(&self, fight: &FightData) {
+        let mut battle_npcs = self.battle_npcs.borrow_mut();
+
+        // The team and every warrior are only sent when the battle starts,
+        // later updates contain the changed values.
+        if let Some(my_team) = fight.my_team {
+            let npcs_lock = Npcs::get().lock_ref();
+            let opposing_npcs = fight
+                .w
+                .iter()
+                .flatten()
+                .filter(|(id, warrior)| {
+                    **id < 0 && warrior.team.is_some_and(|team| team != my_team)
+                })
+                .filter_map(|(id, warrior)| {
+                    let npc = npcs_lock.get(&-id)?;
+                    let battle_npc = BattleNpc {
+                        template_id: npc.template_id,
+                        hpp: warrior.hpp.unwrap_or(100),
+                    };
+
+                    Some((*id, battle_npc))
+                });
+
+            *battle_npcs = BTreeMap::from_iter(opposing_npcs);
+        } else if let Some(warriors) = fight.w.as_ref() {
+            warriors.iter().for_each(|(id, warrior)| {
+                if let Some(battle_npc) = battle_npcs.get_mut(id)
+                    && let Some(hpp) = warrior.hpp
+                {
+                    battle_npc.hpp = hpp;
+                }
+            });
+        }
+
+        if fight.end_battle != Some(true) {
+            return;
+        }
+
+        let killed = std::mem::take(&mut *battle_npcs)
+            .into_values()
+            .filter(|battle_npc| battle_npc.hpp == 0);
+
+        if !Addons::is_active(ADDON_NAME) {
+            return;
+        }
+
+        killed.for_each(|battle_npc| self.add_kill(battle_npc.template_id));
+    }
+
+    fn add_kill(&self, template_id: Id) {
+        let (nick, lvl) = NpcTemplates::get()
+            .lock_ref()
+            .get(&template_id)
+            .map(|template| (template.nick.clone().unwrap_or_default(), template.level))
+            .unwrap_or_default();
+        let mut kills_lock = self.kills.lock_mut();
+        let kill_entry = match kills_lock.get(&template_id) {
+            Some(kill_entry) => KillEntry {
+                count: kill_entry.count + 1,
+                ..kill_entry.clone()
+            },
+            None => KillEntry {
+                nick,
+                lvl,
+                count: 1,
+            },
+        };
+
+        kills_lock.insert_cloned(template_id, kill_entry);
+    }
+
+    fn to_export(&self) -> SessionExport {
+        let elapsed_ms = self.elapsed_ms.get();
+        let exp = self.exp.get();
+        let gold = self.gold.get();
+        let loot = self.loot.get();
+        let kills = self
+            .kills
+            .lock_ref()
+            .iter()
+            .map(|(template_id, kill_entry)| KillExport {
+                template_id: *template_id,
+                nick: kill_entry.nick.clone(),
+                lvl: kill_entry.lvl,
+                count: kill_entry.count,
+                per_hour: Self::per_hour(kill_entry.count as f64, elapsed_ms),
+            })
+            .collect();
+
+        SessionExport {
+            started_at: self.started_at.get(),
+            duration_sec: (elapsed_ms / 1000.0) as u64,
+            exp,
+            exp_per_hour: Self::per_hour(exp as f64, elapsed_ms),
+            gold,
+            gold_per_hour: Self::per_hour(gold as f64, elapsed_ms),
+            loot,
+            loot_per_hour: Self::per_hour(loot.total() as f64, elapsed_ms),
+            kills,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct KillExport {
+    template_id: Id,
+    nick: String,
+    lvl: Option<u16>,
+    count: u32,
+    per_hour: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct SessionExport {
+    started_at: f64,
+    duration_sec: u64,
+    exp: u64,
+    exp_per_hour: Option<f64>,
+    gold: i64,
+    gold_per_hour: Option<f64>,
+    loot: LootCounts,
+    loot_per_hour: Option<f64>,
+    kills: Vec<KillExport>,
+}
+
+impl SessionExport {
+    fn to_json(&self) -> JsResult<String> {
+        serde_json::to_string_pretty(self).map_err(map_err!(from))
+    }
+
+    fn to_csv(&self) -> String {
+        fn escape(value: &str) -> String {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        }
+        fn rate(per_hour: Option<f64>) -> String {
+            per_hour.map(|rate| format!("{rate:.2}")).unwrap_or_default()
+        }
+
+        let mut lines = vec![
+            "category,name,count,per_hour".to_owned(),
+            format!("session,duration_sec,{},", self.duration_sec),
+            format!("exp,exp,{},{}", self.exp, rate(self.exp_per_hour)),
+            format!("gold,gold,{},{}", self.gold, rate(self.gold_per_hour)),
+            format!(
+                "loot,total,{},{}",
+                self.loot.total(),
+                rate(self.loot_per_hour)
+            ),
+        ];
+        let duration_ms = self.duration_sec as f64 * 1000.0;
+
+        RARITIES.iter().for_each(|(rarity, _)| {
+            let count = self.loot.get(*rarity);
+            lines.push(format!(
+                "loot,{},{count},{}",
+                <&'static str>::from(rarity),
+                rate(Session::per_hour(count as f64, duration_ms))
+            ));
+        });
+        self.kills.iter().for_each(|kill| {
+            let name = match kill.lvl {
+                Some(lvl) => format!("{} ({lvl})", kill.nick),
+                None => kill.nick.clone(),
+            };
+            lines.push(format!(
+                "kill,{},{},{}",
+                escape(&name),
+                kill.count,
+                rate(kill.per_hour)
+            ));
+        });
+
+        lines.join("\n")
+    }
+}
+
+#[derive(ActiveSettings, Default)]
+struct ActiveSettings {
+    #[setting(skip)]
+    session: Session,
+}
+
+impl ActiveSettings {
+    fn init(&'static self) -> JsResult<()> {
+        self.session.reset();
+
+        let session = &self.session;
+        let future = async move {
+            loop {
+                delay(1_000).await;
+                session
+                    .elapsed_ms
+                    .set(js_sys::Date::now() - session.started_at.get());
+            }
+        };
+        wasm_bindgen_futures::spawn_local(future);
+
+        Emitter::register_on(EmitterEvent::Hero, move |socket_response| {
+            if let Some(hero_data) = socket_response.h.as_ref() {
+                session.on_hero(hero_data);
+            }
+            Box::pin(async { Ok(()) })
+        })?;
+        Emitter::register_on(EmitterEvent::Fight, move |socket_response| {
+            if let Some(fight) = socket_response.f.as_ref() {
+                session.on_fight(fight);
+            }
+            Box::pin(async { Ok(()) })
+        })?;
+        Emitter::register_on(EmitterEvent::Loot, move |socket_response| {
+            let res = session.on_loot(socket_response);
+            Box::pin(async move { res })
+        })?;
+
+        Ok(())
+    }
+}
+
+pub(crate) fn init() -> JsResult<()> {
+    let active_settings = ActiveSettings::new(ADDON_NAME);
+    active_settings.init()?;
+
+    html::init(active_settings)
+}
@@ -13,7 +13,7 @@ use futures_signals::map_ref;
 use futures_signals::signal::{Mutable, Signal, SignalExt};
 use futures_signals::signal_vec::{SignalVec, SignalVecExt};
 use js_sys::Function;
-use wasm_bindgen::{intern, prelude::*};
+use wasm_bindgen::prelude::*;
 use web_sys::{Event, HtmlCanvasElement, HtmlDivElement, HtmlInputElement};
 
 use crate::addon_window::{prelude::*, ITEM_FRAME};
@@ -24,8 +24,10 @@ use crate::disable_items::{DisabledItems, DISABLED_ITEMS};
 use crate::interface::{tips_parser::tip, ThreadLocalShadowRoot, WINDOWS_ROOT};
 use crate::prelude::*;
 
+use super::planner::{slot_label, PlannedIngredient, MAX_UPGRADE_LEVEL};
+use super::salvage::Salvage;
 use super::{
-    animation_signal, ActiveSettings, ItemState, SelectingItem, Settings, SlotType, UpgradingMode, ADDON_NAME
+    animation_signal, ActiveSettings, ForgeMode, ItemState, SelectingItem, Settings, SlotType, UpgradingMode, ADDON_NAME
 };
 
 thread_local! {
@@ -64,7 +66,11 @@ impl ActiveSettings {
                         .text_signal(map_ref! {
                             let current_size = self.buffer_queue.signal_vec_cloned().len(),
                             let buffer_limit = settings.buffer_limit_signal(self) => {
-                                format!("Stan schowka: {current_size} / {buffer_limit}")
+                                tr!(
+                                    SmartForgeBufferState,
+                                    current_size = current_size,
+                                    buffer_limit = buffer_limit,
+                                )
                             }
                         })
                     })
@@ -75,9 +81,11 @@ impl ActiveSettings {
 
         let content = WindowContent::builder()
             .class_list("f-d[column]")
+            .forge_mode(settings)
             .render_mode(settings)
             .usages_preview(settings)
-            .upgrade_button(settings, self);
+            .upgrade_button(settings, self)
+            .salvage(settings);
 
         AddonWindow::builder(ADDON_NAME)
             .has_item_slots(true)
@@ -91,15 +99,15 @@ impl WindowContent {
     fn upgrade_button(self, settings: &'static Settings, active_settings: &'static ActiveSettings) -> Self {
         let on_click = move |_| {
             if !can_upgrade(settings) {
-                let _ = message("[MDMA::RS] Brak przedmiotów do ulepszania!");
+                let _ = message(tr!(SmartForgeUpgradeEmpty));
                 return;
             }
 
             let question = match (settings.upgrade_button.unique.get(), settings.upgrade_button.heroic.get()) {
                 (false, false) => None,
-                (true, false) => Some(intern("Czy na pewno chcesz użyć unikatowych przedmiotów z ekwipunku do ulepszenia?")),
-                (false, true) => Some(intern("Czy na pewno chcesz użyć heroicznych przedmiotów z ekwipunku do ulepszenia?")),
-                (true, true) => Some(intern("Czy na pewno chcesz użyć unikatowych oraz heroicznych przedmiotów z ekwipunku do ulepszenia?")),
+                (true, false) => Some(tr!(SmartForgeAskUnique)),
+                (false, true) => Some(tr!(SmartForgeAskHeroic)),
+                (true, true) => Some(tr!(SmartForgeAskUniqueHeroic)),
             };
 
             if let Some(question) = question {
@@ -148,26 +156,118 @@ impl WindowContent {
         self.section(
             ContentSection::new()
                 .class_list("d[flex] j-c[center] p-top[6]")
-                .visible_signal(settings.upgrade_button.active.signal())
+                .visible_signal(map_ref! {
+                    let active = settings.upgrade_button.active.signal(),
+                    let upgrading = settings.upgrading_signal() => {
+                        *active && *upgrading
+                    }
+                })
                 .button(
                     Button::builder()
                         .class_list("w[min-content]")
                         .disabled_signal(settings.upgrade_button.disabled_signal_factory(settings))
                         .mixin(|b| apply_methods!(b, {
                             .tip!(settings.upgrade_button.disabled_signal_factory(settings) => {
-                                .text("Brak przedmiotów do spalenia według aktualnych kryteriów.")
+                                .text(tr!(SmartForgeNoIngredients))
                             })
                         }))
-                        .text("Ulepsz")
+                        .text(tr!(SmartForgeUpgrade))
                         .on_click(on_click),
                 ),
         )
     }
 
+    fn forge_mode(self, settings: &'static Settings) -> Self {
+        self.section(
+            ContentSection::new()
+                .class_list("d[flex] f-d[row] j-c[space-around] a-i[center] p-top[6]")
+                .button(settings.forge_mode_button(ForgeMode::Upgrading))
+                .button(settings.forge_mode_button(ForgeMode::Salvaging)),
+        )
+    }
+
+    fn salvage(self, settings: &'static Settings) -> Self {
+        let salvage = &settings.salvage;
+        let on_click = move |_| {
+            // Items which land in the bag while the dialog is open aren't salvaged.
+            let items = salvage.selected_items(settings);
+            if items.is_empty() {
+                if message(tr!(SmartForgeSalvageEmpty)).is_err() {
+                    console_error!();
+                }
+                return;
+            }
+
+            let item_names = items
+                .iter()
+                .filter_map(|(_, item_data)| item_data.name.as_deref())
+                .map(html_escape)
+                .collect::<Vec<_>>()
+                .join("<br>");
+            let question = tr!(SmartForgeAskSalvage, count = items.len(), items = item_names);
+            let callback = closure!(@once move || {
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Err(err_code) = salvage.salvage(settings, items).await {
+                        console_error!(err_code);
+                    }
+                });
+            });
+            ask_alert(AskAlertData::new(&question, callback)).unwrap_js();
+        };
+        let selected_items = salvage
+            .selected_items_signal(settings)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|(_, item_data)| item_data.name.as_deref())
+                    .map(|item_name| ContentSection::new().text(item_name))
+                    .collect()
+            })
+            .to_signal_vec();
+        let log = salvage
+            .log
+            .signal_vec_cloned()
+            .map(|entry| ContentSection::new().text(&entry));
+
+        self.section(
+            ContentSection::new()
+                .class_list("d[flex] f-d[column] g[5] p-top[6] w-s[pre-line]")
+                .visible_signal(settings.forge_mode.signal_ref(|forge_mode| *forge_mode == ForgeMode::Salvaging))
+                .section(
+                    ContentSection::new()
+                        .class_list("t-a[center]")
+                        .text_signal(salvage.selected_items_signal(settings).map(|items| Salvage::outcome(&items))),
+                )
+                .section(
+                    ContentSection::new()
+                        .class_list("d[flex] f-d[column] g[3] max-h[150] scroll-y")
+                        .section_signal_vec(selected_items),
+                )
+                .section(
+                    ContentSection::new()
+                        .class_list("d[flex] j-c[center]")
+                        .button(
+                            Button::builder()
+                                .class_list("w[min-content]")
+                                .disabled_signal(salvage.in_progress.signal())
+                                .text(tr!(SmartForgeSalvage))
+                                .on_click(on_click),
+                        ),
+                )
+                .section(ContentSection::new().class_list("o[60%]").text(tr!(SmartForgeLog)))
+                .section(
+                    ContentSection::new()
+                        .class_list("d[flex] f-d[column] g[3] max-h[100] scroll-y")
+                        .section_signal_vec(log),
+                ),
+        )
+    }
+
     fn usages_preview(self, settings: &'static Settings) -> Self {
         self.section(
             ContentSection::new()
                 .class_list("t-a[center] p-top[6]")
+                .visible_signal(settings.upgrading_signal())
                 .text_signal(settings.usages.signal().map(|usages| {
                     let Some(count) = usages.count else {
                         return String::new();
@@ -176,7 +276,7 @@ impl WindowContent {
                         return String::new();
                     };
 
-                    format!("Dzienny limit: {count}/{limit}")
+                    tr!(SmartForgeDailyLimit, count = count, limit = limit)
                 })),
         )
     }
@@ -206,9 +306,7 @@ impl WindowContent {
 
         self.section(
             ContentSection::new()
-                .visible_signal(settings.mode.signal_ref(|mode| {
-                    matches!(*mode, UpgradingMode::Single | UpgradingMode::Hybrid)
-                }))
+                .visible_signal(settings.mode_visible_signal(&[UpgradingMode::Single, UpgradingMode::Hybrid]))
                 .class_list("d[flex] j-c[center] p-top[6] p-left[5] p-right[5] a-i[center]")
                 .input(settings.get_input(SlotType::Single))
                 .section(
@@ -216,7 +314,7 @@ impl WindowContent {
                         .class_list("pos[relative]")
                         .mixin(|builder| apply_methods!(builder, {
                             .tip!(settings.item_slots.single.slot.signal_ref(|slot| slot.max.is_some() && slot.current.is_some()) => {
-                                .text("Poziom ulepszenia")
+                                .text(tr!(SmartForgeUpgradeLevel))
                                 .child(html!("br"))
                                 .child(html!("b", {
                                     .text_signal(settings.item_slots.single.slot.signal_ref(|slot| {
@@ -253,9 +351,7 @@ impl WindowContent {
         )
         .section(
             ContentSection::new()
-                .visible_signal(settings.mode.signal_ref(|mode| {
-                    matches!(*mode, UpgradingMode::Group | UpgradingMode::Hybrid)
-                }))
+                .visible_signal(settings.mode_visible_signal(&[UpgradingMode::Group, UpgradingMode::Hybrid]))
                 .class_list("d[flex] j-c[center] g[20] p-top[12]")
                 .input(settings.get_input(SlotType::Armor))
                 .input(settings.get_input(SlotType::Jewelry))
@@ -455,6 +551,27 @@ impl Settings {
             .on_click(move |_| self.mode.set(mode))
     }
 
+    fn forge_mode_button(&'static self, forge_mode: ForgeMode) -> Button {
+        Button::builder()
+            .no_hover()
+            .text(forge_mode.into())
+            .selected_signal(self.forge_mode.signal_ref(move |current_mode| *current_mode == forge_mode))
+            .on_click(move |_| self.forge_mode.set(forge_mode))
+    }
+
+    fn upgrading_signal(&self) -> impl Signal<Item = bool> {
+        self.forge_mode.signal_ref(|forge_mode| *forge_mode == ForgeMode::Upgrading)
+    }
+
+    fn mode_visible_signal(&self, modes: &'static [UpgradingMode]) -> impl Signal<Item = bool> {
+        map_ref! {
+            let upgrading = self.upgrading_signal(),
+            let mode = self.mode.signal() => {
+                *upgrading && modes.contains(mode)
+            }
+        }
+    }
+
     fn receive_overlay_signal(&'static self, slot_type: SlotType) -> impl Signal<Item = Option<Dom>> {
         map_ref! {
             let slotted_id_opt = self.item_slots[slot_type].signal_ref(|upgrade_slot| upgrade_slot.item_id),
@@ -708,7 +825,7 @@ impl Settings {
                                 match *not_selecting && *not_found {
                                     true => Some(html!("div", {
                                         .style("color", "red")
-                                        .text("Nie wykryto przedmiotu w ekwipunku!")
+                                        .text(tr!(SmartForgeItemNotFound))
                                     })),
                                     false => None,
                                 }
@@ -724,8 +841,8 @@ impl Settings {
                             }
                         })
                         .text_signal(self.slot_occupied_signal(slot_type).map(|occupied| match occupied {
-                            true => "PPM aby usunąć przedmiot z listy ulepszanych",
-                            false => "LPM aby rozpocząć wybór",
+                            true => tr!(SmartForgeSlotRemoveTip),
+                            false => tr!(SmartForgeSlotSelectTip),
                         }))
                         .child_signal(self.item_slots[slot_type].signal_ref(|slot| (slot.max.is_some() && slot.current.is_some()).then(|| html!("br", {}))))
                         .child_signal(self.item_slots[slot_type].signal_ref(|slot| (slot.max.is_some() && slot.current.is_some()).then(|| html!("br", {}))))
@@ -733,7 +850,7 @@ impl Settings {
                             self.item_slots[slot_type]
                                 .signal_ref(|slot| {
                                     if slot.max.is_some() && slot.current.is_some() {
-                                        return String::from("Poziom ulepszenia")
+                                        return String::from(tr!(SmartForgeUpgradeLevel))
                                     }
                                     String::new()
                         }))
@@ -806,7 +923,7 @@ impl Settings {
             html!("br", {}),
             html!("br", {}),
             html!("span", {
-                .text("Ten przedmiot nie podlega ulepszaniu według aktualnych kryteriów.")
+                .text(tr!(SmartForgeNotUpgrading))
             })
         ]
     }
@@ -821,7 +938,7 @@ impl Settings {
             html!("br", {}),
             html!("br", {}),
             html!("div", {
-                .text("Ulepszany przez:")
+                .text(tr!(SmartForgeUpgradedBy))
             }),
             html!("br", {}),
         ];
@@ -837,7 +954,7 @@ impl Settings {
 
         let mut tip_children = vec![
             html!("div", {
-                .text("przedmiotu ulepszanego przez:")
+                .text(tr!(SmartForgeSlotUpgradedBy))
             }),
             html!("br", {}),
         ];
@@ -904,7 +1021,9 @@ impl Settings {
             .upgrade_button_settings(self)
             .item_types_setting(self)
             .buffer_setting(active_settings, self)
-            .excluded_item_names_setting(self, active_settings);
+            .excluded_item_names_setting(self, active_settings)
+            .salvage_setting(self)
+            .planner_setting(self);
 
         SettingsWindow::builder(ADDON_NAME)
             .header(header)
@@ -912,6 +1031,32 @@ impl Settings {
             .build()
     }
 
+    fn planner_slot_option(settings: &'static Settings, slot_type: SlotType) -> ScrollWrapperOption {
+        ScrollWrapperOption::builder()
+            .text(slot_label(slot_type))
+            .on_click(move |_| settings.planner.slot_type.set_neq(slot_type))
+            .build()
+    }
+
+    fn planned_ingredient(ingredient: &PlannedIngredient) -> ContentSection {
+        let state = match ingredient.consumed {
+            true => tr!(SmartForgeIngredientConsumed),
+            false => tr!(SmartForgeIngredientSkipped),
+        };
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between] g[10]")
+            .apply_if(!ingredient.consumed, |section| section.class_list("o[60%]"))
+            .section(ContentSection::new().text(&ingredient.name))
+            .section(ContentSection::new().text(&tr!(
+                SmartForgeIngredientPoints,
+                points = ingredient
+                    .points
+                    .map_or_else(|| "?".to_owned(), |points| points.to_string()),
+                state = state,
+            )))
+    }
+
     fn item_types_checkbox<'a>(&'static self, dummy: &'a Mutable<bool>, item_class: ItemClass) -> Checkbox<bool> {
         let dummy_clone = dummy.clone();
         Checkbox::builder(dummy.clone())
@@ -927,9 +1072,9 @@ impl Settings {
                     }))
                     .apply_if(ItemClass::Upgrade == item_class, |b| apply_methods!(b, {
                         .tip!({
-                            .text("Łup z potworów o randze ")
+                            .text(tr!(SmartForgeUpgradeClassTip))
                             .child(html!("i", {
-                                .text("elita III")
+                                .text(tr!(SmartForgeEliteThree))
                             }))
                         })
                     }))
@@ -951,16 +1096,16 @@ impl WindowContent {
     ) -> Self {
         let excluded_items = &settings.excluded_items;
         let exclusion_list_heading = Heading::builder()
-            .text("Ignorowane przedmioty")
+            .text(tr!(SmartForgeExcludedItems))
             .info_bubble(
                 InfoBubble::builder()
-                    .text("Wielkość liter nie ma znaczenia.\nW niedalekiej przyszłości pojawi się tutaj lista przedmiotów jak w dodatku Znacznik :)")
+                    .text(tr!(SmartForgeExcludedItemsInfo))
                     .build(),
             );
         let mut original_on_click = ItemNameInput::on_click_factory(excluded_items);
         let exclusion_list_input = Input::builder()
             .class_list("m-left[10]")
-            .placeholder("Nazwa Przedmiotu...")
+            .placeholder(tr!(SmartForgeItemNamePlaceholder))
             .maxlength("60")
             .text_align(TextAlign::Left)
             .size(InputSize::Big)
@@ -1007,14 +1152,16 @@ impl WindowContent {
 
     fn buffer_setting(self, active_settings: &'static ActiveSettings, settings: &'static Settings) -> Self {
         let buffer_heading = Heading::builder()
-            .text("Schowek na przedmioty")
+            .text(tr!(SmartForgeBuffer))
             .info_bubble(
                 InfoBubble::builder()
                 .text_signal(|| map_ref! {
                     let current_size = active_settings.buffer_queue.signal_vec_cloned().len(),
                     let buffer_limit = settings.buffer_limit_signal(active_settings) => {
-                        format!(
-                            "Łup z potworów spełniający aktualne kryteria zostaje przechowany w schowku.\nPrzedmioty z łupu zostaną wykorzystane do ulepszania po jego zapełnieniu.\n\n Stan schowka: {current_size} / {buffer_limit}"
+                        tr!(
+                            SmartForgeBufferInfo,
+                            current_size = current_size,
+                            buffer_limit = buffer_limit,
                         )
                     }
                 })
@@ -1022,11 +1169,10 @@ impl WindowContent {
             );
         let buffer_mode_checkbox = Checkbox::builder(settings.buffer_mode.clone()).class_list("w-s[pre-line] l-h[16] p-top[12]")
             .text_signal(settings.buffer_size.signal().map(|slots| {
-                format!(
-                    "Opróżnij schowek przy  ≤ {slots}\nwolny{} miejsc{} w ekwipunku",
-                    if slots == 1 { "m" } else { "ch" },
-                    if slots == 1 { "u" } else { "ach" },
-                )
+                match slots {
+                    1 => tr!(SmartForgeBufferModeOne, slots = slots),
+                    _ => tr!(SmartForgeBufferModeMany, slots = slots),
+                }
             }));
         let buffer_input = Input::builder()
             .class_list("w[200]")
@@ -1043,7 +1189,7 @@ impl WindowContent {
                             })
                     )
                     .tip!({
-                        .text_signal(settings.buffer_size.signal().map(|size| format!("Rozmiar schowka: {size}")))
+                        .text_signal(settings.buffer_size.signal().map(|size| tr!(SmartForgeBufferSize, size = size)))
                     })
                 })
             })
@@ -1054,7 +1200,7 @@ impl WindowContent {
             });
         let buffer_slider_section = ContentSection::new()
             .class_list("d[flex] f-d[row] a-i[center] j-c[space-between]")
-            .section(ContentSection::new().text("Rozmiar"))
+            .section(ContentSection::new().text(tr!(SmartForgeSize)))
             .input(buffer_input);
 
         self.heading(buffer_heading)
@@ -1063,27 +1209,27 @@ impl WindowContent {
                     .class_list("d[flex] f-d[column]")
                     .section(buffer_slider_section)
                     .checkbox(buffer_mode_checkbox)
-                    .checkbox(Checkbox::builder(settings.common.clone()).text("Pakuj przedmioty pospolite"))
-                    .checkbox(Checkbox::builder(settings.unique.clone()).text("Pakuj przedmioty unikatowe"))
+                    .checkbox(Checkbox::builder(settings.common.clone()).text(tr!(SmartForgeBufferCommon)))
+                    .checkbox(Checkbox::builder(settings.unique.clone()).text(tr!(SmartForgeBufferUnique)))
             )
     }
 
     fn upgrade_button_settings(self, settings: &'static Settings) -> Self {
         let heading = Heading::builder()
-            .text("Przycisk Ulepsz")
+            .text(tr!(SmartForgeUpgradeButton))
             .info_bubble(InfoBubble::builder()
                 .mixin(|builder| {
                     apply_methods!(builder, {
                         .tip!({
-                            .text("Przycisk ")
+                            .text(tr!(SmartForgeUpgradeButtonInfoButton))
                             .child(html!("b", {
-                                .text("Ulepsz")
+                                .text(tr!(SmartForgeUpgrade))
                             }))
-                            .text(", umożliwia ulepszanie przedmiotami o rzadkości wyższej niż pospolita.")
+                            .text(tr!(SmartForgeUpgradeButtonInfoRarity))
                             .child(html!("br", {}))
-                            .text("Podczas ulepszania uwzględniane są tylko typy przedmiotów wymienione na liście z zakładki ")
+                            .text(tr!(SmartForgeUpgradeButtonInfoTypes))
                             .child(html!("b", {
-                                .text("Typy przedmiotów")
+                                .text(tr!(SmartForgeItemTypes))
                             }))
                             .text(".")
                         })
@@ -1093,17 +1239,17 @@ impl WindowContent {
             );
         let active_checkbox = Checkbox::builder(settings.upgrade_button.active.clone())
             .label_mixin(|builder| {
-                builder.text("Wyświetlaj przycisk ")
+                builder.text(tr!(SmartForgeUpgradeButtonActive))
                     .child(html!("b", {
-                        .text("Ulepsz")
+                        .text(tr!(SmartForgeUpgrade))
                     }))
             });
         let unique_checkbox = Checkbox::builder(settings.upgrade_button.unique.clone())
-            .text("Ulepszaj przedmiotami unikatowymi");
+            .text(tr!(SmartForgeUpgradeUnique));
         let heroic_checkbox = Checkbox::builder(settings.upgrade_button.heroic.clone())
-            .text("Ulepszaj przedmiotami heroicznymi");
+            .text(tr!(SmartForgeUpgradeHeroic));
         let from_event_checkbox = Checkbox::builder(settings.upgrade_button.from_event.clone())
-            .text("Ulepszaj przedmiotami z eventów");
+            .text(tr!(SmartForgeUpgradeFromEvent));
 
         self.heading(heading)
             .section(
@@ -1137,10 +1283,10 @@ impl WindowContent {
 
         self.heading(
                 Heading::builder()
-                .text("Typy przedmiotów")
+                .text(tr!(SmartForgeItemTypes))
                 .info_bubble(
                     InfoBubble::builder()
-                    .text("Rodzaje przedmiotów spalanych podczas ulepszania.")
+                    .text(tr!(SmartForgeItemTypesInfo))
                     .build(),
                 ),
             )
@@ -1148,7 +1294,7 @@ impl WindowContent {
     }
 
     fn mode_setting(self, settings: &'static Settings) -> Self {
-        self.heading(Heading::builder().text("Tryb ulepszania").class_list("m-top[0]"))
+        self.heading(Heading::builder().text(tr!(SmartForgeUpgradingMode)).class_list("m-top[0]"))
             .section(
                 ContentSection::new()
                     .class_list("d[flex] f-d[row] j-c[space-around] a-i[center]")
@@ -1157,6 +1303,136 @@ impl WindowContent {
                     .button(settings.mode_button(UpgradingMode::Hybrid)),
             )
     }
+
+    fn salvage_setting(self, settings: &'static Settings) -> Self {
+        let salvage = &settings.salvage;
+        let heading = Heading::builder()
+            .text(tr!(SmartForgeSalvaging))
+            .info_bubble(
+                InfoBubble::builder()
+                    .text(tr!(SmartForgeSalvagingInfo))
+                    .build(),
+            );
+        let max_level_input = Input::builder()
+            .value(salvage.max_level.get().to_string())
+            .input_type(InputType::number(0.0, 500.0))
+            .maxlength("3")
+            .on_input(move |event, elem| {
+                event.prevent_default();
+                event.stop_immediate_propagation();
+
+                salvage.max_level.set_neq(elem.value_as_number() as u16);
+            });
+        let max_level_section = ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between] a-i[center]")
+            .section(ContentSection::new().text(tr!(SmartForgeSalvageMaxLevel)))
+            .input(max_level_input);
+
+        self.heading(heading)
+            .section(
+                ContentSection::new()
+                    .class_list("d[flex] f-d[column]")
+                    .section(max_level_section)
+                    .checkbox(Checkbox::builder(salvage.unique.clone()).text(tr!(SmartForgeSalvageUnique)))
+                    .checkbox(Checkbox::builder(salvage.heroic.clone()).text(tr!(SmartForgeSalvageHeroic)))
+                    .checkbox(Checkbox::builder(salvage.from_event.clone()).text(tr!(SmartForgeSalvageFromEvent))),
+            )
+    }
+
+    fn planner_setting(self, settings: &'static Settings) -> Self {
+        let planner = &settings.planner;
+        let heading = Heading::builder()
+            .text(tr!(SmartForgePlanner))
+            .info_bubble(
+                InfoBubble::builder()
+                    .text(tr!(SmartForgePlannerInfo))
+                    .build(),
+            );
+
+        // TODO: Figure out a better solution.
+        let wrapper_state: &'static _ = Box::leak(Box::new(Mutable::default()));
+        let scroll_wrapper = [SlotType::Single, SlotType::Armor, SlotType::Jewelry, SlotType::Weapons]
+            .into_iter()
+            .fold(
+                ScrollWrapper::builder(|| {
+                    || {
+                        wrapper_state.set_neq(false);
+                    }
+                })
+                .class_list("w[150] l[1]")
+                .visible_signal(wrapper_state.signal()),
+                |scroll_wrapper, slot_type| {
+                    scroll_wrapper.option(Settings::planner_slot_option(settings, slot_type))
+                },
+            );
+        let slot_section = ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between]")
+            .section(ContentSection::new().class_list("a-c[center]").text(tr!(SmartForgeSlot)))
+            .button(
+                Button::builder()
+                    .class_list("w[158] t-a[left]")
+                    .no_hover()
+                    .text_signal(planner.slot_type.signal().map(slot_label))
+                    .on_click(|_| wrapper_state.set_neq(true))
+                    .on_mousedown(|event| event.stop_propagation())
+                    .mixin(|builder| {
+                        builder.child(html!("div", {
+                            .class!(pos[absolute] r[8] align-center menu-arrow)
+                        }))
+                    })
+                    .scroll_wrapper(scroll_wrapper.build()),
+            );
+        let target_input = Input::builder()
+            .value(planner.target_level.get().to_string())
+            .input_type(InputType::number(1.0, MAX_UPGRADE_LEVEL as f64))
+            .maxlength("1")
+            .on_input(move |event, elem| {
+                event.prevent_default();
+                event.stop_immediate_propagation();
+
+                let value = (elem.value_as_number() as u8).clamp(1, MAX_UPGRADE_LEVEL);
+                planner.target_level.set_neq(value);
+            });
+        let target_section = ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between] a-i[center]")
+            .section(ContentSection::new().text(tr!(SmartForgeTargetLevel)))
+            .input(target_input);
+        let compute_button = Button::builder()
+            .text(tr!(SmartForgeCompute))
+            .on_click(move |_| planner.refresh(settings));
+
+        let summary = ContentSection::new()
+            .class_list("w-s[pre-line]")
+            .text_signal(planner.plan.signal_ref(|plan| {
+                plan.as_ref().map(|plan| plan.summary()).unwrap_or_default()
+            }));
+        let ingredients = planner
+            .plan
+            .signal_ref(|plan| {
+                plan.as_ref()
+                    .map(|plan| plan.ingredients.iter().map(Settings::planned_ingredient).collect())
+                    .unwrap_or_default()
+            })
+            .to_signal_vec();
+        let simulate_checkbox = Checkbox::builder(settings.simulate.clone())
+            .text(tr!(SmartForgeSimulate));
+
+        self.heading(heading)
+            .section(
+                ContentSection::new()
+                    .class_list("d[flex] f-d[column] g[5]")
+                    .section(slot_section)
+                    .section(target_section)
+                    .section(ContentSection::new().class_list("m-top[6]").button(compute_button))
+                    .section(summary)
+                    .section(
+                        ContentSection::new()
+                            .class_list("d[flex] f-d[column] g[3] max-h[200] scroll-y")
+                            .section_signal_vec(ingredients),
+                    )
+                    .checkbox(simulate_checkbox),
+            )
+    }
 }
 
 
@@ -1164,12 +1440,9 @@ pub(super) fn init(
     settings: &'static Settings,
     active_settings: &'static ActiveSettings,
 ) -> JsResult<()> {
-    let _active_settings_window_handle = WINDOWS_ROOT
-        .try_append_dom(active_settings.render(settings)?)
-        .ok_or_else(|| err_code!())?;
-    let _settings_window_handle = WINDOWS_ROOT
-        .try_append_dom(settings.render(active_settings)?)
-        .ok_or_else(|| err_code!())?;
+    let _active_settings_window_handle =
+        WINDOWS_ROOT.append_window(active_settings.render(settings)?)?;
+    let _settings_window_handle = WINDOWS_ROOT.append_window(settings.render(active_settings)?)?;
     Ok(())
 }
 
@@ -10,6 +10,8 @@
 //2.b for salvaging => salvager&action=salvage&selectedItems=1073050400
 // TODO: Remove item_tpl and item templates from ws message on progress and upgrade.
 mod html;
+mod planner;
+mod salvage;
 
 use std::cell::{Cell, RefCell};
 use std::collections::BTreeMap;
@@ -41,6 +43,9 @@ use crate::prelude::*;
 
 use super::znacznik::ItemContainer;
 
+use planner::Planner;
+use salvage::Salvage;
+
 const ADDON_NAME: AddonName = AddonName::SmartForge;
 #[derive(Debug, Eq, PartialEq, Default, Clone, Copy)]
 enum ItemState {
@@ -155,12 +160,46 @@ enum UpgradingMode {
     Hybrid,
 }
 
+/// Whether the addon window is used for upgrading or salvaging items.
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[repr(u8)]
+enum ForgeMode {
+    #[default]
+    Upgrading,
+    Salvaging,
+}
+
+impl From<ForgeMode> for &'static str {
+    fn from(value: ForgeMode) -> Self {
+        match value {
+            ForgeMode::Upgrading => tr!(SmartForgeUpgrading),
+            ForgeMode::Salvaging => tr!(SmartForgeSalvaging),
+        }
+    }
+}
+
+impl UpgradingMode {
+    /// Slots emptying the buffer in this mode, in the order they are processed.
+    fn slot_types(self) -> &'static [SlotType] {
+        match self {
+            Self::Single => &[SlotType::Single],
+            Self::Group => &[SlotType::Armor, SlotType::Jewelry, SlotType::Weapons],
+            Self::Hybrid => &[
+                SlotType::Armor,
+                SlotType::Jewelry,
+                SlotType::Weapons,
+                SlotType::Single,
+            ],
+        }
+    }
+}
+
 impl From<UpgradingMode> for &'static str {
     fn from(value: UpgradingMode) -> Self {
         match value {
-            UpgradingMode::Hybrid => "Hybrydowe",
-            UpgradingMode::Group => "Po typie",
-            UpgradingMode::Single => "Proste",
+            UpgradingMode::Hybrid => tr!(SmartForgeModeHybrid),
+            UpgradingMode::Group => tr!(SmartForgeModeGroup),
+            UpgradingMode::Single => tr!(SmartForgeModeSingle),
         }
     }
 }
@@ -168,9 +207,9 @@ impl From<UpgradingMode> for &'static str {
 impl<'a> From<&'a UpgradingMode> for &'static str {
     fn from(value: &'a UpgradingMode) -> Self {
         match value {
-            UpgradingMode::Hybrid => "Hybrydowe",
-            UpgradingMode::Group => "Po typie",
-            UpgradingMode::Single => "Proste",
+            UpgradingMode::Hybrid => tr!(SmartForgeModeHybrid),
+            UpgradingMode::Group => tr!(SmartForgeModeGroup),
+            UpgradingMode::Single => tr!(SmartForgeModeSingle),
         }
     }
 }
@@ -214,7 +253,11 @@ struct ItemSlots {
 }
 
 impl ItemSlots {
-    async fn init_slot_progress(&'static self, slot_type: SlotType) -> JsResult<()> {
+    async fn init_slot_progress(
+        &'static self,
+        slot_type: SlotType,
+        planner: &'static Planner,
+    ) -> JsResult<()> {
         let Some(item_id) = self[slot_type].lock_ref().item_id else {
             return Ok(());
         };
@@ -252,9 +295,7 @@ impl ItemSlots {
                 drop(slot_lock);
                 self.clear_slot(slot_type);
 
-                let _ = message(&format!(
-                    r#"[MDMA::RS] Zwalniam slot ulepszania ze względu na poziom ulepszenia przedmiotu "{item_name}"..."#
-                ));
+                let _ = message(&tr!(SmartForgeSlotReleased, item_name = item_name));
                 return Ok(());
             };
             // TODO: Test removing items with loc = "u" thoroughly.
@@ -269,6 +310,9 @@ impl ItemSlots {
             let current = enhance_progress.current.ok_or_else(|| err_code!())?;
             let max = enhance_progress.max.ok_or_else(|| err_code!())?;
 
+            if let Some(level) = enhance_progress.upgrade_level {
+                planner.record_level(level, max);
+            }
             //debug_log!(@f "{current}/{max}");
             slot_lock.current = Some(current);
             slot_lock.max = Some(max);
@@ -674,6 +718,7 @@ impl Not for BufferMode {
 
 #[derive(Settings)]
 struct Settings {
+    forge_mode: Mutable<ForgeMode>,
     /// Whether to upgrade single item or by group or both.
     mode: Mutable<UpgradingMode>,
     item_slots: ItemSlots,
@@ -685,15 +730,20 @@ struct Settings {
     buffer_mode: Mutable<BufferMode>,
     common: Mutable<bool>,
     unique: Mutable<bool>,
+    salvage: Salvage,
+    /// Run the buffer logic without sending any tasks to the game.
+    simulate: Mutable<bool>,
     #[setting(skip)]
     hit_usages_limit: Cell<bool>,
     #[setting(skip)]
     usages: Mutable<UsagesPreview>,
+    planner: Planner,
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
+            forge_mode: Mutable::default(),
             mode: Mutable::default(),
             item_slots: ItemSlots::default(),
             item_types: MutableBTreeMap::from(BTreeMap::from([
@@ -720,8 +770,11 @@ impl Default for Settings {
             buffer_mode: Mutable::new(BufferMode::Fixed),
             common: Mutable::new(true),
             unique: Mutable::default(),
+            salvage: Salvage::default(),
+            simulate: Mutable::new(false),
             hit_usages_limit: Cell::default(),
             usages: Mutable::default(),
+            planner: Planner::default(),
         }
     }
 }
@@ -753,6 +806,33 @@ impl Settings {
     }
 
     fn filter_buffer_item<C>(&self, item_data: &Item, stat_validator: C) -> bool
+    where
+        C: FnOnce(&ItemStats) -> bool,
+    {
+        let Some(item_class) = self.filter_forge_item(item_data, stat_validator) else {
+            return false;
+        };
+
+        let current_mode = self.mode.get();
+        //Anything matching allowed item_types can go into SlotType::Single
+        if current_mode == UpgradingMode::Single {
+            return self.item_slots.occupied(SlotType::Single);
+        }
+        if current_mode == UpgradingMode::Hybrid && self.item_slots.occupied(SlotType::Single) {
+            return true;
+        }
+
+        //Check whether item matches any occupied slot group.
+        [SlotType::Armor, SlotType::Jewelry, SlotType::Weapons]
+            .into_iter()
+            .any(|slot_type| {
+                self.item_slots.occupied(slot_type)
+                    && item_class.is_in_group(slot_type.try_into().unwrap_js())
+            })
+    }
+
+    /// Filters shared by upgrading and salvaging, returns the class of an accepted item.
+    fn filter_forge_item<C>(&self, item_data: &Item, stat_validator: C) -> Option<ItemClass>
     where
         C: FnOnce(&ItemStats) -> bool,
     {
@@ -761,38 +841,34 @@ impl Settings {
             .as_ref()
             .is_none_or(|loc| loc.as_str() != intern(s!("g")))
         {
-            return false;
+            return None;
         }
-        let Some(item_name) = item_data.name.as_ref() else {
-            return false;
-        };
+        let item_name = item_data.name.as_ref()?;
         if self
             .excluded_items
             .lock_ref()
             .contains(&item_name.to_lowercase())
         {
-            return false;
+            return None;
         }
 
-        let Some(stats) = item_data.parse_stats() else {
-            return false;
-        };
+        let stats = item_data.parse_stats()?;
 
         if stats.artisan_worthless || stats.bonus_reselect || stats.personal {
-            return false;
+            return None;
         }
         // TODO: Check for each slotted item rarity when calculating ingredients?
         if stats.target_rarity.is_some() {
-            return false;
+            return None;
         }
 
         if !stat_validator(&stats) {
-            return false;
+            return None;
         }
 
         let Some(item_class) = item_data.cl else {
             //debug_log!("No item_class");
-            return false;
+            return None;
         };
 
         if stats
@@ -800,7 +876,7 @@ impl Settings {
             .is_none_or(|bind_type| bind_type != BindType::Binds)
             && item_class != ItemClass::Upgrade
         {
-            return false;
+            return None;
         }
         //Check whether user wants to destroy items from this class.
         if self
@@ -809,25 +885,10 @@ impl Settings {
             .get(&item_class)
             .is_none_or(|burn_from_class| !burn_from_class)
         {
-            return false;
-        }
-
-        let current_mode = self.mode.get();
-        //Anything matching allowed item_types can go into SlotType::Single
-        if current_mode == UpgradingMode::Single {
-            return self.item_slots.occupied(SlotType::Single);
-        }
-        if current_mode == UpgradingMode::Hybrid && self.item_slots.occupied(SlotType::Single) {
-            return true;
+            return None;
         }
 
-        //Check whether item matches any occupied slot group.
-        [SlotType::Armor, SlotType::Jewelry, SlotType::Weapons]
-            .into_iter()
-            .any(|slot_type| {
-                self.item_slots.occupied(slot_type)
-                    && item_class.is_in_group(slot_type.try_into().unwrap_js())
-            })
+        Some(item_class)
     }
 
     fn observe_enhancement_event(&'static self) -> JsResult<()> {
@@ -958,7 +1019,9 @@ impl Settings {
         lock_manager.add_lock("crafting")?;
         //debug_log!("before add item");
 
-        self.item_slots.init_slot_progress(slot_type).await?;
+        self.item_slots
+            .init_slot_progress(slot_type, &self.planner)
+            .await?;
         //debug_log!("after add item");
 
         lock_manager.remove_lock("crafting")
@@ -985,17 +1048,21 @@ impl Settings {
         // Prevent hero from moving during the addon's initialization.
         lock_manager.add_lock("crafting")?;
 
-        self.item_slots.init_slot_progress(SlotType::Single).await?;
+        self.item_slots
+            .init_slot_progress(SlotType::Single, &self.planner)
+            .await?;
         // Add a delay at the end in order to not receive a warning response.
         delay_range(100, 200).await;
-        self.item_slots.init_slot_progress(SlotType::Armor).await?;
+        self.item_slots
+            .init_slot_progress(SlotType::Armor, &self.planner)
+            .await?;
         delay_range(100, 200).await;
         self.item_slots
-            .init_slot_progress(SlotType::Jewelry)
+            .init_slot_progress(SlotType::Jewelry, &self.planner)
             .await?;
         delay_range(100, 200).await;
         self.item_slots
-            .init_slot_progress(SlotType::Weapons)
+            .init_slot_progress(SlotType::Weapons, &self.planner)
             .await?;
 
         lock_manager.remove_lock("crafting")
@@ -1018,10 +1085,12 @@ impl Settings {
 
     #[cfg(feature = "ni")]
     fn observe_update_placeholder(&'static self) -> JsResult<()> {
-        let items_manager = get_engine().items_manager().ok_or_else(|| err_code!())?;
+        let items_manager = get_engine()
+            .items_manager()
+            .ok_or_else(|| err!(Get, "items manager"))?;
         let original_update_placeholder = items_manager
             .get_update_placeholder()
-            .ok_or_else(|| err_code!())?;
+            .ok_or_else(|| err!(Get, "update placeholder"))?;
         let new_update_placeholder = closure!(
             { let items_manager = items_manager.clone() },
             // This item_id is a string, not a number!
@@ -1069,7 +1138,7 @@ impl Settings {
     fn observe_update_placeholder(&'static self) -> JsResult<()> {
         let original_load_img = crate::bindings::window()
             .get_load_img()
-            .ok_or_else(|| err_code!())?;
+            .ok_or_else(|| err!(Get, "load img"))?;
         let new_load_img = closure!(
             { let original_load_img = original_load_img.clone() },
             move |url: JsValue, id: JsValue, clb: Function| -> JsResult<()> {
@@ -1403,6 +1472,10 @@ impl ActiveSettings {
     }
 
     async fn clear_buffer(&self, settings: &'static Settings) {
+        if settings.simulate.get() {
+            return self.simulate_clear_buffer(settings);
+        }
+
         let event = EmitterEvent::Enhancement;
         let callback_id = Emitter::intercept_once(event, |socket_response| {
             settings.intercept_artisanship(socket_response)
@@ -1423,33 +1496,10 @@ impl ActiveSettings {
         debug_log!("lock_manager be4:", &lock_manager.lock_list());
 
         //let _ = message(intern(s!("[MDMA::RS] Rozpoczynam czyszczenie bufora...")));
-        match settings.mode.get() {
-            //Buffer only fills up if slot is occupied
-            UpgradingMode::Single => {
-                self.intercept_progress(SlotType::Single, settings).await;
-            }
-            UpgradingMode::Group => {
-                let slot_types = [SlotType::Armor, SlotType::Jewelry, SlotType::Weapons];
-
-                for slot_type in slot_types {
-                    if settings.item_slots.occupied(slot_type) {
-                        self.intercept_progress(slot_type, settings).await;
-                    }
-                }
-            }
-            UpgradingMode::Hybrid => {
-                let slot_types = [
-                    SlotType::Armor,
-                    SlotType::Jewelry,
-                    SlotType::Weapons,
-                    SlotType::Single,
-                ];
-
-                for slot_type in slot_types {
-                    if settings.item_slots.occupied(slot_type) {
-                        self.intercept_progress(slot_type, settings).await;
-                    }
-                }
+        //Buffer only fills up if slot is occupied
+        for &slot_type in settings.mode.get().slot_types() {
+            if settings.item_slots.occupied(slot_type) {
+                self.intercept_progress(slot_type, settings).await;
             }
         }
 
@@ -1458,69 +1508,114 @@ impl ActiveSettings {
         //let _ = message(intern(s!("[MDMA::RS] Bufor wyczyszczony pomyślnie.")));
     }
 
-    ///`SlotType` passed into this function has to have an occupied slot.
-    ///`buffer_queue` has to be pre-filtered to only include items that will get burnt.
-    async fn intercept_progress(&self, slot_type: SlotType, settings: &'static Settings) {
-        let mut buffer_queue_lock = self.buffer_queue.lock_mut();
+    /// Goes through the same steps as `clear_buffer`, but only reports which items would get burnt.
+    fn simulate_clear_buffer(&self, settings: &Settings) {
+        // Works on a copy, the buffer is left untouched.
+        let mut buffer = self.buffer_queue.lock_ref().to_vec();
 
-        if buffer_queue_lock.is_empty() {
-            return;
+        for &slot_type in settings.mode.get().slot_types() {
+            let Some(slotted_item_id) = settings.item_slots[slot_type].lock_ref().item_id else {
+                continue;
+            };
+            let ingredients = Self::split_ingredients(&mut buffer, slot_type, settings);
+            if ingredients.is_empty() {
+                continue;
+            }
+
+            let points: Vec<_> = ingredients
+                .iter()
+                .map(|(_, item_data)| settings.planner.ingredient_points(item_data))
+                .collect();
+            let known_points: u32 = points.iter().flatten().sum();
+            let points = match points.iter().any(Option::is_none) {
+                true => format!("{known_points}+?"),
+                false => known_points.to_string(),
+            };
+            let ingredient_names = ingredients
+                .iter()
+                .filter_map(|(_, item_data)| item_data.name.as_deref())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let item_name = Items::get()
+                .lock_ref()
+                .get(&slotted_item_id)
+                .and_then(|item_data| item_data.name.clone())
+                .unwrap_or_default();
+
+            let simulation = tr!(
+                SmartForgeSimulation,
+                item_name = item_name,
+                count = ingredients.len(),
+                points = points,
+                ingredients = ingredient_names,
+            );
+            if message(&simulation).is_err() {
+                console_error!();
+            }
         }
+    }
 
-        let ingredients = buffer_queue_lock
-            .iter()
-            .filter_map(|(item_id, item_data)| {
-                if slot_type == SlotType::Single {
-                    return Some(*item_id);
-                }
+    /// Removes items matching the slot group from the buffer.
+    fn take_ingredients(&self, slot_type: SlotType, settings: &Settings) -> Vec<(Id, Item)> {
+        let mut buffer_lock = self.buffer_queue.lock_mut();
+        let mut buffer = buffer_lock.to_vec();
+        let ingredients = Self::split_ingredients(&mut buffer, slot_type, settings);
+        buffer_lock.replace_cloned(buffer);
 
-                let item_class = item_data.cl?;
+        ingredients
+    }
 
-                item_class
-                    .is_in_group(slot_type.try_into().unwrap_js())
-                    .then_some(*item_id)
-            })
-            .collect::<Vec<_>>();
+    /// Removes items matching the slot group from `buffer`.
+    /// The slotted item itself is removed from `buffer`, but never returned as an ingredient.
+    fn split_ingredients(
+        buffer: &mut Vec<(Id, Item)>,
+        slot_type: SlotType,
+        settings: &Settings,
+    ) -> Vec<(Id, Item)> {
+        let mut ingredients = Vec::new();
+        buffer.retain(|(item_id, item_data)| {
+            if !slot_type.accepts(item_data) {
+                return true;
+            }
+
+            ingredients.push((*item_id, item_data.clone()));
+            false
+        });
+
+        let slotted_item_id = settings.item_slots[slot_type].lock_ref().item_id;
+        ingredients.retain(|(item_id, _)| Some(*item_id) != slotted_item_id);
+
+        ingredients
+    }
+
+    ///`SlotType` passed into this function has to have an occupied slot.
+    ///`buffer_queue` has to be pre-filtered to only include items that will get burnt.
+    async fn intercept_progress(&self, slot_type: SlotType, settings: &'static Settings) {
+        let ingredients = self.take_ingredients(slot_type, settings);
 
         if ingredients.is_empty() {
             return;
         }
 
-        if slot_type == SlotType::Single {
-            //Clear buffer.
-            debug_assert!(ingredients.len() == buffer_queue_lock.len());
-            buffer_queue_lock.clear()
-        } else {
-            //Remove all non-ingredients.
-            buffer_queue_lock.retain(|(item_id, _)| {
-                !ingredients
-                    .iter()
-                    .any(|ingredient_id| *ingredient_id == *item_id)
-            })
-        }
-
-        drop(buffer_queue_lock);
-
         let slotted_item_id = settings.item_slots[slot_type]
             .lock_ref()
             .item_id
             .unwrap_js();
-        let ingredients = ingredients
-            .into_iter()
-            .filter(|ingredient_id| *ingredient_id != slotted_item_id)
-            .map(|ingredient_id| ingredient_id.to_string())
-            .collect::<Vec<String>>();
-        let ingredient_chunks = ingredients.chunks(25).map(|chunk| chunk.join(","));
 
-        for ingredients in ingredient_chunks {
+        for chunk in ingredients.chunks(25) {
+            let chunk = chunk.to_vec();
+            let ingredients = chunk
+                .iter()
+                .map(|(ingredient_id, _)| ingredient_id.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
             let (tx, rx) = futures::channel::oneshot::channel::<bool>();
             Emitter::intercept_once(EmitterEvent::Enhancement, move |socket_response| {
                 Box::pin(async move {
                     let items = socket_response.item.as_mut().ok_or_else(|| err_code!())?;
                     let mut slot_lock = settings.item_slots[slot_type].lock_mut();
                     let item_id = slot_lock.item_id.ok_or_else(|| err_code!())?;
-                    let _item_tpl = items.remove(&-item_id)
-                        .ok_or_else(|| err_code!())?;
+                    let _item_tpl = items.remove(&-item_id).ok_or_else(|| err_code!())?;
                     let item_name = items
                         .get(&item_id)
                         .ok_or_else(|| err_code!())?
@@ -1543,9 +1638,7 @@ impl ActiveSettings {
                     let Some(enhance_progress) = enhancement.progressing else {
                         drop(slot_lock);
                         settings.item_slots.clear_slot(slot_type);
-                        let _ = message(&format!(
-                            r#"[MDMA::RS] Zwalniam slot ulepszania ze względu na poziom ulepszenia przedmiotu "{item_name}"..."#
-                        ));
+                        let _ = message(&tr!(SmartForgeSlotReleased, item_name = item_name));
 
                         tx.send(false).map_err(|_| err_code!())?;
                         return Ok(());
@@ -1554,18 +1647,32 @@ impl ActiveSettings {
                     let new_max = enhance_progress.max.ok_or_else(|| err_code!())?;
 
                     debug_log!(@f "{new_current}/{new_max}");
+                    if let Some(level) = enhance_progress.upgrade_level {
+                        settings.planner.record_level(level, new_max);
+                    }
+                    // Only plain progress tells the points, overflowing points get lost on level up.
+                    if slot_lock.max == Some(new_max)
+                        && let Some(previous_current) = slot_lock.current
+                        && new_current > previous_current
+                    {
+                        settings
+                            .planner
+                            .record_progress(&chunk, new_current - previous_current);
+                    }
                     slot_lock.current = Some(new_current);
                     slot_lock.max = Some(new_max);
-                    let _ = message(&format!(
-                        r#"[MDMA::RS] Ulepszono "{item_name}" do {:.2}%"#,
-                        (new_current as f64 / new_max as f64) * 100.0
+                    let _ = message(&tr!(
+                        SmartForgeUpgraded,
+                        item_name = item_name,
+                        progress = format!("{:.2}", (new_current as f64 / new_max as f64) * 100.0),
                     ));
 
                     tx.send(true).map_err(|_| err_code!())?;
 
                     Ok(())
                 })
-            }).unwrap_js();
+            })
+            .unwrap_js();
             __send_task(&format!("enhancement&action=progress&item={slotted_item_id}&ingredients={ingredients}&answer1001012=1&answer1001015=1")).await.unwrap_js();
             //debug_log!("before waiting for receiver");
             if !rx.await.unwrap_js() {
@@ -0,0 +1,267 @@
+use std::collections::{BTreeMap, HashMap};
+
+use futures_signals::signal::Mutable;
+use proc_macros::Setting;
+
+use crate::prelude::*;
+
+use super::{Settings, SlotType};
+
+/// Highest enhancement level an item can reach.
+pub(super) const MAX_UPGRADE_LEVEL: u8 = 5;
+
+pub(super) fn slot_label(slot_type: SlotType) -> &'static str {
+    match slot_type {
+        SlotType::Single => tr!(SmartForgeSlotSingle),
+        SlotType::Armor => tr!(SmartForgeSlotArmor),
+        SlotType::Jewelry => tr!(SmartForgeSlotJewelry),
+        SlotType::Weapons => tr!(SmartForgeSlotWeapons),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct PlannedIngredient {
+    pub(super) name: String,
+    /// `None` until the game granted points for an ingredient of the same
+    /// rarity and level.
+    pub(super) points: Option<u32>,
+    pub(super) consumed: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct Plan {
+    pub(super) item_name: String,
+    pub(super) ingredients: Vec<PlannedIngredient>,
+    pub(super) points: u32,
+    pub(super) start_level: u8,
+    pub(super) end_level: u8,
+    pub(super) end_current: u32,
+    pub(super) max: u32,
+    pub(super) target_reached: bool,
+    /// Level the game didn't report the required points of yet, planning
+    /// stops there.
+    pub(super) unknown_level: Option<u8>,
+    pub(super) unknown_ingredients: u16,
+    pub(super) consumed: u16,
+    /// `None` if the daily limit is unknown.
+    pub(super) usages_left: Option<u16>,
+}
+
+impl Plan {
+    pub(super) fn summary(&self) -> String {
+        let usages = match self.usages_left {
+            Some(usages_left) => format!("{} / {usages_left}", self.consumed),
+            None => self.consumed.to_string(),
+        };
+        let target = match (self.target_reached, self.unknown_level) {
+            (true, _) => tr!(SmartForgePlanReached).to_owned(),
+            (false, Some(level)) => tr!(SmartForgePlanUnknownLevel, level = level),
+            (false, None) if self.unknown_ingredients > 0 => tr!(
+                SmartForgePlanUnknownPoints,
+                count = self.unknown_ingredients,
+            ),
+            (false, None) => tr!(SmartForgePlanUnreachable).to_owned(),
+        };
+
+        tr!(
+            SmartForgePlanSummary,
+            item_name = self.item_name,
+            start_level = self.start_level,
+            end_level = self.end_level,
+            current = self.end_current,
+            max = self.max,
+            points = self.points,
+            usages = usages,
+            target = target,
+        )
+    }
+}
+
+/// Ingredient points and level requirements are saved with the addon settings,
+/// so a new session can plan before the game reports them again.
+#[derive(Setting)]
+pub(super) struct Planner {
+    #[setting(skip)]
+    pub(super) slot_type: Mutable<SlotType>,
+    #[setting(skip)]
+    pub(super) target_level: Mutable<u8>,
+    #[setting(skip)]
+    pub(super) plan: Mutable<Option<Plan>>,
+    /// Points the game granted per ingredient, by its rarity and level.
+    ingredient_points: Mutable<HashMap<Rarity, BTreeMap<i32, u32>>>,
+    /// Points the game requires to finish each upgrade level.
+    level_points: Mutable<BTreeMap<u8, u32>>,
+}
+
+impl Default for Planner {
+    fn default() -> Self {
+        Self {
+            slot_type: Mutable::new(SlotType::Single),
+            target_level: Mutable::new(MAX_UPGRADE_LEVEL),
+            plan: Mutable::default(),
+            ingredient_points: Mutable::default(),
+            level_points: Mutable::default(),
+        }
+    }
+}
+
+impl Planner {
+    fn ingredient_key(item_data: &Item) -> Option<(Rarity, i32)> {
+        let stats = item_data.parse_stats()?;
+
+        Some((stats.rarity, stats.lvl.unwrap_or(1)))
+    }
+
+    /// Points the game granted for an ingredient of the same rarity and level,
+    /// `None` if no such ingredient was used yet.
+    pub(super) fn ingredient_points(&self, item_data: &Item) -> Option<u32> {
+        let (rarity, lvl) = Self::ingredient_key(item_data)?;
+
+        self.ingredient_points
+            .lock_ref()
+            .get(&rarity)
+            .and_then(|points| points.get(&lvl))
+            .copied()
+    }
+
+    /// Stores the points required for `level` reported in an enhancement
+    /// response.
+    pub(super) fn record_level(&self, level: u8, max: u32) {
+        self.level_points.lock_mut().insert(level, max);
+    }
+
+    /// Stores the points granted for `ingredients` by a progress response.
+    ///
+    /// The game only reports the total, so it's recorded only if every
+    /// ingredient has the same rarity and level.
+    pub(super) fn record_progress(&self, ingredients: &[(Id, Item)], gained: u32) {
+        let mut keys = ingredients
+            .iter()
+            .map(|(_, item_data)| Self::ingredient_key(item_data));
+        let Some(Some((rarity, lvl))) = keys.next() else {
+            return;
+        };
+        if !keys.all(|other_key| other_key == Some((rarity, lvl))) {
+            return;
+        }
+
+        let points = gained / ingredients.len() as u32;
+        self.ingredient_points
+            .lock_mut()
+            .entry(rarity)
+            .or_default()
+            .insert(lvl, points);
+    }
+
+    /// Computes which bag items would be consumed to reach the target level with the slotted item.
+    ///
+    /// Ingredient points and level requirements come from enhancement responses of this
+    /// or earlier sessions,
+    /// ingredients the game didn't grant points for yet are skipped and planning stops at
+    /// the first level with unknown requirements.
+    /// Points exceeding a level are lost, same as with the `answer1001012` prompt.
+    pub(super) fn compute(&self, settings: &Settings) -> Option<Plan> {
+        let slot_type = self.slot_type.get();
+        let (item_id, current, max) = {
+            let slot_lock = settings.item_slots[slot_type].lock_ref();
+            (slot_lock.item_id?, slot_lock.current?, slot_lock.max?)
+        };
+        let items_lock = Items::get().lock_ref();
+        let slotted_item = items_lock.get(&item_id)?;
+        let start_level = slotted_item
+            .parse_stats()
+            .and_then(|stats| stats.enhancement_upgrade_lvl)
+            .unwrap_or_default();
+        let target_level = self.target_level.get().min(MAX_UPGRADE_LEVEL);
+        let usages_left = {
+            let usages_lock = settings.usages.lock_ref();
+            usages_lock
+                .limit
+                .zip(usages_lock.count)
+                .map(|(limit, count)| limit.saturating_sub(count))
+        };
+        let stats_validator = settings.upgrade_button.stats_validator_factory();
+
+        let mut plan = Plan {
+            item_name: slotted_item.name.clone().unwrap_or_default(),
+            start_level,
+            end_level: start_level,
+            end_current: current,
+            max,
+            usages_left,
+            ..Plan::default()
+        };
+        let level_points = self.level_points.lock_ref();
+        let mut level_max = max;
+
+        for (ingredient_id, item_data) in items_lock.iter() {
+            if *ingredient_id == item_id
+                || !settings.filter_buffer_item(item_data, stats_validator)
+                || !slot_type.accepts(item_data)
+            {
+                continue;
+            }
+
+            let points = self.ingredient_points(item_data);
+            let consumed = points.is_some()
+                && plan.end_level < target_level
+                && plan.unknown_level.is_none()
+                && usages_left.is_none_or(|usages_left| plan.consumed < usages_left);
+
+            if points.is_none() {
+                plan.unknown_ingredients += 1;
+            }
+            if let Some(points) = points.filter(|_| consumed) {
+                plan.consumed += 1;
+                plan.points += points;
+                plan.end_current += points;
+
+                if plan.end_current >= level_max {
+                    plan.end_level += 1;
+                    plan.end_current = 0;
+
+                    match level_points.get(&plan.end_level) {
+                        Some(&next_max) => level_max = next_max,
+                        None if plan.end_level < target_level => {
+                            plan.unknown_level = Some(plan.end_level)
+                        }
+                        None => {}
+                    }
+                }
+            }
+
+            plan.ingredients.push(PlannedIngredient {
+                name: item_data.name.clone().unwrap_or_default(),
+                points,
+                consumed,
+            });
+        }
+
+        plan.max = level_max;
+        plan.target_reached = plan.end_level >= target_level;
+
+        Some(plan)
+    }
+
+    pub(super) fn refresh(&self, settings: &Settings) {
+        let plan = self.compute(settings);
+        if plan.is_none() && message(tr!(SmartForgePlanUnavailable)).is_err() {
+            console_error!();
+        }
+
+        self.plan.set(plan);
+    }
+}
+
+impl SlotType {
+    /// Whether the item can be used as an ingredient for the item in this slot.
+    pub(super) fn accepts(self, item_data: &Item) -> bool {
+        if self == SlotType::Single {
+            return true;
+        }
+
+        item_data
+            .cl
+            .is_some_and(|item_class| item_class.is_in_group(self.try_into().unwrap_js()))
+    }
+}
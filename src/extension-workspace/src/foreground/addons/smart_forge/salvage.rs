@@ -0,0 +1,251 @@
+use futures_signals::map_ref;
+use futures_signals::signal::{Mutable, Signal, from_stream};
+use futures_signals::signal_vec::{MutableVec, SignalVecExt};
+use proc_macros::Setting;
+
+use crate::prelude::*;
+
+use super::{Settings, SlotType};
+
+/// Amount of items sent in a single salvage request.
+const SALVAGE_CHUNK_SIZE: usize = 25;
+/// Amount of entries kept in the salvage log.
+const LOG_CAPACITY: usize = 50;
+
+#[derive(Setting)]
+pub(super) struct Salvage {
+    pub(super) unique: Mutable<bool>,
+    pub(super) heroic: Mutable<bool>,
+    pub(super) from_event: Mutable<bool>,
+    /// Highest level of a salvaged item, `0` disables the limit.
+    pub(super) max_level: Mutable<u16>,
+    #[setting(skip)]
+    pub(super) log: MutableVec<String>,
+    #[setting(skip)]
+    pub(super) in_progress: Mutable<bool>,
+}
+
+impl Default for Salvage {
+    fn default() -> Self {
+        Self {
+            unique: Mutable::default(),
+            heroic: Mutable::default(),
+            from_event: Mutable::default(),
+            max_level: Mutable::new(0),
+            log: MutableVec::new(),
+            in_progress: Mutable::new(false),
+        }
+    }
+}
+
+impl Salvage {
+    fn stats_validator_factory(&self) -> impl Fn(&ItemStats) -> bool + use<'_> + Copy {
+        |stats| {
+            let from_rarity = match stats.rarity {
+                Rarity::Upgraded | Rarity::Legendary | Rarity::Artifact => false,
+                Rarity::Unique if !self.unique.get() => false,
+                Rarity::Heroic if !self.heroic.get() => false,
+                _ => true,
+            };
+            let from_event = !stats.from_event || self.from_event.get();
+            let max_level = self.max_level.get();
+            let from_level =
+                max_level == 0 || stats.lvl.is_some_and(|lvl| lvl <= i32::from(max_level));
+
+            from_rarity && from_event && from_level
+        }
+    }
+
+    /// Items from the bag matching the forge filters, slotted items are never salvaged.
+    pub(super) fn selected_items(&self, settings: &Settings) -> Vec<(Id, Item)> {
+        let stats_validator = self.stats_validator_factory();
+        let slotted_ids: Vec<_> = [
+            SlotType::Single,
+            SlotType::Armor,
+            SlotType::Jewelry,
+            SlotType::Weapons,
+        ]
+        .into_iter()
+        .filter_map(|slot_type| settings.item_slots[slot_type].lock_ref().item_id)
+        .collect();
+
+        Items::get()
+            .lock_ref()
+            .iter()
+            .filter(|&(item_id, item_data)| {
+                !slotted_ids.contains(item_id)
+                    && settings
+                        .filter_forge_item(item_data, stats_validator)
+                        .is_some()
+            })
+            .map(|(item_id, item_data)| (*item_id, item_data.clone()))
+            .collect()
+    }
+
+    pub(super) fn selected_items_signal(
+        &'static self,
+        settings: &'static Settings,
+    ) -> impl Signal<Item = Vec<(Id, Item)>> {
+        map_ref! {
+            let _ = Items::get().entries_cloned().to_signal_cloned(),
+            let _ = settings.item_slots[SlotType::Single].signal_ref(|_| ()),
+            let _ = settings.item_slots[SlotType::Armor].signal_ref(|_| ()),
+            let _ = settings.item_slots[SlotType::Jewelry].signal_ref(|_| ()),
+            let _ = settings.item_slots[SlotType::Weapons].signal_ref(|_| ()),
+            let _ = self.unique.signal(),
+            let _ = self.heroic.signal(),
+            let _ = self.from_event.signal(),
+            let _ = self.max_level.signal(),
+            let _ = from_stream(to_stream(settings.item_types.signal_map())),
+            let _ = settings.excluded_items.signal_ref(|_| ()) => {
+                self.selected_items(settings)
+            }
+        }
+    }
+
+    /// Summary of the selected items by rarity.
+    pub(super) fn outcome(items: &[(Id, Item)]) -> String {
+        let (mut common, mut unique, mut heroic) = (0, 0, 0);
+        items
+            .iter()
+            .filter_map(|(_, item_data)| item_data.parse_stats())
+            .for_each(|stats| match stats.rarity {
+                Rarity::Common => common += 1,
+                Rarity::Unique => unique += 1,
+                Rarity::Heroic => heroic += 1,
+                _ => {}
+            });
+
+        tr!(
+            SmartForgeSalvageSummary,
+            count = items.len(),
+            common = common,
+            unique = unique,
+            heroic = heroic,
+        )
+    }
+
+    fn push_log(&self, entry: String) {
+        let mut log_lock = self.log.lock_mut();
+        if log_lock.len() >= LOG_CAPACITY {
+            log_lock.remove(0);
+        }
+
+        log_lock.push_cloned(entry);
+    }
+
+    /// Salvages the `items` confirmed by the user.
+    pub(super) async fn salvage(
+        &self,
+        settings: &'static Settings,
+        items: Vec<(Id, Item)>,
+    ) -> JsResult<()> {
+        if self.in_progress.replace(true) {
+            return Ok(());
+        }
+
+        let res = self.salvage_items(settings, &items).await;
+        self.in_progress.set(false);
+
+        res
+    }
+
+    async fn salvage_items(
+        &self,
+        settings: &'static Settings,
+        items: &[(Id, Item)],
+    ) -> JsResult<()> {
+        let event = EmitterEvent::Enhancement;
+        let callback_id = Emitter::intercept_once(event, |socket_response| {
+            settings.intercept_artisanship(socket_response)
+        })?;
+
+        __send_task("artisanship&action=open").await?;
+        Emitter::wait_for_intercept(event, callback_id).await?;
+
+        let lock_manager = get_engine().lock_manager().ok_or_else(|| err_code!())?;
+        lock_manager.add_lock("crafting")?;
+
+        let res = self.send_salvage_chunks(items).await;
+
+        lock_manager.remove_lock("crafting")?;
+
+        res
+    }
+
+    /// Sends the items in chunks, stops once the game rejects a chunk.
+    async fn send_salvage_chunks(&self, items: &[(Id, Item)]) -> JsResult<()> {
+        for chunk in items.chunks(SALVAGE_CHUNK_SIZE) {
+            let selected_items = chunk
+                .iter()
+                .map(|(item_id, _)| item_id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            let chunk_ids: Vec<_> = chunk.iter().map(|(item_id, _)| *item_id).collect();
+            let (tx, rx) = futures::channel::oneshot::channel::<Vec<Id>>();
+            // Salvaged items get deleted from the bag in the response.
+            Emitter::intercept_once(EmitterEvent::Item, move |socket_response| {
+                Box::pin(async move {
+                    let items = socket_response.item.as_ref().ok_or_else(|| err_code!())?;
+                    let salvaged_ids = chunk_ids
+                        .into_iter()
+                        .filter(|item_id| {
+                            items
+                                .get(item_id)
+                                .is_some_and(|item_data| item_data.del == Some(1))
+                        })
+                        .collect();
+
+                    tx.send(salvaged_ids).map_err(|_| err_code!())
+                })
+            })?;
+            __send_task(&format!(
+                "salvager&action=salvage&selectedItems={selected_items}"
+            ))
+            .await?;
+
+            let salvaged_ids = rx.await.map_err(map_err!(from))?;
+            let salvaged: Vec<_> = chunk
+                .iter()
+                .filter(|(item_id, _)| salvaged_ids.contains(item_id))
+                .collect();
+            if salvaged.is_empty() {
+                if message(tr!(SmartForgeSalvageRejected)).is_err() {
+                    console_error!();
+                }
+                break;
+            }
+
+            let item_names = salvaged
+                .iter()
+                .filter_map(|(_, item_data)| item_data.name.as_deref())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let date = js_sys::Date::new_0();
+            self.push_log(tr!(
+                SmartForgeSalvageLog,
+                time = format!(
+                    "{:02}:{:02}:{:02}",
+                    date.get_hours(),
+                    date.get_minutes(),
+                    date.get_seconds()
+                ),
+                count = salvaged.len(),
+                items = item_names,
+            ));
+            let salvaged_message = tr!(
+                SmartForgeSalvaged,
+                count = salvaged.len(),
+                total = chunk.len(),
+            );
+            if message(&salvaged_message).is_err() {
+                console_error!();
+            }
+
+            // Add a delay in order to not receive a warning response.
+            delay_range(100, 200).await;
+        }
+
+        Ok(())
+    }
+}
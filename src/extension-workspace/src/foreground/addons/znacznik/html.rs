@@ -26,8 +26,9 @@ use crate::interface::tips_parser::tip;
 use crate::interface::{get_windows_stylesheet, ThreadLocalShadowRoot, WINDOWS_ROOT};
 use crate::prelude::*;
 
+use super::descriptors::{self, DescriptorPack, MAX_TEXT_LEN};
 use super::{
-     ActiveSettings, DefaultDescriptors, Descriptor, ItemContainer, OwnDescriptors, SelectingItem, OwnDescriptor, OwnItemHandle, ADDON_NAME
+     ActiveSettings, DefaultDescriptors, Descriptor, ItemContainer, OwnDescriptors, SelectingItem, OwnDescriptor, OwnItemHandle, UserDescriptor, UserDescriptors, ADDON_NAME
 };
 
 const TOP_FADE_MAX: f64 = 0.15;
@@ -372,29 +373,26 @@ impl Descriptor {
 }
 
 impl DefaultDescriptors {
-    fn render(&'static self) -> ContentSection {
+    fn render(&'static self, user_descriptors: &'static UserDescriptors) -> ContentSection {
         let heading = Heading::builder()
-            .text("Znaczniki podstawowe")
+            .text(tr!(ZnacznikDefaultDescriptors))
             .class_list("m[0]");
         let list_signal = self.search_text.signal_ref(
             move |search_text_opt|{
-                let descriptors_lock = self.values.lock_ref();
-                descriptors_lock
-                    .iter()
-                    .filter_map(move |(map_id, descriptor)| {
-                        self.filter_descriptor(*map_id, descriptor, search_text_opt)
+                descriptors::catalog_maps()
+                    .into_iter()
+                    .filter(|(_, (map_name, _))| Self::matches_search(map_name, search_text_opt))
+                    .map(|(map_id, (map_name, entry))| {
+                        let descriptor = self.get_or_insert(map_id, entry);
+                        self.render_descriptor(map_id, map_name, &descriptor, user_descriptors)
                     })
                     .collect()
             },
         );
         let list_len_signal = self.search_text.signal_ref(move |search_text_opt| {
-            let descriptors_lock = self.values.lock_ref();
-            descriptors_lock
-                .iter()
-                .filter(|(map_id, _)| {
-                    let alias = self.alias_list.get(map_id).unwrap_js();
-                    !matches!(search_text_opt.as_ref(), Some(search_text) if !alias.0.to_lowercase().contains(search_text))
-                })
+            descriptors::catalog_maps()
+                .values()
+                .filter(|(map_name, _)| Self::matches_search(map_name, search_text_opt))
                 .count()
         });
         let top_shadow_animation = MutableAnimation::new(0.0);
@@ -441,7 +439,7 @@ impl DefaultDescriptors {
             .event_with_elem(move |_event: Scroll, container: &HtmlDivElement| update_shadow_animations(&top_shadow_animation, &bottom_shadow_animation, container));
         let search_text = &self.search_text;
         let map_search_bar = Input::builder()
-            .placeholder("Szukaj mapy...")
+            .placeholder(tr!(ZnacznikSearchMap))
             .maxlength("100")
             .size(InputSize::Big)
             .text_align(TextAlign::Left)
@@ -460,13 +458,13 @@ impl DefaultDescriptors {
             .heading(heading)
             .checkbox(
                 Checkbox::builder(self.display_dmg_type.active.clone())
-                    .text("Zaznaczaj typy obrażeń broni"),
+                    .text(tr!(ZnacznikDamageTypes)),
             )
             .section_signal(self.display_dmg_type.active.signal_ref(
                 clone!(with_border_mutable => move |active| match active {
                     false => None,
                     true => {
-                        Some(ContentSection::new().checkbox(Checkbox::builder(with_border_mutable.clone()).text("Ikona typu obrażeń wewnątrz ramki")))
+                        Some(ContentSection::new().checkbox(Checkbox::builder(with_border_mutable.clone()).text(tr!(ZnacznikDamageTypeInside))))
                     }
                 }),
             ))
@@ -474,8 +472,8 @@ impl DefaultDescriptors {
                 ContentSection::new()
                 .class_list("p-top[6]")
                 .checkbox_pair(
-                    Checkbox::builder(self.only_text.clone()).text("Tylko podpisy"),
-                    Checkbox::builder(self.only_img.clone()).text("Tylko ikony"),
+                    Checkbox::builder(self.only_text.clone()).text(tr!(ZnacznikOnlyText)),
+                    Checkbox::builder(self.only_img.clone()).text(tr!(ZnacznikOnlyImg)),
                 )
             )
             .section(default_descriptors_list)
@@ -483,26 +481,27 @@ impl DefaultDescriptors {
             .class_list("d[flex] f-d[column]")
     }
 
-    fn filter_descriptor(
+    fn matches_search(map_name: &str, search_text_opt: &Option<String>) -> bool {
+        search_text_opt.as_ref().is_none_or(|search_text| {
+            map_name
+                .to_lowercase()
+                .contains(&search_text.to_lowercase())
+        })
+    }
+
+    fn render_descriptor(
         &'static self,
         map_id: Id,
+        map_name: &str,
         descriptor: &Descriptor,
-        search_text_opt: &Option<String>,
-    ) -> Option<ContentSection> {
-        let alias = self.alias_list.get(&map_id).unwrap_js();
-        if search_text_opt
-            .as_ref()
-            .is_some_and(|search_text| !alias.0.to_lowercase().contains(&search_text.to_lowercase()))
-        {
-            return None;
-        }
-
-        let descriptor_heading = Heading::builder().text(&alias.0).class_list("m[0]");
+        user_descriptors: &'static UserDescriptors,
+    ) -> ContentSection {
+        let descriptor_heading = Heading::builder().text(map_name).class_list("m[0]");
         let default_descriptors_map = &self.values;
 
         let text_active = descriptor.text.active.clone();
         let text_checkbox = Checkbox::builder(descriptor.text.active.clone())
-            .text("Wyświetlaj podpis")
+            .text(tr!(ZnacznikShowText))
             .on_click(move |_event| {
                 text_active.set(!text_active.get());
                 let mut descriptors_lock = default_descriptors_map.lock_mut();
@@ -517,7 +516,7 @@ impl DefaultDescriptors {
             });
         let text_input = Input::builder()
             .maxlength("5")
-            .placeholder("Podpis")
+            .placeholder(tr!(ZnacznikText))
             .size(InputSize::Custom("w[80]"))
             .value_signal(descriptor.text.value.signal_cloned())
             .on_input(move |_event, input_elem| {
@@ -545,7 +544,7 @@ impl DefaultDescriptors {
 
         let img_active = descriptor.img.active.clone();
         let img_checkbox = Checkbox::builder(descriptor.img.active.clone())
-            .text("Wyświetlaj ikonę przedmiotu")
+            .text(tr!(ZnacznikShowImg))
             .on_click(move |_event| {
                 img_active.set(!img_active.get());
                 let mut descriptors_lock = default_descriptors_map.lock_mut();
@@ -561,7 +560,7 @@ impl DefaultDescriptors {
         let img_input = Input::builder()
             .class_list("p-left[5]")
             .size(InputSize::Big)
-            .placeholder("Link do ikony")
+            .placeholder(tr!(ZnacznikImg))
             .value_signal(descriptor.img.value.signal_cloned())
             .confirm_button(
                 InputButton::builder()
@@ -584,20 +583,315 @@ impl DefaultDescriptors {
                         //    .lock_mut()
                         //    .insert_cloned(alias.clone(), descriptor);
                     })
-                    .tip("Zapisz link"),
+                    .tip(tr!(ZnacznikSaveImg)),
             );
         let img_setting = ContentSection::new()
             .class_list("d[flex] g[5] f-d[column]")
             .checkbox(img_checkbox)
             .input(img_input);
 
-        Some(
-            ContentSection::new()
-                .class_list("p[5] g[5] f-d[column] d[flex] b[1] b-r[5] bg[glassy]")
-                .heading(descriptor_heading)
-                .section(text_setting)
-                .section(img_setting),
-        )
+        let overridden_info = user_descriptors
+            .values
+            .signal_map_cloned()
+            .key_cloned(map_id)
+            .map(|user_descriptor| {
+                user_descriptor.map(|_| {
+                    ContentSection::new()
+                        .class_list("mdma-text")
+                        .mixin(|builder| builder.style("color", "orange"))
+                        .text(tr!(ZnacznikOverridden))
+                })
+            });
+
+        ContentSection::new()
+            .class_list("p[5] g[5] f-d[column] d[flex] b[1] b-r[5] bg[glassy]")
+            .heading(descriptor_heading)
+            .section_signal(overridden_info)
+            .section(text_setting)
+            .section(img_setting)
+    }
+}
+
+impl UserDescriptors {
+    fn render(&'static self) -> ContentSection {
+        let heading = Heading::builder()
+            .text(tr!(ZnacznikMapDescriptors))
+            .class_list("m[0]")
+            .info_bubble(
+                InfoBubble::builder()
+                    .text(tr!(ZnacznikMapDescriptorsInfo))
+                    .build(),
+            );
+        let search_text = &self.search_text;
+        let list = self
+            .values
+            .entries_cloned()
+            .filter_signal_cloned(move |(_, user_descriptor)| {
+                let alias = user_descriptor.alias.0.to_lowercase();
+                search_text.signal_ref(move |search_text_opt| {
+                    search_text_opt
+                        .as_ref()
+                        .is_none_or(|search_text| alias.contains(&search_text.to_lowercase()))
+                })
+            })
+            .map(move |(map_id, user_descriptor)| self.render_one(map_id, &user_descriptor));
+        let user_descriptors_list = ContentSection::new()
+            .class_list("g[5] d[flex] f-d[column] max-h[150] scroll-y w[250] m-top[6] b-f[glassy-blur]")
+            .section_signal_vec(list);
+        let map_search_bar = Input::builder()
+            .placeholder(tr!(ZnacznikSearchMap))
+            .maxlength("100")
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .on_input(clone!(search_text => move |_event, input_elem| {
+                let value = input_elem.value();
+                match value.is_empty() {
+                    true => search_text.set_neq(None),
+                    false => search_text.set_neq(Some(value)),
+                }
+            }))
+            .class_list("m-top[6]");
+
+        ContentSection::new()
+            .heading(heading)
+            .section(self.render_draft())
+            .section(user_descriptors_list)
+            .input(map_search_bar)
+            .section(self.render_pack_controls())
+            .class_list("d[flex] f-d[column]")
+    }
+
+    fn render_draft(&'static self) -> ContentSection {
+        let draft = &self.draft;
+        let map_id_input = Input::builder()
+            .input_type(InputType::number(1.0, 99999.0))
+            .placeholder(tr!(ZnacznikMapId))
+            .size(InputSize::Custom("w[80]"))
+            .value_signal(draft.map_id.signal_ref(|map_id| map_id.map(|map_id| map_id.to_string())))
+            .on_input(move |_event, input_elem| {
+                draft.map_id.set_neq(input_elem.value().parse().ok());
+            });
+        let alias_input = Input::builder()
+            .placeholder(tr!(ZnacznikMapName))
+            .maxlength("100")
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .value_signal(draft.alias.signal_cloned())
+            .on_input(move |_event, input_elem| {
+                let value = input_elem.value();
+                draft.alias.set(match value.is_empty() {
+                    true => None,
+                    false => Some(value),
+                });
+            });
+        let text_input = Input::builder()
+            .maxlength(&MAX_TEXT_LEN.to_string())
+            .placeholder(tr!(ZnacznikText))
+            .size(InputSize::Custom("w[80]"))
+            .value_signal(draft.text.signal_cloned())
+            .on_input(move |_event, input_elem| {
+                let value = input_elem.value();
+                draft.text.set(match value.is_empty() {
+                    true => None,
+                    false => Some(value),
+                });
+            });
+        let img_input = Input::builder()
+            .placeholder(tr!(ZnacznikImg))
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .value_signal(draft.img.signal_cloned())
+            .on_input(move |_event, input_elem| {
+                let value = input_elem.value();
+                draft.img.set(match value.is_empty() {
+                    true => None,
+                    false => Some(value),
+                });
+            });
+        let current_map_button = Button::builder()
+            .text(tr!(ZnacznikCurrentMap))
+            .on_click(move |_event| draft.fill_from_current_map());
+        let add_button = Button::builder()
+            .text(tr!(ZnacznikAdd))
+            .on_click(move |_event| {
+                if let Err(msg) = self.add_from_draft() {
+                    if message(msg).is_err() {
+                        console_error!()
+                    }
+                }
+            });
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[5] m-top[6]")
+            .section(
+                ContentSection::new()
+                    .class_list("d[flex] g[5]")
+                    .input(map_id_input)
+                    .input(text_input),
+            )
+            .input(alias_input)
+            .input(img_input)
+            .button_pair(ButtonPair::builder(current_map_button, add_button))
+    }
+
+    fn render_one(&'static self, map_id: Id, user_descriptor: &UserDescriptor) -> ContentSection {
+        let descriptor = &user_descriptor.descriptor;
+        let descriptor_heading = Heading::builder()
+            .text(&format!("{} ({map_id})", user_descriptor.alias))
+            .class_list("m[0]");
+        let user_descriptors_map = &self.values;
+
+        let text_active = descriptor.text.active.clone();
+        let text_checkbox = Checkbox::builder(descriptor.text.active.clone())
+            .text(tr!(ZnacznikShowText))
+            .on_click(move |_event| {
+                text_active.set(!text_active.get());
+                let mut descriptors_lock = user_descriptors_map.lock_mut();
+                let Entry::Occupied(mut entry) = descriptors_lock.entry(map_id) else {
+                    console_error!();
+                    return;
+                };
+                entry.modify_cloned(|_| {});
+            });
+        let text_input = Input::builder()
+            .maxlength(&MAX_TEXT_LEN.to_string())
+            .placeholder(tr!(ZnacznikText))
+            .size(InputSize::Custom("w[80]"))
+            .value_signal(descriptor.text.value.signal_cloned())
+            .on_input(move |_event, input_elem| {
+                let value = input_elem.value();
+                let mut descriptors_lock = user_descriptors_map.lock_mut();
+                let Entry::Occupied(mut entry) = descriptors_lock.entry(map_id) else {
+                    console_error!();
+                    return;
+                };
+                entry.modify_cloned(|user_descriptor| {
+                    user_descriptor.descriptor.text.value.set(match value.is_empty() {
+                        true => None,
+                        false => Some(value),
+                    });
+                });
+            });
+        let text_setting = ContentSection::new()
+            .class_list("d[flex] g[5]")
+            .checkbox(text_checkbox)
+            .input(text_input);
+
+        let img_active = descriptor.img.active.clone();
+        let img_checkbox = Checkbox::builder(descriptor.img.active.clone())
+            .text(tr!(ZnacznikShowImg))
+            .on_click(move |_event| {
+                img_active.set(!img_active.get());
+                let mut descriptors_lock = user_descriptors_map.lock_mut();
+                let Entry::Occupied(mut entry) = descriptors_lock.entry(map_id) else {
+                    console_error!();
+                    return;
+                };
+                entry.modify_cloned(|_| {});
+            });
+        let img_input = Input::builder()
+            .class_list("p-left[5]")
+            .size(InputSize::Big)
+            .placeholder(tr!(ZnacznikImg))
+            .value_signal(descriptor.img.value.signal_cloned())
+            .confirm_button(
+                InputButton::builder()
+                    .on_click(move |_event, input_elem| {
+                        let value = input_elem.value();
+                        let mut descriptors_lock = user_descriptors_map.lock_mut();
+                        let Entry::Occupied(mut entry) = descriptors_lock.entry(map_id) else {
+                            console_error!();
+                            return;
+                        };
+                        entry.modify_cloned(|user_descriptor| {
+                            user_descriptor.descriptor.img.value.set(match value.is_empty() {
+                                true => None,
+                                false => Some(value),
+                            });
+                        });
+                    })
+                    .tip(tr!(ZnacznikSaveImg)),
+            );
+        let img_setting = ContentSection::new()
+            .class_list("d[flex] g[5] f-d[column]")
+            .checkbox(img_checkbox)
+            .input(img_input);
+        let remove_button = Button::builder()
+            .text(tr!(ZnacznikRemove))
+            .on_click(move |_event| self.remove_one(&map_id));
+
+        ContentSection::new()
+            .class_list("p[5] g[5] f-d[column] d[flex] b[1] b-r[5] bg[glassy]")
+            .heading(descriptor_heading)
+            .apply_if(Catalog::by_map_id(map_id).is_some(), |section| {
+                section.section(
+                    ContentSection::new()
+                        .class_list("mdma-text")
+                        .mixin(|builder| builder.style("color", "orange"))
+                        .text(tr!(ZnacznikOverrides)),
+                )
+            })
+            .section(text_setting)
+            .section(img_setting)
+            .button(remove_button)
+    }
+
+    fn render_pack_controls(&'static self) -> ContentSection {
+        let import_input = Input::builder()
+            .placeholder(tr!(ZnacznikPackPlaceholder))
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .confirm_button(
+                InputButton::builder()
+                    .on_click(move |_event, input_elem| {
+                        let pack = match serde_json::from_str::<DescriptorPack>(&input_elem.value()) {
+                            Ok(pack) => pack,
+                            Err(_err) => {
+                                debug_log!(_err.to_string());
+                                if message(tr!(ZnacznikPackInvalid)).is_err() {
+                                    console_error!()
+                                }
+                                return;
+                            }
+                        };
+                        let (imported, overridden) = self.import_pack(pack);
+                        input_elem.set_value("");
+                        let msg = tr!(
+                            ZnacznikImported,
+                            imported = imported,
+                            overridden = overridden,
+                        );
+                        if message(&msg).is_err() {
+                            console_error!()
+                        }
+                    })
+                    .tip(tr!(ZnacznikImport)),
+            );
+        let export_button = Button::builder()
+            .text(tr!(ZnacznikExport))
+            .on_click(move |_event| {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let pack = match serde_json::to_string(&self.export_pack()).map_err(map_err!(from)) {
+                        Ok(pack) => pack,
+                        Err(err) => return console_error!(err),
+                    };
+                    let clipboard = window().navigator().clipboard();
+                    if let Err(err) = wasm_bindgen_futures::JsFuture::from(clipboard.write_text(&pack))
+                        .await
+                        .map_err(map_err!())
+                    {
+                        return console_error!(err);
+                    }
+                    if message(tr!(ZnacznikExported)).is_err() {
+                        console_error!()
+                    }
+                });
+            });
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[5] m-top[6]")
+            .input(import_input)
+            .button(export_button)
     }
 }
 
@@ -876,7 +1170,7 @@ impl OwnDescriptors {
         wasm_bindgen_futures::spawn_local(future);
 
         ContentSection::new()
-            .heading(Heading::builder().text("Twoje znaczniki"))
+            .heading(Heading::builder().text(tr!(ZnacznikUserDescriptors)))
             .section(
                 ContentSection::new()
                     .class_list("d[flex] f-d[row] j-c[center] flex-w[wrap] w[250]")
@@ -918,8 +1212,8 @@ impl OwnDescriptors {
                                     )
                                     .tip!({
                                         .text_signal(SELECTING_ITEM.with(|selecting_item| selecting_item.active.signal().map(|active| match active {
-                                            true => "LPM na przedmiot w ekwipunku, aby dodać go do listy znaczników",
-                                            false => "LPM aby rozpocząć wybór przedmiotu",
+                                            true => tr!(ZnacznikSelectingTip),
+                                            false => tr!(ZnacznikSelectTip),
                                         })))
                                     })
                                 })
@@ -944,7 +1238,7 @@ impl OwnDescriptors {
 
                     let text_active = descriptor.text.active.clone();
                     let text_checkbox = Checkbox::builder(descriptor.text.active.clone())
-                        .text("Wyświetlaj podpis")
+                        .text(tr!(ZnacznikShowText))
                         .on_click(move |_event| {
                             text_active.set(!text_active.get());
                             let mut descriptors_lock = self.values.lock_mut();
@@ -954,7 +1248,7 @@ impl OwnDescriptors {
                         });
                     let text_input = Input::builder()
                         .maxlength("5")
-                        .placeholder("Podpis")
+                        .placeholder(tr!(ZnacznikText))
                         .size(InputSize::Custom("w[80]"))
                         .value_signal(descriptor.text.value.signal_cloned())
                         .on_input(move |_event, input_elem| {
@@ -974,7 +1268,7 @@ impl OwnDescriptors {
                     
                     let rarity_setting = descriptor.rarity.clone();
                     let rarity_checkbox = Checkbox::builder(descriptor.rarity.active.clone())
-                        .text("Zmień rzadkość")
+                        .text(tr!(ZnacznikChangeRarity))
                         .on_click(move |_| {
                             rarity_setting.active.set(!rarity_setting.active.get());
                             let mut descriptors_lock = self.values.lock_mut();
@@ -989,7 +1283,7 @@ impl OwnDescriptors {
                     let rarity_button = Button::builder()
                         .class_list("w[108] t-a[left]")
                         .no_hover()
-                        .text("Rzadkość")
+                        .text(tr!(ZnacznikRarity))
                         .mixin(move |builder| {
                             builder
                                 .child(html!("div", {
@@ -1002,49 +1296,49 @@ impl OwnDescriptors {
                                             .class!(w[100] t-a[center] l[1] scroll-wrapper)
                                             .child(html!("div", {
                                                 .class!(scroll-option)
-                                                .text("Brak")
+                                                .text(tr!(ZnacznikRarityNone))
                                                 .event_with_options(&EventOptions::bubbles(), clone!(rarity_value => move |_: Click| {
                                                     rarity_value.set(None);
                                                 }))
                                             }))
                                             .child(html!("div", {
                                                 .class!(scroll-option)
-                                                .text("Pospolity")
+                                                .text(tr!(ZnacznikRarityCommon))
                                                 .event_with_options(&EventOptions::bubbles(), clone!(rarity_value => move |_: Click| {
                                                     rarity_value.set(Some(Rarity::Common));
                                                 }))
                                             }))
                                             .child(html!("div", {
                                                 .class!(scroll-option)
-                                                .text("Unikatowy")
+                                                .text(tr!(ZnacznikRarityUnique))
                                                 .event_with_options(&EventOptions::bubbles(), clone!(rarity_value => move |_: Click| {
                                                     rarity_value.set(Some(Rarity::Unique));
                                                 }))
                                             }))
                                             .child(html!("div", {
                                                 .class!(scroll-option)
-                                                .text("Heroiczny")
+                                                .text(tr!(ZnacznikRarityHeroic))
                                                 .event_with_options(&EventOptions::bubbles(), clone!(rarity_value => move |_: Click| {
                                                     rarity_value.set(Some(Rarity::Heroic));
                                                 }))
                                             }))
                                             .child(html!("div", {
                                                 .class!(scroll-option)
-                                                .text("Ulepszony")
+                                                .text(tr!(ZnacznikRarityUpgraded))
                                                 .event_with_options(&EventOptions::bubbles(), clone!(rarity_value => move |_: Click| {
                                                     rarity_value.set(Some(Rarity::Upgraded));
                                                 }))
                                             }))
                                             .child(html!("div", {
                                                 .class!(scroll-option)
-                                                .text("Legendarny")
+                                                .text(tr!(ZnacznikRarityLegendary))
                                                 .event_with_options(&EventOptions::bubbles(), clone!(rarity_value => move |_: Click| {
                                                     rarity_value.set(Some(Rarity::Legendary));
                                                 }))
                                             }))
                                             .child(html!("div", {
                                                 .class!(scroll-option)
-                                                .text("Artefakt")
+                                                .text(tr!(ZnacznikRarityArtifact))
                                                 .event_with_options(&EventOptions::bubbles(), clone!(rarity_value => move |_: Click| {
                                                     rarity_value.set(Some(Rarity::Artifact));
                                                 }))
@@ -1069,11 +1363,11 @@ impl OwnDescriptors {
 
                     let img_setting = descriptor.img.clone();
                     let img_checkbox = Checkbox::builder(descriptor.img.active.clone())
-                        .text("Zmień ikonę")
+                        .text(tr!(ZnacznikChangeImg))
                         .on_click(move |event| {
                             if img_setting.value.lock_ref().is_none() && !img_setting.active.get() {
                                 event.prevent_default();
-                                if message("Ustaw ikonę do podmiany.").is_err() {
+                                if message(tr!(ZnacznikImgRequired)).is_err() {
                                     console_error!()
                                 }
                                 return;
@@ -1088,7 +1382,7 @@ impl OwnDescriptors {
                     let img_input = Input::builder()
                         .class_list("p-left[5]")
                         .size(InputSize::Big)
-                        .placeholder("Link do ikony")
+                        .placeholder(tr!(ZnacznikImg))
                         .value_signal(descriptor.img.value.signal_cloned())
                         .confirm_button(
                             InputButton::builder()
@@ -1104,7 +1398,7 @@ impl OwnDescriptors {
                                     descriptors_lock
                                         .insert_cloned(id, descriptor);
                                 })
-                                .tip("Zapisz link"),
+                                .tip(tr!(ZnacznikSaveImg)),
                         );
                     let img_setting = ContentSection::new()
                         .class_list("d[flex] f-d[column]")
@@ -1161,7 +1455,7 @@ impl OwnDescriptors {
                 .child_signal(item_not_found_signal(item_id).map(|active| match active {
                     true => Some(html!("div", {
                         .style("color", "red")
-                        .text("Nie wykryto przedmiotu w ekwipunku!")
+                        .text(tr!(ZnacznikItemNotFound))
                     })),
                     false => None,
                 }))
@@ -1169,9 +1463,9 @@ impl OwnDescriptors {
                     true => Some(html!("br", {})),
                     false => None,
                 }))
-                .text("LPM aby edytować znacznik przedmiotu")
+                .text(tr!(ZnacznikEditTip))
                 .child(html!("br", {}))
-                .text("PPM aby usunąć przedmiot z listy znaczników")
+                .text(tr!(ZnacznikRemoveTip))
             })
         })
     }
@@ -1354,7 +1648,8 @@ impl ActiveSettings {
         let header = WindowHeader::new(decor);
 
         let content = WindowContent::builder()
-            .section(self.default_descriptors.render())
+            .section(self.default_descriptors.render(&self.user_descriptors))
+            .section(self.user_descriptors.render())
             .section(self.character_descriptors.render_user_aliases())
             .class_list("f-d[column]");
 
@@ -1397,8 +1692,6 @@ fn update_shadow_animations(
 pub(super) fn init(
     active_settings: &'static ActiveSettings,
 ) -> JsResult<()> {
-    let _active_settings_window_handle = WINDOWS_ROOT
-        .try_append_dom(active_settings.render()?)
-        .ok_or_else(|| err_code!())?;
+    let _active_settings_window_handle = WINDOWS_ROOT.append_window(active_settings.render()?)?;
     Ok(())
 }
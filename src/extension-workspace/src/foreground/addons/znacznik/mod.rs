@@ -9,14 +9,16 @@ use std::fmt::{self, Debug};
 use std::ops::Deref;
 use std::rc::Rc;
 
+use common::catalog::CatalogEntry;
 use discard::Discard;
 use dominator::events::{Click, Load, MouseButton};
 use dominator::traits::StaticEvent;
 use dominator::{Dom, DomBuilder, DomHandle, apply_methods, clone};
+use futures::StreamExt;
 use futures::channel::oneshot;
 use futures_signals::map_ref;
 use futures_signals::signal::{Mutable, Signal, SignalExt};
-use futures_signals::signal_map::MutableBTreeMap;
+use futures_signals::signal_map::{Entry, MapDiff, MutableBTreeMap};
 use html::SELECTING_ITEM;
 use js_sys::Function;
 use proc_macros::{ActiveSettings, Setting};
@@ -99,7 +101,7 @@ struct SelectingItem {
 }
 
 #[repr(transparent)]
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize, Clone, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize, Clone, Debug, Default)]
 struct LocationAlias(String);
 
 impl fmt::Display for LocationAlias {
@@ -130,10 +132,12 @@ struct Descriptor {
 }
 
 impl Descriptor {
-    fn new(text: String, img: String) -> Self {
+    fn new(text: String, img: Option<String>) -> Self {
+        let img_active = img.is_some();
+
         Self {
             text: DescriptorSetting::new(Some(text), true),
-            img: DescriptorSetting::new(Some(img), true),
+            img: DescriptorSetting::new(img, img_active),
         }
     }
 }
@@ -159,9 +163,8 @@ type DomHandleMap = Rc<RefCell<HashMap<Id, DomHandle>>>;
 struct DefaultDescriptors {
     #[setting(skip)]
     search_text: Mutable<Option<String>>,
+    /// Descriptors of the catalog maps, added once they're displayed.
     values: MutableBTreeMap<Id, Descriptor>,
-    #[setting(skip)]
-    alias_list: HashMap<Id, LocationAlias>,
     display_dmg_type: DamageTypeDisplay,
     only_text: Mutable<bool>,
     only_img: Mutable<bool>,
@@ -173,8 +176,7 @@ impl Default for DefaultDescriptors {
     fn default() -> Self {
         Self {
             search_text: Mutable::default(),
-            values: descriptors::get_default(),
-            alias_list: descriptors::alias_list(),
+            values: MutableBTreeMap::default(),
             display_dmg_type: DamageTypeDisplay::default(),
             only_text: Mutable::default(),
             only_img: Mutable::default(),
@@ -184,6 +186,24 @@ impl Default for DefaultDescriptors {
 }
 
 impl DefaultDescriptors {
+    /// Default descriptor of the map, `None` if the current catalog doesn't know it.
+    fn get(&self, map_id: Id) -> Option<Descriptor> {
+        Catalog::by_map_id(map_id).map(|entry| self.get_or_insert(map_id, entry))
+    }
+
+    fn get_or_insert(&self, map_id: Id, entry: &CatalogEntry) -> Descriptor {
+        if let Some(descriptor) = self.values.lock_ref().get(&map_id) {
+            return descriptor.clone();
+        }
+
+        let descriptor = Descriptor::new(entry.label.clone(), entry.img_url());
+        self.values
+            .lock_mut()
+            .insert_cloned(map_id, descriptor.clone());
+
+        descriptor
+    }
+
     fn try_remove_one_descriptor(&self, item_id: i32) {
         if let Some(shadow_handle) = self.dom_handles.borrow_mut().remove(&item_id) {
             shadow_handle.discard();
@@ -191,6 +211,173 @@ impl DefaultDescriptors {
     }
 }
 
+/// Descriptor added by the user for any map id, either by hand or from a descriptor pack.
+/// Takes precedence over the default descriptor of the same map id.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+struct UserDescriptor {
+    alias: LocationAlias,
+    descriptor: Descriptor,
+}
+
+impl UserDescriptor {
+    fn new(alias: LocationAlias, text: Option<String>, img: Option<String>) -> Self {
+        let text_active = text.is_some();
+        let img_active = img.is_some();
+
+        Self {
+            alias,
+            descriptor: Descriptor {
+                text: DescriptorSetting::new(text, text_active),
+                img: DescriptorSetting::new(img, img_active),
+            },
+        }
+    }
+}
+
+/// Values of the "add descriptor" form.
+#[derive(Default, Clone)]
+struct UserDescriptorDraft {
+    map_id: Mutable<Option<Id>>,
+    alias: Mutable<Option<String>>,
+    text: Mutable<Option<String>>,
+    img: Mutable<Option<String>>,
+}
+
+impl UserDescriptorDraft {
+    fn clear(&self) {
+        self.map_id.set(None);
+        self.alias.set(None);
+        self.text.set(None);
+        self.img.set(None);
+    }
+
+    fn fill_from_current_map(&self) {
+        let town_lock = Town::get().lock_ref();
+        self.map_id.set(town_lock.id);
+        self.alias.set(town_lock.name.clone());
+    }
+}
+
+#[derive(Setting, Clone, Default)]
+struct UserDescriptors {
+    values: MutableBTreeMap<Id, UserDescriptor>,
+    #[setting(skip)]
+    search_text: Mutable<Option<String>>,
+    #[setting(skip)]
+    draft: UserDescriptorDraft,
+}
+
+impl UserDescriptors {
+    fn add_from_draft(&self) -> Result<(), &'static str> {
+        let map_id = self
+            .draft
+            .map_id
+            .get()
+            .filter(|map_id| *map_id > 0)
+            .ok_or(tr!(ZnacznikMapIdRequired))?;
+        let alias = self
+            .draft
+            .alias
+            .get_cloned()
+            .map(|alias| alias.trim().to_owned())
+            .filter(|alias| !alias.is_empty())
+            .ok_or(tr!(ZnacznikMapNameRequired))?;
+        let entry = descriptors::PackEntry {
+            map_name: alias,
+            text: self.draft.text.get_cloned(),
+            img: self.draft.img.get_cloned(),
+        };
+        let user_descriptor = entry
+            .into_user_descriptor()
+            .ok_or(tr!(ZnacznikDescriptorRequired))?;
+
+        self.insert_or_update(map_id, user_descriptor);
+        self.draft.clear();
+
+        Ok(())
+    }
+
+    /// Updates the values of an already existing descriptor in place so that rendered items keep
+    /// observing the same `Mutable`s.
+    fn insert_or_update(&self, map_id: Id, user_descriptor: UserDescriptor) {
+        let mut values_lock = self.values.lock_mut();
+
+        match values_lock.entry(map_id) {
+            Entry::Occupied(mut entry) => entry.modify_cloned(|old| {
+                old.alias = user_descriptor.alias;
+                old.descriptor
+                    .text
+                    .value
+                    .set(user_descriptor.descriptor.text.value.get_cloned());
+                old.descriptor
+                    .text
+                    .active
+                    .set(user_descriptor.descriptor.text.active.get());
+                old.descriptor
+                    .img
+                    .value
+                    .set(user_descriptor.descriptor.img.value.get_cloned());
+                old.descriptor
+                    .img
+                    .active
+                    .set(user_descriptor.descriptor.img.active.get());
+            }),
+            Entry::Vacant(entry) => {
+                entry.insert_cloned(user_descriptor);
+            }
+        }
+    }
+
+    fn remove_one(&self, map_id: &Id) {
+        let Some(user_descriptor) = self.values.lock_mut().remove(map_id) else {
+            return;
+        };
+        // Restores item containers of descriptors which have no default fallback.
+        user_descriptor.descriptor.text.active.set_neq(false);
+        user_descriptor.descriptor.img.active.set_neq(false);
+    }
+
+    fn export_pack(&self) -> descriptors::DescriptorPack {
+        descriptors::DescriptorPack {
+            name: None,
+            descriptors: self
+                .values
+                .lock_ref()
+                .iter()
+                .map(|(map_id, user_descriptor)| {
+                    (
+                        *map_id,
+                        descriptors::PackEntry::from_user_descriptor(user_descriptor),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Merges the pack into the user descriptors.
+    ///
+    /// Returns the amount of imported entries and how many of them override a default
+    /// descriptor.
+    fn import_pack(&self, pack: descriptors::DescriptorPack) -> (usize, usize) {
+        let mut imported = 0;
+        let mut overridden = 0;
+
+        for (map_id, entry) in pack.descriptors {
+            let Some(user_descriptor) = entry.into_user_descriptor() else {
+                continue;
+            };
+            if Catalog::by_map_id(map_id).is_some() {
+                overridden += 1;
+            }
+
+            self.insert_or_update(map_id, user_descriptor);
+            imported += 1;
+        }
+
+        (imported, overridden)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 struct OwnDescriptor {
     text: DescriptorSetting<String>,
@@ -724,10 +911,12 @@ struct OwnDescriptors {
 impl OwnDescriptors {
     #[cfg(feature = "ni")]
     fn init(&'static self) -> JsResult<()> {
-        let items_manager = get_engine().items_manager().ok_or_else(|| err_code!())?;
+        let items_manager = get_engine()
+            .items_manager()
+            .ok_or_else(|| err!(Get, "items manager"))?;
         let original_update_placeholder = items_manager
             .get_update_placeholder()
-            .ok_or_else(|| err_code!())?;
+            .ok_or_else(|| err!(Get, "update placeholder"))?;
         let new_update_placeholder = closure!(
             { let items_manager = items_manager.clone() },
             // This item_id is a string, not a number!
@@ -763,7 +952,7 @@ impl OwnDescriptors {
     fn init(&'static self) -> JsResult<()> {
         let original_load_img = crate::bindings::window()
             .get_load_img()
-            .ok_or_else(|| err_code!())?;
+            .ok_or_else(|| err!(Get, "load img"))?;
         let new_load_img = closure!(
             { let original_load_img = original_load_img.clone() },
             move |url: JsValue, id: JsValue, clb: Function| -> JsResult<()> {
@@ -855,6 +1044,7 @@ impl OwnDescriptors {
 struct ActiveSettings {
     default_descriptors: DefaultDescriptors,
     character_descriptors: OwnDescriptors,
+    user_descriptors: UserDescriptors,
 }
 
 impl ActiveSettings {
@@ -880,8 +1070,14 @@ impl ActiveSettings {
         let stats = item_data.parse_stats().ok_or_else(|| err_code!())?;
 
         let shadow_tree_handle = if let Some((map_id, _, _, _)) = stats.custom_teleport {
-            let descriptors_lock = self.default_descriptors.values.lock_ref();
-            let Some(descriptor) = descriptors_lock.get(&map_id) else {
+            let user_descriptor = self
+                .user_descriptors
+                .values
+                .lock_ref()
+                .get(&map_id)
+                .map(|user_descriptor| user_descriptor.descriptor.clone());
+            let Some(descriptor) = user_descriptor.or_else(|| self.default_descriptors.get(map_id))
+            else {
                 return Ok(());
             };
 
@@ -945,12 +1141,56 @@ impl ActiveSettings {
         self.observe_update_placeholder()
     }
 
+    fn init_user_descriptors(&'static self) {
+        // Skip the initial `MapDiff::Replace`.
+        let future = to_stream(self.user_descriptors.values.signal_map_cloned())
+            .skip(1)
+            .for_each(move |diff| {
+                match diff {
+                    MapDiff::Insert { key, .. } | MapDiff::Remove { key } => {
+                        self.rerender_teleports(Some(key))
+                    }
+                    MapDiff::Replace { .. } | MapDiff::Clear {} => self.rerender_teleports(None),
+                    // Values are updated in place.
+                    MapDiff::Update { .. } => {}
+                }
+
+                async {}
+            });
+        wasm_bindgen_futures::spawn_local(future);
+    }
+
+    /// Renders descriptors of teleports leading to `map_id` once again, or of all teleports if
+    /// `map_id` is `None`.
+    fn rerender_teleports(&'static self, map_id: Option<Id>) {
+        let items_lock = Items::get().lock_ref();
+
+        for (item_id, item_data) in items_lock.iter() {
+            let Some((teleport_map_id, _, _, _)) = item_data
+                .parse_stats()
+                .and_then(|stats| stats.custom_teleport)
+            else {
+                continue;
+            };
+            if map_id.is_some_and(|map_id| map_id != teleport_map_id) {
+                continue;
+            }
+
+            self.default_descriptors.try_remove_one_descriptor(*item_id);
+            if let Err(err) = self.try_render_default(*item_id, item_data) {
+                console_error!(err);
+            }
+        }
+    }
+
     #[cfg(feature = "ni")]
     fn observe_update_placeholder(&'static self) -> JsResult<()> {
-        let items_manager = get_engine().items_manager().ok_or_else(|| err_code!())?;
+        let items_manager = get_engine()
+            .items_manager()
+            .ok_or_else(|| err!(Get, "items manager"))?;
         let original_update_placeholder = items_manager
             .get_update_placeholder()
-            .ok_or_else(|| err_code!())?;
+            .ok_or_else(|| err!(Get, "update placeholder"))?;
         let new_update_placeholder = closure!(
             { let items_manager = items_manager.clone() },
             // This item_id is a string, not a number!
@@ -996,7 +1236,7 @@ impl ActiveSettings {
     fn observe_update_placeholder(&'static self) -> JsResult<()> {
         let original_load_img = crate::bindings::window()
             .get_load_img()
-            .ok_or_else(|| err_code!())?;
+            .ok_or_else(|| err!(Get, "load img"))?;
         let new_load_img = closure!(
             { let original_load_img = original_load_img.clone() },
             move |url: JsValue, id: JsValue, clb: Function| -> JsResult<()> {
@@ -1279,6 +1519,7 @@ pub(crate) fn init() -> JsResult<()> {
 
     html::init(active_settings)?;
     active_settings.init_default_descriptors()?;
+    active_settings.init_user_descriptors();
     active_settings.character_descriptors.init()?;
     #[cfg(feature = "ni")]
     active_settings.observe_items();
@@ -76,7 +76,7 @@ impl GameApi {
 
         let renderer = get_engine().renderer().ok_or_else(|| err_code!())?;
         let after_call_draw_add_to_renderer = closure!(move || -> JsResult<()> {
-            crate::color_mark::ColorMark::on_call_draw_add_to_renderer(&renderer)
+            crate::overlay::Overlay::on_call_draw_add_to_renderer(&renderer)
             // emitter::Emitter::get().emit(
             //     &emitter::EmitterEvent::CallDrawAddToRenderer,
             //     &mut JsValue::undefined(),
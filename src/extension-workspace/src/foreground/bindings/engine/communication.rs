@@ -1,9 +1,10 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::{BTreeMap, HashMap};
 use std::convert::Infallible;
 use std::fmt;
 use std::str::FromStr;
 
+use enum_iterator::Sequence;
 use futures::channel::oneshot;
 use futures_signals::signal::Mutable;
 use futures_signals::signal_map::MutableBTreeMapLockMut;
@@ -20,7 +21,8 @@ use wasm_bindgen::{intern, prelude::*};
 use web_sys::MessageEvent;
 
 use crate::bindings::window;
-use crate::interface::{CONSOLE_LOGS, ConsoleLog, ConsoleLogTypes};
+use crate::interface::{ConsoleLog, ConsoleLogTypes};
+use crate::utils::logging::push_console_log;
 use crate::prelude::*;
 
 use super::types::{EquipmentSlot, ItemClass};
@@ -51,6 +53,14 @@ thread_local! {
     #[doc = "Keeps track of an internal task queue and replaces empty tasks with ones from the queue."]
     #[doc = "Does not keep track of order."]
     static TASKS: RefCell<Vec<(String, oneshot::Sender<()>)>> = const { RefCell::new(Vec::new()) };
+    /// Time the hero last attacked someone, in milliseconds since the epoch.
+    static LAST_ATTACK: Cell<f64> = const { Cell::new(f64::NEG_INFINITY) };
+}
+
+/// Returns whether the hero attacked someone in the last `ms` milliseconds,
+/// telling fights the hero started apart from being attacked.
+pub(crate) fn attack_sent_within(ms: f64) -> bool {
+    js_sys::Date::now() - LAST_ATTACK.get() <= ms
 }
 
 fn clear_queue(task: &mut String) {
@@ -132,6 +142,10 @@ impl Communication {
 
                     clear_queue(&mut task);
 
+                    if task.starts_with(s!("fight&a=attack")) {
+                        LAST_ATTACK.set(js_sys::Date::now());
+                    }
+
                     if callback.is_truthy() && payload.is_truthy() {
                         original_send.send_task_with_callback_and_payload(
                             &context, &task, &callback, &payload,
@@ -234,13 +248,7 @@ pub fn observe_on_message(
                     get_engine().set_ev(ev);
                 }
 
-                CONSOLE_LOGS.with_borrow_mut(|logs| {
-                    if logs.len() == logs.capacity() {
-                        logs.pop_back();
-                    }
-
-                    logs.push_front(console_log);
-                });
+                push_console_log(console_log);
 
                 dispatch_events(res.clone());
                 if Emitter::emit_events(&mut res).await {
@@ -551,7 +559,8 @@ pub struct Response {
     pub friends: Option<Vec<Friend>>,
     #[serde(rename = "friends_max")]
     pub friends_max: Option<u8>,
-    pub enemies: Option<serde_json::Value>,
+    #[serde(deserialize_with = "enemies_de")]
+    pub enemies: Option<Vec<Enemy>>,
     #[serde(rename = "enemies_max")]
     pub enemies_max: Option<u8>,
     pub h: Option<HeroData>,
@@ -588,7 +597,14 @@ impl Response {
             "businessCards" => Some(self.business_cards.is_some()),
             "cl" => Some(self.collisions.is_some()),
             "gw2" => Some(self.gateways.is_some()),
-            "chat" => Some(self.chat.is_some()),
+            "chat" => {
+                let Some(chat) = self.chat.as_ref() else {
+                    return Some(false);
+                };
+                chat.retain_in_value(value);
+
+                Some(true)
+            }
             "emo" => Some(self.emo.is_some()),
             "enhancement" => Some(self.enhancement.is_some()),
             "ev" => Some(self.ev.is_some()),
@@ -643,11 +659,26 @@ pub struct TownData {
 
 #[skip_serializing_none]
 #[serde_as]
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct FightData {
     #[serde_as(as = "Option<BoolFromInt>")]
     pub end_battle: Option<bool>,
+    /// Team of the hero, only sent when the battle starts.
+    #[serde(rename = "myteam")]
+    pub my_team: Option<u8>,
+    /// Warriors taking part in the battle, npcs have negative ids.
+    #[serde_as(as = "Option<HashMap<DisplayFromStr, _>>")]
+    pub w: Option<HashMap<Id, Warrior>>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Warrior {
+    pub name: Option<String>,
+    pub team: Option<u8>,
+    /// Health in percent.
+    pub hpp: Option<u8>,
 }
 
 #[derive(Debug, Serialize_repr, Deserialize_repr, Clone, Copy, PartialEq, Eq)]
@@ -869,6 +900,26 @@ impl Relation {
     }
 }
 
+impl FromStr for Relation {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Relation::*;
+
+        match s {
+            "none" => Ok(None),
+            "friend" => Ok(Friend),
+            "enemy" => Ok(Enemy),
+            "clan" => Ok(Clan),
+            "clan-ally" => Ok(ClanAlly),
+            "clan-enemy" => Ok(ClanEnemy),
+            "fraction-ally" => Ok(FractionAlly),
+            "fraction-enemy" => Ok(FractionEnemy),
+            _ => Err("expected a valid relation name"),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for Relation {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
@@ -1036,13 +1087,38 @@ impl IntoIterator for BusinessCards {
 }
 
 #[skip_serializing_none]
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
 pub(crate) struct ChatMessage {
     code: Option<String>,
     related: Option<Vec<Id>>,
+    /// Missing in system messages.
+    #[serde(rename = "authorBusinessCard")]
+    author: Option<ChatBusinessCard>,
+    /// Only present in private messages.
+    #[serde(rename = "receiverBusinessCard")]
+    receiver: Option<ChatBusinessCard>,
+    text: Option<String>,
+    /// Unix timestamp in seconds.
+    ts: Option<f64>,
 }
 
 impl ChatMessage {
+    pub(crate) fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    pub(crate) fn ts(&self) -> Option<f64> {
+        self.ts
+    }
+
+    pub(crate) fn author_nick(&self) -> Option<&str> {
+        self.author.as_ref()?.nick.as_deref()
+    }
+
+    pub(crate) fn receiver_nick(&self) -> Option<&str> {
+        self.receiver.as_ref()?.nick.as_deref()
+    }
+
     pub(crate) fn get_parsed_code(&self) -> Option<Value> {
         serde_json::from_str(self.code.as_ref()?).ok()
     }
@@ -1109,16 +1185,83 @@ impl From<u64> for MessageId {
     }
 }
 
+/// Business card attached to chat messages, unlike [`BusinessCard`] it carries the nick.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub(crate) struct ChatBusinessCard {
+    nick: Option<String>,
+}
+
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Sequence,
+)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ChatChannelName {
+    Global,
+    Local,
+    Trade,
+    Group,
+    Clan,
+    Personal,
+    System,
+}
+
+impl ChatChannelName {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Local => "local",
+            Self::Trade => "trade",
+            Self::Group => "group",
+            Self::Clan => "clan",
+            Self::Personal => "personal",
+            Self::System => "system",
+        }
+    }
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub(crate) struct SystemChannel {
+pub(crate) struct ChatChannel {
     msg: Option<Vec<ChatMessage>>,
 }
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Channels {
-    system: Option<SystemChannel>,
+    global: Option<ChatChannel>,
+    local: Option<ChatChannel>,
+    trade: Option<ChatChannel>,
+    group: Option<ChatChannel>,
+    clan: Option<ChatChannel>,
+    personal: Option<ChatChannel>,
+    system: Option<ChatChannel>,
+}
+
+impl Channels {
+    fn get(&self, channel_name: ChatChannelName) -> Option<&ChatChannel> {
+        match channel_name {
+            ChatChannelName::Global => self.global.as_ref(),
+            ChatChannelName::Local => self.local.as_ref(),
+            ChatChannelName::Trade => self.trade.as_ref(),
+            ChatChannelName::Group => self.group.as_ref(),
+            ChatChannelName::Clan => self.clan.as_ref(),
+            ChatChannelName::Personal => self.personal.as_ref(),
+            ChatChannelName::System => self.system.as_ref(),
+        }
+    }
+
+    fn get_mut(&mut self, channel_name: ChatChannelName) -> Option<&mut ChatChannel> {
+        match channel_name {
+            ChatChannelName::Global => self.global.as_mut(),
+            ChatChannelName::Local => self.local.as_mut(),
+            ChatChannelName::Trade => self.trade.as_mut(),
+            ChatChannelName::Group => self.group.as_mut(),
+            ChatChannelName::Clan => self.clan.as_mut(),
+            ChatChannelName::Personal => self.personal.as_mut(),
+            ChatChannelName::System => self.system.as_mut(),
+        }
+    }
 }
 
 #[skip_serializing_none]
@@ -1129,7 +1272,59 @@ pub struct Chat {
 
 impl Chat {
     pub(crate) fn get_system_messages(&self) -> Option<&Vec<ChatMessage>> {
-        self.channels.as_ref()?.system.as_ref()?.msg.as_ref()
+        self.get_messages(ChatChannelName::System)
+    }
+
+    pub(crate) fn get_messages(&self, channel_name: ChatChannelName) -> Option<&Vec<ChatMessage>> {
+        self.channels.as_ref()?.get(channel_name)?.msg.as_ref()
+    }
+
+    pub(crate) fn iter_messages(&self) -> impl Iterator<Item = (ChatChannelName, &ChatMessage)> {
+        enum_iterator::all::<ChatChannelName>()
+            .filter_map(|channel_name| Some((channel_name, self.get_messages(channel_name)?)))
+            .flat_map(|(channel_name, messages)| {
+                messages.iter().map(move |message| (channel_name, message))
+            })
+    }
+
+    /// Removes every message for which `f` returns false.
+    pub(crate) fn retain_messages<F>(&mut self, mut f: F)
+    where
+        F: FnMut(ChatChannelName, &ChatMessage) -> bool,
+    {
+        let Some(channels) = self.channels.as_mut() else {
+            return;
+        };
+
+        for channel_name in enum_iterator::all::<ChatChannelName>() {
+            if let Some(messages) = channels
+                .get_mut(channel_name)
+                .and_then(|channel| channel.msg.as_mut())
+            {
+                messages.retain(|message| f(channel_name, message));
+            }
+        }
+    }
+
+    /// Removes the messages missing from `self` out of the raw chat response.
+    fn retain_in_value(&self, value: &mut Value) {
+        for channel_name in enum_iterator::all::<ChatChannelName>() {
+            let pointer = format!("/channels/{}/msg", channel_name.as_str());
+            let Some(raw_messages) = value.pointer_mut(&pointer).and_then(Value::as_array_mut)
+            else {
+                continue;
+            };
+            let messages = self.get_messages(channel_name);
+
+            raw_messages.retain(|raw_message| {
+                // Keep whatever couldn't be parsed, it couldn't have been removed either.
+                let Ok(raw_message) = ChatMessage::deserialize(raw_message) else {
+                    return true;
+                };
+
+                messages.is_some_and(|messages| messages.contains(&raw_message))
+            });
+        }
     }
 }
 
@@ -1184,9 +1379,9 @@ pub struct HeroData {
     // pub cur_battle_set: Option<u32>,
     // pub cur_skill_set: Option<u32>,
     // pub dir: Option<u8>,
-    // pub exp: Option<u64>,
+    pub exp: Option<u64>,
     // pub gender: Option<String>,
-    // pub gold: Option<u64>,
+    pub gold: Option<u64>,
     // pub goldlim: Option<u64>,
     // pub healpower: Option<u32>,
     // pub honor: Option<u32>,
@@ -1229,7 +1424,7 @@ pub struct HeroData {
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
 #[serde(default)]
 pub struct HeroClan {
-    //pub id: Option<Id>,
+    pub id: Option<Id>,
     //pub name: Option<String>,
     //pub rank: Option<u8>,
 }
@@ -1462,7 +1657,7 @@ impl DamageType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub(crate) enum Rarity {
     Common,
     Unique,
@@ -1744,6 +1939,9 @@ impl Item {
 }
 
 pub(crate) trait PeerData {
+    /// Hero's relation to every peer of this type.
+    const RELATION: Relation;
+
     fn id(&self) -> Id;
     fn lvl(&mut self) -> u16;
     fn oplvl(&mut self) -> u16;
@@ -1752,13 +1950,14 @@ pub(crate) trait PeerData {
     fn x(&mut self) -> u8;
     fn y(&mut self) -> u8;
     fn map_name(&mut self) -> String;
-    fn relation(&self) -> Relation;
     fn is_online(&self) -> bool;
 }
 
 macro_rules! make_peer {
     ($peer_name:ident, $relation:expr, { fn is_online(&$this:ident) -> bool $code:block }) => {
         impl PeerData for $peer_name {
+            const RELATION: Relation = $relation;
+
             #[inline]
             fn id(&self) -> Id {
                 self.id
@@ -1799,11 +1998,6 @@ macro_rules! make_peer {
                 std::mem::take(&mut self.map_name)
             }
 
-            #[inline]
-            fn relation(&self) -> Relation {
-                $relation
-            }
-
             #[inline]
             fn is_online(&$this) -> bool $code
         }
@@ -1854,6 +2048,69 @@ impl Serialize for Friend {
     }
 }
 
+/// Enemies are sent in the same format as friends.
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct Enemy(pub Friend);
+
+impl PeerData for Enemy {
+    const RELATION: Relation = Relation::Enemy;
+
+    #[inline]
+    fn id(&self) -> Id {
+        self.0.id()
+    }
+
+    #[inline]
+    fn lvl(&mut self) -> u16 {
+        self.0.lvl()
+    }
+
+    #[inline]
+    fn oplvl(&mut self) -> u16 {
+        self.0.oplvl()
+    }
+
+    #[inline]
+    fn nick(&mut self) -> String {
+        self.0.nick()
+    }
+
+    #[inline]
+    fn prof(&mut self) -> Profession {
+        self.0.prof()
+    }
+
+    #[inline]
+    fn x(&mut self) -> u8 {
+        self.0.x()
+    }
+
+    #[inline]
+    fn y(&mut self) -> u8 {
+        self.0.y()
+    }
+
+    #[inline]
+    fn map_name(&mut self) -> String {
+        self.0.map_name()
+    }
+
+    #[inline]
+    fn is_online(&self) -> bool {
+        self.0.is_online()
+    }
+}
+
+pub fn enemies_de<'de, D>(deserializer: D) -> Result<Option<Vec<Enemy>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let enemies = friends_de(deserializer)?;
+
+    Ok(enemies.map(|enemies| enemies.into_iter().map(Enemy).collect()))
+}
+
 pub fn friends_de<'de, D>(deserializer: D) -> Result<Option<Vec<Friend>>, D::Error>
 where
     D: Deserializer<'de>,
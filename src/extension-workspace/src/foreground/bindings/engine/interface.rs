@@ -37,3 +37,16 @@ extern "C" {
     #[wasm_bindgen(method, getter = "initBanners")]
     pub(crate) fn get_init_banners(this: &Interface) -> Option<Function>;
 }
+
+impl Interface {
+    /// Canvas element the map is drawn on.
+    pub(crate) fn get_game_canvas(&self) -> JsResult<web_sys::HtmlCanvasElement> {
+        let canvas = self.__get_game_canvas()?.get(0);
+
+        if canvas.is_undefined() {
+            return Err(common::err_code!());
+        }
+
+        Ok(canvas.unchecked_into())
+    }
+}
@@ -332,19 +332,3 @@ impl Engine {
     // }
 }
 
-//impl Interface {
-//    pub fn get_game_canvas(&self) -> JsResult<CanvasRenderingContext2d> {
-//        let canvas = self.__get_game_canvas().map_err(map_err!())?.get(0);
-//
-//        if canvas.is_undefined() {
-//            return Err(err_code!());
-//        }
-//
-//        canvas
-//            .unchecked_into::<HtmlCanvasElement>()
-//            .get_context(intern("2d"))
-//            .map_err(map_err!())?
-//            .map(JsCast::unchecked_into)
-//            .ok_or_else(|| err_code!())
-//    }
-//}
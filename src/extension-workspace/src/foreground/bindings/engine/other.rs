@@ -6,7 +6,8 @@ use futures_signals::{
 use wasm_bindgen::JsValue;
 
 use crate::{
-    color_mark::{Color, ColorMark},
+    color_mark::ColorMark,
+    overlay::OverlayColor,
     prelude::*,
 };
 
@@ -225,6 +226,27 @@ impl Other {
         })
     }
 
+    /// Signal of the hero's relation to the player, players from the hero's
+    /// enemy list are enemies even if the game reports no relation.
+    pub fn relation_signal(&self) -> impl Signal<Item = Relation> + use<> {
+        map_ref! {
+            let relation = self.relation.signal(),
+            let on_enemy_list = Peers::get().is_enemy_signal(self.char_id) => {
+                match (*relation, *on_enemy_list) {
+                    (Relation::None, true) => Relation::Enemy,
+                    (relation, _) => relation,
+                }
+            }
+        }
+    }
+
+    /// Signal identifying whether a player is on the hero's enemy list.
+    pub fn enemy_signal(&self) -> impl Signal<Item = bool> + use<> {
+        self.relation_signal()
+            .map(|relation| relation == Relation::Enemy)
+            .dedupe()
+    }
+
     pub fn coords_signal(&self) -> impl Signal<Item = (u8, u8)> + use<> {
         map_ref! {
             let x = self.x.signal(),
@@ -238,7 +260,7 @@ impl Other {
         self.wanted.get()
     }
 
-    pub(crate) fn init_color_mark(&self, color: Color, addon_name: AddonName) -> JsResult<()> {
+    pub(crate) fn init_color_mark(&self, color: OverlayColor, addon_name: AddonName) -> JsResult<()> {
         ColorMark::init_with_player_data(color, addon_name, self)
     }
 }
@@ -1,6 +1,6 @@
 use std::fmt;
 
-use common::err_code;
+use common::{err_code, tr};
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::JsValue;
 
@@ -149,38 +149,38 @@ impl ItemClass {
 
     pub fn to_str_pretty(self) -> &'static str {
         match self {
-            ItemClass::OneHandWeapon => "Broń jednoręczna",
-            ItemClass::TwoHandWeapon => "Broń dwuręczna",
-            ItemClass::OneAndHalfHandWeapon => "Broń półtoraręczna",
-            ItemClass::DistanceWeapon => "Broń dystansowa",
-            ItemClass::HelpWeapon => "Broń pomocnicza",
-            ItemClass::WandWeapon => "Różdżki magiczne",
-            ItemClass::OrbWeapon => "Orby magiczne",
-            ItemClass::Armor => "Zbroje",
-            ItemClass::Helmet => "Hełmy",
-            ItemClass::Boots => "Buty",
-            ItemClass::Gloves => "Rękawice",
-            ItemClass::Ring => "Pierścienie",
-            ItemClass::Necklace => "Naszyjniki",
-            ItemClass::Shield => "Tarcze",
-            ItemClass::Neutral => "Neutralne",
-            ItemClass::Consume => "Konsumpcyjne",
-            ItemClass::Gold => "Złoto",
-            ItemClass::Keys => "Klucze",
-            ItemClass::Quest => "Questowe",
-            ItemClass::Renewable => "Odnawialne", // ???
-            ItemClass::Arrows => "Strzały",
-            ItemClass::Talisman => "Talizmany",
-            ItemClass::Book => "Książki",
-            ItemClass::Bag => "Torby",
-            ItemClass::Bless => "Błogosławieństwa",
-            ItemClass::Upgrade => "Ulepszenia",
-            ItemClass::Recipe => "Recepty",
-            ItemClass::Coinage => "Waluta",
-            ItemClass::Quiver => "Strzały", // In game it's also denoted like that ???
-            ItemClass::Outfits => "Stroje",
-            ItemClass::Pets => "Maskotki",
-            ItemClass::Teleports => "Teleporty",
+            ItemClass::OneHandWeapon => tr!(ItemClassOneHandWeapon),
+            ItemClass::TwoHandWeapon => tr!(ItemClassTwoHandWeapon),
+            ItemClass::OneAndHalfHandWeapon => tr!(ItemClassOneAndHalfHandWeapon),
+            ItemClass::DistanceWeapon => tr!(ItemClassDistanceWeapon),
+            ItemClass::HelpWeapon => tr!(ItemClassHelpWeapon),
+            ItemClass::WandWeapon => tr!(ItemClassWandWeapon),
+            ItemClass::OrbWeapon => tr!(ItemClassOrbWeapon),
+            ItemClass::Armor => tr!(ItemClassArmor),
+            ItemClass::Helmet => tr!(ItemClassHelmet),
+            ItemClass::Boots => tr!(ItemClassBoots),
+            ItemClass::Gloves => tr!(ItemClassGloves),
+            ItemClass::Ring => tr!(ItemClassRing),
+            ItemClass::Necklace => tr!(ItemClassNecklace),
+            ItemClass::Shield => tr!(ItemClassShield),
+            ItemClass::Neutral => tr!(ItemClassNeutral),
+            ItemClass::Consume => tr!(ItemClassConsume),
+            ItemClass::Gold => tr!(ItemClassGold),
+            ItemClass::Keys => tr!(ItemClassKeys),
+            ItemClass::Quest => tr!(ItemClassQuest),
+            ItemClass::Renewable => tr!(ItemClassRenewable), // ???
+            ItemClass::Arrows => tr!(ItemClassArrows),
+            ItemClass::Talisman => tr!(ItemClassTalisman),
+            ItemClass::Book => tr!(ItemClassBook),
+            ItemClass::Bag => tr!(ItemClassBag),
+            ItemClass::Bless => tr!(ItemClassBless),
+            ItemClass::Upgrade => tr!(ItemClassUpgrade),
+            ItemClass::Recipe => tr!(ItemClassRecipe),
+            ItemClass::Coinage => tr!(ItemClassCoinage),
+            ItemClass::Quiver => tr!(ItemClassArrows), // In game it's also denoted like that ???
+            ItemClass::Outfits => tr!(ItemClassOutfits),
+            ItemClass::Pets => tr!(ItemClassPets),
+            ItemClass::Teleports => tr!(ItemClassTeleports),
         }
     }
 }
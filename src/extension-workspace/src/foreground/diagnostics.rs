@@ -0,0 +1,203 @@
+//! Opt-in error reporting. Errors are batched together with a short trail of
+//! events preceding them and periodically sent to the backend through the
+//! background port.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use common::messaging::prelude::*;
+use futures::StreamExt;
+use futures_signals::signal::{Mutable, SignalExt};
+
+use crate::prelude::*;
+
+/// How often pending errors are sent, fatal errors are sent right away.
+const FLUSH_INTERVAL: u32 = 5 * 60_000;
+/// Highest amount of distinct errors in a single report, repeated errors only
+/// increase the count of the existing entry.
+const MAX_ERRORS: usize = 50;
+const MAX_EVENTS: usize = 20;
+const MAX_EVENT_LENGTH: usize = 120;
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    /// Errors logged since the page was loaded, counted even when reporting
+    /// is disabled since they're displayed in the popup.
+    static ERROR_COUNT: Cell<u32> = const { Cell::new(0) };
+    static PENDING_ERRORS: RefCell<Vec<ReportedError>> = const { RefCell::new(Vec::new()) };
+    /// Last report sent, kept in case it gets rejected.
+    static SENT_REPORT: RefCell<Option<DiagnosticsReport>> = const { RefCell::new(None) };
+    static EVENTS: RefCell<VecDeque<String>> = RefCell::new(VecDeque::with_capacity(MAX_EVENTS));
+}
+
+pub(crate) struct Diagnostics;
+
+impl Diagnostics {
+    /// Keeps the reporting state in sync with the manager setting and starts
+    /// the flush interval.
+    pub(crate) fn init(enabled: &Mutable<bool>) {
+        let future = enabled.signal().to_stream().for_each(|enabled| async move {
+            ENABLED.set(enabled);
+
+            // Nothing collected before opting out should be sent afterwards.
+            if !enabled {
+                PENDING_ERRORS.with_borrow_mut(Vec::clear);
+                EVENTS.with_borrow_mut(VecDeque::clear);
+                SENT_REPORT.set(None);
+            }
+        });
+
+        wasm_bindgen_futures::spawn_local(future);
+        wasm_bindgen_futures::spawn_local(Self::start_flush_interval());
+    }
+
+    pub(crate) fn enabled() -> bool {
+        ENABLED.get()
+    }
+
+    pub(crate) fn error_count() -> u32 {
+        ERROR_COUNT.get()
+    }
+
+    pub(crate) fn record_error(error: &MdmaError) {
+        ERROR_COUNT.set(ERROR_COUNT.get().saturating_add(1));
+
+        if !Self::enabled() {
+            return;
+        }
+
+        Self::add_pending(ReportedError {
+            code: error.code,
+            kind: error.kind.as_str().to_string(),
+            fatal: error.is_fatal(),
+            count: 1,
+        });
+
+        if error.is_fatal() {
+            wasm_bindgen_futures::spawn_local(async {
+                // Reporting errors through `console_error!` would record them again.
+                if let Err(err) = Self::flush().await {
+                    debug_log!(err);
+                }
+            });
+        }
+    }
+
+    fn add_pending(error: ReportedError) {
+        PENDING_ERRORS.with_borrow_mut(|errors| {
+            match errors
+                .iter_mut()
+                .find(|reported| reported.code == error.code && reported.kind == error.kind)
+            {
+                Some(reported) => {
+                    reported.count = reported.count.saturating_add(error.count);
+                    reported.fatal |= error.fatal;
+                }
+                None if errors.len() < MAX_ERRORS => errors.push(error),
+                None => {}
+            }
+        });
+    }
+
+    /// Puts the errors and events of a report rejected by the backend back,
+    /// so they're sent with the next flush. Reports sent by other game tabs
+    /// are ignored.
+    pub(crate) fn on_rejected(report: Option<DiagnosticsReport>) {
+        let Some(report) = report else {
+            return;
+        };
+        if !Self::enabled() || SENT_REPORT.with_borrow(|sent| sent.as_ref() != Some(&report)) {
+            return;
+        }
+
+        SENT_REPORT.set(None);
+        report.errors.into_iter().for_each(Self::add_pending);
+        EVENTS.with_borrow_mut(|events| {
+            // Events recorded since then are newer than the rejected ones.
+            for event in report.events.into_iter().rev() {
+                if events.len() == MAX_EVENTS {
+                    break;
+                }
+                events.push_front(event);
+            }
+        });
+    }
+
+    /// Adds an event to the trail sent along with the errors, only the last
+    /// [`MAX_EVENTS`] are kept.
+    pub(crate) fn record_event(event: &str) {
+        if !Self::enabled() {
+            return;
+        }
+
+        let event = match event.char_indices().nth(MAX_EVENT_LENGTH) {
+            Some((index, _)) => &event[..index],
+            None => event,
+        };
+
+        EVENTS.with_borrow_mut(|events| {
+            if events.len() == MAX_EVENTS {
+                events.pop_front();
+            }
+            events.push_back(event.to_string());
+        });
+    }
+
+    /// Sends the pending errors to the background, does nothing if there are
+    /// none or the port isn't connected yet.
+    pub(crate) async fn flush() -> JsResult<()> {
+        if !Self::enabled() || Port::try_get().is_none() {
+            return Ok(());
+        }
+
+        let errors = PENDING_ERRORS.with_borrow_mut(std::mem::take);
+
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        let report = DiagnosticsReport {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            interface: match cfg!(feature = "ni") {
+                true => "ni",
+                false => "si",
+            }
+            .to_string(),
+            addons: Self::active_addons(),
+            errors,
+            events: EVENTS.with_borrow_mut(|events| events.drain(..).collect()),
+        };
+
+        SENT_REPORT.set(Some(report.clone()));
+
+        Port::send(
+            &Message::builder(Task::Diagnostics, Target::Background, MessageKind::Request)
+                .diagnostics(report)
+                .build(),
+        )
+        .await
+    }
+
+    fn active_addons() -> Vec<String> {
+        let Some(addons) = Addons::try_get() else {
+            return Vec::new();
+        };
+
+        addons
+            .iter()
+            .flatten()
+            .filter(|(_, addon_data)| addon_data.active.get())
+            .map(|(addon_name, _)| addon_name.as_str().to_string())
+            .collect()
+    }
+
+    async fn start_flush_interval() {
+        loop {
+            delay(FLUSH_INTERVAL).await;
+
+            if let Err(err) = Self::flush().await {
+                debug_log!(err);
+            }
+        }
+    }
+}
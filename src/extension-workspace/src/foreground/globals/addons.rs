@@ -1,6 +1,12 @@
 use std::{cell::RefCell, sync::OnceLock};
 
-use common::err_code;
+use common::{
+    addon_dashboard::{AddonDashboard, AddonStatus, AddonToggle},
+    err_code,
+    i18n::Msg,
+    messaging::prelude::*,
+    tr,
+};
 use futures::stream::StreamExt;
 use futures_signals::signal::{Mutable, Signal, SignalExt};
 use serde_json::{Value, json};
@@ -8,6 +14,8 @@ use wasm_bindgen::intern;
 use web_sys::HtmlElement;
 
 use crate::{
+    diagnostics::Diagnostics,
+    hotkeys::{Hotkey, HotkeyAction, Hotkeys},
     s,
     utils::{JsResult, UnwrapJsExt},
 };
@@ -21,6 +29,11 @@ use super::{
 
 static ADDONS: OnceLock<Addons> = OnceLock::new();
 
+thread_local! {
+    /// Addons which returned an error from their `init`.
+    static FAILED_ADDONS: RefCell<Vec<AddonName>> = const { RefCell::new(Vec::new()) };
+}
+
 macro_rules! init_window_stream {
     ($addon_window:expr, $stream_key:expr, $addon_name:expr) => {
         Self::init_stream(
@@ -106,6 +119,37 @@ macro_rules! init_window_stream {
     };
 }
 
+macro_rules! init_hotkey_stream {
+    ($hotkey:expr, $stream_key:expr, $addon_name:expr) => {
+        Self::init_stream(
+            $hotkey.value.signal_cloned(),
+            |value| {
+                json!({
+                    intern($crate::s!("hotkeys")): {
+                        intern($crate::s!($stream_key)): {
+                            intern($crate::s!("value")): value,
+                        }
+                    }
+                })
+            },
+            $addon_name,
+        );
+        Self::init_stream(
+            $hotkey.active.signal(),
+            |active| {
+                json!({
+                    intern($crate::s!("hotkeys")): {
+                        intern($crate::s!($stream_key)): {
+                            intern($crate::s!("active")): active,
+                        }
+                    }
+                })
+            },
+            $addon_name,
+        );
+    };
+}
+
 macro_rules! create_addons {
     (
         free { $($free_addon_fieldname:ident),+ $(,)? },
@@ -126,7 +170,18 @@ macro_rules! __inner_create_addons {
             () => {
                 $(if $crate::globals::addons::Addons::get()[$crate::globals::addons::AddonName::$variant].is_some() {
                     common::debug_log!(stringify!($field));
-                    $crate::addons::$field::init()?;
+                    // A single broken addon shouldn't stop the rest of the manager from loading,
+                    // errors which weren't classified by `err!` still stop it as they used to.
+                    if let Err(err) = $crate::addons::$field::init() {
+                        let err = ::common::error::MdmaError::from(err).context(stringify!($field));
+                        $crate::globals::addons::Addons::mark_failed(
+                            $crate::globals::addons::AddonName::$variant,
+                        );
+                        if err.is_fatal() || err.kind == ::common::error::ErrorKind::Unknown {
+                            return Err(err.fatal().into());
+                        }
+                        $crate::console_error!(err);
+                    }
                 })+
             };
         }
@@ -145,11 +200,26 @@ macro_rules! __inner_create_addons {
         }
 
         impl AddonName {
+            pub(crate) const ALL: &[Self] = &[$(Self::$variant),+];
+
             pub(crate) const fn key_str(&self) -> &'static str {
                 match self {
                     $(Self::$variant => stringify!($field),)+
                 }
             }
+
+            pub(crate) fn from_key(key: &str) -> Option<Self> {
+                match key {
+                    $(stringify!($field) => Some(Self::$variant),)+
+                    _ => None,
+                }
+            }
+
+            pub(crate) const fn requires_premium(self) -> bool {
+                match self {
+                    $(Self::$variant => requires_premium!($status),)+
+                }
+            }
         }
 
         #[derive(Debug)]
@@ -204,6 +274,15 @@ macro_rules! has_access {
     };
 }
 
+macro_rules! requires_premium {
+    (free) => {
+        false
+    };
+    (premium) => {
+        true
+    };
+}
+
 create_addons! {
     free {
         accept_group,
@@ -214,6 +293,9 @@ create_addons! {
         smart_forge,
         znacznik,
         grounded_mob_timers,
+        session_tracker,
+        chat_history,
+        clan_roster,
     },
     premium {
         adaptive_builds,
@@ -228,6 +310,11 @@ impl Addons {
         ADDONS.wait()
     }
 
+    /// Returns `None` if the addons weren't initialized yet.
+    pub(crate) fn try_get() -> Option<&'static Addons> {
+        ADDONS.get()
+    }
+
     pub fn get_addon(addon_name: AddonName) -> Option<&'static AddonData> {
         Self::get()[addon_name].as_ref()
     }
@@ -284,26 +371,132 @@ impl Addons {
     }
 }
 
+impl Addons {
+    pub(crate) fn mark_failed(addon_name: AddonName) {
+        FAILED_ADDONS.with_borrow_mut(|failed| failed.push(addon_name));
+    }
+
+    /// Snapshot of the addons displayed on the popup dashboard.
+    fn dashboard(&self) -> AddonDashboard {
+        let addons = AddonName::ALL
+            .iter()
+            .map(|&addon_name| {
+                let addon_data = self[addon_name].as_ref();
+
+                AddonStatus {
+                    key: addon_name.key_str().to_owned(),
+                    name: addon_name.as_str().to_owned(),
+                    premium: addon_name.requires_premium(),
+                    available: addon_data.is_some(),
+                    active: addon_data.is_some_and(|data| data.active.get()),
+                    failed: FAILED_ADDONS.with_borrow(|failed| failed.contains(&addon_name)),
+                }
+            })
+            .collect();
+
+        AddonDashboard {
+            addons,
+            interface: match cfg!(feature = "ni") {
+                true => "ni",
+                false => "si",
+            }
+            .to_owned(),
+            errors: Diagnostics::error_count(),
+            game_connected: true,
+        }
+    }
+
+    /// Sends the dashboard to the background, does nothing if the addons
+    /// weren't initialized yet.
+    pub(crate) async fn report_dashboard() -> JsResult<()> {
+        let Some(addons) = Self::try_get() else {
+            return Ok(());
+        };
+
+        Port::send(
+            &Message::builder(
+                Task::AddonDashboard,
+                Target::Background,
+                MessageKind::Request,
+            )
+            .addon_dashboard(addons.dashboard())
+            .character(Hero::get().character())
+            .build(),
+        )
+        .await
+    }
+
+    /// Reports the dashboard right away and again on every addon toggle.
+    pub(crate) fn init_dashboard() {
+        for (_, addon_data) in Self::get().iter().flatten() {
+            let future = addon_data
+                .active
+                .signal()
+                .to_stream()
+                .skip(1)
+                .for_each(|_| async {
+                    if let Err(err_code) = Self::report_dashboard().await {
+                        crate::console_error!(err_code);
+                    }
+                });
+
+            wasm_bindgen_futures::spawn_local(future);
+        }
+
+        wasm_bindgen_futures::spawn_local(async {
+            if let Err(err_code) = Self::report_dashboard().await {
+                crate::console_error!(err_code);
+            }
+        });
+    }
+
+    /// Applies the addon toggles made in the popup, a message without them
+    /// asks for a fresh report instead.
+    pub(crate) fn on_dashboard_message(toggles: Option<Vec<AddonToggle>>) {
+        let Some(toggles) = toggles else {
+            return wasm_bindgen_futures::spawn_local(async {
+                if let Err(err_code) = Self::report_dashboard().await {
+                    crate::console_error!(err_code);
+                }
+            });
+        };
+        let Some(addons) = Self::try_get() else {
+            return;
+        };
+
+        for toggle in toggles {
+            if let Some(addon_data) =
+                AddonName::from_key(&toggle.key).and_then(|addon_name| addons[addon_name].as_ref())
+            {
+                addon_data.active.set_neq(toggle.active);
+            }
+        }
+    }
+}
+
 impl AddonName {
-    pub(crate) const fn as_str(&self) -> &'static str {
+    pub(crate) fn as_str(&self) -> &'static str {
         use AddonName::*;
 
         match self {
-            AcceptGroup => "Akceptowanie Zaproszeń Do Grupy",
-            AcceptSummon => "Akceptowanie Przywołań",
-            AdaptiveBuilds => "Adaptacyjne Zestawy Do Walki",
+            AcceptGroup => tr!(AddonAcceptGroup),
+            AcceptSummon => tr!(AddonAcceptSummon),
+            AdaptiveBuilds => tr!(AddonAdaptiveBuilds),
             #[cfg(feature = "antyduch")]
-            AntyDuch => "Anty Duch",
+            AntyDuch => tr!(AddonAntyDuch),
             // AutoHeal => "AutoHeal",
-            BetterGroupInvites => "Zapraszanie Do Grupy",
-            BetterWhoIsHere => "Gracze Na Mapie",
-            BetterMessages => "Poprawione Powiadomienia",
-            Kastrat => "Kastrat",
-            OnlinePeers => "Rówieśnicy Online",
-            SmartForge => "Super Rzemieślnik",
-            Znacznik => "Znacznik",
-            HeroNeon => "Neon Bohatera",
-            GroundedMobTimers => "Timery Mobów Na Ziemi"
+            BetterGroupInvites => tr!(AddonBetterGroupInvites),
+            BetterWhoIsHere => tr!(AddonBetterWhoIsHere),
+            BetterMessages => tr!(AddonBetterMessages),
+            Kastrat => tr!(AddonKastrat),
+            OnlinePeers => tr!(AddonOnlinePeers),
+            SmartForge => tr!(AddonSmartForge),
+            Znacznik => tr!(AddonZnacznik),
+            HeroNeon => tr!(AddonHeroNeon),
+            GroundedMobTimers => tr!(AddonGroundedMobTimers),
+            SessionTracker => tr!(AddonSessionTracker),
+            ChatHistory => tr!(AddonChatHistory),
+            ClanRoster => tr!(AddonClanRoster),
         }
     }
 
@@ -326,7 +519,10 @@ impl AddonName {
             SmartForge => "smart-forge",
             Znacznik => "znacznik",
             HeroNeon => "hero-neon",
-            GroundedMobTimers => "grounded-mob-timers"
+            GroundedMobTimers => "grounded-mob-timers",
+            SessionTracker => "session-tracker",
+            ChatHistory => "chat-history",
+            ClanRoster => "clan-roster",
         }
     }
 
@@ -339,34 +535,40 @@ impl AddonName {
                 AcceptSummon => None,
                 AdaptiveBuilds => None,
                 #[cfg(feature = "antyduch")]
-                AntyDuch => Some("Anty Duch"),
+                AntyDuch => Some(tr!(AddonAntyDuch)),
                 // AutoHeal => "auto-heal",
-                BetterGroupInvites => Some("Zapraszanie Do Grupy"),
-                BetterWhoIsHere => Some("Gracze Na Mapie"),
-                BetterMessages => Some("Poprawione Powiadomienia"),
-                Kastrat => Some("Kastrat"),
-                OnlinePeers => Some("Rówieśnicy Online"),
-                SmartForge => Some("Super Rzemieślnik"),
-                Znacznik => Some("Znacznik"),
-                HeroNeon => Some("Neon Bohatera"),
+                BetterGroupInvites => Some(tr!(AddonBetterGroupInvites)),
+                BetterWhoIsHere => Some(tr!(AddonBetterWhoIsHere)),
+                BetterMessages => Some(tr!(AddonBetterMessages)),
+                Kastrat => Some(tr!(AddonKastrat)),
+                OnlinePeers => Some(tr!(AddonOnlinePeers)),
+                SmartForge => Some(tr!(AddonSmartForge)),
+                Znacznik => Some(tr!(AddonZnacznik)),
+                HeroNeon => Some(tr!(AddonHeroNeon)),
                 GroundedMobTimers => None,
+                SessionTracker => Some(tr!(AddonSessionTracker)),
+                ChatHistory => Some(tr!(AddonChatHistory)),
+                ClanRoster => Some(tr!(AddonClanRoster)),
             },
             WindowType::SettingsWindow => match self {
-                AcceptGroup => Some("Konfiguracja Akceptowania Zaproszeń Do Grup"),
-                AcceptSummon => Some("Konfiguracja Akceptowania Przywołań"),
-                AdaptiveBuilds => Some("Konfiguracja Adaptacyjnych Zestawów Do Walki"),
+                AcceptGroup => Some(tr!(AddonSettingsAcceptGroup)),
+                AcceptSummon => Some(tr!(AddonSettingsAcceptSummon)),
+                AdaptiveBuilds => Some(tr!(AddonSettingsAdaptiveBuilds)),
                 #[cfg(feature = "antyduch")]
-                AntyDuch => Some("Konfiguracja Anty Ducha"),
+                AntyDuch => Some(tr!(AddonSettingsAntyDuch)),
                 // AutoHeal => "auto-heal",
-                BetterGroupInvites => Some("Konfiguracja Wysyłania Zaproszeń Do Grup"),
-                BetterWhoIsHere => Some("Konfiguracja Graczy Na Mapie"),
-                BetterMessages => None,
-                Kastrat => Some("Konfiguracja Kastrata"),
-                OnlinePeers => Some("Konfiguracja Rówieśników Online"),
-                SmartForge => Some("Konfiguracja Super Rzemieślnika"),
+                BetterGroupInvites => Some(tr!(AddonSettingsBetterGroupInvites)),
+                BetterWhoIsHere => Some(tr!(AddonSettingsBetterWhoIsHere)),
+                BetterMessages => Some(tr!(AddonSettingsBetterMessages)),
+                Kastrat => Some(tr!(AddonSettingsKastrat)),
+                OnlinePeers => Some(tr!(AddonSettingsOnlinePeers)),
+                SmartForge => Some(tr!(AddonSettingsSmartForge)),
                 Znacznik => None,
                 HeroNeon => None,
-                GroundedMobTimers => Some("Konfiguracja Timerów Mobów Na Ziemi")
+                GroundedMobTimers => Some(tr!(AddonSettingsGroundedMobTimers)),
+                SessionTracker => None,
+                ChatHistory => Some(tr!(AddonSettingsChatHistory)),
+                ClanRoster => Some(tr!(AddonSettingsClanRoster)),
             },
         };
 
@@ -374,6 +576,79 @@ impl AddonName {
     }
 }
 
+/// How well an addon works on the interface the manager was built for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InterfaceSupport {
+    Full,
+    /// Contains a description of the missing functionality.
+    Partial(Msg),
+}
+
+impl InterfaceSupport {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => tr!(InterfaceSupportFull),
+            Self::Partial(_) => tr!(InterfaceSupportPartial),
+        }
+    }
+}
+
+impl AddonName {
+    /// Every addon is listed on its own, so that new ones have to be checked
+    /// on both interfaces.
+    #[cfg(feature = "ni")]
+    pub(crate) const fn interface_support(self) -> InterfaceSupport {
+        use AddonName::*;
+
+        match self {
+            AcceptGroup => InterfaceSupport::Full,
+            AcceptSummon => InterfaceSupport::Full,
+            AdaptiveBuilds => InterfaceSupport::Full,
+            #[cfg(feature = "antyduch")]
+            AntyDuch => InterfaceSupport::Full,
+            BetterGroupInvites => InterfaceSupport::Full,
+            BetterWhoIsHere => InterfaceSupport::Full,
+            BetterMessages => InterfaceSupport::Full,
+            Kastrat => InterfaceSupport::Full,
+            OnlinePeers => InterfaceSupport::Full,
+            SmartForge => InterfaceSupport::Full,
+            Znacznik => InterfaceSupport::Full,
+            HeroNeon => InterfaceSupport::Full,
+            GroundedMobTimers => InterfaceSupport::Full,
+            SessionTracker => InterfaceSupport::Full,
+            ChatHistory => InterfaceSupport::Full,
+            ClanRoster => InterfaceSupport::Full,
+        }
+    }
+
+    /// Every addon is listed on its own, so that new ones have to be checked
+    /// on both interfaces.
+    #[cfg(not(feature = "ni"))]
+    pub(crate) const fn interface_support(self) -> InterfaceSupport {
+        use AddonName::*;
+
+        match self {
+            AcceptGroup => InterfaceSupport::Full,
+            AcceptSummon => InterfaceSupport::Full,
+            AdaptiveBuilds => InterfaceSupport::Full,
+            #[cfg(feature = "antyduch")]
+            AntyDuch => InterfaceSupport::Full,
+            BetterGroupInvites => InterfaceSupport::Full,
+            BetterWhoIsHere => InterfaceSupport::Partial(Msg::InterfaceSupportBetterWhoIsHere),
+            BetterMessages => InterfaceSupport::Full,
+            Kastrat => InterfaceSupport::Partial(Msg::InterfaceSupportKastrat),
+            OnlinePeers => InterfaceSupport::Full,
+            SmartForge => InterfaceSupport::Partial(Msg::InterfaceSupportSmartForge),
+            Znacznik => InterfaceSupport::Partial(Msg::InterfaceSupportZnacznik),
+            HeroNeon => InterfaceSupport::Full,
+            GroundedMobTimers => InterfaceSupport::Full,
+            SessionTracker => InterfaceSupport::Full,
+            ChatHistory => InterfaceSupport::Full,
+            ClanRoster => InterfaceSupport::Full,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct AddonWindowDetails {
     pub active: Mutable<bool>,
@@ -431,11 +706,35 @@ impl AddonWindowDetails {
     }
 }
 
+#[derive(Debug)]
+pub struct AddonHotkeys {
+    pub(crate) open_window: Hotkey,
+    pub(crate) toggle: Hotkey,
+}
+
+impl AddonHotkeys {
+    fn new(config: &Value, addon_name: AddonName) -> Self {
+        let hotkeys_value = &config[intern(s!("hotkeys"))];
+        let this = Self {
+            open_window: Hotkey::from_config(&hotkeys_value[intern(s!("open_window"))]),
+            toggle: Hotkey::from_config(&hotkeys_value[intern(s!("toggle"))]),
+        };
+
+        Hotkeys::register(HotkeyAction::OpenWindow(addon_name), &this.open_window);
+        Hotkeys::register(HotkeyAction::ToggleAddon(addon_name), &this.toggle);
+
+        this
+    }
+}
+
 #[derive(Debug)]
 pub struct AddonData {
     pub active: Mutable<bool>,
     pub active_settings_window: AddonWindowDetails,
     pub settings_window: AddonWindowDetails,
+    /// Hotkeys available for every addon, addon specific ones are a part of
+    /// the addon settings.
+    pub hotkeys: AddonHotkeys,
     settings: Value,
 }
 
@@ -446,11 +745,13 @@ impl AddonData {
             AddonWindowDetails::new(&config, addon_name, WindowType::AddonWindow);
         let settings_window =
             AddonWindowDetails::new(&config, addon_name, WindowType::SettingsWindow);
+        let hotkeys = AddonHotkeys::new(&config, addon_name);
 
         let addon_data = Self {
             active,
             active_settings_window,
             settings_window,
+            hotkeys,
             settings: config,
         };
 
@@ -475,6 +776,10 @@ impl AddonData {
         init_window_stream!(self.active_settings_window, "active_settings", addon_name);
 
         init_window_stream!(self.settings_window, "settings", addon_name);
+
+        init_hotkey_stream!(self.hotkeys.open_window, "open_window", addon_name);
+
+        init_hotkey_stream!(self.hotkeys.toggle, "toggle", addon_name);
     }
 
     fn init_stream<T: serde::Serialize + 'static>(
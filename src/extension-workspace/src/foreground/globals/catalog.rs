@@ -0,0 +1,74 @@
+use std::cell::Cell;
+
+use common::{
+    catalog::{CatalogEntry, GameCatalog},
+    debug_log,
+    messaging::prelude::*,
+};
+
+use crate::utils::{JsResult, UnwrapJsExt};
+
+use super::port::Port;
+
+thread_local! {
+    static CATALOG: Cell<Option<&'static GameCatalog>> = const { Cell::new(None) };
+}
+
+/// Titans, colossi and elites II along with the maps they can be found on.
+///
+/// Starts out with the catalog bundled with the extension, which gets replaced
+/// if the backend serves a newer one.
+pub struct Catalog;
+
+impl Catalog {
+    pub fn get() -> &'static GameCatalog {
+        if let Some(catalog) = CATALOG.get() {
+            return catalog;
+        }
+
+        let catalog: &'static GameCatalog = Box::leak(Box::new(GameCatalog::bundled().unwrap_js()));
+        CATALOG.set(Some(catalog));
+
+        catalog
+    }
+
+    pub fn by_map_id(map_id: i32) -> Option<&'static CatalogEntry> {
+        Self::get().by_map_id(map_id)
+    }
+
+    pub fn by_map_name(map_name: &str) -> Option<&'static CatalogEntry> {
+        Self::get().by_map_name(map_name)
+    }
+
+    pub fn is_colossus(warrior_type: i32) -> bool {
+        Self::get().is_colossus(warrior_type)
+    }
+
+    /// Asks the backend for its catalog, the response is handled by
+    /// [`Catalog::on_refresh`].
+    pub(super) async fn refresh() -> JsResult<()> {
+        Port::send(&Message::new(
+            Task::Catalog,
+            Target::Background,
+            MessageKind::Request,
+        ))
+        .await
+    }
+
+    /// Replaces the catalog if the backend served a newer one. Addons query
+    /// the catalog whenever they need it, so there's nothing to notify.
+    pub(super) fn on_refresh(catalog: Option<GameCatalog>) {
+        let Some(catalog) = catalog else {
+            return;
+        };
+
+        if catalog.version <= Self::get().version {
+            return;
+        }
+
+        debug_log!(@f "Updated the game catalog to version {}.", catalog.version);
+
+        // Replaced catalogs are leaked, since they can still be borrowed.
+        CATALOG.set(Some(Box::leak(Box::new(catalog))));
+    }
+}
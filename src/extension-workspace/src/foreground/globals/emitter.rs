@@ -25,8 +25,10 @@ const MESSAGE_INTERCEPTORS_CAPACITY: usize = 1;
 pub enum EmitterEvent {
     Artisanship,
     Ask,
+    Chat,
     Emo,
     Enhancement,
+    Fight,
     Friends,
     Hero,
     Item,
@@ -239,8 +241,10 @@ impl Emitter {
             match event {
                 Artisanship => socket_response.artisanship.is_some(),
                 Ask => socket_response.ask.is_some(),
+                Chat => socket_response.chat.is_some(),
                 Emo => socket_response.emo.is_some(),
                 Enhancement => socket_response.enhancement.is_some(),
+                Fight => socket_response.f.is_some(),
                 Friends => socket_response.friends.is_some(),
                 Hero => socket_response.h.is_some(),
                 Item => socket_response.item.is_some(),
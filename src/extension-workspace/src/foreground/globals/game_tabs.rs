@@ -0,0 +1,187 @@
+//! Coordination with the other game tabs open in the browser, e.g. alts played
+//! next to the main character. Signals are relayed by the background.
+
+use common::{
+    err_code,
+    game_tabs::{TabBroadcast, TabHero, TabSignal},
+    messaging::prelude::*,
+    tr,
+};
+use futures::StreamExt;
+use futures_signals::{
+    map_ref,
+    signal::{self, Mutable, Signal, SignalExt},
+};
+
+use crate::{
+    bindings::{
+        engine::communication::{self, FightData},
+        message,
+    },
+    utils::JsResult,
+};
+
+use super::{
+    GlobalBTreeMap,
+    emitter::{Emitter, EmitterEvent},
+    hero::Hero,
+    party::PartyBTreeMap as Party,
+    port::Port,
+    world_config::WorldConfig,
+};
+
+/// Fights starting this long after the hero attacked someone were started by
+/// the hero.
+const OWN_ATTACK_MS: f64 = 5_000.0;
+
+pub struct GameTabs;
+
+impl GameTabs {
+    /// Other tabs are told about attacks on the hero only if `attack_alerts`
+    /// is enabled.
+    pub(crate) fn init(attack_alerts: &Mutable<bool>) -> JsResult<()> {
+        // Signals notify after the whole response got dispatched, so the world
+        // is known by the time the nick is. The leader answers the invite
+        // requests, so the tab is identified again once it changes.
+        let future = map_ref! {
+            let nick_known = Hero::get().nick.signal_ref(|nick| !nick.is_empty()),
+            let _leads_party = Self::leads_party_signal() =>
+            *nick_known
+        }
+        .to_stream()
+        .for_each(|nick_known| async move {
+            if !nick_known {
+                return;
+            }
+            if let Err(err_code) = Self::identify().await {
+                console_error!(err_code);
+            }
+        });
+        wasm_bindgen_futures::spawn_local(future);
+
+        let attack_alerts = attack_alerts.clone();
+        Emitter::register_on(EmitterEvent::Fight, move |socket_response| {
+            let attacker = socket_response
+                .f
+                .as_ref()
+                .and_then(Self::opposing_player)
+                .filter(|_| {
+                    attack_alerts.get() && !communication::attack_sent_within(OWN_ATTACK_MS)
+                });
+
+            Box::pin(async move {
+                match attacker {
+                    Some(attacker) => Self::broadcast(TabSignal::UnderAttack { attacker }).await,
+                    None => Ok(()),
+                }
+            })
+        })?;
+
+        Ok(())
+    }
+
+    fn tab_hero() -> Option<TabHero> {
+        let hero = Hero::try_get()?;
+        let nick = hero.nick.get_cloned();
+        let world = WorldConfig::world_name();
+
+        if nick.is_empty() || world.is_empty() {
+            return None;
+        }
+
+        let leads_party = Party::get()
+            .lock_ref()
+            .get(&hero.char_id)
+            .is_some_and(|member| member.commander.get());
+
+        Some(TabHero {
+            account_id: hero.account_string.clone(),
+            char_id: hero.char_id_string.clone(),
+            nick,
+            world,
+            leads_party,
+        })
+    }
+
+    /// Whether the hero leads its party, `false` outside of a party.
+    fn leads_party_signal() -> impl Signal<Item = bool> {
+        Party::get()
+            .signal_map_cloned()
+            .key_cloned(Hero::get().char_id)
+            .switch(|member| {
+                signal::option(member.map(|member| member.commander.signal()))
+                    .map(Option::unwrap_or_default)
+            })
+            .dedupe()
+    }
+
+    /// Tells the background which character is played in this tab, needs to be
+    /// repeated after the background got restarted.
+    pub(super) async fn identify() -> JsResult<()> {
+        let Some(tab_hero) = Self::tab_hero() else {
+            return Ok(());
+        };
+
+        Port::send(
+            &Message::builder(Task::GameTab, Target::Background, MessageKind::Request)
+                .game_tab(tab_hero)
+                .build(),
+        )
+        .await
+    }
+
+    /// Sends the signal to every other identified game tab.
+    pub(crate) async fn broadcast(signal: TabSignal) -> JsResult<()> {
+        let from = Self::tab_hero().ok_or_else(|| err_code!())?;
+
+        Port::send(
+            &Message::builder(Task::TabBroadcast, Target::Background, MessageKind::Request)
+                .tab_broadcast(TabBroadcast::new(from, signal))
+                .build(),
+        )
+        .await
+    }
+
+    /// Handles a signal relayed from another game tab.
+    pub(super) fn on_broadcast(broadcast: Option<TabBroadcast>) {
+        let Some(TabBroadcast { from, signal }) = broadcast else {
+            return;
+        };
+
+        if let Err(err_code) = Self::on_signal(from, signal) {
+            console_error!(err_code);
+        }
+    }
+
+    fn on_signal(from: TabHero, signal: TabSignal) -> JsResult<()> {
+        match signal {
+            TabSignal::InviteRequest => {
+                // Characters from other worlds can't be invited.
+                if !Self::tab_hero().is_some_and(|hero| hero.is_same_world(&from)) {
+                    return Ok(());
+                }
+
+                message(&tr!(TabsInviting, nick = from.nick))?;
+                communication::send_task(&communication::party::invite(&from.char_id))
+            }
+            TabSignal::UnderAttack { attacker } => {
+                message(&tr!(TabsUnderAttack, nick = from.nick, attacker = attacker))?;
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Name of a player from the opposing team, only returned when the battle
+    /// starts.
+    fn opposing_player(fight: &FightData) -> Option<String> {
+        let my_team = fight.my_team?;
+
+        fight
+            .w
+            .as_ref()?
+            .iter()
+            .find(|(id, warrior)| **id > 0 && warrior.team.is_some_and(|team| team != my_team))
+            .and_then(|(_, warrior)| warrior.name.clone())
+    }
+}
@@ -1,6 +1,8 @@
 use std::sync::OnceLock;
 
-use common::{err_code, map_err, web_extension_sys::cookies::CookieDetails};
+use common::{
+    err_code, game_tabs::CharacterId, map_err, web_extension_sys::cookies::CookieDetails,
+};
 use futures_signals::{
     map_ref,
     signal::{Mutable, Signal},
@@ -85,15 +87,25 @@ impl Hero {
         HERO.wait()
     }
 
+    /// Like [`Hero::get`], but returns `None` instead of waiting when the hero
+    /// isn't initialized yet.
+    pub fn try_get() -> Option<&'static Self> {
+        HERO.get()
+    }
+
+    /// Character the background stores the console logs and the addon
+    /// dashboard of this tab under.
+    pub fn character(&self) -> CharacterId {
+        CharacterId::new(self.account_string.clone(), self.char_id_string.clone())
+    }
+
     /// SAFETY: Can be called only if Port is initialized.
     // TODO: Move the account_string and char_id into separate encrypted variables
     // and init hero later on ?
     pub(super) async fn init() -> Result<(), GlobalsError> {
         let mut hero = Self::default();
         let Ok(account_string) = Self::fetch_account_id().await else {
-            crate::bindings::message(s!(
-                "[MDMA::RS] Nie udało się wczytać zestawu ze względu na API Margonem! Spróbuj ponownie za chwilę..."
-            ))?;
+            crate::bindings::message(common::tr!(HeroApiError))?;
             return Err(GlobalsError::Unauthorized);
         };
         hero.account = account_string.parse().map_err(map_err!(from))?;
@@ -1,15 +1,14 @@
 use std::sync::OnceLock;
 
+use common::tr;
 use futures::StreamExt;
 use futures_signals::signal::{Mutable, MutableLockMut, Signal, SignalExt};
-use wasm_bindgen::intern;
 
 use crate::{
     bindings::{
         engine::communication::{CharacterSettings, HeroSettingsData},
         message,
     },
-    s,
     utils::JsResult,
 };
 
@@ -56,21 +55,13 @@ impl HeroSettings {
             .for_each(|ids_to_update| async move {
                 let (friend_notif_id, clan_notif_id) = ids_to_update;
                 if let Some(friend_notif_id) = friend_notif_id {
-                    let _ = message(intern(s!(
-                        "[MDMA::RS] Włączam informowanie o logowaniu się przyjaciół."
-                    )));
-                    let _ = message(intern(s!(
-                        "[MDMA::RS] Ta opcja jest potrzebna do poprawnego działania zestawu!"
-                    )));
+                    let _ = message(tr!(HeroSettingsFriendNotifications));
+                    let _ = message(tr!(HeroSettingsRequired));
                     CharacterSettings::init_setting(friend_notif_id).await
                 }
                 if let Some(clan_notif_id) = clan_notif_id {
-                    let _ = message(intern(s!(
-                        "[MDMA::RS] Włączam informowanie o logowaniu się klanowiczów."
-                    )));
-                    let _ = message(intern(s!(
-                        "[MDMA::RS] Ta opcja jest potrzebna do poprawnego działania zestawu!"
-                    )));
+                    let _ = message(tr!(HeroSettingsClanNotifications));
+                    let _ = message(tr!(HeroSettingsRequired));
                     CharacterSettings::init_setting(clan_notif_id).await
                 }
             });
@@ -1,9 +1,11 @@
 pub mod addons;
+pub mod catalog;
 pub mod collisions;
 // TODO: Document this module.
 /// Emitter emits all interceptors and handlers for the specified event, waiters
 /// get notified before handlers are run.
 pub mod emitter;
+pub mod game_tabs;
 pub mod hero;
 pub(crate) mod hero_settings;
 pub mod items;
@@ -21,20 +23,22 @@ use std::future::Future;
 use std::pin::Pin;
 
 use common::debug_log;
+use common::i18n::Locale;
+use common::messaging::prelude::{Message, MessageKind, Target, Task};
 use futures::StreamExt;
 use futures_signals::signal::{Mutable, SignalExt};
 use futures_signals::signal_map::{
     Len, MutableBTreeMap, MutableBTreeMapEntries, MutableBTreeMapKeys, MutableBTreeMapLockMut,
     MutableBTreeMapLockRef, MutableSignalMap,
 };
-use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use wasm_bindgen::{JsValue, intern};
 
 use crate::{
     bindings::engine::communication::{self, __send_task, Id, Response},
+    hotkeys::{Hotkey, HotkeyAction, HotkeyValue, Hotkeys},
     s,
-    utils::{JsResult, UnwrapJsExt, delay, delay_range, window_events},
+    utils::{JsResult, UnwrapJsExt, delay, delay_range},
 };
 
 pub mod prelude {
@@ -42,7 +46,9 @@ pub mod prelude {
     pub use super::{
         GlobalBTreeMap, ItemId, OtherId,
         addons::{AddonData, AddonDataMarker, AddonName, AddonWindowDetails, Addons, WindowType},
+        catalog::Catalog,
         emitter::{Emitter, EmitterEvent},
+        game_tabs::GameTabs,
         hero::Hero,
         hero_settings::HeroSettings,
         items::ItemBTreeMap as Items,
@@ -65,55 +71,33 @@ pub type ItemId = Id;
 
 pub(crate) struct ManagerGlobals {
     pub(crate) widget_active: Mutable<bool>,
-    pub(crate) hotkey: Mutable<ManagerHotkey>,
+    pub(crate) hotkey: Hotkey,
+    /// Whether the user opted into sending error reports.
+    pub(crate) diagnostics: Mutable<bool>,
+    /// Whether the other game tabs are told when the hero gets attacked.
+    pub(crate) attack_alerts: Mutable<bool>,
+    /// Language chosen by the user, `None` follows the domain of the game.
+    pub(crate) language: Mutable<Option<Locale>>,
 }
 
 impl ManagerGlobals {
-    fn new(widget_active: Mutable<bool>, hotkey: Mutable<ManagerHotkey>) -> Self {
+    fn new(
+        widget_active: Mutable<bool>,
+        hotkey: Hotkey,
+        diagnostics: Mutable<bool>,
+        attack_alerts: Mutable<bool>,
+        language: Mutable<Option<Locale>>,
+    ) -> Self {
         Self {
             widget_active,
             hotkey,
+            diagnostics,
+            attack_alerts,
+            language,
         }
     }
 }
 
-#[derive(Deserialize, Serialize)]
-pub(crate) struct ManagerHotkey {
-    pub(crate) value: String,
-    pub(crate) ctrl_key: bool,
-    pub(crate) shift_key: bool,
-    pub(crate) alt_key: bool,
-}
-
-impl Default for ManagerHotkey {
-    fn default() -> Self {
-        Self {
-            value: String::from("TAB"),
-            ctrl_key: false,
-            shift_key: false,
-            alt_key: false,
-        }
-    }
-}
-
-impl window_events::Hotkey for ManagerHotkey {
-    fn value(&self) -> &str {
-        &self.value
-    }
-
-    fn alt_key(&self) -> bool {
-        self.alt_key
-    }
-
-    fn ctrl_key(&self) -> bool {
-        self.ctrl_key
-    }
-
-    fn shift_key(&self) -> bool {
-        self.shift_key
-    }
-}
-
 // TODO: Better name for variants.
 #[derive(Debug)]
 pub(super) enum GlobalsError {
@@ -138,7 +122,8 @@ pub(super) struct Globals;
 
 impl Globals {
     pub(super) async fn init() -> Result<&'static ManagerGlobals, GlobalsError> {
-        port::Port::init_authorized().await?;
+        let language = port::Port::init_authorized().await?;
+        catalog::Catalog::refresh().await?;
         premium::Premium::init().await?;
         hero::Hero::init().await?;
 
@@ -159,9 +144,13 @@ impl Globals {
 
         // let widget_active = Self::init_widget_state(&config);
         // let manager_hotkey = Self::init_manager_hotkey(config);
+        // let diagnostics = Self::init_diagnostics_state(&config);
+        // let attack_alerts = Self::init_attack_alerts_state(&config);
+        // let language = Self::init_language_state(language);
         // let manager_globals: &'static ManagerGlobals =
         //     Box::leak(Box::new(ManagerGlobals::new(widget_active,
-        // manager_hotkey))); Ok(Some(manager_globals))
+        // manager_hotkey, diagnostics, attack_alerts, language)));
+        // Ok(Some(manager_globals))
         todo!()
     }
 
@@ -199,11 +188,54 @@ impl Globals {
         widget_active
     }
 
-    fn init_manager_hotkey(config: &mut Value) -> Mutable<ManagerHotkey> {
-        let manager_hotkey = Mutable::new(
-            serde_json::from_value(config[s!("manager_hotkey")].take()).unwrap_or_default(),
-        );
+    /// Reporting is opt-in, so it stays disabled unless the user enabled it.
+    fn init_diagnostics_state(config: &Value) -> Mutable<bool> {
+        let diagnostics = Mutable::new(config[s!("diagnostics")].as_bool().unwrap_or(false));
+
+        // TODO: Save changes once manager settings are sent to the background again.
+        crate::diagnostics::Diagnostics::init(&diagnostics);
+
+        diagnostics
+    }
+
+    /// Alerts are opt-in, other tabs only get notified once the user enabled
+    /// them.
+    fn init_attack_alerts_state(config: &Value) -> Mutable<bool> {
+        // TODO: Save changes once manager settings are sent to the background again.
+        Mutable::new(config[s!("attack_alerts")].as_bool().unwrap_or(false))
+    }
+
+    /// Texts are rendered once, so the stored language is applied when
+    /// connecting to the background and changes take effect after reloading
+    /// the page.
+    fn init_language_state(language: Option<Locale>) -> Mutable<Option<Locale>> {
+        let language = Mutable::new(language);
+        let future = language
+            .signal()
+            .to_stream()
+            .skip(1)
+            .for_each(|language| async move {
+                let msg =
+                    Message::builder(Task::Language, Target::Background, MessageKind::Request)
+                        .maybe_language(language)
+                        .build();
+
+                if let Err(err_code) = port::Port::send(&msg).await {
+                    console_error!(err_code);
+                }
+            });
+        wasm_bindgen_futures::spawn_local(future);
+
+        language
+    }
+
+    /// The manager hotkey is always active, only the keys can be changed.
+    fn init_manager_hotkey(config: &mut Value) -> Hotkey {
+        let value = serde_json::from_value(config[s!("manager_hotkey")].take())
+            .unwrap_or_else(|_| HotkeyValue::new("TAB", false));
+        let manager_hotkey = Hotkey::new(value, true);
         let future = manager_hotkey
+            .value
             .signal_ref(|change| json!(change))
             .to_stream()
             .skip(1)
@@ -229,6 +261,7 @@ impl Globals {
                 // port::Port::send(msg).await;
             });
         wasm_bindgen_futures::spawn_local(future);
+        Hotkeys::register(HotkeyAction::ToggleManager, &manager_hotkey);
 
         manager_hotkey
     }
@@ -13,7 +13,7 @@ use crate::{
     utils::{JsResult, UnwrapJsExt},
 };
 
-use super::GlobalBTreeMap;
+use super::{GlobalBTreeMap, catalog::Catalog};
 
 static NPCS: OnceLock<Npcs> = OnceLock::new();
 static NPC_TEMPLATES: OnceLock<NpcTemplates> = OnceLock::new();
@@ -136,14 +136,14 @@ impl NpcTemplates {
     //    self.0
     //        .lock_ref()
     //        .iter()
-    //        .any(|(_, npc_tpl)| npc_tpl.warrior_type.is_some_and(|wt| wt > 89 && wt < 100))
+    //        .any(|(_, npc_tpl)| npc_tpl.warrior_type.is_some_and(Catalog::is_colossus))
     //}
 
     pub(crate) fn has_colossus_signal() -> impl Signal<Item = bool> {
         signal::not(
             Self::get()
                 .entries_cloned()
-                .filter(|(_, npc_tpl)| npc_tpl.warrior_type.is_some_and(|wt| wt > 89 && wt < 100))
+                .filter(|(_, npc_tpl)| npc_tpl.warrior_type.is_some_and(Catalog::is_colossus))
                 .is_empty(),
         )
         .dedupe()
@@ -7,7 +7,8 @@ use crate::{
         communication::{Emotion, OtherData},
         other::Other,
     },
-    color_mark::{Color, ColorMark},
+    color_mark::ColorMark,
+    overlay::OverlayColor,
     utils::JsResult,
 };
 
@@ -82,7 +83,7 @@ impl OtherBTreeMap {
         };
     }
 
-    pub fn init_color_mark(color: Color, addon_name: AddonName, other_id: OtherId) -> JsResult<()> {
+    pub fn init_color_mark(color: OverlayColor, addon_name: AddonName, other_id: OtherId) -> JsResult<()> {
         ColorMark::init(color, addon_name, other_id)
     }
 
@@ -1,14 +1,14 @@
 use std::{iter::Filter, sync::OnceLock};
 
 use futures_signals::{
-    signal::{Mutable, Signal, SignalExt},
-    signal_map::MutableBTreeMap,
+    signal::{self, Mutable, Signal, SignalExt},
+    signal_map::{MutableBTreeMap, SignalMapExt},
     signal_vec::SignalVecExt,
 };
 
 use crate::{
     bindings::engine::{
-        communication::{BusinessCards, Chat, Id, PeerData, Response},
+        communication::{BusinessCards, Chat, Id, PeerData, Relation, Response},
         peer::Peer,
     },
     utils::JsResult,
@@ -20,15 +20,19 @@ static PEERS: OnceLock<PeerBTreeMap> = OnceLock::new();
 
 pub type PeerId = Id;
 
-/// MutableBTreeMap storing clan members and friends data.
-#[derive(Debug)]
-pub struct PeerBTreeMap(MutableBTreeMap<PeerId, Peer>);
+/// MutableBTreeMap storing clan members, friends and enemies data.
+#[derive(Debug, Default)]
+pub struct PeerBTreeMap {
+    peers: MutableBTreeMap<PeerId, Peer>,
+    /// Size limit of the friend list, `None` until the friends get fetched.
+    pub friends_max: Mutable<Option<u8>>,
+    /// Size limit of the enemy list, `None` until the enemies get fetched.
+    pub enemies_max: Mutable<Option<u8>>,
+}
 
 impl PeerBTreeMap {
     pub(super) fn init() -> JsResult<()> {
-        PEERS
-            .set(Self(MutableBTreeMap::new()))
-            .map_err(|_| common::err_code!())
+        PEERS.set(Self::default()).map_err(|_| common::err_code!())
     }
 
     pub fn get() -> &'static Self {
@@ -66,7 +70,7 @@ impl PeerBTreeMap {
     //}
 
     pub fn online_len_signal(&self) -> impl Signal<Item = usize> {
-        self.0
+        self.peers
             .entries_cloned()
             .filter_signal_cloned(|(_, peer_data)| peer_data.online.signal())
             .len()
@@ -75,7 +79,7 @@ impl PeerBTreeMap {
 
     // FIXME: Probably very poor performance.
     pub fn online_from_keys_signal(&'static self) -> impl Signal<Item = Vec<(PeerId, Peer)>> {
-        self.0
+        self.peers
             .entries_cloned()
             .filter_signal_cloned(|(_, peer_data)| peer_data.online.signal())
             .to_signal_cloned()
@@ -96,7 +100,7 @@ impl PeerBTreeMap {
     }
 
     pub fn online_entries_signal(&self) -> impl Signal<Item = Vec<(PeerId, Peer)>> {
-        self.0
+        self.peers
             .entries_cloned()
             .filter_signal_cloned(|(_, peer_data)| peer_data.online.signal())
             .to_signal_cloned()
@@ -122,10 +126,26 @@ impl PeerBTreeMap {
         }
         if let Some(friends) = socket_response.friends.take() {
             Self::update(friends);
-            let _friends_max = socket_response.friends_max.take();
-            let _enemies = socket_response.enemies.take();
-            let _enemies_max = socket_response.enemies_max.take();
         }
+        if let Some(enemies) = socket_response.enemies.take() {
+            Self::update(enemies);
+        }
+        if let Some(friends_max) = socket_response.friends_max.take() {
+            Self::get().friends_max.set_neq(Some(friends_max));
+        }
+        if let Some(enemies_max) = socket_response.enemies_max.take() {
+            Self::get().enemies_max.set_neq(Some(enemies_max));
+        }
+    }
+
+    /// Whether the player is on the hero's enemy list.
+    pub fn is_enemy_signal(&self, char_id: PeerId) -> impl Signal<Item = bool> + use<> {
+        self.peers
+            .signal_map_cloned()
+            .key_cloned(char_id)
+            .switch(|peer| signal::option(peer.map(|peer| peer.relation.signal())))
+            .map(|relation| relation == Some(Relation::Enemy))
+            .dedupe()
     }
 
     pub(crate) fn update_from_business_cards(new_business_cards: BusinessCards) {
@@ -160,14 +180,19 @@ impl PeerBTreeMap {
         })
     }
 
+    /// Replaces the peers with the same relation as `B`, peers with other
+    /// relations are fetched separately.
     // TODO: Add clan when peer is a clan member.
     pub(crate) fn update<B: PeerData>(peers: Vec<B>) {
         let mut peers_lock = Self::get().lock_mut();
 
         let keys_to_remove: Vec<_> = peers_lock
-            .keys()
-            .filter(|char_id| !peers.iter().any(|peer| peer.id() == **char_id))
-            .copied()
+            .iter()
+            .filter(|(char_id, peer_data)| {
+                peer_data.relation.get() == B::RELATION
+                    && !peers.iter().any(|peer| peer.id() == **char_id)
+            })
+            .map(|(char_id, _)| *char_id)
             .collect();
 
         keys_to_remove.into_iter().for_each(|char_id| {
@@ -180,7 +205,7 @@ impl PeerBTreeMap {
                 .and_modify_cloned(|old_peer_data| {
                     old_peer_data.nick.set_neq(peer.nick());
                     old_peer_data.prof.set_neq(peer.prof());
-                    old_peer_data.relation.set_neq(peer.relation());
+                    old_peer_data.relation.set_neq(B::RELATION);
                     old_peer_data.online.set_neq(peer.is_online());
                     old_peer_data.lvl.set_neq(peer.lvl());
                     old_peer_data.operational_lvl.set_neq(peer.oplvl());
@@ -194,7 +219,7 @@ impl PeerBTreeMap {
                     clan: Mutable::new(None),
                     nick: Mutable::new(peer.nick()),
                     prof: Mutable::new(peer.prof()),
-                    relation: Mutable::new(peer.relation()),
+                    relation: Mutable::new(B::RELATION),
                     online: Mutable::new(peer.is_online()),
                     lvl: Mutable::new(peer.lvl()),
                     operational_lvl: Mutable::new(peer.oplvl()),
@@ -208,7 +233,7 @@ impl PeerBTreeMap {
 
 impl GlobalBTreeMap<PeerId, Peer> for PeerBTreeMap {
     fn get(&self) -> &MutableBTreeMap<PeerId, Peer> {
-        &self.0
+        &self.peers
     }
 }
 
@@ -7,9 +7,11 @@ use std::{
 };
 
 use common::{
-    closure, debug_log, err_code, map_err,
+    closure, debug_log, err_code,
+    i18n::Locale,
+    map_err,
     messaging::prelude::*,
-    sleep,
+    sleep, tr,
     web_extension_sys::{
         browser,
         cookies::{self, CookieDetails},
@@ -29,7 +31,13 @@ use crate::{
     utils::{JsResult, UnwrapJsExt},
 };
 
-use super::{GlobalsError, addons::AddonName, hero::Hero};
+use super::{
+    GlobalsError,
+    addons::{AddonName, Addons},
+    catalog::Catalog,
+    game_tabs::GameTabs,
+    hero::Hero,
+};
 
 static PORT: OnceLock<Port> = OnceLock::new();
 
@@ -53,6 +61,11 @@ pub struct Port {
     port: RefCell<common::web_extension_sys::runtime::port::Port>,
     tx: mpsc::UnboundedSender<Message>,
     pub rx: RefCell<mpsc::UnboundedReceiver<Message>>,
+    /// Reconnection attempts since the background last responded.
+    reconnects: Cell<u8>,
+    /// Set once the game session got initialized, a restarted background
+    /// needs it again.
+    session_initialized: Cell<bool>,
 }
 
 // SAFETY: no threads on wasm32.
@@ -60,10 +73,19 @@ unsafe impl Send for Port {}
 unsafe impl Sync for Port {}
 
 impl Port {
+    const MAX_RECONNECTS: u8 = 5;
+    const RECONNECT_DELAY: u32 = 1_000;
+
     pub fn get() -> &'static Self {
         PORT.wait()
     }
 
+    /// Like [`Port::get`], but returns `None` instead of waiting when the
+    /// connection isn't initialized yet.
+    pub fn try_get() -> Option<&'static Self> {
+        PORT.get()
+    }
+
     pub async fn send(msg: &Message) -> JsResult<()> {
         // if !Self::get().active.get() {
         //     Self::reconnect().await;
@@ -76,38 +98,15 @@ impl Port {
 }
 
 impl Port {
-    /// Connect to the background runtime via a message port.
+    /// Connect to the background runtime via a message port, returns the
+    /// language chosen in the manager settings.
     ///
     /// # Errors
     /// If the user isn't authorized after the connection is made return an
     /// [`Unauthorized`][GlobalsError::Unauthorized] error.
-    pub(super) async fn init_authorized() -> Result<(), GlobalsError> {
-        let port = CONNECT_INFO
-            .with(|connect_info| browser().runtime().connect(EXTENSION_ID, &*connect_info));
+    pub(super) async fn init_authorized() -> Result<Option<Locale>, GlobalsError> {
         let (tx, mut rx) = mpsc::unbounded();
-
-        port.on_disconnect().add_listener(&closure!(
-            @once
-            { let tx = tx.clone() },
-            move || {
-                if let Some(err) = browser().runtime().last_error() {
-                    debug_log!(err);
-                    console_error!();
-                }
-
-                debug_log!("Disconnected foreground port.");
-                tx.close_channel();
-            },
-        ));
-        port.on_message().add_listener(&closure!(
-            { let tx = tx.clone() },
-            move |message: JsValue| {
-                let item = serde_wasm_bindgen::from_value(message).unwrap_js();
-                common::debug_log!(@f "{:#?}", &item);
-
-                tx.unbounded_send(item).unwrap_js();
-            },
-        ));
+        let port = Self::connect(&tx);
 
         port.post_message(
             Message::builder(Task::Handshake, Target::Background, MessageKind::Request)
@@ -124,6 +123,11 @@ impl Port {
 
         validator.validate(&msg)?;
 
+        let language = msg.language;
+        if let Some(locale) = language {
+            Locale::set_current(locale);
+        }
+
         if let Some(error) = msg.error {
             crate::prelude::message(&error)?;
 
@@ -153,8 +157,164 @@ impl Port {
             port: RefCell::new(port),
             rx: RefCell::new(rx),
             tx,
+            reconnects: Cell::new(0),
+            session_initialized: Cell::new(false),
         })
-        .map_err(|_| GlobalsError::unrecoverable())
+        .map_err(|_| GlobalsError::unrecoverable())?;
+
+        Ok(language)
+    }
+
+    /// Opens a port to the background, received messages are forwarded into
+    /// `tx`.
+    fn connect(
+        tx: &mpsc::UnboundedSender<Message>,
+    ) -> common::web_extension_sys::runtime::port::Port {
+        let port = CONNECT_INFO
+            .with(|connect_info| browser().runtime().connect(EXTENSION_ID, &*connect_info));
+
+        port.on_disconnect().add_listener(&closure!(
+            @once
+            { let tx = tx.clone() },
+            move || {
+                if let Some(err) = browser().runtime().last_error() {
+                    debug_log!(err);
+                    console_error!();
+                }
+
+                debug_log!("Disconnected foreground port.");
+
+                // The background service worker got restarted.
+                match PORT.get() {
+                    Some(port) => port.reconnect(),
+                    None => tx.close_channel(),
+                }
+            },
+        ));
+        port.on_message().add_listener(&closure!(
+            { let tx = tx.clone() },
+            move |message: JsValue| {
+                let item: Message = serde_wasm_bindgen::from_value(message).unwrap_js();
+                common::debug_log!(@f "{:#?}", &item);
+
+                // Connection state changes, catalog refreshes, synced clan
+                // rosters, addon toggles from the popup, signals from other
+                // game tabs and rejected diagnostics reports can arrive at any
+                // time, so they're handled here instead of by whoever awaits
+                // the next response.
+                if item.task == Task::ConnectionState {
+                    if let Err(err_code) = Self::on_connection_state(&item) {
+                        console_error!(err_code);
+                    }
+                    return;
+                }
+                if item.task == Task::Catalog {
+                    Catalog::on_refresh(item.catalog);
+                    return;
+                }
+                if item.task == Task::ClanRoster {
+                    crate::addons::clan_roster::on_sync(item.clan_roster);
+                    return;
+                }
+                if item.task == Task::AddonDashboard {
+                    Addons::on_dashboard_message(item.addon_toggles);
+                    return;
+                }
+                if item.task == Task::TabBroadcast {
+                    GameTabs::on_broadcast(item.tab_broadcast);
+                    return;
+                }
+                if item.task == Task::Diagnostics {
+                    crate::diagnostics::Diagnostics::on_rejected(item.diagnostics);
+                    return;
+                }
+                // Only awaited during initialization, later ones respond to
+                // reconnecting.
+                if item.task == Task::Handshake
+                    && item.kind == MessageKind::Response
+                    && let Some(port) = PORT.get()
+                {
+                    if let Err(err_code) = port.on_reconnected(&item) {
+                        console_error!(err_code);
+                    }
+                    return;
+                }
+                // Responses to the session re-sent after reconnecting, its
+                // settings were already applied during initialization.
+                if item.task == Task::InitSession
+                    && PORT.get().is_some_and(|port| port.session_initialized.get())
+                {
+                    return;
+                }
+
+                tx.unbounded_send(item).unwrap_js();
+            },
+        ));
+
+        port
+    }
+
+    /// Replaces the port after the background service worker got restarted.
+    fn reconnect(&'static self) {
+        let attempt = self.reconnects.get() + 1;
+
+        self.reconnects.set(attempt);
+
+        if attempt > Self::MAX_RECONNECTS {
+            self.tx.close_channel();
+
+            if let Err(err_code) = crate::prelude::message(tr!(PortDisconnected)) {
+                console_error!(err_code);
+            }
+            return;
+        }
+
+        wasm_bindgen_futures::spawn_local(async move {
+            sleep(Self::RECONNECT_DELAY * u32::from(attempt)).await;
+
+            let port = Self::connect(&self.tx);
+            let handshake = Message::builder(Task::Handshake, Target::Background, MessageKind::Request)
+                .build()
+                .to_value();
+
+            match handshake {
+                Ok(handshake) => port.post_message(handshake.as_ref()),
+                Err(err_code) => console_error!(err_code),
+            }
+
+            self.port.replace(port);
+        });
+    }
+
+    fn on_reconnected(&self, msg: &Message) -> JsResult<()> {
+        debug_log!("Reconnected foreground port.");
+
+        self.reconnects.set(0);
+
+        wasm_bindgen_futures::spawn_local(async {
+            if let Err(err_code) = Self::resume_session().await {
+                console_error!(err_code);
+            }
+            if let Err(err_code) = GameTabs::identify().await {
+                console_error!(err_code);
+            }
+        });
+
+        match msg.error.as_deref() {
+            Some(error) => crate::prelude::message(error).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    fn on_connection_state(msg: &Message) -> JsResult<()> {
+        let txt = match msg.connection.ok_or_else(|| err_code!())? {
+            ConnectionState::Connected => tr!(PortServerReconnected),
+            ConnectionState::Reconnecting { attempt: 1, .. } => tr!(PortServerReconnecting),
+            // Don't spam the user on every failed attempt.
+            ConnectionState::Connecting | ConnectionState::Reconnecting { .. } => return Ok(()),
+        };
+
+        crate::prelude::message(txt).map(|_| ())
     }
 
     // TODO: Better name.
@@ -173,7 +333,7 @@ impl Port {
             match msg.kind {
                 MessageKind::Event => match msg.task {
                     Task::Handshake => {
-                        crate::prelude::message("[MDMA::RS] Odśwież grę, aby wczytać zestaw!")?;
+                        crate::prelude::message(tr!(PortReloadGame))?;
                     }
                     _ => unreachable!(),
                 },
@@ -214,7 +374,57 @@ impl Port {
                         crate::prelude::message(&err)?;
                     }
                 }
-                Task::InitSession => return Ok(msg.settings.unwrap_or_default()),
+                Task::InitSession => {
+                    Self::get().session_initialized.set(true);
+
+                    return Ok(msg.settings.unwrap_or_default());
+                }
+                _ => unreachable!(),
+            };
+        }
+    }
+
+    /// Initializes the game session again in a restarted background, does
+    /// nothing if the tab didn't initialize it yet.
+    async fn resume_session() -> JsResult<()> {
+        if !Self::get().session_initialized.get() {
+            return Ok(());
+        }
+
+        Self::send(&Message::new(
+            Task::InitSession,
+            Target::Background,
+            MessageKind::Request,
+        ))
+        .await
+    }
+
+    /// Fetches the MDMA console entries persisted before the last page reload.
+    pub(crate) async fn load_console_logs() -> JsResult<Vec<PersistedLog>> {
+        Self::send(
+            &Message::builder(Task::ConsoleLogs, Target::Background, MessageKind::Request)
+                .character(Hero::get().character())
+                .build(),
+        )
+        .await?;
+
+        let validator = MessageValidator::builder(Target::Background)
+            .kind(MessageKind::Response)
+            .build();
+        let mut rx_lock = Self::get().rx.borrow_mut();
+
+        loop {
+            let msg = rx_lock.next().await.ok_or_else(|| err_code!())?;
+
+            validator.validate(&msg)?;
+
+            match msg.task {
+                Task::OpenPopup => {
+                    if let Some(err) = msg.error {
+                        crate::prelude::message(&err)?;
+                    }
+                }
+                Task::ConsoleLogs => return Ok(msg.console_logs.unwrap_or_default()),
                 _ => unreachable!(),
             };
         }
@@ -25,6 +25,16 @@ impl Premium {
         PREMIUM.with_borrow(|premium| premium.is_some())
     }
 
+    /// Access to the additional Hero Neon styles.
+    pub fn neon() -> bool {
+        PREMIUM.with_borrow(|premium| premium.as_ref().is_some_and(|premium| premium.neon))
+    }
+
+    /// Access to the Hero Neon animations.
+    pub fn animation() -> bool {
+        PREMIUM.with_borrow(|premium| premium.as_ref().is_some_and(|premium| premium.animation))
+    }
+
     #[cfg(feature = "antyduch")]
     pub fn anty_duch() -> bool {
         PREMIUM.with_borrow(|premium| premium.as_ref().is_some_and(|premium| premium.antyduch))
@@ -2,7 +2,9 @@ use std::sync::OnceLock;
 
 use futures_signals::signal::{Mutable, MutableLockMut, MutableLockRef, MutableSignalRef};
 
-use crate::{bindings::engine::communication::TownData, utils::JsResult};
+use crate::{
+    bindings::engine::communication::TownData, diagnostics::Diagnostics, utils::JsResult,
+};
 
 static TOWN: OnceLock<Town> = OnceLock::new();
 
@@ -40,8 +42,9 @@ impl Town {
     pub(crate) fn merge(new_town: TownData) {
         let mut town_lock = Self::get().lock_mut();
 
-        if new_town.name.is_some() {
-            town_lock.name = new_town.name;
+        if let Some(name) = new_town.name {
+            Diagnostics::record_event(&format!("map: {name}"));
+            town_lock.name = Some(name);
         }
         if new_town.id.is_some() {
             town_lock.id = new_town.id;
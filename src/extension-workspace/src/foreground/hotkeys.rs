@@ -0,0 +1,508 @@
+//! Central registry of the hotkeys used by the manager and the addons. Every
+//! bindable action is registered here, so conflicting bindings are detected no
+//! matter which window the hotkey was changed from.
+
+use std::iter;
+use std::ops::Deref;
+
+use dominator::events::{Click, KeyDown};
+use dominator::{Dom, html};
+use futures_signals::map_ref;
+use futures_signals::signal::{Mutable, Signal};
+use futures_signals::signal_vec::{MutableVec, SignalVecExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasm_bindgen::intern;
+use web_sys::HtmlInputElement;
+
+use common::i18n::Msg;
+
+use crate::addon_window::MdmaAddonWindow;
+use crate::addon_window::ui_components::{Checkbox, Input, InputType};
+use crate::interface::ALLOWED_CHARS;
+use crate::prelude::*;
+use crate::utils::window_events;
+
+/// Bindable keys handled by the game, binding them without modifiers triggers
+/// both the game and the manager action. Keys are uppercased, same as the bound
+/// ones.
+const GAME_KEYS: &[(&str, Msg)] = &[
+    ("W", Msg::HotkeysGameMove),
+    ("A", Msg::HotkeysGameMove),
+    ("S", Msg::HotkeysGameMove),
+    ("D", Msg::HotkeysGameMove),
+    ("E", Msg::HotkeysGameInteract),
+    ("Q", Msg::HotkeysGameShortcut),
+    ("R", Msg::HotkeysGameShortcut),
+    ("T", Msg::HotkeysGameShortcut),
+    ("X", Msg::HotkeysGameShortcut),
+    ("Z", Msg::HotkeysGameShortcut),
+    ("TAB", Msg::HotkeysGameShortcut),
+];
+/// Browser shortcuts which can't be overridden by the page.
+const BROWSER_KEYS: &[(&str, Msg)] = &[
+    ("W", Msg::HotkeysBrowserCloseTab),
+    ("T", Msg::HotkeysBrowserNewTab),
+    ("N", Msg::HotkeysBrowserNewWindow),
+    ("R", Msg::HotkeysBrowserReload),
+];
+
+thread_local! {
+    static REGISTERED: MutableVec<RegisteredHotkey> = MutableVec::new();
+    /// Changed whenever a binding changes, so the displayed conflicts of every
+    /// registered hotkey get recomputed.
+    static REVISION: Mutable<u32> = Mutable::new(0);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HotkeyAction {
+    ToggleManager,
+    OpenWindow(AddonName),
+    ToggleAddon(AddonName),
+    Invite,
+    MassInvite,
+    AttackToggle,
+    TrackTarget,
+    ClearTarget,
+    SwitchBuild,
+}
+
+impl HotkeyAction {
+    pub(crate) const fn addon(self) -> Option<AddonName> {
+        match self {
+            Self::ToggleManager => None,
+            Self::OpenWindow(addon_name) | Self::ToggleAddon(addon_name) => Some(addon_name),
+            Self::Invite | Self::MassInvite => Some(AddonName::BetterGroupInvites),
+            Self::AttackToggle | Self::TrackTarget | Self::ClearTarget => Some(AddonName::Kastrat),
+            Self::SwitchBuild => Some(AddonName::AdaptiveBuilds),
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::ToggleManager => tr!(HotkeysToggleManager),
+            Self::OpenWindow(_) => tr!(HotkeysOpenWindow),
+            Self::ToggleAddon(_) => tr!(HotkeysToggleAddon),
+            Self::Invite => tr!(HotkeysInvite),
+            Self::MassInvite => tr!(HotkeysMassInvite),
+            Self::AttackToggle => tr!(HotkeysAttackToggle),
+            Self::TrackTarget => tr!(HotkeysTrackTarget),
+            Self::ClearTarget => tr!(HotkeysClearTarget),
+            Self::SwitchBuild => tr!(HotkeysSwitchBuild),
+        }
+    }
+
+    /// The manager would be unreachable without its hotkey.
+    const fn can_disable(self) -> bool {
+        !matches!(self, Self::ToggleManager)
+    }
+
+    pub(crate) fn describe(self) -> String {
+        match self.addon() {
+            Some(addon_name) => format!("{}: {}", addon_name.as_str(), self.label()),
+            None => self.label().to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "StoredHotkeyValue")]
+pub(crate) struct HotkeyValue {
+    pub(crate) value: String,
+    pub(crate) alt_key: bool,
+    pub(crate) ctrl_key: bool,
+    pub(crate) shift_key: bool,
+}
+
+/// Hotkeys without modifiers used to be stored as plain strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredHotkeyValue {
+    Key(String),
+    Full {
+        value: String,
+        #[serde(default)]
+        alt_key: bool,
+        #[serde(default)]
+        ctrl_key: bool,
+        #[serde(default)]
+        shift_key: bool,
+    },
+}
+
+impl From<StoredHotkeyValue> for HotkeyValue {
+    fn from(stored: StoredHotkeyValue) -> Self {
+        match stored {
+            StoredHotkeyValue::Key(value) => Self::new(&value.to_ascii_uppercase(), false),
+            StoredHotkeyValue::Full {
+                value,
+                alt_key,
+                ctrl_key,
+                shift_key,
+            } => Self {
+                value,
+                alt_key,
+                ctrl_key,
+                shift_key,
+            },
+        }
+    }
+}
+
+impl HotkeyValue {
+    pub(crate) fn new(value: &str, shift_key: bool) -> Self {
+        Self {
+            value: value.to_owned(),
+            alt_key: false,
+            ctrl_key: false,
+            shift_key,
+        }
+    }
+
+    /// Returns `None` if the pressed key can't be bound, blurs the input if
+    /// the user is done with it.
+    fn from_key_down(event: &KeyDown, input_elem: &HtmlInputElement) -> Option<Self> {
+        if event.repeat() {
+            return None;
+        }
+
+        let value = event.key();
+        let mut chars = value.chars();
+
+        if chars
+            .next()
+            .is_none_or(|c| !is_from_polish_alphabet(c) && !ALLOWED_CHARS.contains(c))
+        {
+            event.prevent_default();
+            event.stop_propagation();
+            input_elem.blur().unwrap_js();
+            return None;
+        }
+        if chars.next().is_some() {
+            event.prevent_default();
+            event.stop_propagation();
+
+            if value != intern("Tab") {
+                if value == intern("Escape")
+                    || !matches!(value.as_str(), "Control" | "Alt" | "Shift")
+                {
+                    input_elem.blur().unwrap_js();
+                }
+                return None;
+            }
+        }
+
+        let value = value
+            .chars()
+            .map(polish_to_ascii)
+            .collect::<String>()
+            .to_ascii_uppercase();
+
+        Some(Self {
+            value,
+            alt_key: event.alt_key(),
+            ctrl_key: event.ctrl_key(),
+            shift_key: event.shift_key(),
+        })
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    pub(crate) fn display(&self) -> String {
+        self.ctrl_key
+            .then_some("Ctrl")
+            .into_iter()
+            .chain(self.alt_key.then_some("Alt"))
+            .chain(self.shift_key.then_some("Shift"))
+            .chain(iter::once(self.value.as_str()))
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join(" + ")
+    }
+
+    /// Description of the game or browser shortcut bound to the same keys.
+    fn reserved_by(&self) -> Option<&'static str> {
+        let known_keys = match (self.ctrl_key, self.alt_key) {
+            (false, false) => GAME_KEYS,
+            (true, false) => BROWSER_KEYS,
+            _ => return None,
+        };
+
+        known_keys
+            .iter()
+            .find(|(key, _)| *key == self.value)
+            .map(|(_, description)| description.text())
+    }
+}
+
+impl window_events::Hotkey for HotkeyValue {
+    fn value(&self) -> &str {
+        &self.value
+    }
+
+    fn alt_key(&self) -> bool {
+        self.alt_key
+    }
+
+    fn ctrl_key(&self) -> bool {
+        self.ctrl_key
+    }
+
+    fn shift_key(&self) -> bool {
+        self.shift_key
+    }
+}
+
+#[derive(Debug, Clone, Setting)]
+pub(crate) struct Hotkey {
+    pub(crate) value: Mutable<HotkeyValue>,
+    pub(crate) active: Mutable<bool>,
+}
+
+impl Hotkey {
+    pub(crate) fn new(value: HotkeyValue, active: bool) -> Self {
+        Self {
+            value: Mutable::new(value),
+            active: Mutable::new(active),
+        }
+    }
+
+    /// Unbound and inactive unless the config says otherwise.
+    pub(crate) fn from_config(config: &Value) -> Self {
+        let value = serde_json::from_value(config[intern(s!("value"))].clone()).unwrap_or_default();
+        let active = config[intern(s!("active"))].as_bool().unwrap_or(false);
+
+        Self::new(value, active)
+    }
+
+    pub(crate) fn is_pressed(&self, event: &KeyDown) -> JsResult<bool> {
+        if !self.active.get() {
+            return Ok(false);
+        }
+
+        window_events::validate_keydown_event(event, self.value.lock_ref().deref())
+    }
+
+    pub(crate) fn checkbox(&self, action: HotkeyAction) -> Checkbox<bool> {
+        let hotkey = self.clone();
+
+        Checkbox::builder(self.active.clone()).on_click(move |event: Click| {
+            if !Hotkeys::set_active(action, &hotkey, !hotkey.active.get()) {
+                event.prevent_default();
+            }
+        })
+    }
+
+    pub(crate) fn input(&self, action: HotkeyAction) -> Input {
+        let hotkey = self.clone();
+
+        Input::builder()
+            .input_type(InputType::keybind())
+            .value(self.value.lock_ref().display())
+            .maxlength("1")
+            .on_key_down(move |event, input_elem| {
+                let Some(new_value) = HotkeyValue::from_key_down(&event, input_elem) else {
+                    return;
+                };
+
+                event.prevent_default();
+                event.stop_propagation();
+                input_elem.blur().unwrap_js();
+                Hotkeys::bind(action, &hotkey, new_value);
+                input_elem.set_value(&hotkey.value.lock_ref().display());
+            })
+    }
+}
+
+#[derive(Clone)]
+struct RegisteredHotkey {
+    action: HotkeyAction,
+    hotkey: Hotkey,
+}
+
+impl RegisteredHotkey {
+    fn conflicts_signal(&self) -> impl Signal<Item = String> + use<> {
+        let action = self.action;
+
+        map_ref! {
+            let value = self.hotkey.value.signal_cloned(),
+            let active = self.hotkey.active.signal(),
+            let _revision = REVISION.with(Mutable::signal) => {
+                let conflicts = match *active && !value.is_empty() {
+                    true => Hotkeys::conflicts(action, value),
+                    false => Vec::new(),
+                };
+
+                match conflicts.is_empty() {
+                    true => String::new(),
+                    false => tr!(HotkeysConflicts, actions = conflicts.join(", ")),
+                }
+            }
+        }
+    }
+
+    fn render(&self) -> Dom {
+        let label = self.action.describe();
+        let input = self.hotkey.input(self.action).class_list("m[0] p[0]");
+
+        html!(s!("div"), {
+            .child(html!(s!("div"), {
+                .class(s!("widget-label"))
+                .apply(|builder| match self.action.can_disable() {
+                    true => builder.checkbox(self.hotkey.checkbox(self.action).text(&label)),
+                    false => builder.text(&label),
+                })
+                .input(input)
+            }))
+            .child(html!(s!("div"), {
+                .class(s!("hotkey-conflict"))
+                .text_signal(self.conflicts_signal())
+            }))
+        })
+    }
+}
+
+pub(crate) struct Hotkeys;
+
+impl Hotkeys {
+    /// Makes the hotkey configurable from the manager settings, registering the
+    /// same action again replaces the previous hotkey.
+    pub(crate) fn register(action: HotkeyAction, hotkey: &Hotkey) {
+        let registered = RegisteredHotkey {
+            action,
+            hotkey: hotkey.clone(),
+        };
+
+        REGISTERED.with(|hotkeys| {
+            let mut hotkeys_lock = hotkeys.lock_mut();
+
+            match hotkeys_lock.iter().position(|other| other.action == action) {
+                Some(index) => hotkeys_lock.set_cloned(index, registered),
+                None => hotkeys_lock.push_cloned(registered),
+            }
+        });
+        REVISION.with(|revision| revision.replace_with(|revision| revision.wrapping_add(1)));
+    }
+
+    /// Descriptions of active actions and reserved shortcuts bound to the same
+    /// keys.
+    pub(crate) fn conflicts(action: HotkeyAction, value: &HotkeyValue) -> Vec<String> {
+        Self::conflicting(action, value)
+            .into_iter()
+            .map(|registered| registered.action.describe())
+            .chain(
+                value
+                    .reserved_by()
+                    .map(|description| tr!(HotkeysReservedConflict, description = description)),
+            )
+            .collect()
+    }
+
+    fn conflicting(action: HotkeyAction, value: &HotkeyValue) -> Vec<RegisteredHotkey> {
+        REGISTERED.with(|hotkeys| {
+            hotkeys
+                .lock_ref()
+                .iter()
+                .filter(|other| {
+                    other.action != action
+                        && other.hotkey.active.get()
+                        && other.hotkey.value.lock_ref().deref() == value
+                })
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Disables active actions bound to the same keys. Returns `false` if one
+    /// of them can't be disabled.
+    fn resolve_conflicts(action: HotkeyAction, value: &HotkeyValue) -> bool {
+        let conflicting = Self::conflicting(action, value);
+
+        if let Some(blocking) = conflicting
+            .iter()
+            .find(|registered| !registered.action.can_disable())
+        {
+            if message(&tr!(
+                HotkeysAlreadyBound,
+                keys = value.display(),
+                action = blocking.action.describe(),
+            ))
+            .is_err()
+            {
+                console_error!()
+            }
+            return false;
+        }
+
+        for registered in conflicting {
+            registered.hotkey.active.set_neq(false);
+            if message(&tr!(HotkeysDisabled, action = registered.action.describe())).is_err() {
+                console_error!()
+            }
+        }
+
+        if let Some(description) = value.reserved_by() {
+            if message(&tr!(
+                HotkeysReserved,
+                keys = value.display(),
+                description = description,
+            ))
+            .is_err()
+            {
+                console_error!()
+            }
+        }
+
+        true
+    }
+
+    /// Returns `false` if the new keys weren't bound.
+    pub(crate) fn bind(action: HotkeyAction, hotkey: &Hotkey, value: HotkeyValue) -> bool {
+        if hotkey.active.get() && !Self::resolve_conflicts(action, &value) {
+            return false;
+        }
+
+        hotkey.value.set_neq(value);
+        REVISION.with(|revision| revision.replace_with(|revision| revision.wrapping_add(1)));
+
+        true
+    }
+
+    /// Returns `false` if the state wasn't changed.
+    pub(crate) fn set_active(action: HotkeyAction, hotkey: &Hotkey, active: bool) -> bool {
+        if active {
+            let value = hotkey.value.get_cloned();
+
+            if value.is_empty() {
+                if message(tr!(HotkeysUnbound)).is_err() {
+                    console_error!()
+                }
+                return false;
+            }
+            if !Self::resolve_conflicts(action, &value) {
+                return false;
+            }
+        } else if !action.can_disable() {
+            return false;
+        }
+
+        hotkey.active.set_neq(active);
+        REVISION.with(|revision| revision.replace_with(|revision| revision.wrapping_add(1)));
+
+        true
+    }
+
+    /// Every registered hotkey, displayed in the manager settings.
+    pub(crate) fn render_settings() -> Dom {
+        html!(s!("div"), {
+            .class(s!("mdma-hotkeys"))
+            .children_signal_vec(
+                REGISTERED
+                    .with(|hotkeys| hotkeys.signal_vec_cloned())
+                    .map(|registered| registered.render()),
+            )
+        })
+    }
+}
@@ -1,8 +1,12 @@
+use common::messaging::prelude::LogLevel;
 use dominator::{Dom, events::Click, html};
 use wasm_bindgen::{JsValue, intern};
 use web_sys::HtmlElement;
 
-use crate::{addon_window::MdmaAddonWindow, prelude::*, utils::logging::console_log};
+use crate::{
+    addon_window::MdmaAddonWindow, interface::console::LogSource, prelude::*,
+    utils::logging::console_log_with,
+};
 
 impl AddonData {
     /// Renders an addon box in the UI.
@@ -41,7 +45,7 @@ impl AddonData {
     }
 
     /// Toggles the current addon active state
-    fn toggle_addon(addon_name: AddonName) {
+    pub(super) fn toggle_addon(addon_name: AddonName) {
         let addon_data = Addons::get_addon(addon_name).unwrap_js();
         let after_toggle = !addon_data.active.get();
 
@@ -58,6 +62,6 @@ impl AddonData {
             }
         };
 
-        console_log(msg);
+        console_log_with(LogLevel::Info, LogSource::Addon(addon_name), msg);
     }
 }
@@ -0,0 +1,507 @@
+//! MDMA console window, log filtering, persistence between page reloads and
+//! the diagnostics bundle export.
+
+use std::cell::Cell;
+
+use common::i18n::Msg;
+use common::messaging::prelude::*;
+use dominator::{
+    Dom, clone,
+    events::{Change, Click, Input as InputEvent, KeyDown},
+    html, with_node,
+};
+use futures::FutureExt;
+use futures_signals::{
+    map_ref,
+    signal::{Mutable, Signal, SignalExt},
+    signal_vec::SignalVecExt,
+};
+use serde_json::{Map, Value, json};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Blob, BlobPropertyBag, HtmlElement, HtmlInputElement, HtmlSelectElement, Url};
+
+use crate::prelude::*;
+
+use super::{CONSOLE_LOGS, CONSOLE_MESSAGES, ConsoleLog, ConsoleLogTypes};
+
+/// Highest amount of logs kept between page reloads.
+const PERSISTED_LOGS: usize = 200;
+const SAVE_INTERVAL: u32 = 10_000;
+/// Milliseconds the downloaded bundle stays available to the browser.
+const REVOKE_URL_DELAY: i32 = 10_000;
+/// Settings with a key containing any of these are left out of the
+/// diagnostics bundle.
+const SECRET_KEYS: [&str; 6] = ["token", "password", "secret", "cookie", "session", "auth"];
+
+thread_local! {
+    static CONSOLE_FILTER: ConsoleFilter = ConsoleFilter::default();
+    /// Whether logs were added since they were last persisted.
+    static UNSAVED_LOGS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Origin of a console log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LogSource {
+    Manager,
+    Communication,
+    Addon(AddonName),
+}
+
+impl LogSource {
+    fn key(self) -> &'static str {
+        match self {
+            Self::Manager => "manager",
+            Self::Communication => "communication",
+            Self::Addon(addon_name) => addon_name.key_str(),
+        }
+    }
+
+    /// Addon owning the module, e.g. `foreground::addons::kastrat::html`, the
+    /// manager for modules outside of `addons`.
+    pub(crate) fn from_module_path(module_path: &str) -> Self {
+        module_path
+            .split("::")
+            .skip_while(|segment| *segment != "addons")
+            .nth(1)
+            .and_then(AddonName::from_key)
+            .map_or(Self::Manager, Self::Addon)
+    }
+
+    fn from_key(key: &str) -> Self {
+        match key {
+            "communication" => Self::Communication,
+            key => Addons::try_get()
+                .and_then(|addons| {
+                    addons
+                        .iter()
+                        .flatten()
+                        .find(|(addon_name, _)| addon_name.key_str() == key)
+                })
+                .map_or(Self::Manager, |(addon_name, _)| Self::Addon(addon_name)),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Manager => tr!(ConsoleSourceManager),
+            Self::Communication => tr!(ConsoleSourceCommunication),
+            Self::Addon(addon_name) => addon_name.as_str(),
+        }
+    }
+}
+
+struct ConsoleFilter {
+    /// Lowest level of displayed logs.
+    level: Mutable<LogLevel>,
+    /// `None` displays logs from every source.
+    source: Mutable<Option<LogSource>>,
+    query: Mutable<String>,
+}
+
+impl Default for ConsoleFilter {
+    fn default() -> Self {
+        Self {
+            level: Mutable::new(LogLevel::Debug),
+            source: Mutable::new(None),
+            query: Mutable::new(String::new()),
+        }
+    }
+}
+
+impl ConsoleFilter {
+    fn matches_signal(&self, log: &ConsoleLog) -> impl Signal<Item = bool> + use<> {
+        let level = log.level;
+        let source = log.source;
+        let text = log.to_text().unwrap_or_default().to_lowercase();
+
+        map_ref! {
+            let min_level = self.level.signal(),
+            let selected_source = self.source.signal(),
+            let query = self.query.signal_ref(|query| query.trim().to_lowercase()) => {
+                level >= *min_level
+                    && selected_source.is_none_or(|selected| selected == source)
+                    && (query.is_empty() || text.contains(query.as_str()))
+            }
+        }
+    }
+}
+
+impl ConsoleLog {
+    fn restore(log: PersistedLog) -> Self {
+        let type_ = match log.level {
+            LogLevel::Error => ConsoleLogTypes::Error,
+            _ => ConsoleLogTypes::Message,
+        };
+        // Errors are persisted in the copied json format, which `MdmaError::from_js` can read.
+        let inner = match type_ {
+            ConsoleLogTypes::Error => js_sys::JSON::parse(&log.text)
+                .ok()
+                .filter(JsValue::is_object)
+                .unwrap_or_else(|| JsValue::from_str(&log.text)),
+            _ => JsValue::from_str(&log.text),
+        };
+
+        Self {
+            type_,
+            level: log.level,
+            source: LogSource::from_key(&log.source),
+            inner,
+            created_at: log.ev,
+            logged_at: log.logged_at,
+            restored: true,
+        }
+    }
+
+    fn to_persisted(&self) -> Option<PersistedLog> {
+        Some(PersistedLog {
+            level: self.level,
+            source: self.source.key().to_string(),
+            text: self.to_text()?,
+            ev: self.created_at,
+            logged_at: self.logged_at,
+        })
+    }
+}
+
+/// Restores logs from before the last page reload and starts persisting new
+/// ones.
+///
+/// # SAFETY
+/// Has to be called after [`Port`] connection is initialized.
+pub(crate) async fn init() -> JsResult<()> {
+    // New logs still get persisted if the old ones couldn't be loaded.
+    match Port::load_console_logs().await {
+        Ok(persisted) => restore(persisted),
+        Err(err_code) => console_error!(err_code),
+    }
+
+    // Sending a message over the port doesn't wait for anything, so the logs
+    // get saved before the page unloads.
+    window().add_event_listener_with_callback(
+        "pagehide",
+        &closure!(@once move || {
+            if let Some(Err(err_code)) = save().now_or_never() {
+                debug_log!(err_code);
+            }
+        }),
+    )?;
+    wasm_bindgen_futures::spawn_local(start_save_interval());
+
+    Ok(())
+}
+
+fn restore(persisted: Vec<PersistedLog>) {
+    let restored: Vec<ConsoleLog> = persisted.into_iter().map(ConsoleLog::restore).collect();
+
+    // Restored logs are older than every log added during the initialization.
+    CONSOLE_LOGS.with_borrow_mut(|logs| {
+        for log in restored.iter().rev() {
+            if logs.len() == logs.capacity() {
+                break;
+            }
+            logs.push_back(log.clone());
+        }
+    });
+    CONSOLE_MESSAGES.with(|messages| {
+        let mut messages_lock = messages.lock_mut();
+
+        for (index, log) in restored.into_iter().enumerate() {
+            messages_lock.insert_cloned(index, log);
+        }
+    });
+}
+
+pub(crate) fn mark_unsaved() {
+    UNSAVED_LOGS.set(true);
+}
+
+async fn start_save_interval() {
+    loop {
+        delay(SAVE_INTERVAL).await;
+
+        if let Err(err_code) = save().await {
+            console_error!(err_code);
+        }
+    }
+}
+
+async fn save() -> JsResult<()> {
+    if !UNSAVED_LOGS.replace(false) {
+        return Ok(());
+    }
+
+    let logs = CONSOLE_MESSAGES.with(|logs| {
+        let mut persisted: Vec<PersistedLog> = logs
+            .lock_ref()
+            .iter()
+            .rev()
+            .filter_map(ConsoleLog::to_persisted)
+            .take(PERSISTED_LOGS)
+            .collect();
+
+        persisted.reverse();
+        persisted
+    });
+
+    Port::send(
+        &Message::builder(Task::ConsoleLogs, Target::Background, MessageKind::Request)
+            .console_logs(logs)
+            .character(Hero::get().character())
+            .build(),
+    )
+    .await
+}
+
+/// Removes every log, including the persisted ones.
+fn clear() {
+    CONSOLE_LOGS.with_borrow_mut(|logs| logs.retain(ConsoleLog::is_communication_data));
+    CONSOLE_MESSAGES.with(|messages| messages.lock_mut().clear());
+    mark_unsaved();
+
+    wasm_bindgen_futures::spawn_local(async {
+        if let Err(err_code) = save().await {
+            console_error!(err_code);
+        }
+    });
+}
+
+/// Downloads a json file with the console logs, addon settings and version
+/// info, meant to be attached to bug reports.
+pub(crate) fn export_bundle() -> JsResult<()> {
+    let logs = CONSOLE_MESSAGES.with(|logs| {
+        logs.lock_ref()
+            .iter()
+            .filter_map(ConsoleLog::to_persisted)
+            .collect::<Vec<_>>()
+    });
+    let settings = Addons::try_get()
+        .map(|addons| {
+            addons
+                .iter()
+                .flatten()
+                .map(|(addon_name, addon_data)| {
+                    let mut settings = json!({
+                        "active": addon_data.active.get(),
+                        "settings": Addons::__internal_get_settings(addon_name),
+                        "active_settings": Addons::__internal_get_active_settings(addon_name),
+                    });
+
+                    strip_secrets(&mut settings);
+                    (addon_name.key_str().to_string(), settings)
+                })
+                .collect::<Map<String, Value>>()
+        })
+        .unwrap_or_default();
+    let bundle = json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "interface": match cfg!(feature = "ni") {
+            true => "ni",
+            false => "si",
+        },
+        "user_agent": window().navigator().user_agent().ok(),
+        "exported_at": String::from(js_sys::Date::new_0().to_iso_string()),
+        "logs": logs,
+        "settings": settings,
+    });
+    let contents = serde_json::to_string_pretty(&bundle).map_err(map_err!(from))?;
+
+    download(
+        &format!("mdma-diagnostics-{}.json", js_sys::Date::now() as u64),
+        &contents,
+    )
+}
+
+/// Removes every value with a key which could hold credentials.
+fn strip_secrets(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            object.retain(|key, _| {
+                let key = key.to_lowercase();
+                !SECRET_KEYS.iter().any(|secret| key.contains(secret))
+            });
+            object.values_mut().for_each(strip_secrets);
+        }
+        Value::Array(array) => array.iter_mut().for_each(strip_secrets),
+        _ => {}
+    }
+}
+
+fn download(file_name: &str, contents: &str) -> JsResult<()> {
+    let options = BlobPropertyBag::new();
+    options.set_type("application/json");
+
+    let blob = Blob::new_with_str_sequence_and_options(
+        &js_sys::Array::of1(&JsValue::from_str(contents)),
+        &options,
+    )?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+    let anchor = document()
+        .create_element(s!("a"))?
+        .unchecked_into::<HtmlElement>();
+
+    anchor.set_attribute(s!("href"), &url)?;
+    anchor.set_attribute(s!("download"), file_name)?;
+    anchor.click();
+
+    // Revoking the url right away can cancel the download before it starts.
+    let revoke = closure!(@once move || {
+        if let Err(err_code) = Url::revoke_object_url(&url) {
+            console_error!(err_code);
+        }
+    });
+
+    window()
+        .set_timeout_with_callback_and_timeout_and_arguments_0(&revoke, REVOKE_URL_DELAY)
+        .map(|_| ())
+}
+
+pub(super) fn get_console_content() -> Dom {
+    let msgs = CONSOLE_MESSAGES.with(|logs| logs.signal_vec_cloned().map(get_console_text_div));
+
+    html!(s!("div"), {
+        .attr(s!("id"), s!("mdma-console-content"))
+        .class(s!("mdma-content"))
+        .child(get_console_decoder())
+        .child(get_console_filter())
+        .children_signal_vec(msgs)
+    })
+}
+
+fn get_console_filter() -> Dom {
+    const LEVELS: [(LogLevel, Msg); 4] = [
+        (LogLevel::Debug, Msg::ConsoleLevelAll),
+        (LogLevel::Info, Msg::ConsoleLevelInfo),
+        (LogLevel::Warn, Msg::ConsoleLevelWarn),
+        (LogLevel::Error, Msg::ConsoleLevelError),
+    ];
+
+    let (level, source, query) = CONSOLE_FILTER.with(|filter| {
+        (
+            filter.level.clone(),
+            filter.source.clone(),
+            filter.query.clone(),
+        )
+    });
+    let sources = [LogSource::Manager].into_iter().chain(
+        Addons::try_get()
+            .into_iter()
+            .flat_map(|addons| addons.iter().flatten())
+            .map(|(addon_name, _)| LogSource::Addon(addon_name)),
+    );
+
+    html!(s!("div"), {
+        .class(s!("mdma-console-filter"))
+        .children(LEVELS.map(|(button_level, text)| {
+            html!(s!("span"), {
+                .class(s!("mdma-console-filter-button"))
+                .class_signal("active", level.signal_ref(move |level| *level == button_level))
+                .text(text.text())
+                .event(clone!(level => move |_: Click| level.set(button_level)))
+            })
+        }))
+        .child(html!(s!("select") => HtmlSelectElement, {
+            .class(s!("mdma-console-source"))
+            .child(html!(s!("option"), {
+                .attr(s!("value"), "")
+                .text(tr!(ConsoleSourceAll))
+            }))
+            .children(sources.map(|source| {
+                html!(s!("option"), {
+                    .attr(s!("value"), source.key())
+                    .text(source.label())
+                })
+            }))
+            .with_node!(select => {
+                .event(move |_: Change| {
+                    let key = select.value();
+                    source.set((!key.is_empty()).then(|| LogSource::from_key(&key)));
+                })
+            })
+        }))
+        .child(html!(s!("input") => HtmlInputElement, {
+            .class(s!("mdma-console-search"))
+            .attr(s!("type"), s!("text"))
+            .attr(s!("placeholder"), tr!(ConsoleSearch))
+            .with_node!(input => {
+                .event(move |_: InputEvent| {
+                    query.set(input.value());
+                })
+            })
+        }))
+        .child(html!(s!("span"), {
+            .class(s!("mdma-console-filter-button"))
+            .attr(s!("title"), tr!(ConsoleClearTip))
+            .text(tr!(ConsoleClear))
+            .event(|_: Click| clear())
+        }))
+    })
+}
+
+/// Input decoding error codes from a pasted code or a copied console log.
+fn get_console_decoder() -> Dom {
+    html!(s!("input") => HtmlInputElement, {
+        .class(s!("mdma-console-decoder"))
+        .attr(s!("type"), s!("text"))
+        .attr(s!("placeholder"), tr!(ConsoleDecoderPlaceholder))
+        .with_node!(input => {
+            .event(move |event: KeyDown| {
+                if event.key() != "Enter" {
+                    return;
+                }
+
+                decode_error_report(&input.value());
+                input.set_value("");
+            })
+        })
+    })
+}
+
+fn decode_error_report(report: &str) {
+    use common::error::{DecodedLocation, parse_error_report};
+
+    let reported_codes = parse_error_report(report);
+
+    if reported_codes.is_empty() {
+        return logging::console_log(JsValue::from_str(tr!(ConsoleDecoderNoCodes)));
+    }
+
+    for reported in reported_codes {
+        let text = match reported.release {
+            // Indexes differ between releases, so only the matching symbol table can decode them.
+            Some(release) if release != DecodedLocation::release() => tr!(
+                ConsoleDecoderOtherRelease,
+                code = reported.code,
+                release = release
+            ),
+            _ => DecodedLocation::decode(reported.code).to_string(),
+        };
+
+        logging::console_log(JsValue::from_str(&text));
+    }
+}
+
+fn get_console_text_div(log: ConsoleLog) -> Dom {
+    let visible = CONSOLE_FILTER.with(|filter| filter.matches_signal(&log));
+
+    html!(s!("div"), {
+        .class(s!("mdma-console-text"))
+        .style_signal("display", visible.map(|visible| (!visible).then_some("none")))
+        .apply_if(log.restored, |builder| builder.style(s!("opacity"), "0.6"))
+        .apply(|builder| match log.level {
+            LogLevel::Error => builder.style(s!("color"), s!("red")),
+            LogLevel::Warn => builder.style(s!("color"), s!("orange")),
+            LogLevel::Debug | LogLevel::Info => builder,
+        })
+        .child(html!(s!("span"), {
+            .class(s!("mdma-console-source"))
+            .text(&format!("[{}] ", log.source.label()))
+        }))
+        .apply(|builder| match log.inner.as_string() {
+            Some(text) => builder.text(&text),
+            None if log.type_ == ConsoleLogTypes::Error => builder
+                .style(s!("white-space"), s!("pre-line"))
+                .text(&MdmaError::from_js(&log.inner).to_string()),
+            None => builder.text(tr!(ConsoleUnknownEntry)),
+        })
+    })
+}
@@ -1,12 +1,11 @@
 mod addons;
+pub(crate) mod console;
 pub(crate) mod dom_utils;
 pub(crate) mod tips_parser;
 mod window;
 
 use std::cell::RefCell;
 use std::collections::VecDeque;
-use std::iter;
-use std::ops::Deref;
 use std::thread::LocalKey;
 
 use dominator::events::{MouseButton, MouseDown, Wheel};
@@ -22,14 +21,20 @@ use web_sys::{HtmlDivElement, ShadowRoot};
 #[cfg(feature = "ni")]
 use crate::addon_window::ITEM_FRAME;
 use crate::addon_window::MdmaAddonWindow;
-use crate::addon_window::ui_components::{Checkbox, Input, InputType};
-use crate::globals::addons::AddonData;
-use crate::globals::{ManagerGlobals, ManagerHotkey};
+use console::LogSource;
+use crate::addon_window::ui_components::Checkbox;
+use crate::globals::addons::{AddonData, InterfaceSupport};
+use crate::globals::ManagerGlobals;
+use crate::hotkeys::{HotkeyAction, Hotkeys};
+use crate::overlay::Overlay;
 use crate::prelude::*;
 
+/// Highest amount of logs displayed in the console window.
+pub(crate) const CONSOLE_CAPACITY: usize = 500;
+
 thread_local! {
-    pub(crate) static CONSOLE_LOGS: RefCell<VecDeque<ConsoleLog>> = RefCell::new(VecDeque::with_capacity(500));
-    pub(crate) static CONSOLE_MESSAGES: MutableVec<ConsoleLog> = MutableVec::with_capacity(500);
+    pub(crate) static CONSOLE_LOGS: RefCell<VecDeque<ConsoleLog>> = RefCell::new(VecDeque::with_capacity(CONSOLE_CAPACITY));
+    pub(crate) static CONSOLE_MESSAGES: MutableVec<ConsoleLog> = MutableVec::with_capacity(CONSOLE_CAPACITY);
     pub(crate) static INTERFACE_VISIBLE: Mutable<bool> = Mutable::new(false);
     pub(crate) static WINDOWS_ROOT: RefCell<Option<ShadowRoot>> = const { RefCell::new(None) };
     pub(crate) static INTERFACE_ROOT: RefCell<Option<ShadowRoot>> = const { RefCell::new(None)};
@@ -46,28 +51,92 @@ pub(crate) enum ConsoleLogTypes {
 #[derive(Debug, Clone)]
 pub(crate) struct ConsoleLog {
     type_: ConsoleLogTypes,
+    level: LogLevel,
+    source: LogSource,
     inner: JsValue,
     created_at: Option<f64>,
+    /// Unix timestamp in milliseconds.
+    logged_at: f64,
+    /// Whether the log was restored from before the last page reload.
+    restored: bool,
 }
 
 impl ConsoleLog {
     pub(crate) fn new(type_: ConsoleLogTypes, inner: JsValue, created_at: Option<f64>) -> Self {
+        let (level, source) = match type_ {
+            ConsoleLogTypes::Error => (LogLevel::Error, LogSource::Manager),
+            ConsoleLogTypes::Message => (LogLevel::Info, LogSource::Manager),
+            ConsoleLogTypes::CommunicationData => (LogLevel::Debug, LogSource::Communication),
+        };
+
         Self {
             type_,
+            level,
+            source,
             inner,
             created_at,
+            logged_at: js_sys::Date::now(),
+            restored: false,
         }
     }
+
+    pub(crate) fn level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub(crate) fn source(mut self, source: LogSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub(crate) fn is_communication_data(&self) -> bool {
+        self.type_ == ConsoleLogTypes::CommunicationData
+    }
+
+    /// Text of the log as copied from the console, errors are represented by
+    /// a json line which can be decoded with `extract_filenames`. Errors thrown by js
+    /// have no code to decode.
+    pub(crate) fn to_text(&self) -> Option<String> {
+        if let Some(log) = self.inner.as_string() {
+            return Some(log);
+        }
+        if self.type_ != ConsoleLogTypes::Error {
+            return None;
+        }
+
+        let error = MdmaError::from_js(&self.inner);
+        let context = serde_json::to_string(&error.context).unwrap_or_default();
+        let code = match error.is_js() {
+            true => String::from("null"),
+            false => error.code.to_string(),
+        };
+
+        Some(format!(
+            "{{\"mdma_err\": {code}, \"kind\": \"{}\", \"fatal\": {}, \"context\": {context}, \"ev\": \"{:?}\", \"v\": \"{}\"}}",
+            error.kind.as_str(),
+            error.is_fatal(),
+            self.created_at,
+            env!("CARGO_PKG_VERSION")
+        ))
+    }
 }
 
 pub(crate) trait ThreadLocalShadowRoot {
     fn try_append_dom(&'static self, dom: Dom) -> Option<DomHandle>;
+    /// Without the root no window can be displayed, so its absence is fatal.
+    fn append_window(&'static self, dom: Dom) -> MdmaResult<DomHandle>;
 }
 
 impl ThreadLocalShadowRoot for LocalKey<RefCell<Option<ShadowRoot>>> {
     fn try_append_dom(&'static self, dom: Dom) -> Option<DomHandle> {
         self.with_borrow(|root| root.as_ref().map(|root| dominator::append_dom(root, dom)))
     }
+
+    fn append_window(&'static self, dom: Dom) -> MdmaResult<DomHandle> {
+        self.try_append_dom(dom)
+            .ok_or_else(|| err!(Get, "windows root").fatal())
+    }
 }
 
 pub(crate) fn get_windows_stylesheet() -> Dom {
@@ -243,8 +312,6 @@ fn render_interface(manager_globals: &'static ManagerGlobals) -> Dom {
     use dominator::events::KeyDown;
     use window::MdmaWindow;
 
-    use crate::utils::window_events;
-
     let chat_visible: &'static Mutable<Option<&str>> = Box::leak(Box::new(Mutable::new(None)));
 
     #[cfg(feature = "ni")]
@@ -264,7 +331,7 @@ fn render_interface(manager_globals: &'static ManagerGlobals) -> Dom {
     );
     let console_window = MdmaWindow::render(
         &mut MdmaWindow::new(WindowType::Console),
-        get_console_content(),
+        console::get_console_content(),
     );
 
     html!(s!("div"), {
@@ -274,14 +341,47 @@ fn render_interface(manager_globals: &'static ManagerGlobals) -> Dom {
         .children([mdma_window, settings_window, addons_window, console_window]
         )
         .global_event_with_options(&EventOptions::preventable(), |event: KeyDown| {
-            match window_events::validate_keydown_event(&event, manager_globals.hotkey.lock_ref().deref()) {
-                Ok(key_matched) => if !key_matched { return },
+            match manager_globals.hotkey.is_pressed(&event) {
+                Ok(true) => {
+                    event.prevent_default();
+                    event.stop_propagation();
+                    return INTERFACE_VISIBLE.with(|visible| visible.set(!visible.get()));
+                }
+                Ok(false) => {}
                 Err(err) => return console_error!(err),
-            };
+            }
 
-            event.prevent_default();
-            event.stop_propagation();
-            INTERFACE_VISIBLE.with(|visible| visible.set(!visible.get()));
+            // Hotkeys available for every addon, addon specific ones are handled by the addons.
+            for (addon_name, addon_data) in Addons::get().iter().flatten() {
+                let action = match addon_data.hotkeys.open_window.is_pressed(&event) {
+                    Ok(true) => HotkeyAction::OpenWindow(addon_name),
+                    Ok(false) => match addon_data.hotkeys.toggle.is_pressed(&event) {
+                        Ok(true) => HotkeyAction::ToggleAddon(addon_name),
+                        Ok(false) => continue,
+                        Err(err) => return console_error!(err),
+                    },
+                    Err(err) => return console_error!(err),
+                };
+
+                event.prevent_default();
+                event.stop_propagation();
+
+                match action {
+                    HotkeyAction::OpenWindow(_) => {
+                        // Addons without their own window open the settings window instead.
+                        let window_type = match addon_data.active_settings_window.root.borrow().is_some() {
+                            true => WindowType::AddonWindow,
+                            false => WindowType::SettingsWindow,
+                        };
+
+                        if let Err(err) = Addons::toggle_addon_active_state(addon_name, window_type) {
+                            console_error!(err);
+                        }
+                    }
+                    _ => AddonData::toggle_addon(addon_name),
+                }
+                return;
+            }
         })
     })
 }
@@ -329,6 +429,7 @@ fn observe_chat(chat_visible: &'static Mutable<Option<&'static str>>) -> JsResul
         .map_err(map_err!())
 }
 
+#[derive(Clone, Copy, PartialEq)]
 pub(crate) enum WindowType {
     Mdma,
     Settings,
@@ -349,31 +450,6 @@ fn get_addons_content() -> Dom {
     })
 }
 
-fn get_console_content() -> Dom {
-    use futures_signals::signal_vec::SignalVecExt;
-
-    let msgs = CONSOLE_MESSAGES.with(|logs| logs.signal_vec_cloned().map(get_console_text_div));
-
-    html!(s!("div"), {
-        .attr(s!("id"), s!("mdma-console-content"))
-        .class(s!("mdma-content"))
-        .children_signal_vec(msgs)
-    })
-}
-
-fn get_console_text_div(log: ConsoleLog) -> Dom {
-    html!(s!("div"), {
-        .class(s!("mdma-console-text"))
-        .apply_if(log.type_ == ConsoleLogTypes::Error, |builder| {
-            builder.style(s!("color"), s!("red"))
-        })
-        .apply(|builder| match log.inner.as_string() {
-            Some(text) => builder.text(&text),
-            None => builder.text(intern(s!("An error has occured!"))),
-        })
-    })
-}
-
 enum MdmaContentButton {
     GitHub,
     Discord,
@@ -382,7 +458,7 @@ enum MdmaContentButton {
 fn get_mdma_content() -> Dom {
     let version_elem = html!(s!("span"), {
         .class(s!("mdma-version"))
-        .text(&(string!("Wersja: ") + s!(env!("CARGO_PKG_VERSION"))))
+        .text(&tr!(ManagerVersion, version = env!("CARGO_PKG_VERSION")))
     });
     //let build_elem = html!(s!("span"), {
     //    .class(s!("mdma-build"))
@@ -499,10 +575,10 @@ pub(crate) fn render_widget(
                     .apply_if(toggle_manager, |builder| {
                         builder
                             .child(html!(intern(s!("br")), {}))
-                            .text(intern(s!("LPM aby otworzyć okno zestawu dodatków")))
+                            .text(tr!(WidgetOpenManager))
                     })
                     .child(html!(intern(s!("br")), {}))
-                    .text(intern(s!("PPM aby otworzyć menu rozszerzenia")))
+                    .text(tr!(WidgetOpenPopup))
                 })
                 .event(move |event: MouseDown| {
                     if toggle_manager && event.button() == MouseButton::Left {
@@ -528,7 +604,37 @@ pub(crate) fn render_widget(
 
 pub const ALLOWED_CHARS: &str = "!@#$%^&*()_+-={}[]\\|;:'\",.<>/?`~€§";
 
+fn render_language_select(language: &'static Mutable<Option<Locale>>) -> Dom {
+    use dominator::{events::Change, with_node};
+    use web_sys::HtmlSelectElement;
+
+    let selected = language.get();
+
+    html!(s!("select") => HtmlSelectElement, {
+        .attr(s!("title"), tr!(ManagerLanguageTip))
+        .child(html!(s!("option"), {
+            .attr(s!("value"), "")
+            .apply_if(selected.is_none(), |builder| builder.attr(s!("selected"), ""))
+            .text(tr!(ManagerLanguageAuto))
+        }))
+        .children([Locale::Pl, Locale::En].map(|locale| {
+            html!(s!("option"), {
+                .attr(s!("value"), locale.key())
+                .apply_if(selected == Some(locale), |builder| builder.attr(s!("selected"), ""))
+                .text(locale.name())
+            })
+        }))
+        .with_node!(select => {
+            .event(move |_: Change| {
+                language.set_neq(Locale::from_key(&select.value()));
+            })
+        })
+    })
+}
+
 fn get_settings_content(manager_globals: &'static ManagerGlobals) -> Dom {
+    use futures_signals::signal_vec::SignalVecExt;
+
     let widget = render_widget(true, manager_globals.widget_active.signal());
     let parent = match cfg!(feature = "ni") {
         true => document()
@@ -539,100 +645,60 @@ fn get_settings_content(manager_globals: &'static ManagerGlobals) -> Dom {
     };
     dominator::append_dom(&parent, widget);
 
-    let hotkey_lock = manager_globals.hotkey.lock_ref();
-    let display_value = hotkey_lock
-        .ctrl_key
-        .then_some("Ctrl")
-        .into_iter()
-        .chain(hotkey_lock.alt_key.then_some("Alt"))
-        .chain(hotkey_lock.shift_key.then_some("Shift"))
-        .chain(iter::once(hotkey_lock.value.as_str()))
-        .map(ToString::to_string)
-        .collect::<Vec<String>>()
-        .join(" + ");
-
-    drop(hotkey_lock);
-
-    let manager_keybind_input = Input::builder()
-        .class_list("m[0] p[0]")
-        .input_type(InputType::keybind())
-        .value(display_value)
-        .maxlength("1")
-        .on_key_down(move |event, input_elem| {
-            if event.repeat() {
-                return;
-            }
-
-            let value = event.key();
-            let mut chars = value.chars();
-
-            if chars
-                .next()
-                .is_none_or(|c| !is_from_polish_alphabet(c) && !ALLOWED_CHARS.contains(c))
-            {
-                event.prevent_default();
-                event.stop_propagation();
-                input_elem.blur().unwrap_js();
-                return;
-            }
-            if chars.next().is_some() {
-                event.prevent_default();
-                event.stop_propagation();
-
-                if value != intern("Tab") {
-                    if value == intern("Escape")
-                        || !matches!(value.as_str(), "Control" | "Alt" | "Shift")
-                    {
-                        input_elem.blur().unwrap_js();
-                    }
-                    return;
-                }
-            }
-
-            let value = value
-                .chars()
-                .map(polish_to_ascii)
-                .collect::<String>()
-                .to_ascii_uppercase();
-            let mut new_hotkey = ManagerHotkey::default();
-            let display_value = event
-                .ctrl_key()
-                .then(|| {
-                    new_hotkey.ctrl_key = true;
-                    "Ctrl"
-                })
-                .into_iter()
-                .chain(event.alt_key().then(|| {
-                    new_hotkey.alt_key = true;
-                    "Alt"
-                }))
-                .chain(event.shift_key().then(|| {
-                    new_hotkey.shift_key = true;
-                    "Shift"
-                }))
-                .chain(iter::once(value.as_str()))
-                .map(ToString::to_string)
-                .collect::<Vec<String>>()
-                .join(" + ");
-
-            event.prevent_default();
-            event.stop_propagation();
-            input_elem.blur().unwrap_js();
-            input_elem.set_value(&display_value);
-            new_hotkey.value = value;
-            manager_globals.hotkey.set(new_hotkey);
-        });
-
     html!(s!("div"), {
         .class(s!("mdma-content"))
         .child(html!(s!("div"), {
             .class(s!("widget-label"))
-            .checkbox(Checkbox::builder(manager_globals.widget_active.clone()).text(s!("Wyświetlaj widżet")))
+            .checkbox(Checkbox::builder(manager_globals.widget_active.clone()).text(tr!(ManagerWidgetActive)))
+        }))
+        .child(html!(s!("div"), {
+            .class(s!("widget-label"))
+            .attr("title", tr!(ManagerDiagnosticsTip))
+            .checkbox(Checkbox::builder(manager_globals.diagnostics.clone()).text(tr!(ManagerDiagnostics)))
+        }))
+        .child(html!(s!("div"), {
+            .class(s!("widget-label"))
+            .attr("title", tr!(ManagerAttackAlertsTip))
+            .checkbox(Checkbox::builder(manager_globals.attack_alerts.clone()).text(tr!(ManagerAttackAlerts)))
+        }))
+        .child(html!(s!("div"), {
+            .class(s!("widget-label"))
+            .text(tr!(ManagerLanguage))
+            .child(render_language_select(&manager_globals.language))
+        }))
+        .child(html!(s!("div"), {
+            .class(s!("widget-label"))
+            .text(tr!(ManagerHotkeys))
         }))
+        .child(Hotkeys::render_settings())
         .child(html!(s!("div"), {
             .class(s!("widget-label"))
-            .text("Otwórz manager")
-            .input(manager_keybind_input)
+            .text(match cfg!(feature = "ni") {
+                true => tr!(ManagerAddonSupportNi),
+                false => tr!(ManagerAddonSupportSi),
+            })
+        }))
+        .children(Addons::get().iter().flatten().map(|(addon_name, _)| {
+            let support = addon_name.interface_support();
+
+            html!(s!("div"), {
+                .class(s!("widget-label"))
+                .text(&format!("{}: {}", addon_name.as_str(), support.as_str()))
+                .apply(|builder| match support {
+                    InterfaceSupport::Partial(description) => builder.attr("title", description.text()),
+                    InterfaceSupport::Full => builder,
+                })
+            })
+        }))
+        .child(html!(s!("div"), {
+            .class(s!("widget-label"))
+            .text(tr!(ManagerMapLayers))
+        }))
+        .children_signal_vec(Overlay::layer_names_signal_vec().map(|addon_name| {
+            html!(s!("div"), {
+                .class(s!("widget-label"))
+                .checkbox(Checkbox::builder(Overlay::layer_visible(addon_name)).text(addon_name.as_str()))
+            })
         }))
     })
 }
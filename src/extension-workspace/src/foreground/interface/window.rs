@@ -1,4 +1,4 @@
-use common::{debug_log, map_err};
+use common::{debug_log, map_err, tr};
 use dominator::{Dom, DomBuilder, html};
 use futures_signals::signal::{Mutable, Signal};
 use wasm_bindgen::intern;
@@ -11,6 +11,7 @@ use super::WindowType;
 /// Struct for creating a new addon window with the given header name.
 #[derive(Clone)]
 pub(crate) struct MdmaWindow {
+    window_type: WindowType,
     name: &'static str,
     active: Mutable<bool>,
 }
@@ -18,22 +19,33 @@ pub(crate) struct MdmaWindow {
 impl MdmaWindow {
     pub(crate) fn new(window_type: WindowType) -> Self {
         let window_name = match window_type {
-            WindowType::Addons => "Dodatki",
-            WindowType::Console => "Konsola",
+            WindowType::Addons => tr!(WindowAddons),
+            WindowType::Console => tr!(WindowConsole),
             WindowType::Mdma => "MDMA",
-            WindowType::Settings => "Ustawienia",
+            WindowType::Settings => tr!(WindowSettings),
         };
         Self {
+            window_type,
             name: window_name,
             active: Mutable::new(true),
         }
     }
 
+    /// Class of the window, independent of the language of its name.
+    const fn class(&self) -> &'static str {
+        match self.window_type {
+            WindowType::Addons => "dodatki",
+            WindowType::Console => "konsola",
+            WindowType::Mdma => "mdma",
+            WindowType::Settings => "ustawienia",
+        }
+    }
+
     pub(crate) fn render(state: &mut Self, content: Dom) -> Dom {
         html!(s!("div"), {
             .class(s!("interface-window"))
             .class_signal("active", state.active.signal())
-            .class(state.name.to_lowercase())
+            .class(state.class())
             .style_signal("left", Self::random_position(false))
             .style_signal("top", Self::random_position(true))
             .children(&mut [
@@ -86,22 +98,33 @@ impl MdmaWindow {
     ) -> DomBuilder<HtmlElement> {
         use dominator::{clone, events::Click};
 
-        match state.name == s!("Konsola") {
+        match state.window_type == WindowType::Console {
             false => interface_builder.child(html!(s!("span"), {
                 .class(s!("mdma-dropdown-toggle"))
                 .event(clone!(state => move |_: Click| {
                     state.active.set(!state.active.get())
                 }))
             })),
-            true => interface_builder.child(html!(s!("span"), {
-                .class(s!("mdma-copy-button"))
-                .text(s!("📋"))
-                .event(|_: Click| {
-                    wasm_bindgen_futures::spawn_local(async {
-                        Self::copy_console_text().await
-                    });
-                })
-            })),
+            true => interface_builder
+                .child(html!(s!("span"), {
+                    .class(s!("mdma-export-button"))
+                    .attr(s!("title"), tr!(ConsoleExportBundle))
+                    .text(s!("💾"))
+                    .event(|_: Click| {
+                        if let Err(err_code) = super::console::export_bundle() {
+                            console_error!(err_code);
+                        }
+                    })
+                }))
+                .child(html!(s!("span"), {
+                    .class(s!("mdma-copy-button"))
+                    .text(s!("📋"))
+                    .event(|_: Click| {
+                        wasm_bindgen_futures::spawn_local(async {
+                            Self::copy_console_text().await
+                        });
+                    })
+                })),
         }
     }
 
@@ -121,16 +144,12 @@ impl MdmaWindow {
                 .0
                 .iter()
                 .fold(String::new(), |mut accumulator, elem| {
-                    if let Some(log) = elem.inner.as_string() {
-                        accumulator.push_str(&log);
-                        accumulator.push('\n');
-                    } else if let Some(error_code) = elem.inner.as_f64() {
-                        accumulator.push_str(&format!(
-                            "{{\"mdma_err\": {error_code}, \"ev\": \"{:?}\"}}\n",
-                            elem.created_at
-                        ))
-                    } else {
-                        debug_log!(&elem.inner);
+                    match elem.to_text() {
+                        Some(log) => {
+                            accumulator.push_str(&log);
+                            accumulator.push('\n');
+                        }
+                        None => debug_log!(&elem.inner),
                     }
                     accumulator
                 })
@@ -33,7 +33,9 @@ pub mod prelude {
     pub use crate::globals::prelude::*;
     pub use crate::utils::*;
     pub use crate::{class, console_error, s, string};
-    pub use common::{closure, debug_log, err_code, map_err};
+    pub use common::error::{ErrorContext, ErrorKind, MdmaError, MdmaResult};
+    pub use common::i18n::Locale;
+    pub use common::{closure, debug_log, err, err_code, map_err, tr};
     pub use proc_macros::{ActiveSettings, Setting, Settings};
 }
 
@@ -43,16 +45,22 @@ pub use wasm_bindgen::prelude::*;
 mod addon_window;
 mod addons;
 mod bindings;
+mod diagnostics;
+mod hotkeys;
 pub mod disable_items;
 mod dispatcher;
 mod interface;
+mod overlay;
 mod pathfinder;
+mod player_list;
 mod utils;
 #[macro_use]
 mod macros;
 // TODO: Move to a different directory/module ?
 mod color_mark;
 pub mod globals;
+#[cfg(not(feature = "ni"))]
+mod si_layer;
 
 #[wasm_bindgen]
 pub async fn main(original_init: js_sys::Function) -> Result<(), JsValue> {
@@ -79,11 +87,19 @@ pub async fn main(original_init: js_sys::Function) -> Result<(), JsValue> {
         return Ok(());
     }
 
+    // Replaced by the language chosen in the manager settings once they're loaded.
+    prelude::Locale::set_current(prelude::Locale::from_domain(
+        bindings::is_pl().unwrap_or(true),
+    ));
+
     let communication = prelude::get_engine()
         .communication()
         .ok_or_else(|| common::err_code!())?;
 
     if let Err(err) = init_manager(&communication).await {
+        let err = common::error::MdmaError::from(err).fatal();
+        // Sent right away since the manager won't report anything after this.
+        diagnostics::Diagnostics::record_error(&err);
         web_sys::console::error_5(
             &JsValue::from_str(s!("%c MDMA %c %c Rust ")),
             &JsValue::from_str(s!(
@@ -93,9 +109,9 @@ pub async fn main(original_init: js_sys::Function) -> Result<(), JsValue> {
             &JsValue::from_str(s!(
                 "background: #CE412B; color: black; font-weight: bold; border-radius: 5px;"
             )),
-            &err,
+            &JsValue::from_str(&err.to_string()),
         );
-        let _ = prelude::message(s!("[MDMA::RS] Błąd podczas wczytywania zestawu!"));
+        let _ = prelude::message(common::tr!(ManagerLoadError));
     }
 
     communication.try_init_game(&original_init)
@@ -106,7 +122,9 @@ async fn init_manager(
 ) -> Result<(), JsValue> {
     let manager_globals = match globals::Globals::init().await {
         Ok(manager_globals) => manager_globals,
-        Err(globals::GlobalsError::Unrecoverable(err_code)) => return Err(err_code),
+        Err(globals::GlobalsError::Unrecoverable(err_code)) => {
+            return Err(common::error::MdmaError::from(err_code).fatal().into());
+        }
         Err(globals::GlobalsError::Unauthorized) => return interface::render_unauthorized(),
     };
 
@@ -121,12 +139,22 @@ async fn init_manager(
         // globals::emitter::Emitter::on_call_draw_add_to_renderer()?;
     }
 
+    // Losing the logs from before the reload shouldn't stop the manager.
+    if let Err(err_code) = interface::console::init().await {
+        console_error!(err_code);
+    }
     communication.observe_send()?;
 
+    diagnostics::Diagnostics::record_event("init: windows layer");
     interface::init_windows_layer()?;
+    overlay::Overlay::init()?;
 
+    diagnostics::Diagnostics::record_event("init: addons");
     globals::addons::init_addons!();
+    globals::addons::Addons::init_dashboard();
+    globals::game_tabs::GameTabs::init(&manager_globals.attack_alerts)?;
 
+    diagnostics::Diagnostics::record_event("init: interface");
     interface::init_interface(manager_globals)?;
 
     wasm_bindgen_futures::spawn_local(globals::Globals::start_peers_map_update_interval());
@@ -1,9 +1,9 @@
 #[macro_export]
 macro_rules! console_error {
     () => {{
-        use ::wasm_bindgen::{JsValue, intern};
+        use ::wasm_bindgen::JsValue;
 
-        let _ = $crate::bindings::message(intern($crate::s!("[MDMA::RS] Wystąpił błąd!")));
+        let _ = $crate::bindings::message(::common::tr!(ErrorOccurred));
         let error_code = ::common::err_code!();
         ::web_sys::console::error_5(
             &JsValue::from_str($crate::s!("%c MDMA %c %c Rust ")),
@@ -16,12 +16,20 @@ macro_rules! console_error {
             )),
             &error_code,
         );
-        $crate::utils::logging::__internal_console_error(error_code);
+        $crate::utils::logging::__internal_console_error(error_code, $crate::s!(module_path!()));
     }};
     ($error_code:expr) => {{
-        use ::wasm_bindgen::{JsValue, intern};
+        use ::wasm_bindgen::JsValue;
 
-        let _ = $crate::bindings::message(intern($crate::s!("[MDMA::RS] Wystąpił błąd!")));
+        // Accepts both bare codes from `err_code!` and `MdmaError`s.
+        let error_value: JsValue = ::std::convert::Into::into($error_code);
+        let mdma_error = ::common::error::MdmaError::from_js(&error_value);
+
+        if mdma_error.is_fatal() {
+            let _ = $crate::bindings::message(::common::tr!(ErrorOccurredFatal));
+        } else {
+            let _ = $crate::bindings::message(::common::tr!(ErrorOccurred));
+        }
         ::web_sys::console::error_5(
             &JsValue::from_str($crate::s!("%c MDMA %c %c Rust ")),
             &JsValue::from_str($crate::s!(
@@ -31,9 +39,9 @@ macro_rules! console_error {
             &JsValue::from_str($crate::s!(
                 "background: #CE412B; color: black; font-weight: bold; border-radius: 5px;"
             )),
-            &$error_code,
+            &JsValue::from_str(&mdma_error.to_string()),
         );
-        $crate::utils::logging::__internal_console_error($error_code);
+        $crate::utils::logging::__internal_console_error(error_value, $crate::s!(module_path!()));
     }};
 }
 
@@ -0,0 +1,603 @@
+//! Map overlay shared by the addons.
+//!
+//! Every addon draws into its own layer, which is only displayed while the addon is active
+//! and can additionally be hidden from the manager settings.
+
+use std::{borrow::Cow, cell::RefCell, collections::BTreeMap};
+
+use dominator::html;
+use futures_signals::{
+    signal::Mutable,
+    signal_map::MutableBTreeMap,
+    signal_vec::{MutableVec, SignalVec},
+};
+use wasm_bindgen::prelude::*;
+use web_sys::{HtmlElement, MouseEvent};
+
+use crate::{
+    interface::{ThreadLocalShadowRoot, WINDOWS_ROOT},
+    pathfinder::Pos,
+    prelude::*,
+};
+
+/// Size of a single map tile in pixels.
+const TILE_SIZE: f64 = 32.0;
+/// Draw order of items displayed on top of every other map object.
+const MARK_ORDER: f64 = 20000.0;
+/// Draw order of items displayed just above the map (0).
+const GROUND_ORDER: f64 = 0.001;
+
+thread_local! {
+    static LAYERS: RefCell<BTreeMap<AddonName, &'static OverlayLayer>> = const { RefCell::new(BTreeMap::new()) };
+    static LAYER_NAMES: MutableVec<AddonName> = MutableVec::new();
+    static HOVER_TIP: Mutable<Option<(String, i32, i32)>> = Mutable::new(None);
+}
+
+/// Any css color, e.g. `"lime"` or `"#8caaee"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct OverlayColor(Cow<'static, str>);
+
+impl OverlayColor {
+    pub(crate) const fn named(color: &'static str) -> Self {
+        Self(Cow::Borrowed(color))
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for OverlayColor {
+    fn from(color: String) -> Self {
+        Self(Cow::Owned(color))
+    }
+}
+
+/// Position of the top left corner of the tile relative to the map.
+fn tile_position(pos: Pos) -> (f64, f64) {
+    (pos.x as f64 * TILE_SIZE, pos.y as f64 * TILE_SIZE)
+}
+
+/// Identifies an item inside of a layer, `part` allows multiple items per entity (e.g. a tile and its label).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct OverlayKey {
+    pub(crate) id: Id,
+    pub(crate) part: u8,
+}
+
+impl OverlayKey {
+    pub(crate) const fn new(id: Id, part: u8) -> Self {
+        Self { id, part }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum OverlayShape {
+    /// Frame around a player.
+    OtherMark(OtherId),
+    /// Frame around a npc.
+    NpcMark(Id),
+    /// Filled map tile.
+    Tile(Pos),
+    /// Text centered on a map tile.
+    Label(Pos, String),
+    /// Line connecting the centers of the tiles.
+    Path(Vec<Pos>),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct OverlayItem {
+    shape: OverlayShape,
+    color: OverlayColor,
+    /// Order of the item relative to other items of the same kind, marks are always above the rest.
+    z_index: i16,
+    /// Text displayed while hovering over the item.
+    tip: Option<String>,
+}
+
+impl OverlayItem {
+    pub(crate) fn new(shape: OverlayShape, color: OverlayColor) -> Self {
+        Self {
+            shape,
+            color,
+            z_index: 0,
+            tip: None,
+        }
+    }
+
+    pub(crate) fn z_index(mut self, z_index: i16) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    pub(crate) fn tip(mut self, tip: impl Into<String>) -> Self {
+        self.tip = Some(tip.into());
+        self
+    }
+
+    fn is_mark(&self) -> bool {
+        matches!(
+            self.shape,
+            OverlayShape::OtherMark(_) | OverlayShape::NpcMark(_)
+        )
+    }
+
+    fn order(&self) -> f64 {
+        match self.is_mark() {
+            true => MARK_ORDER + self.z_index as f64,
+            // Keep ground items below anything sorted by the engine.
+            false => GROUND_ORDER + (self.z_index as f64 - i16::MIN as f64) * 1e-8,
+        }
+    }
+
+    /// Tiles covered by the item.
+    fn tiles(&self) -> Vec<Pos> {
+        match &self.shape {
+            OverlayShape::OtherMark(other_id) => Others::get()
+                .lock_ref()
+                .get(other_id)
+                .map(|other| {
+                    let (x, y) = (other.x.get() as usize, other.y.get() as usize);
+                    // Player sprites are taller than a single tile.
+                    vec![Pos::new(x, y), Pos::new(x, y.saturating_sub(1))]
+                })
+                .unwrap_or_default(),
+            OverlayShape::NpcMark(npc_id) => Npcs::get()
+                .lock_ref()
+                .get(npc_id)
+                .map(|npc| vec![Pos::new(npc.x as usize, npc.y as usize)])
+                .unwrap_or_default(),
+            OverlayShape::Tile(pos) | OverlayShape::Label(pos, _) => vec![*pos],
+            OverlayShape::Path(points) => points.clone(),
+        }
+    }
+}
+
+struct OverlayLayer {
+    /// Whether the layer should be displayed while the addon is active.
+    visible: Mutable<bool>,
+    items: MutableBTreeMap<OverlayKey, OverlayItem>,
+}
+
+impl OverlayLayer {
+    fn is_displayed(&self, addon_name: AddonName) -> bool {
+        self.visible.get() && Addons::is_active(addon_name)
+    }
+}
+
+pub(crate) struct Overlay;
+
+impl Overlay {
+    pub(crate) fn init() -> JsResult<()> {
+        Self::init_hover_tip()
+    }
+
+    fn layer(addon_name: AddonName) -> &'static OverlayLayer {
+        if let Some(layer) = LAYERS.with_borrow(|layers| layers.get(&addon_name).copied()) {
+            return layer;
+        }
+
+        let layer: &'static OverlayLayer = Box::leak(Box::new(OverlayLayer {
+            visible: Mutable::new(true),
+            items: MutableBTreeMap::new(),
+        }));
+        LAYERS.with_borrow_mut(|layers| layers.insert(addon_name, layer));
+        LAYER_NAMES.with(|layer_names| layer_names.lock_mut().push(addon_name));
+
+        #[cfg(not(feature = "ni"))]
+        if let Err(err_code) = crate::si_layer::append_to_map(layer.render(addon_name)) {
+            console_error!(err_code);
+        }
+
+        layer
+    }
+
+    pub(crate) fn insert(addon_name: AddonName, key: OverlayKey, item: OverlayItem) {
+        Self::layer(addon_name).items.lock_mut().insert_cloned(key, item);
+    }
+
+    pub(crate) fn remove(addon_name: AddonName, key: OverlayKey) {
+        Self::layer(addon_name).items.lock_mut().remove(&key);
+    }
+
+    /// Replaces every item of the addons layer.
+    pub(crate) fn replace(
+        addon_name: AddonName,
+        items: impl IntoIterator<Item = (OverlayKey, OverlayItem)>,
+    ) {
+        Self::layer(addon_name)
+            .items
+            .lock_mut()
+            .replace_cloned(items.into_iter().collect());
+    }
+
+    pub(crate) fn layer_visible(addon_name: AddonName) -> Mutable<bool> {
+        Self::layer(addon_name).visible.clone()
+    }
+
+    /// Addons which have drawn onto the map at least once.
+    pub(crate) fn layer_names_signal_vec() -> impl SignalVec<Item = AddonName> {
+        LAYER_NAMES.with(|layer_names| layer_names.signal_vec())
+    }
+
+    /// Returns the tip of the topmost displayed item covering the tile.
+    pub(crate) fn hit_test(pos: Pos) -> Option<String> {
+        let layers = LAYERS.with_borrow(|layers| layers.clone());
+
+        layers
+            .into_iter()
+            .filter(|(addon_name, layer)| layer.is_displayed(*addon_name))
+            .flat_map(|(_, layer)| {
+                layer
+                    .items
+                    .lock_ref()
+                    .values()
+                    .filter(|item| item.tip.is_some() && item.tiles().contains(&pos))
+                    .map(|item| (item.order(), item.tip.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .and_then(|(_, tip)| tip)
+    }
+
+    fn init_hover_tip() -> JsResult<()> {
+        let tip = html!("div", {
+            .class("tip-wrapper")
+            .style("pointer-events", "none")
+            .style_signal("display", HOVER_TIP.with(|hover_tip| hover_tip.signal_ref(|tip| tip.is_none().then_some("none"))))
+            .style_signal("left", HOVER_TIP.with(|hover_tip| hover_tip.signal_ref(|tip| tip.as_ref().map(|(_, left, _)| format!("{}px", left + 30)))))
+            .style_signal("top", HOVER_TIP.with(|hover_tip| hover_tip.signal_ref(|tip| tip.as_ref().map(|(_, _, top)| format!("{top}px")))))
+            .child(html!("div", {
+                .class("content")
+                .text_signal(HOVER_TIP.with(|hover_tip| hover_tip.signal_ref(|tip| tip.as_ref().map(|(text, _, _)| text.clone()).unwrap_or_default())))
+            }))
+        });
+        WINDOWS_ROOT.append_window(tip)?;
+
+        let on_mouse_move = closure!(move |event: MouseEvent| {
+            let tip = Self::hovered_tile(&event)
+                .and_then(Self::hit_test)
+                .map(|tip| (tip, event.client_x(), event.client_y()));
+            HOVER_TIP.with(|hover_tip| hover_tip.set(tip));
+        });
+
+        window()
+            .add_event_listener_with_callback("mousemove", &on_mouse_move)
+            .map_err(map_err!())
+    }
+
+    fn hovered_tile(event: &MouseEvent) -> Option<Pos> {
+        let map_element = Self::map_element()?;
+        let target = event.target()?.dyn_into::<web_sys::Node>().ok()?;
+        if !map_element.contains(Some(&target)) {
+            return None;
+        }
+
+        let rect = map_element.get_bounding_client_rect();
+        let (offset_x, offset_y) = Self::map_offset()?;
+        let x = (event.client_x() as f64 - rect.left() + offset_x) / TILE_SIZE;
+        let y = (event.client_y() as f64 - rect.top() + offset_y) / TILE_SIZE;
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+
+        Some(Pos::new(x as usize, y as usize))
+    }
+}
+
+#[cfg(feature = "ni")]
+impl Overlay {
+    fn map_element() -> Option<HtmlElement> {
+        get_engine()
+            .interface()?
+            .get_game_canvas()
+            .ok()
+            .map(JsCast::unchecked_into)
+    }
+
+    fn map_offset() -> Option<(f64, f64)> {
+        let offset = get_engine().map()?.get_offset()?;
+
+        Some((*offset.first()?, *offset.get(1)?))
+    }
+
+    pub(crate) fn on_call_draw_add_to_renderer(
+        renderer: &crate::bindings::engine::renderer::Renderer,
+    ) -> JsResult<()> {
+        let layers = LAYERS.with_borrow(|layers| layers.clone());
+
+        for (addon_name, layer) in layers {
+            if !layer.is_displayed(addon_name) {
+                continue;
+            }
+
+            for (key, item) in layer.items.lock_ref().iter() {
+                match item.get_drawable_obj(*key) {
+                    Ok(Some(drawable_obj)) => renderer.add_1(&drawable_obj),
+                    Ok(None) => {}
+                    Err(err_code) => console_error!(err_code),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "ni")]
+impl OverlayItem {
+    fn get_drawable_obj(&self, key: OverlayKey) -> JsResult<Option<JsValue>> {
+        use js_sys::Function;
+        use serde::Serialize;
+        use web_sys::CanvasRenderingContext2d;
+
+        #[derive(Serialize)]
+        struct OverlayItemExport {
+            rx: f64,
+            ry: f64,
+            #[serde(with = "serde_wasm_bindgen::preserve")]
+            draw: Function,
+            #[serde(rename = "getOrder", with = "serde_wasm_bindgen::preserve")]
+            get_order: Function,
+            d: OverlayItemData,
+        }
+
+        #[derive(Serialize)]
+        struct OverlayItemData {
+            id: f64,
+        }
+
+        let Some(anchor) = self.tiles().first().copied() else {
+            return Ok(None);
+        };
+
+        let item = self.clone();
+        let draw = closure!(@once move |ctx: CanvasRenderingContext2d| {
+            if let Err(err_code) = item.draw(&ctx) {
+                console_error!(err_code);
+            }
+        });
+        let order = self.order();
+        let get_order = Closure::<dyn Fn() -> f64>::wrap(Box::new(move || order))
+            .into_js_value()
+            .unchecked_into();
+        let export = OverlayItemExport {
+            rx: anchor.x as f64,
+            ry: anchor.y as f64,
+            draw,
+            get_order,
+            d: OverlayItemData {
+                id: key.id as f64 - 0.1,
+            },
+        };
+
+        serde_wasm_bindgen::to_value(&export)
+            .map(Some)
+            .map_err(map_err!(from))
+    }
+
+    fn draw(&self, ctx: &web_sys::CanvasRenderingContext2d) -> JsResult<()> {
+        let (offset_x, offset_y) = Overlay::map_offset().ok_or_else(|| err_code!())?;
+
+        ctx.save();
+        let res = self.draw_shape(ctx, offset_x, offset_y);
+        ctx.restore();
+
+        res
+    }
+
+    fn draw_shape(
+        &self,
+        ctx: &web_sys::CanvasRenderingContext2d,
+        offset_x: f64,
+        offset_y: f64,
+    ) -> JsResult<()> {
+        let color = self.color.as_str();
+        ctx.set_line_width(3.0);
+        ctx.set_stroke_style_str(color);
+        ctx.set_fill_style_str(color);
+
+        match &self.shape {
+            OverlayShape::OtherMark(other_id) => {
+                let margin = 5.0;
+                let other = get_engine()
+                    .others()
+                    .ok_or_else(|| err_code!())?
+                    .get_by_id(*other_id)
+                    .or_else(|| get_engine().hero().map(JsCast::unchecked_into))
+                    .ok_or_else(|| err_code!())?;
+                let fw = other.fw().ok_or_else(|| err_code!())? + margin;
+                let fh = other.fh().ok_or_else(|| err_code!())? + margin;
+                let rx = other.rx().ok_or_else(|| err_code!())?;
+                let ry = other.ry().ok_or_else(|| err_code!())?;
+                let left = rx * TILE_SIZE + TILE_SIZE / 2.0 - fw / 2.0 - offset_x;
+                let top = ry * TILE_SIZE - fh + TILE_SIZE + 2.0 - offset_y;
+
+                ctx.stroke_rect(left, top, fw, fh);
+            }
+            OverlayShape::NpcMark(_) => {
+                let pos = self.tiles().first().copied().ok_or_else(|| err_code!())?;
+                let (left, top) = tile_position(pos);
+
+                ctx.stroke_rect(left - offset_x, top - offset_y, TILE_SIZE, TILE_SIZE);
+            }
+            OverlayShape::Tile(pos) => {
+                let (left, top) = tile_position(*pos);
+                let (left, top) = (left - offset_x, top - offset_y);
+
+                ctx.stroke_rect(left, top, TILE_SIZE, TILE_SIZE);
+                ctx.fill_rect(left, top, TILE_SIZE, TILE_SIZE);
+            }
+            OverlayShape::Label(pos, text) => {
+                let (left, top) = tile_position(*pos);
+                let x = left + TILE_SIZE / 2.0 - offset_x;
+                let y = top + TILE_SIZE / 2.0 + 3.0 - offset_y;
+
+                ctx.set_font("11px Arimo");
+                ctx.set_line_cap("round");
+                ctx.set_line_join("round");
+                ctx.set_text_align("center");
+                ctx.set_stroke_style_str("rgba(0, 0, 0, 0.8)");
+                ctx.stroke_text_with_max_width(text, x, y, TILE_SIZE)
+                    .map_err(map_err!())?;
+                ctx.fill_text_with_max_width(text, x, y, TILE_SIZE)
+                    .map_err(map_err!())?;
+            }
+            OverlayShape::Path(points) => {
+                let mut points = points.iter().map(|pos| {
+                    let (left, top) = tile_position(*pos);
+                    (
+                        left + TILE_SIZE / 2.0 - offset_x,
+                        top + TILE_SIZE / 2.0 - offset_y,
+                    )
+                });
+                let Some((x, y)) = points.next() else {
+                    return Ok(());
+                };
+
+                ctx.begin_path();
+                ctx.move_to(x, y);
+                points.for_each(|(x, y)| ctx.line_to(x, y));
+                ctx.stroke();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "ni"))]
+impl Overlay {
+    fn map_element() -> Option<HtmlElement> {
+        document()
+            .get_element_by_id("base")
+            .and_then(|element| element.dyn_into().ok())
+    }
+
+    /// The map container is moved instead of being drawn with an offset.
+    fn map_offset() -> Option<(f64, f64)> {
+        Some((0.0, 0.0))
+    }
+}
+
+#[cfg(not(feature = "ni"))]
+impl OverlayLayer {
+    fn render(&'static self, addon_name: AddonName) -> dominator::Dom {
+        use futures_signals::{map_ref, signal::SignalExt, signal_vec::SignalVecExt};
+
+        let displayed_signal = map_ref! {
+            let visible = self.visible.signal(),
+            let active = Addons::active_signal(addon_name).unwrap_js() => {
+                *visible && *active
+            }
+        };
+
+        html!("div", {
+            .style("position", "absolute")
+            .style("left", "0px")
+            .style("top", "0px")
+            .style("pointer-events", "none")
+            .style_signal("display", displayed_signal.map(|displayed| (!displayed).then_some("none")))
+            .children_signal_vec(
+                self.items
+                    .entries_cloned()
+                    .sort_by_cloned(|(_, a), (_, b)| a.z_index.cmp(&b.z_index))
+                    .map(|(_, item)| item.render())
+            )
+        })
+    }
+}
+
+#[cfg(not(feature = "ni"))]
+impl OverlayItem {
+    fn render(&self) -> dominator::Dom {
+        use futures_signals::signal::SignalExt;
+
+        let z_index = match self.is_mark() {
+            true => 1000 + self.z_index as i32,
+            false => 1,
+        };
+        let color = self.color.as_str();
+
+        match &self.shape {
+            OverlayShape::OtherMark(other_id) => {
+                let Some(other) = Others::get().lock_ref().get(other_id).cloned() else {
+                    return html!("div");
+                };
+                let (width, height) = crate::si_layer::other_element(*other_id)
+                    .map(|element| (element.offset_width() as f64, element.offset_height() as f64))
+                    .unwrap_or((TILE_SIZE, TILE_SIZE * 1.5));
+
+                html!("div", {
+                    .style("position", "absolute")
+                    .style("box-sizing", "border-box")
+                    .style("z-index", &z_index.to_string())
+                    .style("width", &format!("{width:.0}px"))
+                    .style("height", &format!("{height:.0}px"))
+                    .style("border", &format!("3px solid {color}"))
+                    .style_signal("left", other.x.signal().map(move |x| {
+                        format!("{:.0}px", x as f64 * TILE_SIZE + TILE_SIZE / 2.0 - width / 2.0)
+                    }))
+                    .style_signal("top", other.y.signal().map(move |y| {
+                        format!("{:.0}px", y as f64 * TILE_SIZE + TILE_SIZE - height)
+                    }))
+                })
+            }
+            OverlayShape::NpcMark(_) | OverlayShape::Tile(_) | OverlayShape::Label(..) => {
+                let Some(pos) = self.tiles().first().copied() else {
+                    return html!("div");
+                };
+                let (left, top) = tile_position(pos);
+
+                html!("div", {
+                    .style("position", "absolute")
+                    .style("box-sizing", "border-box")
+                    .style("z-index", &z_index.to_string())
+                    .style("left", &format!("{left:.0}px"))
+                    .style("top", &format!("{top:.0}px"))
+                    .style("width", &format!("{TILE_SIZE:.0}px"))
+                    .style("height", &format!("{TILE_SIZE:.0}px"))
+                    .apply(|builder| match &self.shape {
+                        OverlayShape::Tile(_) => builder
+                            .style("border", &format!("3px solid {color}"))
+                            .style("background", color),
+                        OverlayShape::Label(_, text) => builder
+                            .style("font", "11px Arimo")
+                            .style("line-height", &format!("{TILE_SIZE:.0}px"))
+                            .style("text-align", "center")
+                            .style("color", color)
+                            .style("text-shadow", "0 0 2px black")
+                            .text(text),
+                        _ => builder.style("border", &format!("3px solid {color}")),
+                    })
+                })
+            }
+            OverlayShape::Path(points) => {
+                let points = points
+                    .iter()
+                    .map(|pos| {
+                        let (left, top) = tile_position(*pos);
+                        format!("{},{}", left + TILE_SIZE / 2.0, top + TILE_SIZE / 2.0)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                dominator::svg!("svg", {
+                    .style("position", "absolute")
+                    .style("left", "0px")
+                    .style("top", "0px")
+                    .style("overflow", "visible")
+                    .style("z-index", &z_index.to_string())
+                    .attr("width", "1")
+                    .attr("height", "1")
+                    .child(dominator::svg!("polyline", {
+                        .attr("points", &points)
+                        .attr("fill", "none")
+                        .attr("stroke", color)
+                        .attr("stroke-width", "3")
+                    }))
+                })
+            }
+        }
+    }
+}
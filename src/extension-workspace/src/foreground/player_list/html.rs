@@ -0,0 +1,300 @@
+use dominator::html;
+use futures_signals::signal::{Mutable, SignalExt};
+use futures_signals::signal_vec::SignalVecExt;
+use web_sys::HtmlInputElement;
+
+use crate::addon_window::prelude::*;
+use crate::prelude::*;
+
+use super::{LevelDisplay, Ordering, PlayerListSettings, SortBy};
+
+impl PlayerListSettings {
+    /// Sorting, level display and column settings along with the saved
+    /// filter presets.
+    pub(crate) fn render(&'static self) -> ContentSection {
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[6]")
+            .section(self.sort_by_setting())
+            .section(self.sort_order_setting())
+            .section(self.level_display_setting())
+            .checkbox_pair(
+                Checkbox::builder(self.level_column.clone()).text(tr!(PlayersLevelColumn)),
+                Checkbox::builder(self.clan_column.clone()).text(tr!(PlayersClanColumn)),
+            )
+            .section(self.presets_setting())
+    }
+
+    /// Input filtering the list with a query, followed by the saved presets.
+    pub(crate) fn render_filter(&'static self) -> ContentSection {
+        let query_input = Input::builder()
+            .class_list("b-s[none] b[none] bg[none] cursor[auto]")
+            .size(InputSize::Big)
+            .placeholder(tr!(PlayersSearchPlaceholder))
+            .text_align(TextAlign::Left)
+            .value(self.query.get_cloned())
+            .store_root(&self.query_root)
+            .with_tooltip(&self.query_error)
+            .on_input(
+                move |_, input_elem| match self.set_query(input_elem.value()) {
+                    Ok(()) => self.query_error.set_valid(input_elem),
+                    Err(err) => self.query_error.set_invalid(input_elem, err.to_string()),
+                },
+            );
+        let presets = self
+            .presets
+            .entries_cloned()
+            .map(move |(name, query)| self.render_preset_button(name, query));
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column]")
+            .input(query_input)
+            .section(
+                ContentSection::new()
+                    .class_list("d[flex] f-d[row] flex-w[wrap] g[3]")
+                    .section_signal_vec(presets),
+            )
+    }
+
+    fn render_preset_button(&'static self, name: String, query: String) -> ContentSection {
+        let selected_signal = self.query.signal_ref({
+            let query = query.clone();
+            move |current_query| *current_query == query
+        });
+
+        ContentSection::new().button(
+            Button::builder()
+                .text(&name)
+                .selected_signal(selected_signal)
+                .on_click(move |_| self.apply_preset(&query)),
+        )
+    }
+
+    /// Filters the list with the preset, clicking the active preset clears
+    /// the filter instead.
+    fn apply_preset(&self, query: &str) {
+        let query = match self.query.lock_ref().as_str() == query {
+            true => String::new(),
+            false => query.to_owned(),
+        };
+
+        if let Err(err) = self.set_query(query.clone()) {
+            debug_log!(@f "Invalid preset query: {err}");
+            return;
+        }
+
+        if let Some(query_root) = self.query_root.lock_ref().as_ref() {
+            query_root.set_value(&query);
+            self.query_error.set_valid(query_root);
+        }
+    }
+
+    fn presets_setting(&'static self) -> ContentSection {
+        let name_input = Input::builder()
+            .placeholder(tr!(PlayersPresetName))
+            .maxlength("30")
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .store_root(&self.preset_name_root);
+        let query_input = Input::builder()
+            .placeholder(tr!(PlayersPresetQuery))
+            .maxlength("200")
+            .size(InputSize::Big)
+            .text_align(TextAlign::Left)
+            .with_tooltip(&self.preset_error)
+            .on_input(move |_, input_elem| self.preset_error.set_valid(input_elem))
+            .confirm_button(
+                InputButton::builder()
+                    .with_tooltip(&self.preset_error)
+                    .on_click(move |_, input_elem| self.on_save_preset(input_elem)),
+            );
+        let saved_presets = self.presets.entries_cloned().map(move |(name, query)| {
+            Input::builder()
+                .disabled()
+                .class_list("m-bottom[6]")
+                .value(format!("{name}: {query}"))
+                .size(InputSize::Big)
+                .text_align(TextAlign::Left)
+                .confirm_button(
+                    InputButton::builder()
+                        .button_type(InputButtonType::Remove)
+                        .on_click(move |_, _| self.remove_preset(&name)),
+                )
+        });
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[column] g[3]")
+            .section(
+                ContentSection::new()
+                    .class_list("a-c[center]")
+                    .text(tr!(PlayersPresets))
+                    .mixin(|builder| {
+                        builder.child(html!("div", {
+                            .class!(f-s[11] p-top[3])
+                            .text(tr!(PlayersQueryHelp))
+                        }))
+                    }),
+            )
+            .input_signal_vec(saved_presets)
+            .input(name_input)
+            .input(query_input)
+    }
+
+    fn on_save_preset(&self, query_root: &HtmlInputElement) {
+        let Some(name_root) = self.preset_name_root.get_cloned() else {
+            return;
+        };
+        let name = name_root.value().trim().to_owned();
+        let query = query_root.value().trim().to_owned();
+
+        if name.is_empty() {
+            return self
+                .preset_error
+                .set_invalid(query_root, String::from(tr!(PlayersPresetNameRequired)));
+        }
+        if let Err(err) = self.save_preset(name, query) {
+            return self.preset_error.set_invalid(query_root, err.to_string());
+        }
+
+        self.preset_error.set_valid(query_root);
+        name_root.set_value("");
+        query_root.set_value("");
+    }
+
+    fn level_display_setting(&'static self) -> ContentSection {
+        // TODO: Figure out a better solution.
+        let wrapper_state: &'static _ = Box::leak(Box::new(Mutable::default()));
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[row] g[10] j-c[space-between]")
+            .section(
+                ContentSection::new()
+                    .class_list("a-c[center]")
+                    .text(tr!(PlayersLevelDisplay)),
+            )
+            .button(
+                Button::builder()
+                    .class_list("w[208] t-a[left]")
+                    .no_hover()
+                    .text_signal(self.level_display_signal())
+                    .on_click(|_| wrapper_state.set_neq(true))
+                    .on_mousedown(|event| event.stop_propagation())
+                    .mixin(|builder| {
+                        builder.child(html!("div", {
+                            .class!(pos[absolute] r[8] align-center menu-arrow)
+                        }))
+                    })
+                    .scroll_wrapper(
+                        ScrollWrapper::builder(|| {
+                            || {
+                                wrapper_state.set_neq(false);
+                            }
+                        })
+                        .class_list("w[200] l[1]")
+                        .visible_signal(wrapper_state.signal())
+                        .option(self.level_display_option(LevelDisplay::First))
+                        .option(self.level_display_option(LevelDisplay::Last))
+                        .option(self.level_display_option(LevelDisplay::None))
+                        .option(self.level_display_option(LevelDisplay::Only))
+                        .build(),
+                    ),
+            )
+    }
+
+    fn level_display_option(&'static self, level_display: LevelDisplay) -> ScrollWrapperOption {
+        ScrollWrapperOption::builder()
+            .text(level_display.as_str())
+            .on_click(move |_| self.level_display.set_neq(level_display))
+            .build()
+    }
+
+    fn sort_by_setting(&'static self) -> ContentSection {
+        // TODO: Figure out a better solution.
+        let wrapper_state: &'static _ = Box::leak(Box::new(Mutable::default()));
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between]")
+            .section(
+                ContentSection::new()
+                    .class_list("a-c[center]")
+                    .text(tr!(PlayersSortBy)),
+            )
+            .button(
+                Button::builder()
+                    .class_list("w[98] t-a[left]")
+                    .no_hover()
+                    .text_signal(self.sort_by_signal())
+                    .on_click(|_| wrapper_state.set_neq(true))
+                    .on_mousedown(|event| event.stop_propagation())
+                    .mixin(|builder| {
+                        builder.child(html!("div", {
+                            .class!(pos[absolute] r[8] align-center menu-arrow)
+                        }))
+                    })
+                    .scroll_wrapper(
+                        ScrollWrapper::builder(|| {
+                            || {
+                                wrapper_state.set_neq(false);
+                            }
+                        })
+                        .class_list("w[90] l[1]")
+                        .visible_signal(wrapper_state.signal())
+                        .option(self.sort_by_option(SortBy::Lvl))
+                        .option(self.sort_by_option(SortBy::Nick))
+                        .option(self.sort_by_option(SortBy::Prof))
+                        .build(),
+                    ),
+            )
+    }
+
+    fn sort_by_option(&'static self, sort_by: SortBy) -> ScrollWrapperOption {
+        ScrollWrapperOption::builder()
+            .text(sort_by.as_str())
+            .on_click(move |_| self.sort_by.set_neq(sort_by))
+            .build()
+    }
+
+    fn sort_order_setting(&'static self) -> ContentSection {
+        // TODO: Figure out a better solution.
+        let wrapper_state: &'static _ = Box::leak(Box::new(Mutable::default()));
+
+        ContentSection::new()
+            .class_list("d[flex] f-d[row] j-c[space-between]")
+            .section(
+                ContentSection::new()
+                    .class_list("a-c[center]")
+                    .text(tr!(PlayersOrdering)),
+            )
+            .button(
+                Button::builder()
+                    .class_list("w[98] t-a[left]")
+                    .no_hover()
+                    .text_signal(self.sort_ordering_signal())
+                    .on_click(|_| wrapper_state.set_neq(true))
+                    .on_mousedown(|event| event.stop_propagation())
+                    .mixin(move |builder| {
+                        builder.child(html!("div", {
+                            .class!(pos[absolute] r[8] align-center menu-arrow)
+                        }))
+                    })
+                    .scroll_wrapper(
+                        ScrollWrapper::builder(|| {
+                            || {
+                                wrapper_state.set_neq(false);
+                            }
+                        })
+                        .class_list("w[90] l[1]")
+                        .visible_signal(wrapper_state.signal())
+                        .option(self.sort_ordering_option(Ordering::Descending))
+                        .option(self.sort_ordering_option(Ordering::Ascending))
+                        .build(),
+                    ),
+            )
+    }
+
+    fn sort_ordering_option(&'static self, ordering: Ordering) -> ScrollWrapperOption {
+        ScrollWrapperOption::builder()
+            .text(ordering.as_str())
+            .on_click(move |_| self.ordering.set_neq(ordering))
+            .build()
+    }
+}
@@ -0,0 +1,437 @@
+//! Player list shared by the addons listing other players: sorting, level
+//! display, column selection and filtering with a [query](query) along with
+//! saved filter presets.
+
+mod html;
+pub(crate) mod query;
+
+use std::ops::Deref;
+
+use futures_signals::map_ref;
+use futures_signals::signal::{Mutable, Signal, SignalExt};
+use futures_signals::signal_map::MutableBTreeMap;
+use serde_json::{Map, Value};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use web_sys::HtmlInputElement;
+
+use crate::prelude::*;
+
+pub(crate) use query::{PlayerFilter, PlayerSnapshot, QueryError};
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub(crate) enum SortBy {
+    #[default]
+    Lvl = 0,
+    Nick,
+    Prof,
+}
+
+impl SortBy {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Lvl => tr!(PlayersSortLvl),
+            Self::Nick => tr!(PlayersSortNick),
+            Self::Prof => tr!(PlayersSortProf),
+        }
+    }
+}
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub(crate) enum Ordering {
+    Ascending = 0,
+    #[default]
+    Descending,
+}
+
+impl Ordering {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Descending => tr!(PlayersDescending),
+            Self::Ascending => tr!(PlayersAscending),
+        }
+    }
+}
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, Debug, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub(crate) enum LevelDisplay {
+    First = 0,
+    Last,
+    None,
+    #[default]
+    Only,
+}
+
+impl LevelDisplay {
+    pub(crate) fn as_str(self) -> &'static str {
+        use LevelDisplay::*;
+
+        match self {
+            First => tr!(PlayersLevelFirst),
+            Last => tr!(PlayersLevelLast),
+            None => tr!(PlayersLevelNone),
+            Only => tr!(PlayersLevelOnly),
+        }
+    }
+
+    /// Formats the level of a player, e.g. `(350m|300m)`.
+    pub(crate) fn format(self, lvl: u16, operational_lvl: u16, prof: Profession) -> String {
+        use LevelDisplay::*;
+
+        match self {
+            _ if lvl <= 300 => format!("({lvl}{prof})"),
+            First => format!("({lvl}{prof}|{operational_lvl}{prof})"),
+            Last => format!("({operational_lvl}{prof}|{lvl}{prof})"),
+            None => format!("({operational_lvl}{prof})"),
+            Only => format!("({lvl}{prof})"),
+        }
+    }
+}
+
+/// Player displayed on a player list.
+pub(crate) trait ListedPlayer: Clone + 'static {
+    fn char_id(&self) -> OtherId;
+    fn nick(&self) -> &Mutable<String>;
+    fn lvl(&self) -> &Mutable<u16>;
+    fn operational_lvl(&self) -> &Mutable<u16>;
+    fn prof(&self) -> &Mutable<Profession>;
+    fn relation(&self) -> &Mutable<Relation>;
+    fn clan(&self) -> &Mutable<Option<Clan>>;
+
+    /// Calls `f` with the id and profession of every player the list is
+    /// built from.
+    fn for_each_profession(f: impl FnMut(OtherId, Profession));
+
+    fn relation_signal(&self) -> impl Signal<Item = Relation> + use<Self> {
+        self.relation().signal()
+    }
+
+    fn snapshot_signal(&self) -> impl Signal<Item = PlayerSnapshot> + use<Self> {
+        map_ref! {
+            let nick = self.nick().signal_cloned(),
+            let lvl = self.lvl().signal(),
+            let operational_lvl = self.operational_lvl().signal(),
+            let prof = self.prof().signal(),
+            let relation = self.relation_signal(),
+            let clan = self.clan().signal_ref(|clan| clan.as_ref().map(|clan| clan.name.clone())) => {
+                PlayerSnapshot {
+                    nick: nick.clone(),
+                    lvl: *lvl,
+                    operational_lvl: *operational_lvl,
+                    prof: *prof,
+                    relation: *relation,
+                    clan: clan.clone(),
+                }
+            }
+        }
+    }
+}
+
+impl ListedPlayer for Other {
+    fn char_id(&self) -> OtherId {
+        self.char_id
+    }
+
+    fn nick(&self) -> &Mutable<String> {
+        &self.nick
+    }
+
+    fn lvl(&self) -> &Mutable<u16> {
+        &self.lvl
+    }
+
+    fn operational_lvl(&self) -> &Mutable<u16> {
+        &self.operational_lvl
+    }
+
+    fn prof(&self) -> &Mutable<Profession> {
+        &self.prof
+    }
+
+    fn relation(&self) -> &Mutable<Relation> {
+        &self.relation
+    }
+
+    fn clan(&self) -> &Mutable<Option<Clan>> {
+        &self.clan
+    }
+
+    fn relation_signal(&self) -> impl Signal<Item = Relation> + use<Self> {
+        Other::relation_signal(self)
+    }
+
+    fn for_each_profession(mut f: impl FnMut(OtherId, Profession)) {
+        Others::get()
+            .lock_ref()
+            .iter()
+            .for_each(|(other_id, other_data)| f(*other_id, other_data.prof.get()));
+    }
+}
+
+impl ListedPlayer for Peer {
+    fn char_id(&self) -> OtherId {
+        self.char_id
+    }
+
+    fn nick(&self) -> &Mutable<String> {
+        &self.nick
+    }
+
+    fn lvl(&self) -> &Mutable<u16> {
+        &self.lvl
+    }
+
+    fn operational_lvl(&self) -> &Mutable<u16> {
+        &self.operational_lvl
+    }
+
+    fn prof(&self) -> &Mutable<Profession> {
+        &self.prof
+    }
+
+    fn relation(&self) -> &Mutable<Relation> {
+        &self.relation
+    }
+
+    fn clan(&self) -> &Mutable<Option<Clan>> {
+        &self.clan
+    }
+
+    fn for_each_profession(mut f: impl FnMut(OtherId, Profession)) {
+        Peers::get()
+            .lock_ref()
+            .iter()
+            .for_each(|(peer_id, peer_data)| f(*peer_id, peer_data.prof.get()));
+    }
+}
+
+/// Settings of a player list, stored under the `player_list` key of the
+/// addon's settings.
+#[derive(Setting)]
+pub(crate) struct PlayerListSettings {
+    pub(crate) sort_by: Mutable<SortBy>,
+    pub(crate) ordering: Mutable<Ordering>,
+    pub(crate) level_display: Mutable<LevelDisplay>,
+    pub(crate) level_column: Mutable<bool>,
+    pub(crate) clan_column: Mutable<bool>,
+    /// Saved filter queries by their names.
+    pub(crate) presets: MutableBTreeMap<String, String>,
+    /// Query the list is currently filtered with.
+    #[setting(skip)]
+    pub(crate) query: Mutable<String>,
+    #[setting(skip)]
+    query_error: Mutable<String>,
+    #[setting(skip)]
+    query_root: Mutable<Option<HtmlInputElement>>,
+    #[setting(skip)]
+    filter: Mutable<PlayerFilter>,
+    #[setting(skip)]
+    preset_error: Mutable<String>,
+    #[setting(skip)]
+    preset_name_root: Mutable<Option<HtmlInputElement>>,
+}
+
+impl Default for PlayerListSettings {
+    fn default() -> Self {
+        Self {
+            sort_by: Mutable::default(),
+            ordering: Mutable::default(),
+            level_display: Mutable::default(),
+            level_column: Mutable::new(true),
+            clan_column: Mutable::default(),
+            presets: MutableBTreeMap::new(),
+            query: Mutable::default(),
+            query_error: Mutable::default(),
+            query_root: Mutable::default(),
+            filter: Mutable::default(),
+            preset_error: Mutable::default(),
+            preset_name_root: Mutable::default(),
+        }
+    }
+}
+
+impl PlayerListSettings {
+    /// Keys the list settings were stored under before the list got shared
+    /// between the addons.
+    const LEGACY_KEYS: [&str; 3] = ["sort_by", "ordering", "level_display"];
+
+    /// Moves the list settings stored at the top level of the addon's
+    /// settings to the `player_list` key.
+    pub(crate) fn migrate(&self, addon_name: AddonName) {
+        let settings = Addons::__internal_get_settings(addon_name);
+        if settings.get("player_list").is_some() {
+            return;
+        }
+
+        let legacy_settings: Map<String, Value> = Self::LEGACY_KEYS
+            .into_iter()
+            .filter_map(|key| Some((key.to_owned(), settings.get(key)?.clone())))
+            .collect();
+        if legacy_settings.is_empty() {
+            return;
+        }
+
+        self.update(Value::Object(legacy_settings));
+    }
+
+    pub(crate) fn sort_signal(&'static self) -> impl Signal<Item = SortBy> {
+        self.ordering
+            .signal()
+            .dedupe()
+            .switch(|_| self.sort_by.signal().dedupe())
+    }
+
+    fn sort_ordering_signal(&self) -> impl Signal<Item = &'static str> {
+        self.ordering.signal().map(|ordering| ordering.as_str())
+    }
+
+    fn sort_by_signal(&self) -> impl Signal<Item = &'static str> {
+        self.sort_by.signal().map(|sort| sort.as_str())
+    }
+
+    fn level_display_signal(&self) -> impl Signal<Item = &'static str> {
+        self.level_display
+            .signal()
+            .map(|level_display| level_display.as_str())
+    }
+
+    /// Level of the player formatted according to the level display setting.
+    pub(crate) fn level_text_signal<P: ListedPlayer>(
+        &'static self,
+        player: &P,
+    ) -> impl Signal<Item = String> + use<P> {
+        map_ref! {
+            let level_display = self.level_display.signal(),
+            let lvl = player.lvl().signal(),
+            let oplvl = player.operational_lvl().signal(),
+            let prof = player.prof().signal() => {
+                level_display.format(*lvl, *oplvl, *prof)
+            }
+        }
+    }
+
+    /// Clan name of the player, `None` if the clan column is hidden.
+    pub(crate) fn clan_column_signal<P: ListedPlayer>(
+        &'static self,
+        player: &P,
+    ) -> impl Signal<Item = Option<String>> + use<P> {
+        map_ref! {
+            let clan_column = self.clan_column.signal(),
+            let clan = player.clan().signal_ref(|clan| clan.as_ref().map(|clan| clan.name.clone())) => {
+                clan.clone().filter(|_| *clan_column)
+            }
+        }
+    }
+
+    /// Whether the player matches the current filter query.
+    pub(crate) fn filter_signal<P: ListedPlayer>(
+        &'static self,
+        player: &P,
+    ) -> impl Signal<Item = bool> + use<P> {
+        map_ref! {
+            let filter = self.filter.signal_cloned(),
+            let snapshot = player.snapshot_signal() => {
+                filter.matches(snapshot)
+            }
+        }
+        .dedupe()
+    }
+
+    /// Filters the list with the query, the previous filter is kept if the
+    /// query is invalid.
+    pub(crate) fn set_query(&self, query: String) -> Result<(), QueryError> {
+        let filter = query.parse::<PlayerFilter>()?;
+
+        self.filter.set(filter);
+        self.query.set_neq(query);
+
+        Ok(())
+    }
+
+    fn save_preset(&self, name: String, query: String) -> Result<(), QueryError> {
+        query.parse::<PlayerFilter>()?;
+        self.presets.lock_mut().insert_cloned(name, query);
+
+        Ok(())
+    }
+
+    fn remove_preset(&self, name: &str) {
+        self.presets.lock_mut().remove(name);
+    }
+
+    pub(crate) fn compare<P: ListedPlayer>(&self, a: &P, b: &P) -> std::cmp::Ordering {
+        let sort_by = self.sort_by.get();
+        let ordering = self.ordering.get();
+
+        match sort_by {
+            SortBy::Nick => match ordering {
+                Ordering::Ascending => Self::compare_nick(a, b),
+                Ordering::Descending => Self::compare_nick(a, b).reverse(),
+            },
+            SortBy::Lvl => {
+                let lvl_order = match ordering {
+                    Ordering::Ascending => Self::compare_lvl(a, b),
+                    Ordering::Descending => Self::compare_lvl(a, b).reverse(),
+                };
+
+                lvl_order.then_with(|| Self::compare_nick(a, b))
+            }
+            SortBy::Prof => {
+                let prof_order = match ordering {
+                    Ordering::Ascending => Self::compare_prof(a, b),
+                    Ordering::Descending => Self::compare_prof(a, b).reverse(),
+                };
+
+                prof_order
+                    .then_with(|| Self::compare_lvl(a, b).reverse())
+                    .then_with(|| Self::compare_nick(a, b))
+            }
+        }
+    }
+
+    /// Implemented for ascending order, where
+    /// the topmost value is the first rendered cell.
+    fn compare_nick<P: ListedPlayer>(a: &P, b: &P) -> std::cmp::Ordering {
+        Locale::current().compare(&a.nick().lock_ref(), &b.nick().lock_ref())
+    }
+
+    /// Implemented for ascending order, where
+    /// the topmost value is the first rendered cell.
+    fn compare_lvl<P: ListedPlayer>(a: &P, b: &P) -> std::cmp::Ordering {
+        a.lvl().lock_ref().cmp(b.lvl().lock_ref().deref())
+    }
+
+    // TODO: Store the profession counts globally instead of recounting on every
+    // update.
+    /// Implemented for ascending order, where
+    /// the topmost value is the first rendered cell.
+    fn compare_prof<P: ListedPlayer>(a: &P, b: &P) -> std::cmp::Ordering {
+        let prof_a = a.prof().get();
+        let prof_b = b.prof().get();
+
+        if prof_a == prof_b {
+            return std::cmp::Ordering::Equal;
+        }
+
+        let mut prof_a_count = 1;
+        let mut prof_b_count = 1;
+
+        P::for_each_profession(|char_id, prof| {
+            if char_id == a.char_id() || char_id == b.char_id() {
+                return;
+            }
+
+            match prof {
+                prof if prof == prof_a => prof_a_count += 1,
+                prof if prof == prof_b => prof_b_count += 1,
+                _ => {}
+            }
+        });
+
+        prof_a_count
+            .cmp(&prof_b_count)
+            .then_with(|| prof_a.cmp(&prof_b))
+    }
+}
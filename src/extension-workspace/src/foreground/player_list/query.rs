@@ -0,0 +1,367 @@
+//! Query language used to filter the player lists.
+//!
+//! A query is a whitespace separated list of terms, a player has to match all
+//! of them to be listed:
+//! - `text` or `nick:text` - nick contains the text,
+//! - `lvl:120-150`, `lvl:120-`, `lvl:-150`, `lvl:120` - level within the range,
+//! - `oplvl:...` - same as `lvl:`, for the operational level,
+//! - `prof:m,t` - one of the professions,
+//! - `clan:text` - clan name contains the text,
+//! - `rel:enemy,clan-enemy` - one of the relations.
+//!
+//! Prefixing a value with `!` negates the term, e.g. `clan:!Foo`. Values
+//! containing whitespace can be quoted, e.g. `clan:"Foo Bar"`.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use common::tr;
+
+use crate::bindings::engine::communication::{Profession, Relation};
+
+/// Player data the filter is matched against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PlayerSnapshot {
+    pub(crate) nick: String,
+    pub(crate) lvl: u16,
+    pub(crate) operational_lvl: u16,
+    pub(crate) prof: Profession,
+    pub(crate) relation: Relation,
+    pub(crate) clan: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum QueryError {
+    UnclosedQuote,
+    UnknownKey(String),
+    EmptyValue(String),
+    InvalidLevel(String),
+    InvalidProfession(String),
+    InvalidRelation(String),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnclosedQuote => f.write_str(tr!(QueryUnclosedQuote)),
+            Self::UnknownKey(key) => f.write_str(&tr!(QueryUnknownKey, key = key)),
+            Self::EmptyValue(key) => f.write_str(&tr!(QueryEmptyValue, key = key)),
+            Self::InvalidLevel(value) => f.write_str(&tr!(QueryInvalidLevel, value = value)),
+            Self::InvalidProfession(value) => {
+                f.write_str(&tr!(QueryInvalidProfession, value = value))
+            }
+            Self::InvalidRelation(value) => f.write_str(&tr!(QueryInvalidRelation, value = value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    /// Lowercase part of the nick.
+    Nick(String),
+    Level(RangeInclusive<u16>),
+    OperationalLevel(RangeInclusive<u16>),
+    Professions(Vec<Profession>),
+    /// Lowercase part of the clan name.
+    Clan(String),
+    Relations(Vec<Relation>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    condition: Condition,
+    negated: bool,
+}
+
+impl Term {
+    fn parse(token: &str) -> Result<Self, QueryError> {
+        let (key, value) = match token.split_once(':') {
+            Some((key, value)) => (key.to_lowercase(), value),
+            None => (String::from("nick"), token),
+        };
+        let (negated, value) = match value.strip_prefix('!') {
+            Some(value) => (true, value),
+            None => (false, value),
+        };
+
+        if value.is_empty() {
+            return Err(QueryError::EmptyValue(key));
+        }
+
+        let condition = match key.as_str() {
+            "nick" => Condition::Nick(value.to_lowercase()),
+            "lvl" => Condition::Level(parse_range(value)?),
+            "oplvl" => Condition::OperationalLevel(parse_range(value)?),
+            "prof" => Condition::Professions(parse_list(&key, value, |prof| {
+                prof.parse()
+                    .map_err(|_| QueryError::InvalidProfession(prof.to_owned()))
+            })?),
+            "clan" => Condition::Clan(value.to_lowercase()),
+            "rel" => Condition::Relations(parse_list(&key, value, |relation| {
+                relation
+                    .parse()
+                    .map_err(|_| QueryError::InvalidRelation(relation.to_owned()))
+            })?),
+            _ => return Err(QueryError::UnknownKey(key)),
+        };
+
+        Ok(Self { condition, negated })
+    }
+
+    fn matches(&self, player: &PlayerSnapshot) -> bool {
+        let matches = match &self.condition {
+            Condition::Nick(text) => player.nick.to_lowercase().contains(text),
+            Condition::Level(range) => range.contains(&player.lvl),
+            Condition::OperationalLevel(range) => range.contains(&player.operational_lvl),
+            Condition::Professions(professions) => professions.contains(&player.prof),
+            Condition::Clan(text) => player
+                .clan
+                .as_ref()
+                .is_some_and(|clan| clan.to_lowercase().contains(text)),
+            Condition::Relations(relations) => relations.contains(&player.relation),
+        };
+
+        matches != self.negated
+    }
+}
+
+/// Parsed query, an empty query matches every player.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct PlayerFilter {
+    terms: Vec<Term>,
+}
+
+impl FromStr for PlayerFilter {
+    type Err = QueryError;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let terms = tokenize(query)?
+            .iter()
+            .map(|token| Term::parse(token))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { terms })
+    }
+}
+
+impl PlayerFilter {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub(crate) fn matches(&self, player: &PlayerSnapshot) -> bool {
+        self.terms.iter().all(|term| term.matches(player))
+    }
+}
+
+/// Splits the query on whitespace outside of quotes, the quotes are removed.
+fn tokenize(query: &str) -> Result<Vec<String>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            c => token.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(QueryError::UnclosedQuote);
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// Parses `a-b`, `a-`, `-b` or `a` into an inclusive range.
+fn parse_range(value: &str) -> Result<RangeInclusive<u16>, QueryError> {
+    let invalid = || QueryError::InvalidLevel(value.to_owned());
+    let parse_bound = |bound: &str, default: u16| match bound.trim() {
+        "" => Ok(default),
+        bound => bound.parse::<u16>().map_err(|_| invalid()),
+    };
+
+    let (start, end) = match value.split_once('-') {
+        Some(("", "")) => return Err(invalid()),
+        Some((start, end)) => (parse_bound(start, u16::MIN)?, parse_bound(end, u16::MAX)?),
+        None => {
+            let lvl = parse_bound(value, u16::MIN)?;
+            (lvl, lvl)
+        }
+    };
+
+    match start <= end {
+        true => Ok(start..=end),
+        false => Err(invalid()),
+    }
+}
+
+/// Parses a comma separated list, empty items are skipped.
+fn parse_list<T>(
+    key: &str,
+    value: &str,
+    parse_item: impl Fn(&str) -> Result<T, QueryError>,
+) -> Result<Vec<T>, QueryError> {
+    let items = value
+        .split(',')
+        .map(|item| item.trim().to_lowercase())
+        .filter(|item| !item.is_empty())
+        .map(|item| parse_item(&item))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match items.is_empty() {
+        true => Err(QueryError::EmptyValue(key.to_owned())),
+        false => Ok(items),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(
+        nick: &str,
+        lvl: u16,
+        prof: Profession,
+        relation: Relation,
+        clan: Option<&str>,
+    ) -> PlayerSnapshot {
+        PlayerSnapshot {
+            nick: nick.to_owned(),
+            lvl,
+            operational_lvl: lvl.min(300),
+            prof,
+            relation,
+            clan: clan.map(str::to_owned),
+        }
+    }
+
+    fn matches(query: &str, player: &PlayerSnapshot) -> bool {
+        query.parse::<PlayerFilter>().unwrap().matches(player)
+    }
+
+    #[test]
+    fn empty_query_matches_everyone() {
+        let filter = "   ".parse::<PlayerFilter>().unwrap();
+
+        assert!(filter.is_empty());
+        assert!(filter.matches(&player("Ąbc", 1, Profession::Warrior, Relation::None, None)));
+    }
+
+    #[test]
+    fn full_query() {
+        let query = "lvl:120-150 prof:m,t clan:!Foo rel:enemy";
+
+        assert!(matches(
+            query,
+            &player("a", 135, Profession::Mage, Relation::Enemy, Some("Bar"))
+        ));
+        assert!(matches(
+            query,
+            &player("a", 150, Profession::Tracker, Relation::Enemy, None)
+        ));
+        assert!(!matches(
+            query,
+            &player("a", 151, Profession::Mage, Relation::Enemy, None)
+        ));
+        assert!(!matches(
+            query,
+            &player("a", 135, Profession::Warrior, Relation::Enemy, None)
+        ));
+        assert!(!matches(
+            query,
+            &player("a", 135, Profession::Mage, Relation::Enemy, Some("foo"))
+        ));
+        assert!(!matches(
+            query,
+            &player("a", 135, Profession::Mage, Relation::Friend, None)
+        ));
+    }
+
+    #[test]
+    fn free_text_matches_nick() {
+        let player = player(
+            "Żelazny Rycerz",
+            70,
+            Profession::Paladin,
+            Relation::None,
+            None,
+        );
+
+        assert!(matches("żelazny", &player));
+        assert!(matches("\"zny ryc\"", &player));
+        assert!(matches("nick:RYCERZ", &player));
+        assert!(!matches("!rycerz", &player));
+        assert!(!matches("mag", &player));
+    }
+
+    #[test]
+    fn level_ranges() {
+        let player = player("a", 400, Profession::Hunter, Relation::None, None);
+
+        assert!(matches("lvl:400", &player));
+        assert!(matches("lvl:300-", &player));
+        assert!(matches("lvl:-400", &player));
+        assert!(!matches("lvl:-399", &player));
+        assert!(matches("oplvl:300", &player));
+        assert!(!matches("oplvl:!250-350", &player));
+    }
+
+    #[test]
+    fn quoted_clan() {
+        let player = player(
+            "a",
+            1,
+            Profession::BladeDancer,
+            Relation::Clan,
+            Some("Foo Bar"),
+        );
+
+        assert!(matches("clan:\"foo bar\"", &player));
+        assert!(matches("clan:\"o b\" rel:clan,clan-ally", &player));
+        assert!(!matches("clan:\"baz\"", &player));
+    }
+
+    #[test]
+    fn invalid_queries() {
+        let error = |query: &str| query.parse::<PlayerFilter>().unwrap_err();
+
+        assert_eq!(error("clan:\"Foo"), QueryError::UnclosedQuote);
+        assert_eq!(
+            error("guild:Foo"),
+            QueryError::UnknownKey(String::from("guild"))
+        );
+        assert_eq!(error("clan:"), QueryError::EmptyValue(String::from("clan")));
+        assert_eq!(
+            error("prof:,"),
+            QueryError::EmptyValue(String::from("prof"))
+        );
+        assert_eq!(error("lvl:-"), QueryError::InvalidLevel(String::from("-")));
+        assert_eq!(
+            error("lvl:150-120"),
+            QueryError::InvalidLevel(String::from("150-120"))
+        );
+        assert_eq!(
+            error("lvl:abc"),
+            QueryError::InvalidLevel(String::from("abc"))
+        );
+        assert_eq!(
+            error("prof:x"),
+            QueryError::InvalidProfession(String::from("x"))
+        );
+        assert_eq!(
+            error("rel:foe"),
+            QueryError::InvalidRelation(String::from("foe"))
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Rendering helpers for the old interface, where the map is built out of DOM elements instead of a canvas.
+
+use dominator::{Dom, DomBuilder};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlDivElement, HtmlElement};
+
+use crate::prelude::*;
+
+/// Appends the dom into a closed shadow root inside of the map container,
+/// so it scrolls together with the map.
+pub(crate) fn append_to_map(dom: Dom) -> JsResult<()> {
+    let layer = DomBuilder::<HtmlDivElement>::new_html("div");
+    let shadow = layer
+        .__internal_shadow_root(web_sys::ShadowRootMode::Closed)
+        .child(dom);
+    let layer = layer.__internal_transfer_callbacks(shadow).into_dom();
+    let base_div = document()
+        .get_element_by_id("base")
+        .ok_or_else(|| err_code!())?;
+    let _layer_handle = dominator::append_dom(&base_div, layer);
+
+    Ok(())
+}
+
+/// Map element of another player.
+pub(crate) fn other_element(other_id: Id) -> Option<HtmlElement> {
+    document()
+        .get_element_by_id(&format!("other{other_id}"))
+        .and_then(|element| element.dyn_into().ok())
+}
+
+/// Draws a glow around the element, `None` removes it.
+pub(crate) fn set_glow(element: &HtmlElement, color: Option<&str>) -> JsResult<()> {
+    let style = element.style();
+
+    match color {
+        Some(color) => style.set_property(
+            "filter",
+            &format!("drop-shadow(0 0 3px {color}) drop-shadow(0 0 3px {color})"),
+        ),
+        None => style.remove_property("filter").map(|_| ()),
+    }
+}
@@ -1,36 +1,61 @@
+use common::messaging::prelude::LogLevel;
 use wasm_bindgen::JsValue;
 
 use crate::bindings::get_engine;
-use crate::interface::{CONSOLE_MESSAGES, ConsoleLog, ConsoleLogTypes};
+use crate::diagnostics::Diagnostics;
+use crate::interface::console::{self, LogSource};
+use crate::interface::{
+    CONSOLE_CAPACITY, CONSOLE_LOGS, CONSOLE_MESSAGES, ConsoleLog, ConsoleLogTypes,
+};
 
-pub(crate) fn __internal_console_error(error_message: JsValue) {
-    use crate::interface::CONSOLE_LOGS;
+/// `module_path` is the module which reported the error, so that the console
+/// can filter errors by the addon they come from.
+pub(crate) fn __internal_console_error(error_message: JsValue, module_path: &str) {
+    Diagnostics::record_error(&common::error::MdmaError::from_js(&error_message));
 
     let console_error = ConsoleLog::new(
         ConsoleLogTypes::Error,
         error_message,
         get_engine().get_ev().ok(),
-    );
+    )
+    .source(LogSource::from_module_path(module_path));
 
-    CONSOLE_LOGS.with_borrow_mut(|logs| {
-        if logs.len() == logs.capacity() {
-            logs.pop_back();
-        }
-        logs.push_front(console_error.clone());
-    });
-    CONSOLE_MESSAGES.with(|logs| logs.lock_mut().push_cloned(console_error))
+    push_console_log(console_error);
 }
 
 pub(crate) fn console_log(msg: JsValue) {
-    use crate::interface::CONSOLE_LOGS;
+    console_log_with(LogLevel::Info, LogSource::Manager, msg);
+}
 
-    let console_log = ConsoleLog::new(ConsoleLogTypes::Message, msg, get_engine().get_ev().ok());
+pub(crate) fn console_log_with(level: LogLevel, source: LogSource, msg: JsValue) {
+    let console_log = ConsoleLog::new(ConsoleLogTypes::Message, msg, get_engine().get_ev().ok())
+        .level(level)
+        .source(source);
+
+    push_console_log(console_log);
+}
+
+/// Adds the log to the ring buffer, communication data isn't displayed in the
+/// console window nor persisted.
+pub(crate) fn push_console_log(log: ConsoleLog) {
+    let displayed = !log.is_communication_data();
 
     CONSOLE_LOGS.with_borrow_mut(|logs| {
         if logs.len() == logs.capacity() {
             logs.pop_back();
         }
-        logs.push_front(console_log.clone());
+        logs.push_front(log.clone());
     });
-    CONSOLE_MESSAGES.with(|logs| logs.lock_mut().push_cloned(console_log))
+
+    if displayed {
+        CONSOLE_MESSAGES.with(|logs| {
+            let mut logs_lock = logs.lock_mut();
+
+            if logs_lock.len() == CONSOLE_CAPACITY {
+                logs_lock.remove(0);
+            }
+            logs_lock.push_cloned(log);
+        });
+        console::mark_unsaved();
+    }
 }
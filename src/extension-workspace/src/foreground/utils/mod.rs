@@ -8,7 +8,7 @@ use std::fmt::Debug;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
-use common::{debug_log, throw_err_code, messaging::prelude::*, err_code};
+use common::{debug_log, throw_err_code, messaging::prelude::*, err_code, map_err};
 use futures::{Stream, StreamExt};
 use futures_signals::signal::{Mutable, Signal, SignalExt, from_stream};
 use futures_signals::signal_map::{self, MapDiff, SignalMap};
@@ -16,8 +16,8 @@ use js_sys::{Math, Promise};
 use pin_project::pin_project;
 use serde::de::DeserializeOwned;
 use serde_json::{Value, json};
-use wasm_bindgen::JsValue;
-use web_sys::{Document, Window};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Document, IdbDatabase, IdbObjectStore, IdbTransactionMode, Window};
 
 use crate::bindings::engine::peer::Peer;
 use crate::globals::peers::PeerId;
@@ -366,6 +366,86 @@ pub(crate) async fn delay_range(min: usize, max: usize) {
     let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
 }
 
+/// Waits for an IndexedDB request to finish and returns its result.
+pub(crate) async fn idb_request_result(request: &web_sys::IdbRequest) -> JsResult<JsValue> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .map_err(map_err!())?;
+
+    request.result().map_err(map_err!())
+}
+
+/// Opens an IndexedDB database, `create_schema` runs when the database gets
+/// created or upgraded to `version`.
+pub(crate) async fn idb_open(
+    name: &str,
+    version: u32,
+    create_schema: fn(&IdbDatabase) -> JsResult<()>,
+) -> JsResult<IdbDatabase> {
+    let open_request = window()
+        .indexed_db()
+        .map_err(map_err!())?
+        .ok_or_else(|| err_code!())?
+        .open_with_u32(name, version)
+        .map_err(map_err!())?;
+    let on_upgrade_needed = common::closure!(
+        @once
+        { let open_request = open_request.clone() },
+        move || -> JsResult<()> {
+            let db: IdbDatabase = open_request.result()?.unchecked_into();
+            create_schema(&db)
+        },
+    );
+    open_request.set_onupgradeneeded(Some(&on_upgrade_needed));
+
+    Ok(idb_request_result(&open_request).await?.unchecked_into())
+}
+
+/// The `store_name` object store of a new transaction on it.
+pub(crate) fn idb_store(
+    db: &IdbDatabase,
+    store_name: &str,
+    mode: IdbTransactionMode,
+) -> JsResult<IdbObjectStore> {
+    db.transaction_with_str_and_mode(store_name, mode)
+        .map_err(map_err!())?
+        .object_store(store_name)
+        .map_err(map_err!())
+}
+
+/// Formats a unix timestamp in seconds as `YYYY-MM-DD HH:MM:SS` in local time.
+pub(crate) fn format_ts(ts: f64) -> String {
+    let date = js_sys::Date::new(&(ts * 1_000.0).into());
+
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}:{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date(),
+        date.get_hours(),
+        date.get_minutes(),
+        date.get_seconds(),
+    )
+}
+
+/// Quotes a CSV field, doubling the quotes inside of it.
+pub(crate) fn csv_escape(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+/// Escapes text inserted into the HTML of game dialogs.
+pub(crate) fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 pub(crate) trait GetOnlinePeer {
     fn get_online(&self, key: &PeerId) -> Option<&Peer>;
 }
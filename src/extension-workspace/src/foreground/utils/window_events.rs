@@ -20,47 +20,6 @@ impl KeyPress {
 make_mouse_event!(MouseOver => web_sys::MouseEvent);
 static_event_impl!(MouseOver => "mouseover");
 
-pub(crate) fn verify_keyboard_event(event: &web_sys::KeyboardEvent, key: &str) -> JsResult<bool> {
-    //let value = event
-    //    .key()
-    //    .chars()
-    //    .map(polish_to_ascii)
-    //    .collect::<String>()
-    //    .to_ascii_uppercase();
-    if event.key().to_lowercase() != key.to_lowercase() {
-        return Ok(false);
-    }
-
-    if event.repeat() {
-        return Ok(false);
-    }
-
-    let target = event.target().ok_or_else(|| err_code!())?;
-    let target_node: &Node = target.dyn_ref().ok_or_else(|| err_code!())?;
-    let target_elem: &Element = target.dyn_ref().ok_or_else(|| err_code!())?;
-
-    if target_node.node_type() != 1 {
-        return Ok(false);
-    }
-    if !target_node.has_child_nodes()
-        && target_node.node_name() == s!("DIV")
-        && target_elem.class_list().length() == 0
-    {
-        return Ok(false);
-    }
-
-    let target_tag_name = target_elem.tag_name().to_uppercase();
-
-    if [s!("INPUT"), s!("TEXTAREA"), s!("SELECT")]
-        .iter()
-        .any(|&s| target_tag_name.contains(s))
-    {
-        return Ok(false);
-    }
-
-    Ok(true)
-}
-
 pub trait Hotkey {
     fn value(&self) -> &str;
     fn alt_key(&self) -> bool;
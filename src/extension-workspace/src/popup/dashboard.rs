@@ -0,0 +1,245 @@
+//! Addons of the game tab along with the state of the extension, displayed
+//! below the user details.
+
+use common::{
+    addon_dashboard::{AddonStatus, AddonToggle},
+    connection::SessionScope,
+    messaging::prelude::*,
+    tr,
+};
+use dominator::{Dom, DomBuilder, events::Change, html, with_node};
+use futures_signals::{
+    map_ref,
+    signal::{Signal, SignalExt},
+};
+use web_sys::{HtmlDivElement, HtmlInputElement, HtmlSelectElement};
+
+use crate::{Popup, console_error};
+
+impl Popup {
+    pub(crate) fn render_dashboard(&'static self) -> impl Signal<Item = Option<Dom>> + use<> {
+        self.user.signal_ref(move |user_data| {
+            user_data.is_some().then(|| {
+                DomBuilder::<HtmlDivElement>::new_html("div")
+                    .class("dashboard")
+                    .child_signal(self.render_dashboard_status())
+                    .child_signal(self.render_addons())
+                    .child_signal(self.render_session_scope())
+                    .into_dom()
+            })
+        })
+    }
+
+    fn render_dashboard_status(&'static self) -> impl Signal<Item = Option<Dom>> + use<> {
+        map_ref! {
+            let dashboard = self.dashboard.signal_cloned(),
+            let connection = self.connection.signal() => {
+                let (game_connected, errors) = dashboard
+                    .as_ref()
+                    .map(|dashboard| (dashboard.game_connected, dashboard.errors))
+                    .unwrap_or_default();
+                let server_connected = *connection == ConnectionState::Connected;
+
+                (game_connected, server_connected, errors)
+            }
+        }
+        .map(|(game_connected, server_connected, errors)| {
+            let status = |ok: bool, text: &str| {
+                html!("div", {
+                    .class(match ok {
+                        true => "dashboard-ok",
+                        false => "dashboard-warn",
+                    })
+                    .text(text)
+                })
+            };
+
+            let dom = DomBuilder::<HtmlDivElement>::new_html("div")
+                .class("dashboard-status")
+                .child(status(
+                    game_connected,
+                    match game_connected {
+                        true => tr!(PopupDashboardGameConnected),
+                        false => tr!(PopupDashboardGameDisconnected),
+                    },
+                ))
+                .child(status(
+                    server_connected,
+                    match server_connected {
+                        true => tr!(PopupDashboardServerConnected),
+                        false => tr!(PopupDashboardServerReconnecting),
+                    },
+                ))
+                .child(status(
+                    errors == 0,
+                    &tr!(PopupDashboardErrors, count = errors),
+                ))
+                .into_dom();
+
+            Some(dom)
+        })
+    }
+
+    fn render_addons(&'static self) -> impl Signal<Item = Option<Dom>> + use<> {
+        self.dashboard.signal_ref(move |dashboard| {
+            let dom = match dashboard
+                .as_ref()
+                .filter(|dashboard| !dashboard.addons.is_empty())
+            {
+                None => html!("div", {
+                    .class("info-box")
+                    .text(tr!(PopupDashboardEmpty))
+                }),
+                Some(dashboard) => DomBuilder::<HtmlDivElement>::new_html("div")
+                    .child(html!("strong", {
+                        .text(&tr!(
+                            PopupDashboardAddons,
+                            interface = dashboard.interface.to_uppercase(),
+                        ))
+                    }))
+                    .child(
+                        DomBuilder::<HtmlDivElement>::new_html("div")
+                            .class("addon-list")
+                            .children(
+                                dashboard
+                                    .addons
+                                    .iter()
+                                    .map(|addon| self.render_addon(addon)),
+                            )
+                            .into_dom(),
+                    )
+                    .into_dom(),
+            };
+
+            Some(dom)
+        })
+    }
+
+    fn render_addon(&'static self, addon: &AddonStatus) -> Dom {
+        let key = addon.key.clone();
+        let badge = |class: &str, text: &str| {
+            html!("span", {
+                .class(["addon-badge", class])
+                .text(text)
+            })
+        };
+
+        html!("label", {
+            .class("addon-row")
+            .apply_if(!addon.available, |builder| {
+                builder
+                    .class("unavailable")
+                    .attr("title", tr!(PopupAddonUnavailable))
+            })
+            .child(html!("input" => HtmlInputElement, {
+                .attr("type", "checkbox")
+                .apply_if(addon.active, |builder| builder.attr("checked", ""))
+                .apply_if(!addon.available, |builder| builder.attr("disabled", ""))
+                .with_node!(input => {
+                    .event(move |_: Change| self.toggle_addon(key.clone(), input.checked()))
+                })
+            }))
+            .child(html!("span", {
+                .class("addon-name")
+                .text(&addon.name)
+            }))
+            .apply_if(addon.premium, |builder| {
+                builder.child(badge("premium", tr!(PopupAddonPremium)))
+            })
+            .apply_if(addon.failed, |builder| {
+                builder.child(badge("failed", tr!(PopupAddonFailed)))
+            })
+        })
+    }
+
+    /// Updates the dashboard right away, the background applies the toggle
+    /// in the game tab or once one connects.
+    fn toggle_addon(&'static self, key: String, active: bool) {
+        if let Some(dashboard) = self.dashboard.lock_mut().as_mut() {
+            dashboard.set_active(&key, active);
+        }
+
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(err_code) = Message::builder(
+                Task::AddonDashboard,
+                Target::Background,
+                MessageKind::Request,
+            )
+            .addon_toggles(vec![AddonToggle::new(key, active)])
+            .build()
+            .execute()
+            .await
+            {
+                console_error!(err_code);
+            }
+        });
+    }
+
+    fn render_session_scope(&'static self) -> impl Signal<Item = Option<Dom>> + use<> {
+        self.session_scope.signal().map(move |selected| {
+            let selected = selected?;
+            let scopes = [
+                (SessionScope::GameCharacter, tr!(PopupSessionScopeCharacter)),
+                (SessionScope::GameAccount, tr!(PopupSessionScopeAccount)),
+                (SessionScope::DiscordAccount, tr!(PopupSessionScopeDiscord)),
+            ];
+
+            let dom = html!("label", {
+                .class("session-scope")
+                .text(tr!(PopupSessionScope))
+                .child(html!("select" => HtmlSelectElement, {
+                    .children(scopes.map(|(scope, text)| {
+                        html!("option", {
+                            .attr("value", &(scope as u8).to_string())
+                            .apply_if(scope == selected, |builder| builder.attr("selected", ""))
+                            .text(text)
+                        })
+                    }))
+                    .with_node!(select => {
+                        .event(move |_: Change| {
+                            let Some(scope) = scopes
+                                .iter()
+                                .map(|(scope, _)| *scope)
+                                .find(|scope| (*scope as u8).to_string() == select.value())
+                            else {
+                                return;
+                            };
+
+                            self.set_session_scope(scope);
+                        })
+                    })
+                }))
+            });
+
+            Some(dom)
+        })
+    }
+
+    /// The select keeps the new scope, the background responds with it once
+    /// the server saved it.
+    fn set_session_scope(&'static self, scope: SessionScope) {
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(err_code) =
+                Message::builder(Task::SessionScope, Target::Background, MessageKind::Request)
+                    .session_scope(scope)
+                    .build()
+                    .execute()
+                    .await
+            {
+                console_error!(err_code);
+            }
+        });
+    }
+
+    /// Asks the background for the last reported dashboard, it's refreshed by
+    /// the game tab if one is open.
+    pub(crate) async fn fetch_dashboard() -> Result<(), wasm_bindgen::JsValue> {
+        Message::new(
+            Task::AddonDashboard,
+            Target::Background,
+            MessageKind::Request,
+        )
+        .execute()
+        .await
+    }
+}
@@ -1,10 +1,10 @@
 use std::sync::OnceLock;
 
-use common::{err_code, messaging::prelude::*};
+use common::{err_code, messaging::prelude::*, tr};
 use futures::{SinkExt, StreamExt, channel::mpsc};
 use wasm_bindgen::prelude::*;
 
-use crate::{DisplayMessage, LoadingReason, Popup, UserData, console_error};
+use crate::{DisplayMessage, DisplayMessageKind, LoadingReason, Popup, UserData, console_error};
 
 // TODO: rename to sth like `RUNTIME_TX` since that's what it is.
 static DISPATCHER: OnceLock<mpsc::UnboundedSender<Message>> = OnceLock::new();
@@ -48,6 +48,34 @@ impl Dispatcher {
             match msg.kind {
                 MessageKind::Event => match msg.task {
                     Task::OpenPopup => common::debug_log!("RECEIVED OPEN POPUP IN POPUP"),
+                    Task::ConnectionState => {
+                        let connection = msg.connection.ok_or_else(|| err_code!())?;
+                        let was_reconnecting = state.message.lock_ref().as_ref().is_some_and(
+                            |display_msg| display_msg.kind == DisplayMessageKind::Warning,
+                        );
+                        state.connection.set_neq(connection);
+
+                        let display_msg = match DisplayMessage::connection(connection) {
+                            None if was_reconnecting => {
+                                Some(DisplayMessage::success(tr!(PopupReconnected).to_owned()))
+                            }
+                            display_msg => display_msg,
+                        };
+
+                        state.message.set_neq(display_msg);
+                    }
+                    // The game tab reported its addons or disconnected.
+                    Task::AddonDashboard => {
+                        state.dashboard.set_neq(msg.addon_dashboard);
+                    }
+                    // The stored session got rejected after reconnecting.
+                    Task::LogOut => {
+                        state.user.set_neq(None);
+                        state.session_scope.set_neq(None);
+                        state.message.set_neq(Some(DisplayMessage::error(
+                            tr!(PopupSessionExpired).to_owned(),
+                        )));
+                    }
                     _ => unreachable!(),
                 },
                 MessageKind::Response => match msg.task {
@@ -72,16 +100,14 @@ impl Dispatcher {
                             }
                             PopupMessage::LoginFailed { reason } => {
                                 let txt = match reason {
-                                    Some(reason) => {
-                                        format!("Wystąpił błąd podczas logowania - {reason}")
-                                    }
-                                    None => "Wystąpił błąd podczas logowania!".to_owned(),
+                                    Some(reason) => tr!(PopupLoginErrorReason, reason = reason),
+                                    None => tr!(PopupLoginError).to_owned(),
                                 };
                                 DisplayMessage::error(txt)
                             }
-                            PopupMessage::RefreshAfterLogin => DisplayMessage::success(
-                                "Odśwież kartę z Margonem, aby wczytać zestaw!".to_owned(),
-                            ),
+                            PopupMessage::RefreshAfterLogin => {
+                                DisplayMessage::success(tr!(PopupReloadGame).to_owned())
+                            }
                         });
 
                         state.message.set_neq(display_msg);
@@ -89,6 +115,13 @@ impl Dispatcher {
                         state
                             .user
                             .set_neq(msg.username.map(|nick| UserData::new(nick, msg.premium)));
+                        state.session_scope.set_neq(msg.session_scope);
+                    }
+                    Task::AddonDashboard => {
+                        state.dashboard.set_neq(msg.addon_dashboard);
+                    }
+                    Task::SessionScope => {
+                        state.session_scope.set_neq(msg.session_scope);
                     }
                     _ => unreachable!(),
                 },
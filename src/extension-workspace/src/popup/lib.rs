@@ -1,8 +1,13 @@
 use std::ops::Deref;
 
 use common::{
-    UnwrapJsExt, debug_log, err_code,
+    UnwrapJsExt,
+    addon_dashboard::AddonDashboard,
+    connection::SessionScope,
+    debug_log, err_code,
+    i18n::Locale,
     messaging::{LogOutDetails, prelude::*},
+    tr,
 };
 use discard::Discard;
 use dominator::{
@@ -20,6 +25,7 @@ use serde::Serialize;
 use wasm_bindgen::prelude::*;
 use web_sys::{HtmlAnchorElement, HtmlButtonElement, HtmlDivElement};
 
+mod dashboard;
 pub mod dispatcher;
 mod exports;
 
@@ -29,6 +35,7 @@ thread_local! {
 
 #[wasm_bindgen(start)]
 async fn main() -> Result<(), JsValue> {
+    init_locale();
     init_global_stylesheets();
 
     let spinner_before_init = DomBuilder::<HtmlDivElement>::new_html("div")
@@ -51,13 +58,24 @@ async fn main() -> Result<(), JsValue> {
 
     validator.validate(&msg)?;
 
+    if let Some(locale) = msg.language {
+        Locale::set_current(locale);
+    }
+
     let state = msg
         .popup
         .ok_or_else(|| err_code!())?
         .state
         .ok_or_else(|| err_code!())?;
     let user_data = msg.username.map(|nick| UserData::new(nick, msg.premium));
-    let popup: &'static _ = Box::leak(Box::new(Popup::new(state, user_data)));
+    let popup: &'static _ = Box::leak(Box::new(Popup::new(state, user_data, msg.session_scope)));
+
+    if let Some(connection) = msg.connection {
+        popup.connection.set_neq(connection);
+        popup
+            .message
+            .set_neq(DisplayMessage::connection(connection));
+    }
 
     spinner_handle.discard();
 
@@ -65,9 +83,21 @@ async fn main() -> Result<(), JsValue> {
 
     dispatcher.spawn_event_loop(popup);
 
+    Popup::fetch_dashboard().await?;
+
     Ok(())
 }
 
+/// The popup isn't tied to any world, so it follows the language of the browser
+/// until the language chosen in the manager settings is received.
+fn init_locale() {
+    let language = web_sys::window()
+        .and_then(|window| window.navigator().language())
+        .unwrap_or_default();
+
+    Locale::set_current(Locale::from_language_tag(&language));
+}
+
 #[derive(Debug, Serialize)]
 struct DateLocaleOptions<'a> {
     year: &'a str,
@@ -142,14 +172,26 @@ struct Popup {
     user: Mutable<Option<UserData>>,
     loading: LoadingQueue,
     message: Mutable<Option<DisplayMessage>>,
+    connection: Mutable<ConnectionState>,
+    /// Last addon dashboard reported by a game tab.
+    dashboard: Mutable<Option<AddonDashboard>>,
+    /// `None` while logged out.
+    session_scope: Mutable<Option<SessionScope>>,
 }
 
 impl Popup {
-    fn new(state: PopupState, user_data: Option<UserData>) -> Self {
+    fn new(
+        state: PopupState,
+        user_data: Option<UserData>,
+        session_scope: Option<SessionScope>,
+    ) -> Self {
         Self {
             user: Mutable::new(user_data),
             loading: LoadingQueue::new(state),
             message: Mutable::new(None),
+            connection: Mutable::new(ConnectionState::Connecting),
+            dashboard: Mutable::new(None),
+            session_scope: Mutable::new(session_scope),
         }
     }
 
@@ -161,6 +203,7 @@ impl Popup {
             .child_signal(self.render_login_button())
             .child_signal(self.render_login_status())
             .child_signal(self.render_welcome())
+            .child_signal(self.render_dashboard())
             .child_signal(self.render_logout_buttons())
             .into_dom()
     }
@@ -173,16 +216,21 @@ impl Popup {
                 .class(msg.kind.to_class())
                 .apply(|b| match msg.kind == DisplayMessageKind::JoinDiscord {
                     false => b.text(&msg.txt),
-                    true => b
-                        .text("Dołącz na ")
-                        .child(
-                            DomBuilder::<HtmlAnchorElement>::new_html("a")
-                                .text("nasz discord")
-                                .attr("href", "https://discord.gg/dodatki-margonem")
-                                .attr("target", "_blank")
-                                .into_dom(),
-                        )
-                        .text(" i spróbuj ponownie!"),
+                    true => {
+                        let (before, after) = tr!(PopupJoinDiscord)
+                            .split_once("{discord}")
+                            .unwrap_or_default();
+
+                        b.text(before)
+                            .child(
+                                DomBuilder::<HtmlAnchorElement>::new_html("a")
+                                    .text(tr!(PopupDiscordLink))
+                                    .attr("href", "https://discord.gg/dodatki-margonem")
+                                    .attr("target", "_blank")
+                                    .into_dom(),
+                            )
+                            .text(after)
+                    }
                 })
                 .into_dom();
 
@@ -228,7 +276,7 @@ impl Popup {
             display_button.then(|| {
                 DomBuilder::<HtmlButtonElement>::new_html("button")
                     .class("login-btn")
-                    .text("Logowanie")
+                    .text(tr!(PopupLogin))
                     .event(move |_: Click| {
                         wasm_bindgen_futures::spawn_local(async move {
                             if let Err(err_code) = self.start_login().await {
@@ -262,10 +310,10 @@ impl Popup {
                 logging_in.then(|| {
                     DomBuilder::<HtmlDivElement>::new_html("div")
                         .class("status")
-                        .text("Rozpoczęto logowanie...")
+                        .text(tr!(PopupLoginStarted))
                         .child(html!("br", {}))
                         .child(html!("small", {
-                            .text("Dokończ logowanie wewnątrz okna Discord!")
+                            .text(tr!(PopupLoginFinish))
                         }))
                         .into_dom()
                 })
@@ -281,44 +329,42 @@ impl Popup {
             let welcome = DomBuilder::<HtmlDivElement>::new_html("div")
                 .class(["status", "success"])
                 .child(html!("strong", {
-                    .text(&format!("Witaj {username}!"))
+                    .text(&tr!(PopupWelcome, username = username))
                 }))
                 .child(html!("br", {}))
                 .child(
                     DomBuilder::<HtmlDivElement>::new_html("div")
                         .class("info-box")
                         .text(match premium.is_some() {
-                            true => "Premium aktywne!",
-                            false => "Premium: brak :(",
+                            true => tr!(PopupPremiumActive),
+                            false => tr!(PopupPremiumNone),
                         })
                         .apply_if(premium.is_some(), |b| {
+                            let date = js_sys::Date::new(&JsValue::from_f64(
+                                (premium.unwrap().exp * 1000) as f64,
+                            ))
+                            .to_locale_date_string(
+                                Locale::current().tag(),
+                                &serde_wasm_bindgen::to_value(&DateLocaleOptions::default())
+                                    .unwrap_js(),
+                            )
+                            .as_string()
+                            .unwrap_js();
+                            let feature = |enabled: bool, name: &str| match enabled {
+                                true => format!("✅ {name}"),
+                                false => format!("❌ {name}"),
+                            };
+
                             b.child(html!("br", {}))
-                                .text(&format!(
-                                        "Ważne do: {}",
-                                        js_sys::Date::new(&JsValue::from_f64(
-                                            (premium.unwrap().exp * 1000) as f64
-                                        ))
-                                        .to_locale_date_string(
-                                            "pl-PL",
-                                            &serde_wasm_bindgen::to_value(
-                                                &DateLocaleOptions::default()
-                                            )
-                                            .unwrap_js(),
-                                        )
-                                        .as_string()
-                                        .unwrap_js(),
-                                    ))
+                                .text(&tr!(PopupPremiumValidUntil, date = date))
                                 .child(html!("br", {}))
                                 .child(html!("br", {}))
-                                .text(match premium.unwrap().neon {
-                                    true => "✅ Neon Bohatera",
-                                    false => "❌ Neon Bohatera",
-                                })
+                                .text(&feature(premium.unwrap().neon, tr!(PopupHeroNeon)))
                                 .child(html!("br", {}))
-                                .text(match premium.unwrap().animation {
-                                    true => "✅ Animacja Chodzenia",
-                                    false => "❌ Animacja Chodzenia",
-                                })
+                                .text(&feature(
+                                    premium.unwrap().animation,
+                                    tr!(PopupWalkAnimation),
+                                ))
                         })
                         .into_dom(),
                 )
@@ -333,8 +379,8 @@ impl Popup {
             user_data.is_some().then(|| {
                 DomBuilder::<HtmlDivElement>::new_html("div")
                     .class("logout-btns")
-                    .child(self.render_logout_button("Wyloguj", false))
-                    .child(self.render_logout_button("Wyloguj ze wszystkich urządzeń", true))
+                    .child(self.render_logout_button(tr!(PopupLogOut), false))
+                    .child(self.render_logout_button(tr!(PopupLogOutAll), true))
                     .into_dom()
             })
         })
@@ -409,6 +455,25 @@ impl DisplayMessage {
             txt: String::new(),
         }
     }
+
+    pub fn warning(txt: String) -> Self {
+        Self {
+            kind: DisplayMessageKind::Warning,
+            txt,
+        }
+    }
+
+    /// Returns `None` when there's nothing to display about the connection.
+    pub fn connection(state: ConnectionState) -> Option<Self> {
+        match state {
+            ConnectionState::Connecting | ConnectionState::Connected => None,
+            ConnectionState::Reconnecting { attempt, retry_in } => Some(Self::warning(tr!(
+                PopupReconnecting,
+                seconds = retry_in.div_ceil(1000),
+                attempt = attempt,
+            ))),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -416,6 +481,7 @@ pub enum DisplayMessageKind {
     Success,
     Error,
     JoinDiscord,
+    Warning,
 }
 
 impl DisplayMessageKind {
@@ -423,7 +489,7 @@ impl DisplayMessageKind {
         match self {
             Self::Success => "success",
             Self::Error => "error",
-            Self::JoinDiscord => "warn",
+            Self::JoinDiscord | Self::Warning => "warn",
         }
     }
 }
@@ -576,4 +642,76 @@ fn init_global_stylesheets() {
     stylesheet!(".logout-btn:hover", {
         .style("background", "#b02a37")
     });
+    stylesheet!(".dashboard", {
+        .styles! {
+            display: "flex",
+            "flex-direction": "column",
+            gap: "10px",
+            width: "100%",
+            color: "white",
+        }
+    });
+    stylesheet!(".dashboard-status", {
+        .styles! {
+            display: "flex",
+            "flex-direction": "column",
+            gap: "4px",
+            "font-size": "12px",
+        }
+    });
+    stylesheet!(".dashboard-ok", {
+        .style("color", "#8fd694")
+    });
+    stylesheet!(".dashboard-warn", {
+        .style("color", "#f0c674")
+    });
+    stylesheet!(".addon-list", {
+        .styles! {
+            display: "flex",
+            "flex-direction": "column",
+            gap: "4px",
+            "margin-top": "6px",
+            "max-height": "180px",
+            "overflow-y": "auto",
+        }
+    });
+    stylesheet!(".addon-row", {
+        .styles! {
+            display: "flex",
+            "align-items": "center",
+            gap: "6px",
+            cursor: "pointer",
+        }
+    });
+    stylesheet!(".addon-row.unavailable", {
+        .styles! {
+            opacity: "0.5",
+            cursor: "default",
+        }
+    });
+    stylesheet!(".addon-badge", {
+        .styles! {
+            padding: "1px 6px",
+            "border-radius": "4px",
+            "font-size": "11px",
+        }
+    });
+    stylesheet!(".addon-badge.premium", {
+        .styles! {
+            background: "#7289DA",
+        }
+    });
+    stylesheet!(".addon-badge.failed", {
+        .styles! {
+            background: "#dc3545",
+        }
+    });
+    stylesheet!(".session-scope", {
+        .styles! {
+            display: "flex",
+            "justify-content": "space-between",
+            "align-items": "center",
+            gap: "10px",
+        }
+    });
 }
@@ -1,54 +1,66 @@
+//! Decodes error codes reported by `err_code!` using the symbol tables generated by
+//! `common/build.rs` for each release.
+//!
+//! Usage:
+//! - `extract_filenames decode <code | log file | -> [--release <version>]`
+//! - `extract_filenames list [--release <version>]`
+//! - `extract_filenames <code>`, same as `decode <code>`
+//!
+//! Tables are read from `MDMA_SYMBOLS_DIR` (relative to `extension-workspace`), `symbols/` by
+//! default. Release builds write them there only when the variable is set, e.g.
+//! `MDMA_SYMBOLS_DIR=symbols cargo build --release`.
+
+use std::collections::HashMap;
 use std::env;
-use std::fs::{self, File};
-use std::io::{self, Write};
-use std::path::Path;
-
-/// Recursively collects all file paths from a `src` directory
-fn collect_src_files(base_dir: &Path, file_names: &mut Vec<String>) -> io::Result<()> {
-    for entry in fs::read_dir(base_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_dir() {
-            // Check if this directory has a `src` subdirectory
-            let src_path = path.join("src");
-            if src_path.exists() && src_path.is_dir() {
-                collect_files_from_src(&src_path, file_names)?;
-            } else {
-                // Recurse into other directories
-                collect_src_files(&path, file_names)?;
-            }
-        }
-    }
-    Ok(())
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+#[path = "../../extension-workspace/src/common/error/report.rs"]
+mod report;
+
+use report::{parse_code, parse_error_report};
+
+fn workspace_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("extension-workspace")
 }
 
-/// Collects .rs files from a src directory
-fn collect_files_from_src(src_dir: &Path, file_names: &mut Vec<String>) -> io::Result<()> {
-    for entry in fs::read_dir(src_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_dir() {
-            // Recursively handle subdirectories
-            collect_files_from_src(&path, file_names)?;
-        } else if let Some(file_str) = path.to_str() {
-            // Only collect .rs files
-            if file_str.ends_with(".rs") {
-                // Find the index after "src\\" or "src/"
-                if let Some(sliced_path) = file_str
-                    .find("extension-workspace\\")
-                    .or_else(|| file_str.find("extension-workspace/"))
-                    .map(|idx| &file_str[idx + 24..])
-                {
-                    //println!("{} => {sliced_path}", file_str);
-
-                    file_names.push(sliced_path.to_string());
-                }
-            }
-        }
-    }
+/// Release of the current workspace, used when neither the report nor the arguments specify one.
+fn workspace_version() -> io::Result<String> {
+    let manifest = fs::read_to_string(workspace_dir().join("Cargo.toml"))?;
 
-    Ok(())
+    manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("version = "))
+        .map(|version| version.trim_matches('"').to_string())
+        .ok_or_else(|| io::Error::other("No version in the workspace manifest"))
+}
+
+/// Same resolution as in `common/build.rs`.
+fn symbols_dir() -> PathBuf {
+    let dir = env::var("MDMA_SYMBOLS_DIR").unwrap_or_else(|_| "symbols".to_string());
+
+    workspace_dir().join(dir)
+}
+
+/// Reads `<release>.txt` from the symbols directory, the first line of the table is the release
+/// it belongs to.
+fn read_symbols(release: &str) -> io::Result<Vec<String>> {
+    let path = symbols_dir().join(format!("{release}.txt"));
+    let table = fs::read_to_string(&path).map_err(|err| {
+        io::Error::new(
+            err.kind(),
+            format!("Missing symbol table {}: {err}", path.display()),
+        )
+    })?;
+
+    Ok(table
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.is_empty())
+        .map(str::to_string)
+        .collect())
 }
 
 fn decode_location(encoded: u32) -> (u16, u16) {
@@ -57,82 +69,96 @@ fn decode_location(encoded: u32) -> (u16, u16) {
     (high, low)
 }
 
-fn main() -> io::Result<()> {
-    // Move to the parent directory of the current working directory
-    let current_dir = env::current_dir()?;
-    let parent_dir = current_dir.parent().expect("No parent directory found");
-    let mut file_names = Vec::new();
-
-    // Collect all `.rs` files from `src` directories under the parent directory
-    collect_src_files(parent_dir, &mut file_names)?;
-
-    if let Some(arg) = env::args().nth(1) {
-        let arg = arg.parse().unwrap();
-        let (file_idx, line) = decode_location(arg);
-        println!(
-            "{arg} => {}:{line} (idx: {})",
-            file_names[file_idx as usize], file_idx
-        );
-
+fn decode(input: &str, release: Option<&str>) -> io::Result<()> {
+    let report = match input {
+        "-" => {
+            let mut report = String::new();
+            io::stdin().read_to_string(&mut report)?;
+            report
+        }
+        input if parse_code(input).is_some() => input.to_string(),
+        path => fs::read_to_string(path)?,
+    };
+    let default_release = match release {
+        Some(release) => release.to_string(),
+        None => workspace_version()?,
+    };
+    let mut tables: HashMap<String, Option<Vec<String>>> = HashMap::new();
+    let reported = parse_error_report(&report);
+
+    if reported.is_empty() {
+        println!("No error codes found");
         return Ok(());
     }
 
-    // Create the full path
-    let file_path = parent_dir.join("extension-workspace\\src\\common\\src\\file_names.rs");
-
-    // Create the file
-    let mut output_file = File::create(file_path)?;
-
-    writeln!(
-        output_file,
-        r#"// This file is generated by generate_file_names.rs
-// Do not edit manually.
-
-use obfstr::obfstr as s;
-use wasm_bindgen::intern as i;
-
-///Retrieves hashed file name index
-///
-///If the pkg_name is not trackable from caller (for instance when using `.unwrap_js`)
-///and the caller file is a lib.rs uses the first lib.rs from partial file paths.
-///
-///When this happens find the error with only the line number.
-pub fn find_file_index(pkg_name: Option<&str>, file: &str) -> Option<u16> {{
-    //Partial file paths:"#
-    )?;
-
-    for (index, file) in file_names.iter().enumerate() {
-        let lint = match index {
-            index if index < 10 => "  ",
-            index if index < 100 => " ",
-            _ => "",
+    for report::ReportedCode {
+        code,
+        release: reported_release,
+    } in reported
+    {
+        // The release passed explicitly takes precedence over the one found in the log.
+        let release = match release {
+            Some(release) => release,
+            None => reported_release.unwrap_or(&default_release),
         };
-        writeln!(output_file, r#"    // {index}{lint} => {file}"#)?;
+        let table = tables.entry(release.to_string()).or_insert_with(|| {
+            read_symbols(release)
+                .inspect_err(|err| eprintln!("{err}"))
+                .ok()
+        });
+        let Some(table) = table else {
+            println!("{code} => ? (v{release}, missing symbol table)");
+            continue;
+        };
+
+        let (file_idx, line) = decode_location(code);
+        let file = table.get(file_idx as usize);
+        let crate_name = file.and_then(|file| file.split('/').next());
+
+        match (crate_name, file) {
+            (Some(crate_name), Some(file)) => {
+                println!("{code} => {crate_name}: {file}:{line} (v{release}, idx: {file_idx})")
+            }
+            _ => println!("{code} => ?:{line} (v{release}, unknown idx: {file_idx})"),
+        }
     }
 
-    writeln!(output_file, "    [")?;
-    for file in file_names {
-        // Write file name as a string literal
-        writeln!(output_file, r#"        i(s!({file:?})),"#)?;
+    Ok(())
+}
+
+fn list(release: Option<&str>) -> io::Result<()> {
+    let release = match release {
+        Some(release) => release.to_string(),
+        None => workspace_version()?,
+    };
+
+    for (index, file) in read_symbols(&release)?.iter().enumerate() {
+        println!("{index:<4}=> {file}");
     }
 
-    writeln!(
-        output_file,
-        r#"    ]
-    .iter()
-    .enumerate()
-    .find(|(_, partial_file_path)| {{
-        file.ends_with(*partial_file_path)
-            && match pkg_name {{
-                Some(pkg_name) => partial_file_path.contains(pkg_name),
-                None => true,
-            }}
-    }})
-    .map(|(index, _)| index as u16)
-}}
-"#
-    )?;
-
-    println!("Generated file names in `file_names.rs`");
     Ok(())
 }
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let release = args
+        .iter()
+        .position(|arg| arg == "--release")
+        .and_then(|index| args.get(index + 1))
+        .map(String::as_str);
+
+    match args.first().map(String::as_str) {
+        Some("decode") => match args.get(1) {
+            Some(input) => decode(input, release),
+            None => Err(io::Error::other("Missing error code or log file")),
+        },
+        Some("list") => list(release),
+        Some(code) if parse_code(code).is_some() => decode(code, release),
+        _ => {
+            println!("Usage:");
+            println!("  extract_filenames decode <code | log file | -> [--release <version>]");
+            println!("  extract_filenames list [--release <version>]");
+            Ok(())
+        }
+    }
+}